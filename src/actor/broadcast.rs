@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::actor::app::WindowId;
+use crate::common::config::LayoutMode;
 use crate::layout_engine::{LayoutKind, VirtualWorkspaceId};
 use crate::sys::screen::SpaceId;
 
@@ -23,6 +24,16 @@ pub enum BroadcastEvent {
         workspace_name: String,
         display_uuid: Option<String>,
     },
+    /// A workspace became the active one on its space. Unlike
+    /// `WorkspaceChanged`, this is not sent when workspaces are created,
+    /// reordered or relaid out.
+    WorkspaceActivated {
+        space_id: SpaceId,
+        display_uuid: Option<String>,
+        workspace_id: VirtualWorkspaceId,
+        workspace_index: Option<u64>,
+        workspace_name: String,
+    },
     WindowsChanged {
         workspace_id: VirtualWorkspaceId,
         workspace_name: String,
@@ -40,6 +51,11 @@ pub enum BroadcastEvent {
         space_id: SpaceId,
         display_uuid: Option<String>,
     },
+    WindowFullscreenChanged {
+        window_id: WindowId,
+        space_id: SpaceId,
+        fullscreen: bool,
+    },
     StacksChanged {
         workspace_id: VirtualWorkspaceId,
         workspace_index: Option<u64>,
@@ -49,6 +65,30 @@ pub enum BroadcastEvent {
         space_id: SpaceId,
         display_uuid: Option<String>,
     },
+    DragStarted {
+        window_id: WindowId,
+    },
+    /// Sent only when the swap target changes; `None` means the dragged window
+    /// no longer overlaps a swap candidate.
+    DragSwapCandidate {
+        window_id: WindowId,
+        target: Option<WindowId>,
+    },
+    DragEnded {
+        window_id: WindowId,
+        swapped: bool,
+    },
+    /// Layout updates were frozen or resumed by `toggle_layout_freeze`.
+    LayoutFreezeChanged {
+        frozen: bool,
+    },
+    /// The active layout mode of a space changed, by command or because a
+    /// workspace with another mode became active.
+    LayoutModeChanged {
+        space_id: SpaceId,
+        display_uuid: Option<String>,
+        mode: LayoutMode,
+    },
 }
 
 pub type BroadcastSender = crate::actor::Sender<BroadcastEvent>;
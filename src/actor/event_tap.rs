@@ -21,7 +21,7 @@ use super::stack_line;
 use crate::actor;
 use crate::actor::wm_controller::{self, WmCommand, WmEvent};
 use crate::common::collections::{HashMap, HashSet};
-use crate::common::config::{Config, HapticPattern, LayoutMode};
+use crate::common::config::{Config, Gesture, HapticPattern, LayoutMode, ScrollSource};
 use crate::common::log::trace_misc;
 use crate::layout_engine::LayoutCommand as LC;
 use crate::sys::event::{self, Hotkey, KeyCode, MouseState, set_mouse_state};
@@ -150,6 +150,7 @@ struct SwipeConfig {
     distance_pct: f64,
     haptics_enabled: bool,
     haptic_pattern: HapticPattern,
+    bound_gestures: HashSet<Gesture>,
 }
 
 impl SwipeConfig {
@@ -171,6 +172,7 @@ impl SwipeConfig {
             distance_pct: g.distance_pct.clamp(0.01, 1.0),
             haptics_enabled: g.haptics_enabled,
             haptic_pattern: g.haptic_pattern,
+            bound_gestures: g.bindings.keys().copied().collect(),
         }
     }
 }
@@ -206,7 +208,7 @@ struct SwipeHandler {
 #[derive(Debug, Clone)]
 struct ScrollConfig {
     enabled: bool,
-    invert_horizontal: bool,
+    mouse_wheel: bool,
     vertical_tolerance: f64,
     fingers: usize,
     distance_pct: f64,
@@ -224,7 +226,7 @@ impl ScrollConfig {
         };
         ScrollConfig {
             enabled: g.enabled,
-            invert_horizontal: g.invert_horizontal,
+            mouse_wheel: g.mouse_wheel_scrolls_strip,
             vertical_tolerance: vt_norm,
             fingers: g.fingers.max(1),
             distance_pct: g.distance_pct.clamp(0.01, 1.0),
@@ -325,6 +327,7 @@ impl EventTap {
     fn desired_event_mask(&self) -> CGEventMask {
         build_event_mask(
             self.gesture_handlers_enabled(),
+            self.scroll.borrow().as_ref().is_some_and(|handler| handler.cfg.mouse_wheel),
             self.keyboard_handlers_enabled(),
             self.mouse_move_handlers_enabled(),
         )
@@ -396,6 +399,7 @@ impl EventTap {
             .unwrap_or(false);
         let event_mask = build_event_mask(
             swipe.is_some() || scroll.is_some(),
+            scroll.as_ref().is_some_and(|handler| handler.cfg.mouse_wheel),
             disable_hotkey.is_some(),
             state.event_processing_enabled
                 && ((state.stack_line_enabled && stack_line_tx.is_some())
@@ -624,6 +628,18 @@ impl EventTap {
             return true;
         }
 
+        if event_type == CGEventType::ScrollWheel {
+            if let Some(handler) = self.scroll.borrow().as_ref().filter(|h| h.cfg.mouse_wheel) {
+                let state = self.state.borrow();
+                let cursor = CGEvent::location(Some(event));
+                let mode = state.layout_mode_at_point(cursor).unwrap_or(state.default_layout_mode);
+                if matches!(mode, LayoutMode::Scrolling) {
+                    self.handle_scroll_wheel_event(handler, event);
+                }
+            }
+            return true;
+        }
+
         let mut state = self.state.borrow_mut();
 
         if !matches!(
@@ -733,10 +749,13 @@ impl EventTap {
                     && state.focus_follows_mouse_enabled
                     && !state.disable_hotkey_active
                 {
+                    let was_above = state.above_window.0;
                     if let Some(wsid) =
                         state.track_mouse_move(loc, window_from_mouse_event(event), ts)
                     {
                         _ = self.events_tx.send(Event::MouseMovedOverWindow(wsid));
+                    } else if was_above.is_some() && state.above_window.0.is_none() {
+                        _ = self.events_tx.send(Event::MouseHoverCleared);
                     }
                 }
             }
@@ -818,22 +837,47 @@ impl EventTap {
                 let horizontal = dx.abs();
                 let vertical = dy.abs();
 
-                if horizontal >= cfg.distance_pct && vertical <= cfg.vertical_tolerance {
-                    let mut dir_left = dx < 0.0;
-                    if cfg.invert_horizontal {
-                        dir_left = !dir_left;
-                    }
-                    let cmd = if dir_left {
-                        LC::NextWorkspace(cfg.skip_empty_workspaces)
+                let gesture =
+                    if horizontal >= cfg.distance_pct && vertical <= cfg.vertical_tolerance {
+                        let mut dir_left = dx < 0.0;
+                        if cfg.invert_horizontal {
+                            dir_left = !dir_left;
+                        }
+                        Some(if dir_left {
+                            Gesture::SwipeLeft
+                        } else {
+                            Gesture::SwipeRight
+                        })
+                    } else if vertical >= cfg.distance_pct && horizontal <= cfg.vertical_tolerance {
+                        // Normalized touch positions grow upwards.
+                        Some(if dy > 0.0 {
+                            Gesture::SwipeUp
+                        } else {
+                            Gesture::SwipeDown
+                        })
                     } else {
-                        LC::PrevWorkspace(cfg.skip_empty_workspaces)
+                        None
                     };
 
+                let cmd = match gesture {
+                    Some(gesture) if cfg.bound_gestures.contains(&gesture) => {
+                        Some(reactor::ReactorCommand::Gesture(gesture))
+                    }
+                    Some(gesture @ (Gesture::SwipeLeft | Gesture::SwipeRight)) => {
+                        Some(reactor::ReactorCommand::GestureSwitchWorkspace {
+                            next: gesture == Gesture::SwipeLeft,
+                            skip_empty: cfg.skip_empty_workspaces,
+                        })
+                    }
+                    _ => None,
+                };
+
+                if let Some(cmd) = cmd {
                     if cfg.haptics_enabled {
                         let _ = haptics::perform_haptic(cfg.haptic_pattern);
                     }
                     wm_sender.send(WmEvent::Command(WmCommand::ReactorCommand(
-                        reactor::Command::Layout(cmd),
+                        reactor::Command::Reactor(cmd),
                     )));
                     st.phase = GesturePhase::Committed;
                 }
@@ -846,6 +890,30 @@ impl EventTap {
         }
     }
 
+    /// Scroll the strip with a mouse's horizontal wheel. Trackpad scrolls are
+    /// left to the touch handler, so they aren't applied twice.
+    fn handle_scroll_wheel_event(&self, handler: &ScrollHandler, event: &CGEvent) {
+        if scroll_wheel_source(event) != ScrollSource::MouseWheel {
+            return;
+        }
+        let Some(wm_sender) = self.wm_sender.as_ref() else {
+            return;
+        };
+        let notches =
+            CGEvent::integer_value_field(Some(event), CGEventField::ScrollWheelEventDeltaAxis2);
+        if notches == 0 {
+            return;
+        }
+        // A notch moves the strip as far as one trackpad scroll step.
+        let cmd = LC::ScrollStrip {
+            delta: -(notches as f64) * handler.cfg.distance_pct,
+            source: Some(ScrollSource::MouseWheel),
+        };
+        wm_sender.send(WmEvent::Command(WmCommand::ReactorCommand(
+            reactor::Command::Layout(cmd),
+        )));
+    }
+
     fn handle_scroll_gesture_event(&self, handler: &ScrollHandler, nsevent: &NSEvent) {
         let cfg = &handler.cfg;
         let state = &handler.state;
@@ -956,12 +1024,10 @@ impl EventTap {
                 st.accum_dx += dx;
                 let step = cfg.distance_pct;
                 if st.accum_dx.abs() >= step {
-                    let delta = if cfg.invert_horizontal {
-                        -st.accum_dx
-                    } else {
-                        st.accum_dx
+                    let cmd = LC::ScrollStrip {
+                        delta: st.accum_dx,
+                        source: Some(ScrollSource::Trackpad),
                     };
-                    let cmd = LC::ScrollStrip { delta };
 
                     wm_sender.send(WmEvent::Command(WmCommand::ReactorCommand(
                         reactor::Command::Layout(cmd),
@@ -987,12 +1053,10 @@ impl EventTap {
                     st.accum_dx += dx;
                     let step = cfg.distance_pct;
                     if st.accum_dx.abs() >= step {
-                        let delta = if cfg.invert_horizontal {
-                            -st.accum_dx
-                        } else {
-                            st.accum_dx
+                        let cmd = LC::ScrollStrip {
+                            delta: st.accum_dx,
+                            source: Some(ScrollSource::Trackpad),
                         };
-                        let cmd = LC::ScrollStrip { delta };
 
                         wm_sender.send(WmEvent::Command(WmCommand::ReactorCommand(
                             reactor::Command::Layout(cmd),
@@ -1337,6 +1401,7 @@ fn touch_normalized_position(touch: &objc2_app_kit::NSTouch) -> Option<(f64, f64
 
 fn build_event_mask(
     gestures_enabled: bool,
+    scroll_wheel_enabled: bool,
     keyboard_enabled: bool,
     mouse_move_enabled: bool,
 ) -> CGEventMask {
@@ -1369,9 +1434,26 @@ fn build_event_mask(
         // NSEventType::Gesture is an NSEventType — it maps via .0
         *&mut m |= 1u64 << (NSEventType::Gesture.0 as u64);
     }
+    if scroll_wheel_enabled {
+        add(&mut m, CGEventType::ScrollWheel);
+    }
     m
 }
 
+/// Trackpad scrolls are continuous, and their momentum keeps coming after the
+/// fingers lift; anything else is a mouse wheel.
+fn scroll_wheel_source(event: &CGEvent) -> ScrollSource {
+    let continuous =
+        CGEvent::integer_value_field(Some(event), CGEventField::ScrollWheelEventIsContinuous);
+    let momentum =
+        CGEvent::integer_value_field(Some(event), CGEventField::ScrollWheelEventMomentumPhase);
+    if continuous != 0 || momentum != 0 {
+        ScrollSource::Trackpad
+    } else {
+        ScrollSource::MouseWheel
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
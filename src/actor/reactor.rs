@@ -9,6 +9,7 @@ mod display_topology;
 mod events;
 mod main_window;
 mod managers;
+mod mirror;
 mod query;
 mod replay;
 pub mod transaction_manager;
@@ -20,8 +21,9 @@ mod testing;
 #[cfg(test)]
 mod tests;
 
+use std::collections::VecDeque;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use events::app::AppEventHandler;
 use events::command::CommandEventHandler;
@@ -31,6 +33,7 @@ use events::system::SystemEventHandler;
 use events::window::WindowEventHandler;
 use main_window::MainWindowTracker;
 use managers::LayoutManager;
+use objc2_app_kit::{NSFloatingWindowLevel, NSNormalWindowLevel};
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 pub use replay::{Record, replay};
 use serde::{Deserialize, Serialize};
@@ -45,16 +48,20 @@ use crate::actor::raise_manager::{self, RaiseManager, RaiseRequest};
 use crate::actor::reactor::events::window_discovery::WindowDiscoveryHandler;
 use crate::actor::{self, menu_bar, stack_line};
 use crate::common::collections::{BTreeMap, HashMap, HashSet};
-use crate::common::config::Config;
+use crate::common::config::{
+    Config, DisplayCycleOrder, EmptyWorkspaceFocus, GapSettings, Gesture, MouseFollowsFocusTarget,
+    MultiSpaceWindowPolicy, PhysicalDisplayOrder, UntitledWindowPolicy,
+};
 use crate::layout_engine::{self as layout, Direction, LayoutEngine, LayoutEvent};
 use crate::model::space_activation::{SpaceActivationConfig, SpaceActivationPolicy};
 use crate::model::tx_store::WindowTxStore;
-use crate::model::virtual_workspace::AppRuleResult;
+use crate::model::virtual_workspace::{AppRuleResult, VirtualWorkspaceId};
 use crate::sys::event::MouseState;
-use crate::sys::executor::Executor;
+use crate::sys::executor::{self, Executor};
 use crate::sys::geometry::{CGRectDef, CGRectExt};
 pub use crate::sys::screen::ScreenInfo;
 use crate::sys::screen::{SpaceId, get_active_space_number, order_visible_spaces_by_position};
+use crate::sys::timer::Timer;
 use crate::sys::window_server::{
     self, WindowServerId, WindowServerInfo, current_cursor_location, space_is_fullscreen,
     wait_for_native_fullscreen_transition, window_level, window_sub_level,
@@ -69,7 +76,7 @@ pub(crate) use crate::model::reactor::{
     WindowState,
 };
 pub use crate::model::reactor::{
-    Command, DisplaySelector, DragSession, DragState, MenuState, MissionControlState,
+    Command, DisplayCycle, DisplaySelector, DragSession, DragState, MenuState, MissionControlState,
     ReactorCommand, RefocusState, Requested, StaleCleanupState, WorkspaceSwitchOrigin,
     WorkspaceSwitchState,
 };
@@ -103,7 +110,7 @@ impl std::ops::Deref for ReactorHandle {
 
 use display_topology::{DisplaySnapshot, DisplayTopologyManager, WindowSnapshot};
 
-use crate::model::server::WindowData;
+use crate::model::server::{LayoutErrorData, WindowData, WorkspaceSwitchData};
 
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug)]
@@ -194,6 +201,11 @@ pub enum Event {
     /// The mouse cursor moved over a new window. Only sent if focus-follows-
     /// mouse is enabled.
     MouseMovedOverWindow(WindowServerId),
+    /// The cursor rested on a window for `focus_follows_mouse_delay_ms`.
+    MouseHoverSettled(WindowServerId),
+    /// The cursor left the tracked window for somewhere focus-follows-mouse
+    /// ignores, such as the desktop or a popup.
+    MouseHoverCleared,
     /// System woke from sleep; used to re-subscribe SLS notifications.
     SystemWoke,
 
@@ -201,6 +213,14 @@ pub enum Event {
     DisplayChurnBegin,
     #[serde(skip)]
     DisplayChurnEnd,
+    /// The coalescing window after a display churn elapsed; the pending
+    /// topology commit may now be reconciled.
+    #[serde(skip)]
+    DisplayChurnSettled,
+    /// A deferred space change reached `pending_space_change_max_age_ms`
+    /// without a matching vector arriving.
+    #[serde(skip)]
+    PendingSpaceChangeExpired,
 
     #[serde(skip)]
     MissionControlNativeEntered,
@@ -254,6 +274,8 @@ pub struct Reactor {
     pending_space_change_manager: managers::PendingSpaceChangeManager,
     active_spaces: HashSet<SpaceId>,
     display_topology_manager: DisplayTopologyManager,
+    mirror_manager: mirror::MirrorManager,
+    event_metrics: managers::EventMetrics,
 }
 
 impl Reactor {
@@ -311,12 +333,21 @@ impl Reactor {
             config: config.clone(),
             one_space,
             app_manager: managers::AppManager::new(),
-            layout_manager: managers::LayoutManager { layout_engine },
+            layout_manager: managers::LayoutManager {
+                layout_engine,
+                last_error: None,
+                gapless_spaces: HashSet::default(),
+                frozen: false,
+            },
             window_manager: managers::WindowManager {
                 windows: HashMap::default(),
                 window_ids: HashMap::default(),
                 visible_windows: HashSet::default(),
                 observed_window_server_ids: HashSet::default(),
+                dimmed_windows: HashMap::default(),
+                always_on_top: HashSet::default(),
+                manually_unmanaged: HashSet::default(),
+                unfocused_new_windows: HashMap::default(),
             },
             window_server_info_manager: managers::WindowServerInfoManager {
                 window_server_info: HashMap::default(),
@@ -341,6 +372,8 @@ impl Reactor {
                 active_workspace_switch: None,
                 pending_workspace_switch_origin: None,
                 pending_workspace_mouse_warp: None,
+                pending_new_windows: Vec::new(),
+                switch_history: VecDeque::new(),
             },
             recording_manager: managers::RecordingManager { record },
             communication_manager: managers::CommunicationManager {
@@ -367,7 +400,9 @@ impl Reactor {
             },
             refocus_manager: managers::RefocusManager {
                 stale_cleanup_state: StaleCleanupState::Enabled,
+                stale_cleanup_enabled: true,
                 refocus_state: RefocusState::None,
+                pending_mouse_focus: None,
             },
             pending_space_change_manager: managers::PendingSpaceChangeManager {
                 pending_space_change: None,
@@ -375,6 +410,8 @@ impl Reactor {
             },
             active_spaces: HashSet::default(),
             display_topology_manager: DisplayTopologyManager::default(),
+            mirror_manager: mirror::MirrorManager::default(),
+            event_metrics: managers::EventMetrics::default(),
         }
     }
 
@@ -450,8 +487,12 @@ impl Reactor {
     fn recompute_and_set_active_spaces(&mut self, spaces: &[Option<SpaceId>]) {
         let cfg = self.activation_cfg();
         let display_uuids = self.display_uuids_for_current_screens();
-        let active_spaces =
-            self.space_activation_policy.compute_active_spaces(cfg, spaces, &display_uuids);
+        let active_spaces = self.space_activation_policy.compute_active_spaces(
+            cfg,
+            &self.config.settings.disabled_displays,
+            spaces,
+            &display_uuids,
+        );
         let previous_active = self.active_spaces.clone();
         self.set_active_spaces(&active_spaces);
         self.handle_active_space_change(previous_active);
@@ -552,6 +593,10 @@ impl Reactor {
     }
 
     fn maybe_commit_display_topology_snapshot(&mut self) {
+        if self.display_topology_manager.commit_hold_remaining().is_some() {
+            // Still inside the coalescing window; DisplayChurnSettled retries.
+            return;
+        }
         let Some((epoch, started_at, flags, pre_known_wsids)) =
             self.display_topology_manager.take_awaiting_commit()
         else {
@@ -722,19 +767,19 @@ impl Reactor {
         }
     }
 
+    // A pending swap still belongs to the ongoing drag, so its session counts
+    // as active; otherwise the next frame change would restart the drag.
     fn get_active_drag_session(&self) -> Option<&DragSession> {
-        if let DragState::Active { session } = &self.drag_manager.drag_state {
-            Some(session)
-        } else {
-            None
+        match &self.drag_manager.drag_state {
+            DragState::Active { session } | DragState::PendingSwap { session, .. } => Some(session),
+            _ => None,
         }
     }
 
     fn get_active_drag_session_mut(&mut self) -> Option<&mut DragSession> {
-        if let DragState::Active { session } = &mut self.drag_manager.drag_state {
-            Some(session)
-        } else {
-            None
+        match &mut self.drag_manager.drag_state {
+            DragState::Active { session } | DragState::PendingSwap { session, .. } => Some(session),
+            _ => None,
         }
     }
 
@@ -758,26 +803,168 @@ impl Reactor {
     async fn run_reactor_loop(mut reactor: Reactor, mut events: Receiver) {
         const MAX_EVENT_BATCH: usize = 64;
 
-        while let Some((span, event)) = events.recv().await {
-            let _guard = span.enter();
-            reactor.handle_loop_event(event);
-            // Drain a bounded batch to reduce recv/select overhead.
-            for _ in 1..MAX_EVENT_BATCH {
-                let Ok((span, event)) = events.try_recv() else {
-                    break;
+        // An event read past the end of a run of coalesced frame changes; it is
+        // handled before anything else is taken from the queue.
+        let mut carried = None;
+        let mut hover_timer = Timer::manual();
+        let mut space_change_timer = Timer::manual();
+        let mut churn_settle_timer = Timer::manual();
+        loop {
+            let (span, event) = match carried.take() {
+                Some(next) => next,
+                None => {
+                    hover_timer.set_next_fire(reactor.mouse_hover_dwell_remaining());
+                    space_change_timer.set_next_fire(reactor.pending_space_change_expiry());
+                    churn_settle_timer.set_next_fire(
+                        reactor
+                            .display_topology_manager
+                            .commit_hold_expiry()
+                            .unwrap_or(Duration::MAX),
+                    );
+                    tokio::select! {
+                        next = events.recv() => match next {
+                            Some(next) => next,
+                            None => break,
+                        },
+                        _ = hover_timer.next() => {
+                            let Some((wsid, _)) = reactor.refocus_manager.pending_mouse_focus
+                            else {
+                                continue;
+                            };
+                            // The cursor may have left for a spot the event tap
+                            // doesn't report (e.g. a popup), so check it is still there.
+                            let under_cursor = current_cursor_location()
+                                .ok()
+                                .and_then(window_server::get_window_at_point);
+                            if under_cursor != Some(wsid) {
+                                trace!(?wsid, ?under_cursor, "Dropping hover focus; cursor moved away");
+                                reactor.refocus_manager.pending_mouse_focus = None;
+                                continue;
+                            }
+                            (tracing::Span::current(), Event::MouseHoverSettled(wsid))
+                        }
+                        _ = space_change_timer.next() => {
+                            (tracing::Span::current(), Event::PendingSpaceChangeExpired)
+                        }
+                        _ = churn_settle_timer.next() => {
+                            (tracing::Span::current(), Event::DisplayChurnSettled)
+                        }
+                    }
+                }
+            };
+            let batch_start = Instant::now();
+            let budget = Duration::from_millis(reactor.config.settings.event_batch_budget_ms);
+            let coalesce = Duration::from_millis(reactor.config.settings.frame_change_coalesce_ms);
+            let (span, event) =
+                Self::coalesce_frame_changes(span, event, &mut events, &mut carried, coalesce);
+            let mut flush = Self::ends_event_batch(&event);
+            reactor.event_metrics.record_queue_depth(events.len());
+            {
+                let _guard = span.enter();
+                reactor.handle_loop_event(event);
+            }
+            // Drain a bounded batch to reduce recv/select overhead, but stop early
+            // after a latency-sensitive event or once the time budget is spent.
+            let mut drained = 1;
+            while drained < MAX_EVENT_BATCH && !flush && batch_start.elapsed() < budget {
+                let (span, event) = match carried.take() {
+                    Some(next) => next,
+                    None => match events.try_recv() {
+                        Ok(next) => next,
+                        Err(_) => break,
+                    },
                 };
+                let (span, event) =
+                    Self::coalesce_frame_changes(span, event, &mut events, &mut carried, coalesce);
+                flush = Self::ends_event_batch(&event);
+                reactor.event_metrics.record_queue_depth(events.len());
                 let _guard = span.enter();
                 reactor.handle_loop_event(event);
+                drained += 1;
+            }
+            if flush || drained == MAX_EVENT_BATCH || batch_start.elapsed() >= budget {
+                // Let the other tasks on this thread (e.g. the raise manager) run
+                // before handling whatever is still queued.
+                executor::yield_now().await;
             }
         }
     }
 
+    /// Replaces a frame change with any later ones for the same window that are
+    /// already queued, so a burst from a live resize reaches the layout engine
+    /// as its final frame. Stops at the first event that can't be folded in
+    /// (leaving it in `carried`) or once `interval` has passed.
+    fn coalesce_frame_changes(
+        span: tracing::Span,
+        event: Event,
+        events: &mut Receiver,
+        carried: &mut Option<(tracing::Span, Event)>,
+        interval: Duration,
+    ) -> (tracing::Span, Event) {
+        if interval.is_zero() || !matches!(event, Event::WindowFrameChanged(..)) {
+            return (span, event);
+        }
+        let start = Instant::now();
+        let (mut span, mut event) = (span, event);
+        while start.elapsed() < interval {
+            let Ok((next_span, next)) = events.try_recv() else {
+                break;
+            };
+            if !Self::supersedes_frame_change(&event, &next) {
+                *carried = Some((next_span, next));
+                break;
+            }
+            trace!(?event, "coalesced frame change");
+            (span, event) = (next_span, next);
+        }
+        (span, event)
+    }
+
+    /// Whether `next` can stand in for `prev`: both are frame changes of the same
+    /// window carrying the same transaction, origin and mouse state, so only the
+    /// frame differs.
+    fn supersedes_frame_change(prev: &Event, next: &Event) -> bool {
+        match (prev, next) {
+            (
+                Event::WindowFrameChanged(wid, _, last_seen, requested, mouse_state),
+                Event::WindowFrameChanged(
+                    next_wid,
+                    _,
+                    next_last_seen,
+                    next_requested,
+                    next_mouse_state,
+                ),
+            ) => {
+                wid == next_wid
+                    && last_seen == next_last_seen
+                    && requested.0 == next_requested.0
+                    && mouse_state == next_mouse_state
+            }
+            _ => false,
+        }
+    }
+
+    /// Events after which a batch is cut short so their effects aren't held up
+    /// behind the rest of the queue.
+    fn ends_event_batch(event: &Event) -> bool {
+        matches!(
+            event,
+            Event::MouseUp
+                | Event::ApplicationActivated(..)
+                | Event::ApplicationGloballyActivated(..)
+                | Event::ApplicationMainWindowChanged(..)
+                | Event::Command(..)
+        )
+    }
+
     fn handle_loop_event(&mut self, event: Event) {
         if let Event::Query(req) = event {
             self.handle_query_request(req);
             return;
         }
+        self.event_metrics.record_processed();
         if self.maybe_quarantine_during_churn(&event) {
+            self.event_metrics.record_quarantined();
             Self::note_windowserver_activity(&event);
             trace!(?event, "quarantined event during display churn");
             return;
@@ -794,6 +981,7 @@ impl Reactor {
             Event::WindowMinimized(wid) => Some(wid.idx.get()),
             Event::WindowDeminiaturized(wid) => Some(wid.idx.get()),
             Event::MouseMovedOverWindow(wsid) => Some(wsid.as_u32()),
+            Event::MouseHoverSettled(wsid) => Some(wsid.as_u32()),
             Event::ResyncAppForWindow(wsid) => Some(wsid.as_u32()),
             Event::WindowServerDestroyed(wsid, _) => Some(wsid.as_u32()),
             Event::WindowServerAppeared(wsid, _) => Some(wsid.as_u32()),
@@ -832,6 +1020,8 @@ impl Reactor {
             event,
             Event::DisplayChurnBegin
                 | Event::DisplayChurnEnd
+                | Event::DisplayChurnSettled
+                | Event::PendingSpaceChangeExpired
                 | Event::ScreenParametersChanged(..)
                 | Event::SpaceChanged(..)
                 | Event::SpaceCreated(..)
@@ -908,12 +1098,21 @@ impl Reactor {
                 self.display_topology_manager.begin_churn(epoch, flags, pre_known_wsids);
                 return;
             }
+            Event::DisplayChurnSettled => {
+                self.maybe_commit_display_topology_snapshot();
+                return;
+            }
             Event::DisplayChurnEnd => {
                 let (epoch, _, flags) = self.display_topology_manager.current_churn().unwrap_or((
                     crate::sys::display_churn::epoch(),
                     std::time::Instant::now(),
                     crate::sys::display_churn::flags(),
                 ));
+                let coalesce_ms = self.config.settings.display_churn_coalesce_ms;
+                self.display_topology_manager
+                    .set_coalesce_window(Duration::from_millis(coalesce_ms));
+                // While the commit is held, the reactor loop's timer sends
+                // DisplayChurnSettled once the hold elapses.
                 self.display_topology_manager.end_churn_to_awaiting(epoch, flags);
                 return;
             }
@@ -921,6 +1120,7 @@ impl Reactor {
         }
 
         if self.maybe_quarantine_during_churn(&event) {
+            self.event_metrics.record_quarantined();
             trace!(?event, "quarantined event during display churn");
             return;
         }
@@ -930,6 +1130,7 @@ impl Reactor {
         let raised_window = self.main_window_tracker.handle_event(&event);
         let mut is_resize = false;
         let mut window_was_destroyed = false;
+        let mut destroyed_window_space = None;
 
         match event {
             Event::ApplicationLaunched {
@@ -1001,6 +1202,7 @@ impl Reactor {
                 WindowEventHandler::handle_window_created(self, wid, window, ws_info, mouse_state);
             }
             Event::WindowDestroyed(wid) => {
+                destroyed_window_space = self.best_space_for_window_id(wid);
                 window_was_destroyed = WindowEventHandler::handle_window_destroyed(self, wid);
             }
             Event::WindowServerDestroyed(wsid, sid) => {
@@ -1048,6 +1250,17 @@ impl Reactor {
             Event::MouseMovedOverWindow(wsid) => {
                 WindowEventHandler::handle_mouse_moved_over_window(self, wsid);
             }
+            Event::MouseHoverSettled(wsid) => {
+                WindowEventHandler::handle_mouse_hover_settled(self, wsid);
+            }
+            Event::MouseHoverCleared => {
+                self.refocus_manager.pending_mouse_focus = None;
+            }
+            Event::PendingSpaceChangeExpired => {
+                if !self.is_mission_control_active() {
+                    self.try_apply_pending_space_change();
+                }
+            }
             Event::SystemWoke => SystemEventHandler::handle_system_woke(self),
             Event::MissionControlNativeEntered => {
                 SpaceEventHandler::handle_mission_control_native_entered(self);
@@ -1074,6 +1287,7 @@ impl Reactor {
             raised_window,
             is_resize,
             window_was_destroyed,
+            destroyed_window_space,
             should_update_notifications,
         );
     }
@@ -1083,13 +1297,16 @@ impl Reactor {
         raised_window: Option<WindowId>,
         is_resize: bool,
         window_was_destroyed: bool,
+        destroyed_window_space: Option<SpaceId>,
         should_update_notifications: bool,
     ) {
         if self.display_topology_manager.is_churning_or_awaiting_commit() {
             return;
         }
 
-        if let Some(raised_window) = raised_window {
+        if let Some(raised_window) = raised_window
+            && !self.suppress_new_window_focus(raised_window)
+        {
             if let Some(space) = self.best_space_for_window_id(raised_window) {
                 self.send_layout_event(LayoutEvent::WindowFocused(space, raised_window));
             }
@@ -1113,12 +1330,38 @@ impl Reactor {
             trace!("Workspace switch stabilized with no further frame changes");
         }
 
+        // Once the switch has settled, windows held back while it was in
+        // flight can be assigned to the workspace that ended up active.
+        if self.workspace_switch_manager.active_workspace_switch.is_none()
+            && !self.workspace_switch_manager.pending_new_windows.is_empty()
+        {
+            for wid in std::mem::take(&mut self.workspace_switch_manager.pending_new_windows) {
+                WindowEventHandler::place_new_window(self, wid);
+            }
+            if !self.is_in_drag() {
+                self.update_layout_or_warn(false, false);
+            }
+        }
+
+        if window_was_destroyed
+            && self.config.settings.keep_focus_on_screen_after_close
+            && let Some(space) = destroyed_window_space
+            && self.active_workspace_is_empty(space)
+        {
+            // The last window of the workspace is gone. Stay on its screen
+            // rather than following whatever macOS activates next.
+            self.layout_manager.layout_engine.clear_focused_window();
+            if self.config.settings.mouse_follows_focus {
+                self.warp_mouse_to_space_center(space);
+            }
+        }
+
         // Execute deferred mouse warp after workspace switch completes
         if let Some(wid) = self.workspace_switch_manager.pending_workspace_mouse_warp.take() {
-            if let Some(window_center) = self.window_center_on_known_screen(wid)
+            if let Some(warp_point) = self.window_warp_point_on_known_screen(wid)
                 && let Some(event_tap_tx) = self.communication_manager.event_tap_tx.as_ref()
             {
-                event_tap_tx.send(crate::actor::event_tap::Request::Warp(window_center));
+                event_tap_tx.send(crate::actor::event_tap::Request::Warp(warp_point));
             }
         }
 
@@ -1133,9 +1376,122 @@ impl Reactor {
                 self.notification_manager.last_sls_notification_ids = ids;
             }
         }
+        self.update_inactive_dim();
+        self.update_always_on_top(raised_window.is_some() || layout_changed);
         self.update_event_tap_layout_mode();
     }
 
+    /// Place the placeholders mirroring `source` at its relative position and
+    /// size on their displays.
+    fn sync_mirrors(&mut self, source: WindowId) {
+        let Some(frame) = self.window_manager.windows.get(&source).map(|w| w.frame_monotonic)
+        else {
+            return;
+        };
+        let Some(from) = self
+            .best_space_for_window_id(source)
+            .and_then(|space| self.space_manager.screen_by_space(space))
+            .map(|screen| screen.frame)
+        else {
+            return;
+        };
+        let placements: Vec<_> = self
+            .mirror_manager
+            .links_from(source)
+            .filter_map(|link| {
+                let screen = self
+                    .space_manager
+                    .screens
+                    .iter()
+                    .find(|screen| screen.display_uuid == link.target_display_uuid)?;
+                let space = screen.space.filter(|space| self.is_space_active(*space))?;
+                Some((
+                    space,
+                    link.placeholder,
+                    mirror::mirror_frame(frame, from, screen.frame),
+                ))
+            })
+            .collect();
+        if placements.is_empty() {
+            return;
+        }
+        for (space, placeholder, rect) in placements {
+            self.layout_manager
+                .layout_engine
+                .place_floating_window(space, placeholder, rect);
+        }
+        let _ = self.update_layout_or_warn(false, false);
+    }
+
+    /// Drop always-on-top windows that closed or stopped floating. When
+    /// `reassert` is set (after a raise or a layout change, which is when apps
+    /// tend to reset it) the raised level is applied again.
+    fn update_always_on_top(&mut self, reassert: bool) {
+        if self.window_manager.always_on_top.is_empty() {
+            return;
+        }
+        let mut pinned = std::mem::take(&mut self.window_manager.always_on_top);
+        pinned.retain(|&wid| {
+            let Some(window) = self.window_manager.windows.get(&wid) else {
+                return false;
+            };
+            let floating = self.layout_manager.layout_engine.is_window_floating(wid);
+            let Some(wsid) = window.info.sys_id else {
+                return floating;
+            };
+            if !floating {
+                window_server::set_window_level(wsid, NSNormalWindowLevel);
+                return false;
+            }
+            if reassert && !window_server::set_window_level(wsid, NSFloatingWindowLevel) {
+                warn!(?wid, "Failed to re-assert always-on-top window level");
+            }
+            true
+        });
+        self.window_manager.always_on_top = pinned;
+    }
+
+    /// Dim every tiled window on the active spaces except the focused one, and
+    /// restore windows that should no longer be dimmed.
+    fn update_inactive_dim(&mut self) {
+        let alpha = self.config.settings.inactive_dim;
+        let mut targets: HashSet<WindowServerId> = HashSet::default();
+        if alpha < 1.0 {
+            let focused = self.main_window();
+            let engine = &self.layout_manager.layout_engine;
+            for space in self.iter_active_spaces() {
+                for wid in engine.dimmable_windows_in_active_workspace(space, focused) {
+                    if let Some(wsid) =
+                        self.window_manager.windows.get(&wid).and_then(|w| w.info.sys_id)
+                    {
+                        targets.insert(wsid);
+                    }
+                }
+            }
+        }
+
+        let previous = std::mem::take(&mut self.window_manager.dimmed_windows);
+        for (wsid, _) in previous.iter().filter(|(wsid, _)| !targets.contains(wsid)) {
+            // A window that went away can't be restored; nothing left to do.
+            if !window_server::set_window_alpha(*wsid, 1.0) {
+                debug!(?wsid, "Failed to restore window alpha");
+            }
+        }
+        for wsid in targets {
+            if previous.get(&wsid) != Some(&alpha)
+                && !window_server::set_window_alpha(wsid, alpha as f32)
+            {
+                // Keep whatever alpha it had so it is still restored later.
+                debug!(?wsid, "Failed to dim window");
+                if let Some(&old) = previous.get(&wsid) {
+                    self.window_manager.dimmed_windows.insert(wsid, old);
+                }
+                continue;
+            }
+            self.window_manager.dimmed_windows.insert(wsid, alpha);
+        }
+    }
+
     fn create_window_data(&self, window_id: WindowId) -> Option<WindowData> {
         let window_state = self.window_manager.windows.get(&window_id)?;
         if !window_state.matches_filter(WindowFilter::EffectivelyManageable) {
@@ -1195,6 +1551,8 @@ impl Reactor {
                     is_ax_standard,
                     is_ax_root,
                     &self.window_server_info_manager.window_server_info,
+                    self.config.settings.missing_window_server_id,
+                    self.config.settings.multi_space_windows,
                 );
                 if let Some(window) = self.window_manager.windows.get_mut(&wid) {
                     window.is_manageable = manageable;
@@ -1255,7 +1613,14 @@ impl Reactor {
                 wait_for_native_fullscreen_transition();
                 thread::sleep(Duration::from_millis(50));
 
+                let mut exited_fullscreen = HashSet::default();
                 for window in track.windows {
+                    if let Some(window_id) = window.window_id
+                        && exited_fullscreen.insert(window_id)
+                    {
+                        let user_space = window.last_known_user_space.unwrap_or(space);
+                        self.broadcast_window_fullscreen_changed(window_id, user_space, false);
+                    }
                     if let Some(app) = self.app_manager.apps.get(&window.pid) {
                         if let Err(e) = app.handle.send(Request::GetVisibleWindows) {
                             warn!("Failed to send GetVisibleWindows to app {}: {}", window.pid, e);
@@ -1302,6 +1667,10 @@ impl Reactor {
         for (space, screen) in spaces.iter().copied().zip(&mut self.space_manager.screens) {
             screen.space = space;
         }
+        let screens = &self.space_manager.screens;
+        self.layout_manager
+            .gapless_spaces
+            .retain(|space| screens.iter().any(|screen| screen.space == Some(*space)));
     }
 
     fn reconcile_spaces_with_display_history(
@@ -1424,8 +1793,63 @@ impl Reactor {
         }
     }
 
-    fn maybe_reapply_app_rules_for_window(&mut self, window_id: WindowId) {
-        if !self.config.virtual_workspaces.reapply_app_rules_on_title_change {
+    /// Re-emit the current workspace, window list and window titles of every
+    /// active space to broadcast subscribers. Titles are sent as
+    /// `WindowTitleChanged` with the same previous and new title. Returns the
+    /// number of spaces covered.
+    pub(crate) fn resync_broadcasts(&self) -> usize {
+        let spaces: Vec<_> = self.iter_active_spaces().collect();
+        for &space in &spaces {
+            let engine = &self.layout_manager.layout_engine;
+            engine.broadcast_workspace_state(space);
+            let Some(workspace_id) = engine.active_workspace(space) else {
+                continue;
+            };
+            let workspace_index = engine.active_workspace_idx(space);
+            let workspace_name = engine
+                .workspace_name(space, workspace_id)
+                .unwrap_or_else(|| format!("Workspace {:?}", workspace_id));
+            let display_uuid = self.display_uuid_for_space(space);
+            for wid in engine.windows_in_active_workspace(space) {
+                let Some(window) = self.window_manager.windows.get(&wid) else {
+                    continue;
+                };
+                let event = BroadcastEvent::WindowTitleChanged {
+                    window_id: wid,
+                    workspace_id,
+                    workspace_index,
+                    workspace_name: workspace_name.clone(),
+                    previous_title: window.info.title.clone(),
+                    new_title: window.info.title.clone(),
+                    space_id: space,
+                    display_uuid: display_uuid.clone(),
+                };
+                let _ = self.communication_manager.event_broadcaster.send(event);
+            }
+        }
+        spaces.len()
+    }
+
+    fn broadcast_window_fullscreen_changed(
+        &self,
+        window_id: WindowId,
+        space_id: SpaceId,
+        fullscreen: bool,
+    ) {
+        let event = BroadcastEvent::WindowFullscreenChanged {
+            window_id,
+            space_id,
+            fullscreen,
+        };
+        let _ = self.communication_manager.event_broadcaster.send(event);
+    }
+
+    fn maybe_reapply_app_rules_for_window(&mut self, window_id: WindowId, was_untitled: bool) {
+        // Windows deferred by the untitled-window policy get their first assignment
+        // once a title shows up, regardless of the reapply setting.
+        let retry_deferred = was_untitled
+            && self.config.virtual_workspaces.untitled_window_policy == UntitledWindowPolicy::Defer;
+        if !self.config.virtual_workspaces.reapply_app_rules_on_title_change && !retry_deferred {
             return;
         }
 
@@ -1460,9 +1884,65 @@ impl Reactor {
         self.process_windows_for_app_rules(window_id.pid, vec![window_id], app_info);
     }
 
+    /// Hold a space vector that doesn't match the screen count until the screens
+    /// catch up, keeping the age of any change already waiting so it can expire.
+    fn defer_space_change(&mut self, spaces: Vec<Option<SpaceId>>) {
+        let queued_at = self
+            .pending_space_change_manager
+            .pending_space_change
+            .as_ref()
+            .map(|pending| pending.queued_at)
+            .unwrap_or_else(Instant::now);
+        self.pending_space_change_manager.pending_space_change =
+            Some(PendingSpaceChange { spaces, queued_at });
+        // A deferred change may be waiting on a matching vector that never comes;
+        // give it a chance to expire and be force-applied, unless Mission Control
+        // still holds it.
+        if !self.is_mission_control_active() {
+            self.try_apply_pending_space_change();
+        }
+    }
+
+    /// Time left until a deferred space change may be force-applied. Mission
+    /// Control holds the change until it exits, so no deadline runs meanwhile.
+    fn pending_space_change_expiry(&self) -> Duration {
+        let max_age_ms = self.config.settings.pending_space_change_max_age_ms;
+        match &self.pending_space_change_manager.pending_space_change {
+            Some(pending) if max_age_ms > 0 && !self.is_mission_control_active() => {
+                (pending.queued_at + Duration::from_millis(max_age_ms))
+                    .saturating_duration_since(Instant::now())
+            }
+            _ => Duration::MAX,
+        }
+    }
+
     fn try_apply_pending_space_change(&mut self) {
         if let Some(mut pending) = self.pending_space_change_manager.pending_space_change.take() {
-            if pending.spaces.len() == self.space_manager.screens.len() {
+            let screen_count = self.space_manager.screens.len();
+            let max_age_ms = self.config.settings.pending_space_change_max_age_ms;
+            if pending.spaces.len() != screen_count
+                && max_age_ms > 0
+                && pending.queued_at.elapsed() >= Duration::from_millis(max_age_ms)
+            {
+                // Truncating an oversize vector would zip its spaces onto the
+                // wrong displays; only a short one can be padded safely.
+                if pending.spaces.len() > screen_count {
+                    warn!(
+                        "Dropping stale oversize pending space change (screens={}, spaces_len={})",
+                        screen_count,
+                        pending.spaces.len()
+                    );
+                    return;
+                }
+                warn!(
+                    "Force-applying stale pending space change after {:?} (screens={}, spaces_len={})",
+                    pending.queued_at.elapsed(),
+                    screen_count,
+                    pending.spaces.len()
+                );
+                pending.spaces.resize(screen_count, None);
+            }
+            if pending.spaces.len() == screen_count {
                 if self.handle_fullscreen_space_transition(&mut pending.spaces) {
                     return;
                 }
@@ -1570,10 +2050,19 @@ impl Reactor {
                 layout_dirty: false,
             };
             self.drag_manager.drag_state = DragState::Active { session };
+            let _ = self
+                .communication_manager
+                .event_broadcaster
+                .send(BroadcastEvent::DragStarted { window_id: wid });
         }
         self.drag_manager.skip_layout_for_window = Some(wid);
     }
 
+    fn broadcast_drag_swap_candidate(&self, window_id: WindowId, target: Option<WindowId>) {
+        let event = BroadcastEvent::DragSwapCandidate { window_id, target };
+        let _ = self.communication_manager.event_broadcaster.send(event);
+    }
+
     fn update_active_drag(&mut self, wid: WindowId, new_frame: &CGRect) {
         let resolved_space = match self.get_active_drag_session() {
             Some(session) if session.window == wid => self.resolve_drag_space(session, new_frame),
@@ -1609,9 +2098,30 @@ impl Reactor {
             return session.settled_space.or_else(|| self.best_space_for_window(frame, server_id));
         }
 
-        self.drag_space_candidate(frame)
+        let candidate = self
+            .drag_space_candidate(frame)
             .or_else(|| self.best_space_for_window(frame, server_id))
-            .or(session.settled_space)
+            .or(session.settled_space);
+
+        // Near a shared edge the candidate can flip with every frame, so only
+        // leave the settled space once the center is well past its screen.
+        if let Some(settled) = session.settled_space
+            && candidate != Some(settled)
+            && let Some(screen) = self.space_manager.screen_by_space(settled)
+        {
+            let margin = self.config.settings.window_snapping.drag_space_hysteresis.max(0.0);
+            let sticky_area = CGRect::new(
+                CGPoint::new(screen.frame.origin.x - margin, screen.frame.origin.y - margin),
+                CGSize::new(
+                    screen.frame.size.width + 2.0 * margin,
+                    screen.frame.size.height + 2.0 * margin,
+                ),
+            );
+            if margin > 0.0 && sticky_area.contains(frame.mid()) {
+                return Some(settled);
+            }
+        }
+        candidate
     }
 
     fn best_space_for_window_state(&self, window: &WindowState) -> Option<SpaceId> {
@@ -1625,11 +2135,15 @@ impl Reactor {
             .and_then(|window| self.best_space_for_window_state(window))
     }
 
-    fn finalize_active_drag(&mut self) -> bool {
+    fn finalize_active_drag(&mut self, swapped: bool) -> bool {
         let Some(session) = self.take_active_drag_session() else {
             return false;
         };
         let wid = session.window;
+        let _ = self
+            .communication_manager
+            .event_broadcaster
+            .send(BroadcastEvent::DragEnded { window_id: wid, swapped });
 
         // During a drag the window server can continue reporting the origin
         // space even after the user has moved the window onto another display.
@@ -1656,7 +2170,7 @@ impl Reactor {
                         warn!("Failed to assign window {:?} to workspace {:?}", wid, active_ws);
                     }
                 }
-                self.send_layout_event(LayoutEvent::WindowAdded(space, wid));
+                self.send_layout_event(LayoutEvent::WindowAdded(space, wid, None));
             }
             self.drag_manager.skip_layout_for_window = Some(wid);
             true
@@ -1687,9 +2201,41 @@ impl Reactor {
         needs_layout
     }
 
-    fn window_center_on_known_screen(&self, wid: WindowId) -> Option<CGPoint> {
-        let window_center = self.window_manager.windows.get(&wid)?.frame_monotonic.mid();
-        self.screen_for_point(window_center).map(|_| window_center)
+    fn window_warp_point_on_known_screen(&self, wid: WindowId) -> Option<CGPoint> {
+        let frame = self.window_manager.windows.get(&wid)?.frame_monotonic;
+        let target = self.config.settings.mouse_follows_focus_target;
+        let cursor = match target {
+            MouseFollowsFocusTarget::NearestEdge => current_cursor_location().ok(),
+            MouseFollowsFocusTarget::Center | MouseFollowsFocusTarget::TopLeft => None,
+        };
+        let point = Self::mouse_warp_point(frame, target, cursor);
+        self.screen_for_point(point).map(|_| point)
+    }
+
+    /// The point within `frame` that `mouse_follows_focus` warps to. Corner and
+    /// edge targets stay a few points inside the frame so the cursor lands on
+    /// the window rather than on its resize border.
+    fn mouse_warp_point(
+        frame: CGRect,
+        target: MouseFollowsFocusTarget,
+        cursor: Option<CGPoint>,
+    ) -> CGPoint {
+        const INSET: f64 = 8.0;
+        let inset_x = INSET.min(frame.size.width / 2.0).max(0.0);
+        let inset_y = INSET.min(frame.size.height / 2.0).max(0.0);
+        let min_x = frame.origin.x + inset_x;
+        let min_y = frame.origin.y + inset_y;
+        let max_x = (frame.max().x - inset_x).max(min_x);
+        let max_y = (frame.max().y - inset_y).max(min_y);
+        match (target, cursor) {
+            (MouseFollowsFocusTarget::TopLeft, _) => CGPoint::new(min_x, min_y),
+            (MouseFollowsFocusTarget::NearestEdge, Some(cursor)) => {
+                CGPoint::new(cursor.x.clamp(min_x, max_x), cursor.y.clamp(min_y, max_y))
+            }
+            (MouseFollowsFocusTarget::Center | MouseFollowsFocusTarget::NearestEdge, _) => {
+                frame.mid()
+            }
+        }
     }
 
     fn has_visible_window_server_ids_for_pid(&self, pid: pid_t) -> bool {
@@ -1726,6 +2272,51 @@ impl Reactor {
             && warp_space.is_some_and(|space| self.warp_mouse_to_space_center(space))
     }
 
+    fn active_workspace_is_empty(&self, space: SpaceId) -> bool {
+        self.layout_manager.layout_engine.windows_in_active_workspace(space).is_empty()
+    }
+
+    /// The configured `empty_workspace_focus`, if set and the active workspace
+    /// on `space` has no windows. Unset keeps the usual refocus behavior.
+    fn empty_workspace_focus(&self, space: SpaceId) -> Option<EmptyWorkspaceFocus> {
+        self.config
+            .settings
+            .empty_workspace_focus
+            .filter(|_| self.active_workspace_is_empty(space))
+    }
+
+    /// Apply `mode` after the active workspace on `space` turned out to have
+    /// nothing to focus. Returns true if focus was handled without needing a
+    /// raise.
+    fn focus_empty_workspace(
+        &mut self,
+        space: SpaceId,
+        mode: EmptyWorkspaceFocus,
+        focus_window: &mut Option<WindowId>,
+    ) -> bool {
+        match mode {
+            EmptyWorkspaceFocus::LeaveCursor => {
+                if let Some(wid) = self.window_id_under_cursor() {
+                    // Avoid duplicate focus events for the already focused window.
+                    if self.main_window() != Some(wid) {
+                        *focus_window = Some(wid);
+                    }
+                    return false;
+                }
+                self.focus_untracked_window_under_cursor()
+            }
+            EmptyWorkspaceFocus::WarpToCenter => self.warp_mouse_to_space_center(space),
+            EmptyWorkspaceFocus::FocusDesktop => {
+                let finder = self.app_manager.apps.iter().find_map(|(&pid, app)| {
+                    (app.info.bundle_id.as_deref() == Some("com.apple.finder")).then_some(pid)
+                });
+                finder.is_some_and(|pid| {
+                    window_server::make_process_frontmost_without_windows(pid).is_ok()
+                })
+            }
+        }
+    }
+
     fn insert_app_handle_for_window(
         &self,
         app_handles: &mut HashMap<pid_t, AppThreadHandle>,
@@ -1786,6 +2377,7 @@ impl Reactor {
     fn send_layout_event(&mut self, event: LayoutEvent) {
         let event_clone = event.clone();
         let response = self.layout_manager.layout_engine.handle_event(event);
+        self.apply_multi_space_window_policy(&event_clone);
         self.prepare_refocus_after_layout_event(&event_clone);
         self.handle_layout_response(response, None);
         for space in self.space_manager.iter_known_spaces() {
@@ -1793,6 +2385,27 @@ impl Reactor {
         }
     }
 
+    /// Applies `multi_space_windows` to windows the layout just took in that
+    /// macOS reports on more than one space.
+    fn apply_multi_space_window_policy(&mut self, event: &LayoutEvent) {
+        if self.config.settings.multi_space_windows != MultiSpaceWindowPolicy::Sticky {
+            return;
+        }
+        let (space, wids): (SpaceId, Vec<WindowId>) = match event {
+            LayoutEvent::WindowAdded(space, wid, _) => (*space, vec![*wid]),
+            LayoutEvent::WindowsOnScreenUpdated(space, _, windows, _) => {
+                (*space, windows.iter().map(|(wid, ..)| *wid).collect())
+            }
+            _ => return,
+        };
+        for wid in wids {
+            if self.window_manager.windows.get(&wid).is_some_and(|w| w.is_multi_space) {
+                debug!(?wid, "Window is on several spaces; floating it as sticky");
+                self.layout_manager.layout_engine.float_sticky_window(space, wid);
+            }
+        }
+    }
+
     // Returns true if the window should be raised on mouse over considering
     // active workspace membership and potential occlusion of floating windows above it.
     fn should_raise_on_mouse_over(&self, wid: WindowId) -> bool {
@@ -1804,6 +2417,16 @@ impl Reactor {
             return false;
         }
 
+        let blacklist = &self.config.settings.autoraise_blacklist;
+        if !blacklist.is_empty()
+            && let Some(bundle_id) =
+                self.app_manager.apps.get(&wid.pid).and_then(|app| app.info.bundle_id.as_ref())
+            && blacklist.contains(bundle_id)
+        {
+            trace!(?wid, %bundle_id, "Skipping autoraise for blacklisted app");
+            return false;
+        }
+
         if !window.matches_filter(WindowFilter::EffectivelyManageable)
             && !self.layout_manager.layout_engine.is_window_floating(wid)
         {
@@ -1923,7 +2546,12 @@ impl Reactor {
                             .unwrap_or(false),
                     )
                 };
-                let assign_result = {
+                if !was_assigned {
+                    self.assign_window_to_pinned_workspace(*wid, space, &app_info);
+                }
+                let assign_result = if self.window_manager.manually_unmanaged.contains(wid) {
+                    Ok(AppRuleResult::Unmanaged)
+                } else {
                     let window = self.window_manager.windows.get(wid);
                     self.layout_manager
                         .layout_engine
@@ -1953,6 +2581,9 @@ impl Reactor {
                             windows_needing_layout_refresh.push(*wid);
                         }
                     }
+                    Ok(AppRuleResult::Deferred) => {
+                        trace!("Deferring assignment of untitled window {:?}", wid);
+                    }
                     Ok(AppRuleResult::Unmanaged) => {
                         if let Some(window) = self.window_manager.windows.get_mut(wid) {
                             window.ignore_app_rule = true;
@@ -2030,6 +2661,33 @@ impl Reactor {
         }
     }
 
+    /// Put a window that has no workspace yet into the workspace its app is
+    /// pinned to, so the app rules that run next keep it there.
+    fn assign_window_to_pinned_workspace(
+        &mut self,
+        wid: WindowId,
+        space: SpaceId,
+        app_info: &AppInfo,
+    ) {
+        let Some(&index) = app_info
+            .bundle_id
+            .as_ref()
+            .and_then(|bundle_id| self.app_manager.workspace_pins.get(bundle_id))
+        else {
+            return;
+        };
+        let workspace_manager = self.layout_manager.layout_engine.virtual_workspace_manager_mut();
+        let Some((workspace_id, _)) = workspace_manager.list_workspaces(space).get(index).cloned()
+        else {
+            debug!(
+                ?wid,
+                index, "Pinned workspace does not exist on {:?}; using app rules", space
+            );
+            return;
+        };
+        workspace_manager.assign_window_to_workspace(space, wid, workspace_id);
+    }
+
     fn handle_app_activation_workspace_switch(&mut self, pid: pid_t) {
         use objc2_app_kit::NSRunningApplication;
 
@@ -2107,6 +2765,15 @@ impl Reactor {
         };
         let bundle_id_str = bundle_id.to_string();
 
+        let allowlist = &self.config.settings.auto_focus_allowlist;
+        if !allowlist.is_empty() && !allowlist.contains(&bundle_id_str) {
+            debug!(
+                "App {} is not allowlisted for auto-focus workspace switching, ignoring activation",
+                bundle_id_str
+            );
+            return;
+        }
+
         if self.config.settings.auto_focus_blacklist.contains(&bundle_id_str) {
             debug!(
                 "App {} is blacklisted for auto-focus workspace switching, ignoring activation",
@@ -2142,14 +2809,14 @@ impl Reactor {
             return;
         };
 
-        self.maybe_auto_switch_to_window_workspace(pid, app_window_id, window_space);
+        self.switch_to_window_workspace(window_space, app_window_id, WorkspaceSwitchOrigin::Auto);
     }
 
-    fn maybe_auto_switch_to_window_workspace(
+    fn switch_to_window_workspace(
         &mut self,
-        pid: pid_t,
-        app_window_id: WindowId,
         window_space: SpaceId,
+        app_window_id: WindowId,
+        origin: WorkspaceSwitchOrigin,
     ) {
         let workspace_manager = self.layout_manager.layout_engine.virtual_workspace_manager();
         let Some(window_workspace) =
@@ -2174,24 +2841,49 @@ impl Reactor {
                 workspaces.iter().enumerate().find(|(_, (ws_id, _))| *ws_id == window_workspace)
             {
                 debug!(
-                    "Auto-switching to workspace {} for activated app (pid: {})",
-                    workspace_index, pid
+                    "Switching to workspace {} for window {:?} ({:?})",
+                    workspace_index, app_window_id, origin
                 );
 
                 self.store_current_floating_positions(window_space);
-                self.workspace_switch_manager
-                    .start_workspace_switch(WorkspaceSwitchOrigin::Auto);
+                self.workspace_switch_manager.start_workspace_switch(origin);
 
                 let response = self.layout_manager.layout_engine.handle_virtual_workspace_command(
                     window_space,
                     &layout::LayoutCommand::SwitchToWorkspace(workspace_index),
                 );
+                self.record_workspace_switch(window_space, Some(current_workspace), origin);
                 self.handle_layout_response(response, Some(window_space));
                 self.update_event_tap_layout_mode();
             }
         }
     }
 
+    fn record_workspace_switch(
+        &mut self,
+        space: SpaceId,
+        from: Option<VirtualWorkspaceId>,
+        origin: WorkspaceSwitchOrigin,
+    ) {
+        let Some(to) = self.layout_manager.layout_engine.active_workspace(space) else {
+            return;
+        };
+        if from == Some(to) {
+            return;
+        }
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or_default();
+        self.workspace_switch_manager.record_switch(WorkspaceSwitchData {
+            timestamp_ms,
+            space_id: space.get(),
+            from_workspace: from.map(|id| format!("{:?}", id)),
+            to_workspace: format!("{:?}", to),
+            origin,
+        });
+    }
+
     fn handle_layout_response(
         &mut self,
         response: layout::EventResponse,
@@ -2216,6 +2908,26 @@ impl Reactor {
         if let Some(dir) = boundary_hit
             && self.config.settings.layout.scrolling.gestures.propagate_to_workspace_swipe
         {
+            let gestures = &self.config.settings.gestures;
+            let gesture = match dir {
+                Direction::Left | Direction::Right => {
+                    let left = (dir == Direction::Left) != gestures.invert_horizontal_swipe;
+                    Some(if left {
+                        Gesture::SwipeLeft
+                    } else {
+                        Gesture::SwipeRight
+                    })
+                }
+                _ => None,
+            };
+            if let Some(gesture) = gesture.filter(|g| gestures.bindings.contains_key(g)) {
+                if gestures.haptics_enabled {
+                    let _ = crate::sys::haptics::perform_haptic(gestures.haptic_pattern);
+                }
+                CommandEventHandler::handle_command_reactor(self, ReactorCommand::Gesture(gesture));
+                self.update_event_tap_layout_mode();
+                return;
+            }
             let skip_empty = self.config.settings.gestures.skip_empty;
             let cmd = if self.config.settings.gestures.invert_horizontal_swipe {
                 match dir {
@@ -2266,36 +2978,31 @@ impl Reactor {
                 WorkspaceSwitchState::Active
             ) && !self.is_in_drag()
             {
-                if let Some(wid) = self.window_id_under_cursor() {
+                if let Some((space, mode)) = workspace_switch_space
+                    .and_then(|space| Some((space, self.empty_workspace_focus(space)?)))
+                {
+                    self.focus_empty_workspace(space, mode, &mut focus_window)
+                } else if let Some(wid) = self.window_id_under_cursor() {
                     // Avoid duplicate focus events for the already focused window.
                     if self.main_window() != Some(wid) {
                         focus_window = Some(wid);
                     }
                     false
                 } else {
-                    let skip_center_warp = workspace_switch_space
-                        .map(|space| {
-                            self.layout_manager
-                                .layout_engine
-                                .windows_in_active_workspace(space)
-                                .is_empty()
-                        })
-                        .unwrap_or(false);
-                    let warp_space = if skip_center_warp {
-                        None
-                    } else {
-                        workspace_switch_space.or_else(|| self.workspace_command_space())
-                    };
+                    let warp_space =
+                        workspace_switch_space.or_else(|| self.workspace_command_space());
                     self.try_focus_or_warp_without_raise(warp_space, &mut focus_window)
                 }
             } else if let Some(space) = pending_refocus_space.take() {
                 if let Some(wid) = self.last_focused_window_in_space(space) {
                     focus_window = Some(wid);
                     false
-                } else if !self.is_in_drag() {
-                    self.try_focus_or_warp_without_raise(Some(space), &mut focus_window)
-                } else {
+                } else if self.is_in_drag() {
                     false
+                } else if let Some(mode) = self.empty_workspace_focus(space) {
+                    self.focus_empty_workspace(space, mode, &mut focus_window)
+                } else {
+                    self.try_focus_or_warp_without_raise(Some(space), &mut focus_window)
                 }
             } else {
                 false
@@ -2353,12 +3060,28 @@ impl Reactor {
             self.insert_app_handle_for_window(&mut app_handles, wid);
         }
 
-        let raise_windows: Vec<WindowId> = raise_windows
+        let mut raise_windows: Vec<WindowId> = raise_windows
             .into_iter()
             .filter(|wid| self.is_window_on_active_space(*wid))
             .collect();
         let focus_window = focus_window.filter(|wid| self.is_window_on_active_space(*wid));
 
+        if workspace_switch_space.is_some() && self.config.settings.layout.floating.strict_z_order {
+            // Windows within one raise request are raised concurrently, so queue one
+            // request per window to keep the stacking order the layout engine asked for.
+            for wid in raise_windows.drain(..) {
+                let msg = raise_manager::Event::RaiseRequest(RaiseRequest {
+                    raise_windows: vec![vec![wid]],
+                    focus_window: None,
+                    app_handles: app_handles.clone(),
+                    focus_quiet,
+                });
+                if let Err(e) = self.communication_manager.raise_manager_tx.try_send(msg) {
+                    warn!("Failed to send raise request to raise manager: {}", e);
+                }
+            }
+        }
+
         let mut windows_by_app_and_screen = HashMap::default();
         for &wid in &raise_windows {
             windows_by_app_and_screen
@@ -2375,7 +3098,7 @@ impl Reactor {
                     self.workspace_switch_manager.pending_workspace_mouse_warp = Some(wid);
                     None
                 } else {
-                    self.window_center_on_known_screen(wid)
+                    self.window_warp_point_on_known_screen(wid)
                 }
             } else {
                 None
@@ -2466,6 +3189,7 @@ impl Reactor {
                     "Clearing pending drag swap; dragged window entered new space"
                 );
                 self.drag_manager.drag_state = DragState::Inactive;
+                self.broadcast_drag_swap_candidate(wid, None);
             }
             trace!(
                 ?wid,
@@ -2495,6 +3219,10 @@ impl Reactor {
                     "Detected swap candidate; deferring until MouseUp"
                 );
             }
+            // Frame changes that keep the same target don't re-announce it.
+            if previous_pending != Some((wid, target_wid)) {
+                self.broadcast_drag_swap_candidate(wid, Some(target_wid));
+            }
 
             if let Some(session) = self.take_active_drag_session() {
                 self.drag_manager.drag_state =
@@ -2522,6 +3250,7 @@ impl Reactor {
                 ?pending_target,
                 "Clearing pending drag swap; overlap ended before MouseUp"
             );
+            self.broadcast_drag_swap_candidate(wid, None);
             if let Some(session) = self.take_active_drag_session() {
                 self.drag_manager.drag_state = DragState::Active { session };
             } else {
@@ -2617,7 +3346,7 @@ impl Reactor {
 
     fn prepare_refocus_after_layout_event(&mut self, event: &LayoutEvent) {
         match event {
-            LayoutEvent::WindowAdded(space, wid) => {
+            LayoutEvent::WindowAdded(space, wid, _) => {
                 self.request_refocus_if_hidden(*space, *wid);
             }
             LayoutEvent::WindowsOnScreenUpdated(space, _, windows, _) => {
@@ -2632,6 +3361,46 @@ impl Reactor {
         }
     }
 
+    /// Remember windows that must not take focus when they first become main.
+    fn note_new_windows(&mut self, windows: impl IntoIterator<Item = WindowId>) {
+        if self.config.settings.new_window_focus_steal {
+            return;
+        }
+        let now = std::time::Instant::now();
+        for wid in windows {
+            self.window_manager.unfocused_new_windows.insert(wid, now);
+        }
+    }
+
+    /// Returns true if focusing `wid` was suppressed because it is a new window
+    /// and focus stealing is off; focus is handed back to the previous window.
+    fn suppress_new_window_focus(&mut self, wid: WindowId) -> bool {
+        let grace = Duration::from_millis(self.config.settings.new_window_focus_grace_ms);
+        let now = std::time::Instant::now();
+        self.window_manager
+            .unfocused_new_windows
+            .retain(|_, created| now.duration_since(*created) < grace);
+        if self.window_manager.unfocused_new_windows.remove(&wid).is_none() {
+            return false;
+        }
+
+        if let Some(previous) = self
+            .layout_manager
+            .layout_engine
+            .focused_window()
+            .filter(|&previous| previous != wid)
+            .filter(|previous| self.window_manager.windows.contains_key(previous))
+        {
+            debug!(
+                ?wid,
+                ?previous,
+                "New window focus suppressed; restoring previous focus"
+            );
+            self.raise_window(previous, Quiet::Yes, None);
+        }
+        true
+    }
+
     #[instrument(skip(self))]
     fn raise_window(&mut self, wid: WindowId, quiet: Quiet, warp: Option<CGPoint>) {
         let mut app_handles = HashMap::default();
@@ -2671,22 +3440,31 @@ impl Reactor {
         }
     }
 
-    fn update_focus_follows_mouse_state(&self) {
+    fn update_focus_follows_mouse_state(&mut self) {
         let should_enable = self.config.settings.focus_follows_mouse
             && matches!(self.menu_manager.menu_state, MenuState::Closed)
             && !self.is_mission_control_active();
+        if !should_enable {
+            self.refocus_manager.pending_mouse_focus = None;
+        }
         self.set_focus_follows_mouse_enabled(should_enable);
     }
 
-    fn update_event_tap_layout_mode(&mut self) {
-        let Some(event_tap_tx) = self.communication_manager.event_tap_tx.as_ref() else {
-            return;
-        };
+    /// Time left until the window under the cursor is focused, if a
+    /// focus-follows-mouse dwell is in progress.
+    fn mouse_hover_dwell_remaining(&self) -> Duration {
+        self.refocus_manager.pending_mouse_focus.map_or(Duration::MAX, |(_, at)| {
+            at.saturating_duration_since(Instant::now())
+        })
+    }
 
+    /// Tell the event tap and broadcast subscribers about spaces whose active
+    /// layout mode changed, whether by command or by a workspace switch.
+    fn update_event_tap_layout_mode(&mut self) {
         let last_modes = &self.notification_manager.last_layout_modes_by_space;
         let mut modes: Vec<(SpaceId, crate::common::config::LayoutMode)> =
             Vec::with_capacity(self.space_manager.screens.len());
-        let mut changed = false;
+        let mut changed = Vec::new();
 
         for screen in &self.space_manager.screens {
             let Some(space) = screen.space else {
@@ -2700,18 +3478,29 @@ impl Reactor {
 
             let mode = self.layout_manager.layout_engine.active_layout_mode_at(space);
             if last_modes.get(&space).copied() != Some(mode) {
-                changed = true;
+                changed.push((space, screen.display_uuid.clone(), mode));
             }
             modes.push((space, mode));
         }
 
-        if modes.is_empty() || (!changed && modes.len() == last_modes.len()) {
+        if modes.is_empty() || (changed.is_empty() && modes.len() == last_modes.len()) {
             return;
         }
 
         let modes_by_space = modes.iter().copied().collect();
         self.notification_manager.last_layout_modes_by_space = modes_by_space;
-        event_tap_tx.send(crate::actor::event_tap::Request::LayoutModesChanged(modes));
+        for (space_id, display_uuid, mode) in changed {
+            let _ = self.communication_manager.event_broadcaster.send(
+                BroadcastEvent::LayoutModeChanged {
+                    space_id,
+                    display_uuid: Some(display_uuid),
+                    mode,
+                },
+            );
+        }
+        if let Some(event_tap_tx) = self.communication_manager.event_tap_tx.as_ref() {
+            event_tap_tx.send(crate::actor::event_tap::Request::LayoutModesChanged(modes));
+        }
     }
 
     fn set_mission_control_active(&mut self, active: bool) {
@@ -2756,6 +3545,29 @@ impl Reactor {
         self.best_space_for_window_id(wid)
     }
 
+    /// Gaps to lay out `space` with, after per-display overrides are applied.
+    fn effective_gaps_for_space(&self, space: SpaceId) -> GapSettings {
+        let display_uuid = self
+            .space_manager
+            .screen_by_space(space)
+            .and_then(|screen| screen.display_uuid_owned());
+        self.gaps_for_space_on_display(space, display_uuid.as_deref())
+    }
+
+    /// The gaps `space` is laid out with on `display_uuid`, honoring
+    /// per-space layout settings and `ToggleGaps`.
+    fn gaps_for_space_on_display(&self, space: SpaceId, display_uuid: Option<&str>) -> GapSettings {
+        if self.layout_manager.gapless_spaces.contains(&space) {
+            return GapSettings::default();
+        }
+        self.layout_manager
+            .layout_engine
+            .space_layout_settings(space)
+            .unwrap_or(&self.config.settings.layout)
+            .gaps
+            .effective_for_display(display_uuid)
+    }
+
     fn workspace_command_space(&self) -> Option<SpaceId> {
         let candidate = self
             .space_for_cursor_screen()
@@ -2914,6 +3726,23 @@ impl Reactor {
                 self.screen_for_direction_from_point(origin, *direction)
             }
             DisplaySelector::Index(index) => self.screens_in_physical_order().get(*index).copied(),
+            DisplaySelector::Cycle(cycle) => {
+                let screens = self.screens_in_cycle_order();
+                if screens.is_empty() {
+                    return None;
+                }
+                let origin = origin_override.or_else(|| self.current_screen_center());
+                let current = origin.and_then(|point| {
+                    screens.iter().position(|screen| screen.frame.contains(point))
+                });
+                let len = screens.len();
+                let next = match (current, cycle) {
+                    (Some(idx), DisplayCycle::Next) => (idx + 1) % len,
+                    (Some(idx), DisplayCycle::Prev) => (idx + len - 1) % len,
+                    (None, _) => 0,
+                };
+                screens.get(next).copied()
+            }
             DisplaySelector::Uuid(uuid) => {
                 self.space_manager.screens.iter().find(|screen| screen.display_uuid == *uuid)
             }
@@ -2933,6 +3762,30 @@ impl Reactor {
         screens
     }
 
+    fn screens_in_cycle_order(&self) -> Vec<&ScreenInfo> {
+        let mut screens = self.screens_in_physical_order();
+        match &self.config.settings.display_cycle_order {
+            DisplayCycleOrder::Physical(PhysicalDisplayOrder::LeftToRight) => {}
+            DisplayCycleOrder::Physical(PhysicalDisplayOrder::TopToBottom) => {
+                screens.sort_by(|a, b| {
+                    let y_order = a.frame.origin.y.total_cmp(&b.frame.origin.y);
+                    if y_order == std::cmp::Ordering::Equal {
+                        a.frame.origin.x.total_cmp(&b.frame.origin.x)
+                    } else {
+                        y_order
+                    }
+                });
+            }
+            DisplayCycleOrder::Uuids(uuids) => {
+                // Displays missing from the list keep their left-to-right order after it.
+                screens.sort_by_key(|screen| {
+                    uuids.iter().position(|uuid| *uuid == screen.display_uuid).unwrap_or(usize::MAX)
+                });
+            }
+        }
+        screens
+    }
+
     fn store_current_floating_positions(&mut self, space: SpaceId) {
         let floating_windows_in_workspace = self
             .layout_manager
@@ -2969,8 +3822,21 @@ impl Reactor {
         is_workspace_switch: bool,
         context: &'static str,
     ) -> bool {
+        if self.layout_manager.frozen {
+            trace!("Layout frozen; skipping layout update");
+            return false;
+        }
         LayoutManager::update_layout(self, is_resize, is_workspace_switch).unwrap_or_else(|e| {
             warn!(error = ?e, "{}", context);
+            let timestamp_ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or_default();
+            self.layout_manager.last_error = Some(LayoutErrorData {
+                timestamp_ms,
+                context: context.to_string(),
+                message: e.to_string(),
+            });
             false
         })
     }
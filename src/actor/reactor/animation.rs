@@ -21,6 +21,7 @@ pub struct Animation<'a> {
     start: Instant,
     interval: Duration,
     frames: u32,
+    easing: AnimationEasing,
 
     windows: Vec<(
         &'a AppThreadHandle,
@@ -33,7 +34,7 @@ pub struct Animation<'a> {
 }
 
 impl<'a> Animation<'a> {
-    pub fn new(fps: f64, duration: f64, _: AnimationEasing) -> Self {
+    pub fn new(fps: f64, duration: f64, easing: AnimationEasing) -> Self {
         let interval = Duration::from_secs_f64(1.0 / fps);
         // let now = unsafe { CFAbsoluteTimeGetCurrent() };
         let now = Instant::now();
@@ -41,6 +42,7 @@ impl<'a> Animation<'a> {
             start: now, // + interval, // not necessary, provide one extra frame to get things going
             interval,
             frames: (duration * fps).round() as u32,
+            easing,
             windows: vec![],
         }
     }
@@ -80,7 +82,7 @@ impl<'a> Animation<'a> {
 
             next_frames.clear();
             for (_, _, from, to, _, _) in &self.windows {
-                next_frames.push(get_frame(*from, *to, t));
+                next_frames.push(get_frame(*from, *to, sample_easing(self.easing, t)));
             }
 
             let deadline = self.start + frame * self.interval;
@@ -117,8 +119,7 @@ impl<'a> Animation<'a> {
     }
 }
 
-fn get_frame(a: CGRect, b: CGRect, t: f64) -> CGRect {
-    let s = ease(t);
+fn get_frame(a: CGRect, b: CGRect, s: f64) -> CGRect {
     CGRect {
         origin: CGPoint {
             x: blend(a.origin.x, b.origin.x, s),
@@ -131,12 +132,58 @@ fn get_frame(a: CGRect, b: CGRect, t: f64) -> CGRect {
     }
 }
 
-// https://notes.yvt.jp/Graphics/Easing-Functions/
-fn ease(t: f64) -> f64 {
-    if t < 0.5 {
-        (1.0 - f64::sqrt(1.0 - f64::powi(2.0 * t, 2))) / 2.0
-    } else {
-        (f64::sqrt(1.0 - f64::powi(-2.0 * t + 2.0, 2)) + 1.0) / 2.0
+/// Progress along the move at time `t` (0.0 to 1.0) for `easing`. Starts at
+/// 0.0 and ends at exactly 1.0; `Spring` overshoots in between.
+// https://easings.net
+pub fn sample_easing(easing: AnimationEasing, t: f64) -> f64 {
+    use std::f64::consts::PI;
+
+    use AnimationEasing::*;
+
+    let t = t.clamp(0.0, 1.0);
+    if t >= 1.0 {
+        return 1.0;
+    }
+    let in_out = |ease_in: fn(f64) -> f64| {
+        if t < 0.5 {
+            ease_in(2.0 * t) / 2.0
+        } else {
+            1.0 - ease_in(2.0 - 2.0 * t) / 2.0
+        }
+    };
+    let circ = |t: f64| 1.0 - (1.0 - t * t).sqrt();
+    let expo = |t: f64| {
+        if t <= 0.0 {
+            0.0
+        } else {
+            2f64.powf(10.0 * t - 10.0)
+        }
+    };
+    match easing {
+        Linear => t,
+        EaseInSine => 1.0 - (t * PI / 2.0).cos(),
+        EaseOutSine => (t * PI / 2.0).sin(),
+        EaseInOutSine => -((PI * t).cos() - 1.0) / 2.0,
+        EaseInQuad => t.powi(2),
+        EaseOutQuad => 1.0 - (1.0 - t).powi(2),
+        EaseInOutQuad => in_out(|t| t.powi(2)),
+        EaseInCubic => t.powi(3),
+        EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+        EaseInOutCubic => in_out(|t| t.powi(3)),
+        EaseInQuart => t.powi(4),
+        EaseOutQuart => 1.0 - (1.0 - t).powi(4),
+        EaseInOutQuart => in_out(|t| t.powi(4)),
+        EaseInQuint => t.powi(5),
+        EaseOutQuint => 1.0 - (1.0 - t).powi(5),
+        EaseInOutQuint => in_out(|t| t.powi(5)),
+        EaseInExpo => expo(t),
+        EaseOutExpo => 1.0 - expo(1.0 - t),
+        EaseInOutExpo => in_out(expo),
+        EaseInCirc => circ(t),
+        EaseOutCirc => 1.0 - circ(1.0 - t),
+        // `EaseInOut` has always been the circular curve.
+        EaseInOut | EaseInOutCirc => in_out(circ),
+        Spring => 1.0 - (-8.0 * t).exp() * (12.0 * t).cos(),
     }
 }
 
@@ -145,6 +192,16 @@ fn blend(a: f64, b: f64, s: f64) -> f64 { (1.0 - s) * a + s * b }
 pub struct AnimationManager;
 
 impl AnimationManager {
+    /// Whether layout changes on `space` animate: the `animate` setting of the
+    /// layout its active workspace uses wins over the global `animate`.
+    pub fn animation_enabled_for_space(reactor: &Reactor, space: SpaceId) -> bool {
+        reactor
+            .layout_manager
+            .layout_engine
+            .layout_specific_animate_settings(space)
+            .unwrap_or(reactor.config.settings.animate)
+    }
+
     pub fn animate_layout(
         reactor: &mut Reactor,
         space: SpaceId,
@@ -250,13 +307,7 @@ impl AnimationManager {
 
         if animated_count > 0 {
             let low_power = power::is_low_power_mode_enabled();
-            let layout_animate = reactor
-                .layout_manager
-                .layout_engine
-                .layout_specific_animate_settings(space)
-                .unwrap_or(reactor.config.settings.animate);
-
-            if is_resize || !layout_animate || low_power {
+            if is_resize || !Self::animation_enabled_for_space(reactor, space) || low_power {
                 anim.skip_to_end();
             } else {
                 anim.run();
@@ -372,3 +423,55 @@ impl AnimationManager {
         any_frame_changed
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_easing_starts_at_zero_and_ends_at_one() {
+        use AnimationEasing::*;
+
+        for easing in [
+            Linear,
+            EaseInOut,
+            Spring,
+            EaseInSine,
+            EaseOutSine,
+            EaseInOutSine,
+            EaseInQuad,
+            EaseOutQuad,
+            EaseInOutQuad,
+            EaseInCubic,
+            EaseOutCubic,
+            EaseInOutCubic,
+            EaseInQuart,
+            EaseOutQuart,
+            EaseInOutQuart,
+            EaseInQuint,
+            EaseOutQuint,
+            EaseInOutQuint,
+            EaseInExpo,
+            EaseOutExpo,
+            EaseInOutExpo,
+            EaseInCirc,
+            EaseOutCirc,
+            EaseInOutCirc,
+        ] {
+            assert!(sample_easing(easing, 0.0).abs() < 1e-9, "{easing:?} at 0");
+            assert_eq!(sample_easing(easing, 1.0), 1.0, "{easing:?} at 1");
+        }
+    }
+
+    #[test]
+    fn easings_shape_the_middle_of_the_move() {
+        assert_eq!(sample_easing(AnimationEasing::Linear, 0.25), 0.25);
+        assert!((sample_easing(AnimationEasing::EaseInOut, 0.5) - 0.5).abs() < 1e-9);
+        assert!(sample_easing(AnimationEasing::EaseInCubic, 0.25) < 0.25);
+        assert!(sample_easing(AnimationEasing::EaseOutCubic, 0.25) > 0.25);
+        let peak = (1..100)
+            .map(|i| sample_easing(AnimationEasing::Spring, f64::from(i) / 100.0))
+            .fold(0.0, f64::max);
+        assert!(peak > 1.0, "spring should overshoot, peaked at {peak}");
+    }
+}
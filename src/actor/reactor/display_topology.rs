@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use tracing::{debug, info};
 
@@ -52,6 +52,10 @@ pub struct DisplayTopologyManager {
     state: TopologyState,
     pub quarantine_stats: QuarantineStats,
     churn_pre_known_wsids: HashSet<WindowServerId>,
+    /// How long a pending commit is held after churn ends so that a follow-up
+    /// churn (e.g. a flaky dock connection) folds into the same reconcile.
+    coalesce_window: Duration,
+    commit_not_before: Option<Instant>,
 }
 
 impl DisplayTopologyManager {
@@ -65,15 +69,64 @@ impl DisplayTopologyManager {
         )
     }
 
+    pub fn set_coalesce_window(&mut self, window: Duration) { self.coalesce_window = window; }
+
+    /// Time left before a pending commit may be reconciled, if it is still
+    /// being held open for coalescing.
+    pub fn commit_hold_remaining(&self) -> Option<Duration> {
+        if !matches!(self.state, TopologyState::AwaitingCommitSnapshot { .. }) {
+            return None;
+        }
+        self.commit_not_before
+            .and_then(|deadline| deadline.checked_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    /// Time until a held commit should be retried, zero once the hold has
+    /// elapsed. `None` when no hold is pending, including after the commit has
+    /// been taken once.
+    pub fn commit_hold_expiry(&self) -> Option<Duration> {
+        if !matches!(self.state, TopologyState::AwaitingCommitSnapshot { .. }) {
+            return None;
+        }
+        self.commit_not_before
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
     pub fn begin_churn(
         &mut self,
         epoch: u64,
         flags: DisplayReconfigFlags,
-        pre_known_wsids: HashSet<WindowServerId>,
+        mut pre_known_wsids: HashSet<WindowServerId>,
     ) {
         let now = Instant::now();
+        if self.commit_hold_remaining().is_some()
+            && let TopologyState::AwaitingCommitSnapshot {
+                started_at,
+                flags: prev_flags,
+                pre_known_wsids: prev_pre_known,
+                ..
+            } = std::mem::take(&mut self.state)
+        {
+            // Fold into the pending cycle: keep what was known before the first
+            // churn so the eventual reconcile diffs against the original state.
+            pre_known_wsids.extend(prev_pre_known);
+            let flags = flags | prev_flags;
+            self.state = TopologyState::Churning { epoch, started_at, flags };
+            self.churn_pre_known_wsids = pre_known_wsids;
+            self.commit_not_before = None;
+            debug!(
+                epoch,
+                flags = ?flags,
+                pre_known = self.churn_pre_known_wsids.len(),
+                "display churn begin; coalescing with pending commit"
+            );
+            return;
+        }
+
         self.state = TopologyState::Churning { epoch, started_at: now, flags };
         self.churn_pre_known_wsids = pre_known_wsids;
+        self.commit_not_before = None;
         debug!(
             epoch,
             flags = ?flags,
@@ -92,6 +145,8 @@ impl DisplayTopologyManager {
             flags,
             pre_known_wsids,
         };
+        self.commit_not_before =
+            (!self.coalesce_window.is_zero()).then(|| now + self.coalesce_window);
         info!(
             epoch,
             flags = ?flags,
@@ -111,7 +166,11 @@ impl DisplayTopologyManager {
                 started_at,
                 flags,
                 pre_known_wsids,
-            } => Some((epoch, started_at, flags, pre_known_wsids)),
+            } => {
+                // The hold is over; a restored commit waits on snapshots only.
+                self.commit_not_before = None;
+                Some((epoch, started_at, flags, pre_known_wsids))
+            }
             other => {
                 self.state = other;
                 None
@@ -149,7 +208,10 @@ impl DisplayTopologyManager {
 
     pub fn quarantine_resync(&mut self) { self.quarantine_stats.resync_dropped += 1; }
 
-    pub fn mark_stable(&mut self) { self.state = TopologyState::Stable; }
+    pub fn mark_stable(&mut self) {
+        self.state = TopologyState::Stable;
+        self.commit_not_before = None;
+    }
 }
 
 #[cfg(test)]
@@ -188,4 +250,43 @@ mod tests {
             TopologyState::AwaitingCommitSnapshot { .. }
         ));
     }
+
+    #[test]
+    fn churn_within_coalesce_window_folds_into_pending_commit() {
+        let mut manager = DisplayTopologyManager::default();
+        manager.set_coalesce_window(Duration::from_secs(60));
+
+        let mut first = HashSet::default();
+        first.insert(WindowServerId::new(1));
+        manager.begin_churn(1, DisplayReconfigFlags::REMOVE, first);
+        manager.end_churn_to_awaiting(1, DisplayReconfigFlags::REMOVE);
+        assert!(manager.commit_hold_remaining().is_some());
+        assert!(manager.commit_hold_expiry().is_some_and(|left| !left.is_zero()));
+
+        let mut second = HashSet::default();
+        second.insert(WindowServerId::new(2));
+        manager.begin_churn(2, DisplayReconfigFlags::ADD, second);
+        assert!(matches!(manager.state(), TopologyState::Churning { .. }));
+        assert!(manager.commit_hold_remaining().is_none());
+
+        manager.end_churn_to_awaiting(2, DisplayReconfigFlags::ADD);
+        let (epoch, started_at, flags, pre_known) =
+            manager.take_awaiting_commit().expect("pending commit");
+        assert_eq!(epoch, 2);
+        assert!(pre_known.contains(&WindowServerId::new(1)));
+        assert!(pre_known.contains(&WindowServerId::new(2)));
+
+        // A commit put back while the topology settles doesn't re-arm the timer.
+        manager.restore_awaiting_commit(epoch, started_at, flags, pre_known);
+        assert!(manager.commit_hold_expiry().is_none());
+    }
+
+    #[test]
+    fn churn_without_coalesce_window_does_not_hold_commit() {
+        let mut manager = DisplayTopologyManager::default();
+        manager.begin_churn(1, DisplayReconfigFlags::ADD, HashSet::default());
+        manager.end_churn_to_awaiting(1, DisplayReconfigFlags::ADD);
+        assert!(manager.commit_hold_remaining().is_none());
+        assert!(manager.commit_hold_expiry().is_none());
+    }
 }
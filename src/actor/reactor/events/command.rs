@@ -1,18 +1,23 @@
-use tracing::{error, info, warn};
+use objc2_app_kit::{NSFloatingWindowLevel, NSNormalWindowLevel};
+use tracing::{debug, error, info, warn};
 
 use super::super::ScreenInfo;
-use crate::actor::app::{AppThreadHandle, Quiet, WindowId};
+use crate::actor::app::{AppThreadHandle, Quiet, WindowId, pid_t};
+use crate::actor::broadcast::BroadcastEvent;
+use crate::actor::reactor::events::window_discovery::WindowDiscoveryHandler;
+use crate::actor::reactor::mirror::MirrorLink;
 use crate::actor::reactor::transaction_manager::TransactionId;
 use crate::actor::reactor::{
-    Command, DisplaySelector, Reactor, ReactorCommand, WorkspaceSwitchOrigin,
+    Command, DisplaySelector, Reactor, ReactorCommand, RefocusState, WindowFilter,
+    WorkspaceSwitchOrigin,
 };
 use crate::actor::stack_line::Event as StackLineEvent;
 use crate::actor::wm_controller::WmEvent;
 use crate::actor::{menu_bar, raise_manager};
-use crate::common::collections::HashMap;
+use crate::common::collections::{HashMap, HashSet};
 use crate::common::config::{self as config, Config};
 use crate::common::log::{MetricsCommand, handle_command};
-use crate::layout_engine::{EventResponse, LayoutCommand, LayoutEvent};
+use crate::layout_engine::{Direction, EventResponse, LayoutCommand, LayoutEvent};
 use crate::sys::window_server::{self as window_server, WindowServerId};
 
 pub struct CommandEventHandler;
@@ -38,12 +43,21 @@ impl CommandEventHandler {
     }
 
     pub fn handle_command_layout(reactor: &mut Reactor, cmd: LayoutCommand) {
+        Self::handle_command_layout_with_origin(reactor, cmd, WorkspaceSwitchOrigin::Manual);
+    }
+
+    fn handle_command_layout_with_origin(
+        reactor: &mut Reactor,
+        cmd: LayoutCommand,
+        origin: WorkspaceSwitchOrigin,
+    ) {
         info!(?cmd);
         let is_workspace_switch = matches!(
             cmd,
             LayoutCommand::NextWorkspace(_)
                 | LayoutCommand::PrevWorkspace(_)
                 | LayoutCommand::SwitchToWorkspace(_)
+                | LayoutCommand::SwitchToWorkspaceNamed { .. }
                 | LayoutCommand::SwitchToLastWorkspace
         );
         let requires_workspace_space = matches!(
@@ -51,8 +65,12 @@ impl CommandEventHandler {
             LayoutCommand::NextWorkspace(_)
                 | LayoutCommand::PrevWorkspace(_)
                 | LayoutCommand::SwitchToWorkspace(_)
+                | LayoutCommand::SwitchToWorkspaceNamed { .. }
                 | LayoutCommand::SetWorkspaceLayout { .. }
                 | LayoutCommand::CreateWorkspace
+                | LayoutCommand::EnsureWorkspaces { .. }
+                | LayoutCommand::DeleteWorkspace { .. }
+                | LayoutCommand::MoveWorkspace { .. }
                 | LayoutCommand::SwitchToLastWorkspace
         );
         let command_space = reactor.workspace_command_space();
@@ -64,20 +82,28 @@ impl CommandEventHandler {
         } else {
             None
         };
+        let switch_from = workspace_space.filter(|_| is_workspace_switch).map(|space| {
+            (
+                space,
+                reactor.layout_manager.layout_engine.active_workspace(space),
+            )
+        });
         if is_workspace_switch {
-            reactor
-                .workspace_switch_manager
-                .start_workspace_switch(WorkspaceSwitchOrigin::Manual);
+            reactor.workspace_switch_manager.start_workspace_switch(origin);
         } else {
             reactor.workspace_switch_manager.mark_workspace_switch_inactive();
         }
 
-        let response = match &cmd {
+        let mut response = match &cmd {
             LayoutCommand::NextWorkspace(_)
             | LayoutCommand::PrevWorkspace(_)
             | LayoutCommand::SwitchToWorkspace(_)
+            | LayoutCommand::SwitchToWorkspaceNamed { .. }
             | LayoutCommand::SetWorkspaceLayout { .. }
             | LayoutCommand::CreateWorkspace
+            | LayoutCommand::EnsureWorkspaces { .. }
+            | LayoutCommand::DeleteWorkspace { .. }
+            | LayoutCommand::MoveWorkspace { .. }
             | LayoutCommand::SwitchToLastWorkspace => {
                 if let Some(space) = workspace_space {
                     reactor
@@ -88,7 +114,9 @@ impl CommandEventHandler {
                     EventResponse::default()
                 }
             }
-            LayoutCommand::MoveWindowToWorkspace { .. } => {
+            LayoutCommand::MoveWindowToWorkspace { .. }
+            | LayoutCommand::MoveWindowToLastWorkspace
+            | LayoutCommand::GatherAppWindows => {
                 if let Some(space) = command_space {
                     reactor
                         .layout_manager
@@ -98,6 +126,83 @@ impl CommandEventHandler {
                     EventResponse::default()
                 }
             }
+            LayoutCommand::CycleFloatingPreset => {
+                let screen = command_space.and_then(|space| {
+                    reactor.space_manager.screen_by_space(space).map(|screen| (space, screen.frame))
+                });
+                if let Some((space, frame)) = screen {
+                    reactor.layout_manager.layout_engine.cycle_floating_preset(space, frame)
+                } else {
+                    EventResponse::default()
+                }
+            }
+            LayoutCommand::DockFloatingToEdge(direction) => {
+                let screen = command_space.and_then(|space| {
+                    reactor.space_manager.screen_by_space(space).map(|screen| (space, screen.frame))
+                });
+                if let Some((space, frame)) = screen {
+                    reactor
+                        .layout_manager
+                        .layout_engine
+                        .dock_floating_to_edge(space, frame, direction)
+                } else {
+                    EventResponse::default()
+                }
+            }
+            LayoutCommand::CloseOtherWindows => {
+                if let Some(space) = command_space {
+                    Self::close_other_windows(reactor, space);
+                } else {
+                    warn!("Close other windows ignored: no active space");
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::ToggleAlwaysOnTop => {
+                Self::toggle_always_on_top(reactor);
+                EventResponse::default()
+            }
+            LayoutCommand::ToggleWindowManaged => {
+                Self::toggle_window_managed(reactor);
+                EventResponse::default()
+            }
+            LayoutCommand::SetStaleCleanup(enabled) => {
+                reactor.refocus_manager.stale_cleanup_enabled = enabled;
+                EventResponse::default()
+            }
+            LayoutCommand::PurgeStaleWindows => {
+                let purged = WindowDiscoveryHandler::purge_missing_windows(reactor);
+                info!(purged, "Purged stale windows");
+                EventResponse::default()
+            }
+            LayoutCommand::ToggleLayoutFreeze => {
+                let frozen = !reactor.layout_manager.frozen;
+                reactor.layout_manager.frozen = frozen;
+                info!(frozen, "Toggled layout freeze");
+                let _ = reactor
+                    .communication_manager
+                    .event_broadcaster
+                    .send(BroadcastEvent::LayoutFreezeChanged { frozen });
+                EventResponse::default()
+            }
+            LayoutCommand::ReconcileWorkspaces => {
+                if let Some(space) = command_space {
+                    Self::reconcile_workspaces(reactor, space);
+                } else {
+                    warn!("Reconcile workspaces ignored: no active space");
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::ToggleGaps => {
+                if let Some(space) = command_space {
+                    let gapless = &mut reactor.layout_manager.gapless_spaces;
+                    if !gapless.remove(&space) {
+                        gapless.insert(space);
+                    }
+                } else {
+                    warn!("Toggle gaps ignored: no active space");
+                }
+                EventResponse::default()
+            }
             _ => {
                 let (visible_spaces, visible_space_centers) =
                     reactor.visible_spaces_for_layout(false);
@@ -114,6 +219,14 @@ impl CommandEventHandler {
             }
         };
 
+        if let Some((space, from)) = switch_from {
+            reactor.record_workspace_switch(space, from, origin);
+        }
+        if origin == WorkspaceSwitchOrigin::Gesture {
+            // Boundary hits are turned into gestures themselves; forwarding one
+            // from a gesture's own command could run the same binding forever.
+            response.boundary_hit = None;
+        }
         reactor.handle_layout_response(response, workspace_space);
         if requires_workspace_space {
             reactor.update_event_tap_layout_mode();
@@ -126,6 +239,8 @@ impl CommandEventHandler {
 
     pub fn handle_config_updated(reactor: &mut Reactor, new_cfg: Config) {
         let old_keys = reactor.config.keys.clone();
+        let disabled_displays_changed =
+            reactor.config.settings.disabled_displays != new_cfg.settings.disabled_displays;
 
         reactor.config = new_cfg;
         reactor
@@ -140,6 +255,10 @@ impl CommandEventHandler {
 
         reactor.drag_manager.update_config(reactor.config.settings.window_snapping);
 
+        if disabled_displays_changed {
+            reactor.recompute_and_set_active_spaces_from_current_screens();
+        }
+
         if let Some(tx) = &reactor.communication_manager.stack_line_tx {
             if let Err(e) = tx.try_send(StackLineEvent::ConfigUpdated(reactor.config.clone())) {
                 warn!("Failed to send config update to stack line: {}", e);
@@ -213,9 +332,103 @@ impl CommandEventHandler {
             ReactorCommand::MoveWindowToDisplay { selector, window_id } => {
                 Self::handle_command_reactor_move_window_to_display(reactor, &selector, window_id);
             }
+            ReactorCommand::SendToDisplayActiveWorkspace { selector, follow } => {
+                Self::handle_command_reactor_send_to_display_active_workspace(
+                    reactor, &selector, follow,
+                );
+            }
+            ReactorCommand::MirrorToDisplay { selector, placeholder } => {
+                Self::handle_command_reactor_mirror_to_display(reactor, &selector, placeholder);
+            }
+            ReactorCommand::ClearMirror => {
+                if let Some(wid) = reactor.main_window() {
+                    reactor.mirror_manager.unlink(wid);
+                }
+            }
+            ReactorCommand::MoveWindowBeside { target_wsid, direction } => {
+                Self::handle_command_reactor_move_window_beside(reactor, target_wsid, direction);
+            }
+            ReactorCommand::ReassignWindow { wsid } => {
+                Self::handle_command_reactor_reassign_window(reactor, wsid);
+            }
+            ReactorCommand::MatchWorkspaceOfCursorWindow => {
+                Self::handle_command_reactor_match_workspace_of_cursor_window(reactor);
+            }
+            ReactorCommand::SetNewWindowFocusSteal(enabled) => {
+                reactor.config.settings.new_window_focus_steal = enabled;
+                if enabled {
+                    reactor.window_manager.unfocused_new_windows.clear();
+                }
+            }
+            ReactorCommand::GestureSwitchWorkspace { next, skip_empty } => {
+                let cmd = if next {
+                    LayoutCommand::NextWorkspace(skip_empty)
+                } else {
+                    LayoutCommand::PrevWorkspace(skip_empty)
+                };
+                Self::handle_command_layout_with_origin(
+                    reactor,
+                    cmd,
+                    WorkspaceSwitchOrigin::Gesture,
+                );
+            }
+            ReactorCommand::Gesture(gesture) => {
+                match reactor.config.settings.gestures.bindings.get(&gesture).cloned() {
+                    Some(cmd) => Self::handle_command_layout_with_origin(
+                        reactor,
+                        cmd,
+                        WorkspaceSwitchOrigin::Gesture,
+                    ),
+                    None => debug!(?gesture, "no command bound to gesture"),
+                }
+            }
+            ReactorCommand::NextWorkspaceWithIndicator { skip_empty } => {
+                let space = reactor.workspace_command_space();
+                Self::handle_command_layout(reactor, LayoutCommand::NextWorkspace(skip_empty));
+                if let Some(space) = space {
+                    Self::show_workspace_indicator(reactor, space);
+                }
+            }
+            ReactorCommand::ToggleFloatingVisibility => {
+                Self::handle_command_reactor_toggle_floating_visibility(reactor);
+            }
+            ReactorCommand::ToggleCompactStacks => {
+                Self::handle_command_reactor_toggle_compact_stacks(reactor);
+            }
+            ReactorCommand::ApplyLayoutPreset { name, space } => {
+                Self::handle_command_reactor_apply_layout_preset(reactor, name, space);
+            }
+            ReactorCommand::FocusApp { bundle_id, launch } => {
+                Self::handle_command_reactor_focus_app(reactor, &bundle_id, launch);
+            }
+            ReactorCommand::PinAppToWorkspace { bundle_id, workspace_index } => {
+                reactor.app_manager.workspace_pins.insert(bundle_id, workspace_index);
+            }
+            ReactorCommand::UnpinApp { bundle_id } => {
+                reactor.app_manager.workspace_pins.remove(&bundle_id);
+            }
         }
     }
 
+    fn show_workspace_indicator(reactor: &Reactor, space: crate::sys::screen::SpaceId) {
+        let Some(tx) = &reactor.communication_manager.stack_line_tx else {
+            return;
+        };
+        let Some(screen) = reactor.space_manager.screen_by_space(space) else {
+            return;
+        };
+        let engine = &reactor.layout_manager.layout_engine;
+        let Some(workspace_id) = engine.active_workspace(space) else {
+            return;
+        };
+        let name = engine.workspace_name(space, workspace_id).unwrap_or_default();
+        let label = match engine.virtual_workspace_manager().active_workspace_idx(space) {
+            Some(index) => format!("{}  {}", index + 1, name),
+            None => name,
+        };
+        tx.send(StackLineEvent::ShowWorkspaceIndicator { screen: screen.frame, label });
+    }
+
     pub fn handle_command_reactor_serialize(reactor: &mut Reactor) {
         if let Ok(state) = reactor.serialize_state() {
             println!("{}", state);
@@ -294,6 +507,55 @@ impl CommandEventHandler {
         }
     }
 
+    pub fn handle_command_reactor_focus_app(reactor: &mut Reactor, bundle_id: &str, launch: bool) {
+        let pids: Vec<pid_t> = reactor
+            .app_manager
+            .apps
+            .iter()
+            .filter(|(_, app)| app.info.bundle_id.as_deref() == Some(bundle_id))
+            .map(|(pid, _)| *pid)
+            .collect();
+
+        if pids.is_empty() {
+            if !launch {
+                warn!(bundle_id, "Focus app ignored: app is not running");
+                return;
+            }
+            if let Err(e) = std::process::Command::new("open").arg("-b").arg(bundle_id).spawn() {
+                warn!(bundle_id, "Failed to launch app: {}", e);
+            }
+            return;
+        }
+
+        let mut candidates: Vec<WindowId> = reactor
+            .window_manager
+            .windows
+            .keys()
+            .copied()
+            .filter(|wid| pids.contains(&wid.pid) && reactor.window_is_standard(*wid))
+            .collect();
+        candidates.sort();
+        let Some(window_id) = reactor
+            .layout_manager
+            .layout_engine
+            .most_recently_focused(candidates.iter().copied())
+            .or_else(|| candidates.first().copied())
+        else {
+            warn!(bundle_id, "Focus app ignored: app has no windows");
+            return;
+        };
+
+        let Some(space) = reactor.best_space_for_window_id(window_id) else {
+            warn!(?window_id, "Focus app ignored: space unknown");
+            return;
+        };
+        reactor.switch_to_window_workspace(space, window_id, WorkspaceSwitchOrigin::Manual);
+
+        let window_server_id =
+            reactor.window_manager.windows.get(&window_id).and_then(|w| w.info.sys_id);
+        Self::handle_command_reactor_focus_window(reactor, window_id, window_server_id);
+    }
+
     fn focus_first_window_on_screen(reactor: &mut Reactor, screen: &ScreenInfo) -> bool {
         if let Some(space) = screen.space {
             let focus_target = reactor.last_focused_window_in_space(space).or_else(|| {
@@ -457,6 +719,178 @@ impl CommandEventHandler {
             return;
         }
 
+        let response = Self::move_window_to_screen(
+            reactor,
+            window_id,
+            source_space,
+            &target_screen,
+            target_space,
+        );
+
+        reactor.handle_layout_response(response, None);
+
+        let _ = reactor.update_layout_or_warn(false, false);
+    }
+
+    /// Move the focused window into the workspace currently active on the selected
+    /// display. Focus follows the window only when `follow` is set.
+    pub fn handle_command_reactor_send_to_display_active_workspace(
+        reactor: &mut Reactor,
+        selector: &DisplaySelector,
+        follow: bool,
+    ) {
+        if reactor.is_in_drag() {
+            warn!("Ignoring send-to-display-active-workspace while a drag is active");
+            return;
+        }
+        let Some(window_id) = reactor.main_window() else {
+            warn!("Send to display active workspace ignored because no window is focused");
+            return;
+        };
+        let Some(state) = reactor.window_manager.windows.get(&window_id) else {
+            return;
+        };
+        let (window_server_id, window_frame) = (state.info.sys_id, state.frame_monotonic);
+        let Some(source_space) = Self::assigned_space_for_window(reactor, window_id)
+            .or_else(|| reactor.best_space_for_window(&window_frame, window_server_id))
+        else {
+            warn!(
+                ?window_id,
+                "Send to display active workspace ignored: source space unknown"
+            );
+            return;
+        };
+
+        let origin_point = reactor
+            .space_manager
+            .screen_by_space(source_space)
+            .map(|s| s.frame.mid())
+            .or_else(|| reactor.current_screen_center());
+        let Some(target_screen) = reactor.screen_for_selector(selector, origin_point).cloned()
+        else {
+            warn!(
+                ?selector,
+                "Send to display active workspace ignored: display not found"
+            );
+            return;
+        };
+        let Some(target_space) =
+            target_screen.space.filter(|space| reactor.is_space_active(*space))
+        else {
+            warn!(
+                ?selector,
+                "Send to display active workspace ignored: display has no active space"
+            );
+            return;
+        };
+        if target_space == source_space {
+            return;
+        }
+        if reactor.layout_manager.layout_engine.active_workspace(target_space).is_none() {
+            warn!(
+                ?selector,
+                ?target_space,
+                "Send to display active workspace ignored: display has no active workspace"
+            );
+            return;
+        }
+
+        let mut response = Self::move_window_to_screen(
+            reactor,
+            window_id,
+            source_space,
+            &target_screen,
+            target_space,
+        );
+        if !follow {
+            // Leave the window on the other display and hand focus back to whatever
+            // remains on the source workspace.
+            response.focus_window = None;
+            response.raise_windows.clear();
+            reactor.refocus_manager.refocus_state = RefocusState::Pending(source_space);
+        }
+
+        reactor.handle_layout_response(response, None);
+
+        let _ = reactor.update_layout_or_warn(false, false);
+    }
+
+    pub fn handle_command_reactor_mirror_to_display(
+        reactor: &mut Reactor,
+        selector: &DisplaySelector,
+        placeholder: WindowServerId,
+    ) {
+        let Some(source) = reactor.main_window() else {
+            warn!("Mirror to display ignored because no window is focused");
+            return;
+        };
+        let Some(placeholder) = reactor.window_manager.window_ids.get(&placeholder).copied() else {
+            warn!(
+                ?placeholder,
+                "Mirror to display ignored: unknown placeholder window"
+            );
+            return;
+        };
+        if placeholder == source {
+            warn!("Mirror to display ignored: a window can't mirror itself");
+            return;
+        }
+        let origin_point = reactor
+            .best_space_for_window_id(source)
+            .and_then(|space| reactor.space_manager.screen_by_space(space))
+            .map(|screen| screen.frame.mid());
+        let Some(target_screen) = reactor.screen_for_selector(selector, origin_point).cloned()
+        else {
+            warn!(?selector, "Mirror to display ignored: display not found");
+            return;
+        };
+        let Some(target_space) =
+            target_screen.space.filter(|space| reactor.is_space_active(*space))
+        else {
+            warn!(
+                ?selector,
+                "Mirror to display ignored: display has no active space"
+            );
+            return;
+        };
+
+        if let Some(placeholder_space) = Self::assigned_space_for_window(reactor, placeholder)
+            && placeholder_space != target_space
+        {
+            let mut response = Self::move_window_to_screen(
+                reactor,
+                placeholder,
+                placeholder_space,
+                &target_screen,
+                target_space,
+            );
+            // Focus stays on the mirrored window.
+            response.focus_window = None;
+            reactor.handle_layout_response(response, None);
+        }
+
+        reactor.mirror_manager.link(MirrorLink {
+            source,
+            placeholder,
+            target_display_uuid: target_screen.display_uuid.clone(),
+        });
+        reactor.sync_mirrors(source);
+    }
+
+    /// Center `window_id` on `target_screen` and hand it to the active workspace of
+    /// `target_space`.
+    fn move_window_to_screen(
+        reactor: &mut Reactor,
+        window_id: WindowId,
+        source_space: crate::sys::screen::SpaceId,
+        target_screen: &ScreenInfo,
+        target_space: crate::sys::screen::SpaceId,
+    ) -> EventResponse {
+        let Some(state) = reactor.window_manager.windows.get(&window_id) else {
+            return EventResponse::default();
+        };
+        let (window_server_id, window_frame) = (state.info.sys_id, state.frame_monotonic);
+
         let mut target_frame = window_frame;
         let size = window_frame.size;
         let dest_rect = target_screen.frame;
@@ -494,18 +928,325 @@ impl CommandEventHandler {
             state.frame_monotonic = target_frame;
         }
 
-        let response = reactor.layout_manager.layout_engine.move_window_to_space(
+        reactor.layout_manager.layout_engine.move_window_to_space(
             source_space,
             target_space,
             target_screen.frame.size,
             window_id,
-        );
+        )
+    }
+
+    pub fn handle_command_reactor_reassign_window(reactor: &mut Reactor, wsid: WindowServerId) {
+        let Some(window_id) = reactor.window_manager.window_ids.get(&wsid).copied() else {
+            warn!(?wsid, "Reassign window ignored: unknown window");
+            return;
+        };
+        let Some(space) = reactor.best_space_for_window_id(window_id) else {
+            return;
+        };
+        let Some(current) = reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager()
+            .workspace_for_window(space, window_id)
+        else {
+            debug!(?window_id, "Reassign window ignored: window has no workspace");
+            return;
+        };
+        let Some(app_info) =
+            reactor.app_manager.apps.get(&window_id.pid).map(|app| app.info.clone())
+        else {
+            return;
+        };
+
+        let target = {
+            let window = reactor.window_manager.windows.get(&window_id);
+            reactor
+                .layout_manager
+                .layout_engine
+                .virtual_workspace_manager_mut()
+                .auto_assignment_target(
+                    space,
+                    app_info.bundle_id.as_deref(),
+                    app_info.localized_name.as_deref(),
+                    window.map(|w| w.info.title.as_str()),
+                    window.and_then(|w| w.info.ax_role.as_deref()),
+                    window.and_then(|w| w.info.ax_subrole.as_deref()),
+                )
+        };
+        let target = match target {
+            Ok(Some(target)) => target,
+            Ok(None) => {
+                debug!(
+                    ?window_id,
+                    "Reassign window ignored: app rules leave it unmanaged"
+                );
+                return;
+            }
+            Err(e) => {
+                warn!(?window_id, "Failed to resolve workspace for window: {:?}", e);
+                return;
+            }
+        };
+        if target == current {
+            return;
+        }
+
+        let response = reactor
+            .layout_manager
+            .layout_engine
+            .move_window_to_workspace_id(space, window_id, target);
+        reactor.handle_layout_response(response, None);
+    }
+
+    pub fn handle_command_reactor_match_workspace_of_cursor_window(reactor: &mut Reactor) {
+        if reactor.is_in_drag() {
+            warn!("Ignoring match-workspace-of-cursor-window while a drag is active");
+            return;
+        }
+        let Some(window_id) = reactor.main_window() else {
+            warn!("Match workspace of cursor window ignored because no window is focused");
+            return;
+        };
+        let Some(target) = reactor.window_id_under_cursor().filter(|&wid| wid != window_id) else {
+            return;
+        };
+        let (Some(source_space), Some(target_space)) = (
+            Self::assigned_space_for_window(reactor, window_id),
+            Self::assigned_space_for_window(reactor, target),
+        ) else {
+            debug!(
+                ?window_id,
+                ?target,
+                "Match workspace of cursor window ignored: window is not managed"
+            );
+            return;
+        };
+        let vwm = reactor.layout_manager.layout_engine.virtual_workspace_manager();
+        let Some(target_workspace) = vwm.workspace_for_window(target_space, target) else {
+            return;
+        };
+
+        let response = if source_space == target_space {
+            if vwm.workspace_for_window(source_space, window_id) == Some(target_workspace) {
+                return;
+            }
+            reactor.layout_manager.layout_engine.move_window_to_workspace_id(
+                source_space,
+                window_id,
+                target_workspace,
+            )
+        } else {
+            // A window under the cursor on another display is in that display's
+            // active workspace, which is where moving to its screen lands.
+            let Some(target_screen) = reactor.space_manager.screen_by_space(target_space).cloned()
+            else {
+                return;
+            };
+            Self::move_window_to_screen(
+                reactor,
+                window_id,
+                source_space,
+                &target_screen,
+                target_space,
+            )
+        };
+        reactor.handle_layout_response(response, None);
+        let _ = reactor.update_layout_or_warn(false, false);
+    }
 
+    pub fn handle_command_reactor_move_window_beside(
+        reactor: &mut Reactor,
+        target_wsid: WindowServerId,
+        direction: Direction,
+    ) {
+        if reactor.is_in_drag() {
+            warn!("Ignoring move-window-beside while a drag is active");
+            return;
+        }
+        let Some(target) = reactor.window_manager.window_ids.get(&target_wsid).copied() else {
+            warn!(?target_wsid, "Move window beside ignored: unknown target window");
+            return;
+        };
+        let Some(window_id) = reactor.main_window() else {
+            warn!("Move window beside ignored because no window is focused");
+            return;
+        };
+        let response = reactor
+            .layout_manager
+            .layout_engine
+            .move_window_beside(window_id, target, direction);
         reactor.handle_layout_response(response, None);
+    }
 
+    pub fn handle_command_reactor_toggle_floating_visibility(reactor: &mut Reactor) {
+        let Some(space) = reactor.workspace_command_space() else {
+            warn!("Toggle floating visibility ignored: no active space");
+            return;
+        };
+        let response = reactor.layout_manager.layout_engine.cycle_window_visibility(space);
+        reactor.handle_layout_response(response, None);
+    }
+
+    pub fn handle_command_reactor_toggle_compact_stacks(reactor: &mut Reactor) {
+        let Some(space) = reactor.workspace_command_space() else {
+            warn!("Toggle compact stacks ignored: no active space");
+            return;
+        };
+        reactor.layout_manager.layout_engine.toggle_compact_stacks(space);
         let _ = reactor.update_layout_or_warn(false, false);
     }
 
+    pub fn handle_command_reactor_apply_layout_preset(
+        reactor: &mut Reactor,
+        name: Option<String>,
+        space: Option<crate::sys::screen::SpaceId>,
+    ) {
+        let Some(space) = space.or_else(|| reactor.workspace_command_space()) else {
+            warn!("Apply layout preset ignored: no active space");
+            return;
+        };
+        let settings = match name {
+            Some(name) => match reactor.config.settings.layout_presets.get(&name) {
+                Some(settings) => Some(settings.clone()),
+                None => {
+                    warn!("Apply layout preset ignored: unknown preset {name:?}");
+                    return;
+                }
+            },
+            None => None,
+        };
+        reactor.layout_manager.layout_engine.set_space_layout_settings(space, settings);
+        let _ = reactor.update_layout_or_warn(false, false);
+    }
+
+    pub fn close_other_windows(reactor: &mut Reactor, space: crate::sys::screen::SpaceId) {
+        let engine = &reactor.layout_manager.layout_engine;
+        let windows = engine.windows_in_active_workspace(space);
+        // Without a focused window in this workspace there is nothing to keep,
+        // and closing everything is never what was meant.
+        let Some(focused) = reactor.main_window().filter(|wid| windows.contains(wid)) else {
+            warn!("Close other windows ignored: focused window is not in the active workspace");
+            return;
+        };
+        let targets: Vec<WindowId> = windows
+            .into_iter()
+            .filter(|&wid| wid != focused)
+            .filter(|&wid| {
+                let is_standard =
+                    reactor.window_manager.windows.get(&wid).is_some_and(|w| w.info.is_standard);
+                is_standard || !engine.is_window_floating(wid)
+            })
+            .collect();
+        for wid in targets {
+            reactor.request_close_window(wid);
+        }
+    }
+
+    fn toggle_window_managed(reactor: &mut Reactor) {
+        let Some(wid) = reactor.main_window() else {
+            warn!("Toggle window managed ignored: no focused window");
+            return;
+        };
+        let Some(app_info) = reactor.app_manager.apps.get(&wid.pid).map(|app| app.info.clone())
+        else {
+            return;
+        };
+        if !reactor.window_manager.manually_unmanaged.remove(&wid) {
+            reactor.window_manager.manually_unmanaged.insert(wid);
+        }
+        reactor.process_windows_for_app_rules(wid.pid, vec![wid], app_info);
+    }
+
+    fn toggle_always_on_top(reactor: &mut Reactor) {
+        let Some(wid) = reactor.main_window() else {
+            return;
+        };
+        if reactor.window_manager.always_on_top.remove(&wid) {
+            if let Some(wsid) = reactor.window_manager.windows.get(&wid).and_then(|w| w.info.sys_id)
+            {
+                window_server::set_window_level(wsid, NSNormalWindowLevel);
+            }
+            return;
+        }
+        if !reactor.layout_manager.layout_engine.is_window_floating(wid) {
+            warn!(?wid, "Always on top ignored: window is not floating");
+            return;
+        }
+        // Later raises and layout changes re-assert the level when finalized.
+        if let Some(wsid) = reactor.window_manager.windows.get(&wid).and_then(|w| w.info.sys_id)
+            && !window_server::set_window_level(wsid, NSFloatingWindowLevel)
+        {
+            warn!(?wid, "Failed to raise always-on-top window level");
+        }
+        reactor.window_manager.always_on_top.insert(wid);
+        reactor.raise_window(wid, Quiet::Yes, None);
+    }
+
+    fn reconcile_workspaces(reactor: &mut Reactor, space: crate::sys::screen::SpaceId) {
+        let mut windows_by_pid: HashMap<pid_t, Vec<WindowId>> = HashMap::default();
+        for (&wid, state) in &reactor.window_manager.windows {
+            if state.matches_filter(WindowFilter::Manageable)
+                && reactor.best_space_for_window_state(state) == Some(space)
+            {
+                windows_by_pid.entry(wid.pid).or_default().push(wid);
+            }
+        }
+        let workspace_of = |reactor: &Reactor, wid| {
+            reactor
+                .layout_manager
+                .layout_engine
+                .virtual_workspace_manager()
+                .workspace_for_window(space, wid)
+        };
+        let before: HashMap<WindowId, _> = windows_by_pid
+            .values()
+            .flatten()
+            .map(|&wid| (wid, workspace_of(reactor, wid)))
+            .collect();
+        for (pid, mut window_ids) in windows_by_pid {
+            let Some(app_info) = reactor.app_manager.apps.get(&pid).map(|app| app.info.clone())
+            else {
+                continue;
+            };
+            window_ids.sort_unstable();
+            reactor.process_windows_for_app_rules(pid, window_ids, app_info);
+        }
+
+        // Assignments for windows that no longer exist only confuse the trees.
+        let mut stale: Vec<WindowId> = reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager()
+            .window_to_workspace
+            .keys()
+            .filter(|(window_space, wid)| {
+                *window_space == space && !reactor.window_manager.windows.contains_key(wid)
+            })
+            .map(|&(_, wid)| wid)
+            .collect();
+        stale.sort_unstable();
+
+        let mut relocated: HashSet<WindowId> = before
+            .into_iter()
+            .filter(|&(wid, workspace)| workspace_of(reactor, wid) != workspace)
+            .map(|(wid, _)| wid)
+            .collect();
+        let pids: Vec<pid_t> = reactor.app_manager.apps.keys().copied().collect();
+        relocated.extend(
+            reactor
+                .layout_manager
+                .layout_engine
+                .reconcile_workspace_trees(space, pids, &stale),
+        );
+        info!(
+            ?space,
+            relocated = relocated.len(),
+            stale = stale.len(),
+            "Reconciled workspace trees with workspace assignments"
+        );
+    }
+
     pub fn handle_command_reactor_close_window(
         reactor: &mut Reactor,
         window_server_id: Option<WindowServerId>,
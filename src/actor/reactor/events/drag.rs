@@ -8,6 +8,7 @@ pub struct DragEventHandler;
 impl DragEventHandler {
     pub fn handle_mouse_up(reactor: &mut Reactor) {
         let mut need_layout_refresh = false;
+        let mut swapped = false;
 
         let pending_swap = reactor.get_pending_drag_swap();
 
@@ -50,10 +51,11 @@ impl DragEventHandler {
                 reactor.handle_layout_response(response, None);
 
                 need_layout_refresh = true;
+                swapped = true;
             }
         }
 
-        let finalize_needs_layout = reactor.finalize_active_drag();
+        let finalize_needs_layout = reactor.finalize_active_drag(swapped);
 
         reactor.drag_manager.reset();
         reactor.drag_manager.drag_state = DragState::Inactive;
@@ -6,8 +6,8 @@ use tracing::{debug, info, trace, warn};
 
 use crate::actor::app::Request;
 use crate::actor::reactor::{
-    Event, FullscreenSpaceTrack, FullscreenWindowTrack, LayoutEvent, PendingSpaceChange, Reactor,
-    ScreenInfo, StaleCleanupState,
+    Event, FullscreenSpaceTrack, FullscreenWindowTrack, LayoutEvent, Reactor, ScreenInfo,
+    StaleCleanupState,
 };
 use crate::actor::wm_controller::WmEvent;
 use crate::common::collections::{HashMap, HashSet};
@@ -326,15 +326,34 @@ impl SpaceEventHandler {
     }
 
     pub fn handle_space_changed(reactor: &mut Reactor, mut spaces: Vec<Option<SpaceId>>) {
-        // Also drop any space update that reports more spaces than screens; these are
-        // transient and can reorder active workspaces across displays.
-        if spaces.len() > reactor.space_manager.screens.len() {
-            warn!(
-                "Dropping oversize spaces vector (screens={}, spaces_len={})",
-                reactor.space_manager.screens.len(),
-                spaces.len()
-            );
-            return;
+        // A vector that doesn't match the screen count (e.g. right after a dock is
+        // plugged in, before the new screens are reported) would be zipped onto the
+        // wrong displays. Hold it until the screens catch up or it expires.
+        let screen_count = reactor.space_manager.screens.len();
+        if spaces.len() != screen_count {
+            if spaces.iter().any(|space| space.is_some()) {
+                warn!(
+                    "Deferring space change: have {} screens but {} spaces",
+                    screen_count,
+                    spaces.len()
+                );
+                reactor.defer_space_change(spaces);
+                return;
+            }
+            // All-`None` updates that report more spaces than screens are transient
+            // and can reorder active workspaces across displays.
+            if spaces.len() > screen_count {
+                warn!(
+                    "Dropping oversize spaces vector (screens={}, spaces_len={})",
+                    screen_count,
+                    spaces.len()
+                );
+                return;
+            }
+        } else {
+            // This vector is newer than anything still deferred, even if it turns
+            // out to be a duplicate of what is applied.
+            reactor.pending_space_change_manager.pending_space_change = None;
         }
 
         // NSWorkspace can emit repeated ActiveDisplay notifications with an unchanged
@@ -348,26 +367,13 @@ impl SpaceEventHandler {
             return;
         }
 
-        // If a topology change is in-flight, ignore space updates that don't match the
-        // current screen count; wait for the matching vector before applying changes.
-        if reactor.pending_space_change_manager.topology_relayout_pending
-            && spaces.len() != reactor.space_manager.screens.len()
-        {
-            warn!(
-                "Dropping space change during topology change (screens={}, spaces_len={})",
-                reactor.space_manager.screens.len(),
-                spaces.len()
-            );
-            return;
-        }
         // TODO: this logic is flawed if multiple spaces are changing at once
         if reactor.handle_fullscreen_space_transition(&mut spaces) {
             return;
         }
         if reactor.is_mission_control_active() {
             // dont process whilst mc is active
-            reactor.pending_space_change_manager.pending_space_change =
-                Some(PendingSpaceChange { spaces });
+            reactor.defer_space_change(spaces);
             return;
         }
         let spaces_all_none = spaces.iter().all(|space| space.is_none());
@@ -379,15 +385,6 @@ impl SpaceEventHandler {
             reactor.recompute_and_set_active_spaces(&spaces);
             return;
         }
-        if spaces.len() != reactor.space_manager.screens.len() {
-            warn!(
-                "Ignoring space change: have {} screens but {} spaces",
-                reactor.space_manager.screens.len(),
-                spaces.len()
-            );
-            return;
-        }
-
         update_stale_cleanup_state(reactor, false);
 
         let cfg = reactor.activation_cfg();
@@ -456,12 +453,19 @@ fn record_fullscreen_window(
         Entry::Vacant(v) => v.insert(FullscreenSpaceTrack::default()),
     };
 
+    let newly_tracked =
+        window_id.is_some_and(|wid| !entry.windows.iter().any(|w| w.window_id == Some(wid)));
+
     entry.windows.push(FullscreenWindowTrack {
         pid,
         window_id,
         last_known_user_space,
         _last_seen_fullscreen_space: sid,
     });
+
+    if newly_tracked && let Some(wid) = window_id {
+        reactor.broadcast_window_fullscreen_changed(wid, sid, true);
+    }
 }
 
 fn request_visible_windows(reactor: &Reactor, pid: i32, context: &str) {
@@ -1,3 +1,5 @@
+use std::time::{Duration, Instant};
+
 use objc2_core_foundation::CGRect;
 use tracing::{debug, trace, warn};
 
@@ -33,14 +35,16 @@ impl WindowEventHandler {
             reactor.window_server_info_manager.window_server_info.insert(info.id, info);
         }
 
-        let frame = window.frame;
         let mut window_state: WindowState = window.into();
+        window_state.is_multi_space = utils::window_is_multi_space(window_state.info.sys_id);
         let is_manageable = utils::compute_window_manageability(
             window_state.info.sys_id,
             window_state.info.is_minimized,
             window_state.info.is_standard,
             window_state.info.is_root,
             &reactor.window_server_info_manager.window_server_info,
+            reactor.config.settings.missing_window_server_id,
+            reactor.config.settings.multi_space_windows,
         );
         window_state.is_manageable = is_manageable;
         if let Some(wsid) = window_state.info.sys_id {
@@ -51,21 +55,16 @@ impl WindowEventHandler {
             );
         }
 
-        let server_id = window_state.info.sys_id;
         reactor.window_manager.windows.insert(wid, window_state);
+        reactor.note_new_windows([wid]);
 
         if is_manageable {
-            let active_space = active_space_for_window(reactor, &frame, server_id);
-            if let Some(space) = active_space {
-                if let Some(app_info) =
-                    reactor.app_manager.apps.get(&wid.pid).map(|app| app.info.clone())
-                {
-                    if let Some(wsid) = server_id {
-                        reactor.app_manager.mark_wsids_recent(std::iter::once(wsid));
-                    }
-                    reactor.process_windows_for_app_rules(wid.pid, vec![wid], app_info);
-                }
-                maybe_dispatch_window_added_in_space(reactor, wid, space);
+            if reactor.workspace_switch_manager.active_workspace_switch.is_some() {
+                // Which workspace counts as active is still in flux until the
+                // switch's frame changes settle; place the window after that.
+                reactor.workspace_switch_manager.pending_new_windows.push(wid);
+            } else {
+                Self::place_new_window(reactor, wid);
             }
         }
         // TODO: drag state is maybe managed by ensure_active_drag
@@ -74,6 +73,31 @@ impl WindowEventHandler {
         // }
     }
 
+    /// Apply app rules to a newly created window and add it to the layout of
+    /// the active space it appeared on.
+    pub(crate) fn place_new_window(reactor: &mut Reactor, wid: WindowId) {
+        let Some(window) = reactor.window_manager.windows.get(&wid) else {
+            return;
+        };
+        let frame = window.frame_monotonic;
+        let server_id = window.info.sys_id;
+        let Some(space) = active_space_for_window(reactor, &frame, server_id) else {
+            return;
+        };
+        if let Some(app_info) = reactor.app_manager.apps.get(&wid.pid).map(|app| app.info.clone()) {
+            if let Some(wsid) = server_id {
+                reactor.app_manager.mark_wsids_recent(std::iter::once(wsid));
+            }
+            reactor.process_windows_for_app_rules(wid.pid, vec![wid], app_info);
+        }
+        let anchor = if reactor.config.settings.layout.insert_at_cursor {
+            reactor.window_id_under_cursor().filter(|&anchor| anchor != wid)
+        } else {
+            None
+        };
+        maybe_dispatch_window_added_in_space(reactor, wid, space, anchor);
+    }
+
     pub fn handle_window_destroyed(reactor: &mut Reactor, wid: WindowId) -> bool {
         let window_server_id = match reactor.window_manager.windows.get(&wid) {
             Some(window) => window.info.sys_id,
@@ -88,6 +112,8 @@ impl WindowEventHandler {
             debug!(?wid, "Received WindowDestroyed for unknown window - ignoring");
         }
         reactor.window_manager.windows.remove(&wid);
+        reactor.window_manager.manually_unmanaged.remove(&wid);
+        reactor.mirror_manager.unlink(wid);
         reactor.send_layout_event(LayoutEvent::WindowRemoved(wid));
 
         if let DragState::PendingSwap { session, target } = &reactor.drag_manager.drag_state {
@@ -160,6 +186,8 @@ impl WindowEventHandler {
             is_ax_standard,
             is_ax_root,
             &reactor.window_server_info_manager.window_server_info,
+            reactor.config.settings.missing_window_server_id,
+            reactor.config.settings.multi_space_windows,
         );
         if let Some(window) = reactor.window_manager.windows.get_mut(&wid) {
             window.is_manageable = is_manageable;
@@ -168,7 +196,7 @@ impl WindowEventHandler {
         if is_manageable {
             let active_space = active_space_for_window(reactor, &frame, server_id);
             if let Some(space) = active_space {
-                maybe_dispatch_window_added_in_space(reactor, wid, space);
+                maybe_dispatch_window_added_in_space(reactor, wid, space, None);
             }
         }
     }
@@ -373,7 +401,7 @@ impl WindowEventHandler {
                                     );
                                 }
                             }
-                            reactor.send_layout_event(LayoutEvent::WindowAdded(space, wid));
+                            reactor.send_layout_event(LayoutEvent::WindowAdded(space, wid, None));
                         }
                     }
                     let _ = reactor.update_layout_or_warn(false, false);
@@ -405,6 +433,9 @@ impl WindowEventHandler {
             false
         })();
         handle_mouse_up_if_needed(reactor, effective_mouse_state);
+        if reactor.mirror_manager.is_source(wid) {
+            reactor.sync_mirrors(wid);
+        }
         result
     }
 
@@ -415,12 +446,36 @@ impl WindowEventHandler {
                 return;
             }
             window.info.title = new_title.clone();
+            let was_untitled = previous_title.trim().is_empty();
             reactor.broadcast_window_title_changed(wid, previous_title, new_title);
-            reactor.maybe_reapply_app_rules_for_window(wid);
+            reactor.maybe_reapply_app_rules_for_window(wid, was_untitled);
         }
     }
 
     pub fn handle_mouse_moved_over_window(reactor: &mut Reactor, wsid: WindowServerId) {
+        let dwell = Duration::from_millis(reactor.config.settings.focus_follows_mouse_delay_ms);
+        if !dwell.is_zero() {
+            reactor.refocus_manager.pending_mouse_focus = Some((wsid, Instant::now() + dwell));
+            return;
+        }
+        Self::focus_window_under_mouse(reactor, wsid);
+    }
+
+    /// Focus `wsid` if the cursor is still resting on it after the dwell.
+    pub fn handle_mouse_hover_settled(reactor: &mut Reactor, wsid: WindowServerId) {
+        // The timer can fire for a dwell that a later move has since restarted.
+        if reactor
+            .refocus_manager
+            .pending_mouse_focus
+            .is_none_or(|(hovered, deadline)| hovered != wsid || Instant::now() < deadline)
+        {
+            return;
+        }
+        reactor.refocus_manager.pending_mouse_focus = None;
+        Self::focus_window_under_mouse(reactor, wsid);
+    }
+
+    fn focus_window_under_mouse(reactor: &mut Reactor, wsid: WindowServerId) {
         let Some(&wid) = reactor.window_manager.window_ids.get(&wsid) else {
             return;
         };
@@ -459,7 +514,12 @@ fn active_space_for_window(
     None
 }
 
-fn maybe_dispatch_window_added_in_space(reactor: &mut Reactor, wid: WindowId, space: SpaceId) {
+fn maybe_dispatch_window_added_in_space(
+    reactor: &mut Reactor,
+    wid: WindowId,
+    space: SpaceId,
+    anchor: Option<WindowId>,
+) {
     let should_dispatch = reactor
         .window_manager
         .windows
@@ -467,7 +527,7 @@ fn maybe_dispatch_window_added_in_space(reactor: &mut Reactor, wid: WindowId, sp
         .map(|window| window.matches_filter(WindowFilter::EffectivelyManageable))
         .unwrap_or(false);
     if should_dispatch {
-        reactor.send_layout_event(LayoutEvent::WindowAdded(space, wid));
+        reactor.send_layout_event(LayoutEvent::WindowAdded(space, wid, anchor));
     }
 }
 
@@ -42,6 +42,7 @@ impl WindowDiscoveryHandler {
             Self::identify_stale_windows(reactor, pid, &known_visible);
         Self::cleanup_stale_windows(reactor, pid, stale_windows, pending_refresh);
         let new_windows = Self::process_window_list(reactor, new, &app_info);
+        reactor.note_new_windows(new_windows.iter().map(|(wid, _)| *wid));
         Self::update_window_states(reactor, new_windows, &app_info);
 
         Self::emit_layout_events(reactor, pid, &known_visible, &app_info);
@@ -91,7 +92,8 @@ impl WindowDiscoveryHandler {
         let skip_stale_cleanup = matches!(
             reactor.refocus_manager.stale_cleanup_state,
             crate::actor::reactor::StaleCleanupState::Suppressed
-        ) || pending_refresh
+        ) || !reactor.refocus_manager.stale_cleanup_enabled
+            || pending_refresh
             || reactor.is_mission_control_active()
             || reactor.is_in_drag()
             || (known_visible_set.is_empty()
@@ -210,6 +212,26 @@ impl WindowDiscoveryHandler {
         }
     }
 
+    /// Drop every tracked window whose window server id no longer exists,
+    /// regardless of the conditions that gate automatic cleanup. Returns how
+    /// many windows were removed.
+    pub fn purge_missing_windows(reactor: &mut Reactor) -> usize {
+        let missing: Vec<WindowId> = reactor
+            .window_manager
+            .windows
+            .iter()
+            .filter_map(|(&wid, state)| {
+                let ws_id = state.info.sys_id?;
+                window_server::get_window(ws_id).is_none().then_some(wid)
+            })
+            .collect();
+        for &wid in &missing {
+            trace!(?wid, "Purging window whose window server id is gone");
+            reactor.handle_event(Event::WindowDestroyed(wid));
+        }
+        missing.len()
+    }
+
     /// Process new and updated windows, returning lists of new and updated windows.
     fn process_window_list(
         reactor: &mut Reactor,
@@ -246,6 +268,8 @@ impl WindowDiscoveryHandler {
                         info.is_standard,
                         info.is_root,
                         &reactor.window_server_info_manager.window_server_info,
+                        reactor.config.settings.missing_window_server_id,
+                        reactor.config.settings.multi_space_windows,
                     );
                     if let Some(existing) = reactor.window_manager.windows.get_mut(wid) {
                         existing.info.title = info.title.clone();
@@ -258,6 +282,9 @@ impl WindowDiscoveryHandler {
                         existing.info.is_resizable = info.is_resizable;
                         existing.info.min_size = info.min_size;
                         existing.info.max_size = info.max_size;
+                        if existing.info.sys_id != info.sys_id {
+                            existing.is_multi_space = utils::window_is_multi_space(info.sys_id);
+                        }
                         existing.info.sys_id = info.sys_id;
                         existing.info.bundle_id = info.bundle_id.clone();
                         existing.info.path = info.path.clone();
@@ -267,12 +294,15 @@ impl WindowDiscoveryHandler {
                     }
                 } else {
                     let mut state: WindowState = WindowState::from((*info).clone());
+                    state.is_multi_space = utils::window_is_multi_space(state.info.sys_id);
                     let manageable = utils::compute_window_manageability(
                         state.info.sys_id,
                         state.info.is_minimized,
                         state.info.is_standard,
                         state.info.is_root,
                         &reactor.window_server_info_manager.window_server_info,
+                        reactor.config.settings.missing_window_server_id,
+                        reactor.config.settings.multi_space_windows,
                     );
                     state.is_manageable = manageable;
                     reactor.window_manager.windows.insert(*wid, state);
@@ -294,6 +324,8 @@ impl WindowDiscoveryHandler {
                     info.is_standard,
                     info.is_root,
                     &reactor.window_server_info_manager.window_server_info,
+                    reactor.config.settings.missing_window_server_id,
+                    reactor.config.settings.multi_space_windows,
                 );
                 if let Some(existing) = reactor.window_manager.windows.get_mut(&wid) {
                     existing.info.title = info.title.clone();
@@ -306,6 +338,9 @@ impl WindowDiscoveryHandler {
                     existing.info.is_resizable = info.is_resizable;
                     existing.info.min_size = info.min_size;
                     existing.info.max_size = info.max_size;
+                    if existing.info.sys_id != info.sys_id {
+                        existing.is_multi_space = utils::window_is_multi_space(info.sys_id);
+                    }
                     existing.info.sys_id = info.sys_id;
                     existing.info.bundle_id = info.bundle_id.clone();
                     existing.info.path = info.path.clone();
@@ -331,12 +366,15 @@ impl WindowDiscoveryHandler {
         // Update or insert window states
         for (wid, info) in new_windows {
             let mut state: WindowState = info.into();
+            state.is_multi_space = utils::window_is_multi_space(state.info.sys_id);
             let manageable = utils::compute_window_manageability(
                 state.info.sys_id,
                 state.info.is_minimized,
                 state.info.is_standard,
                 state.info.is_root,
                 &reactor.window_server_info_manager.window_server_info,
+                reactor.config.settings.missing_window_server_id,
+                reactor.config.settings.multi_space_windows,
             );
             state.is_manageable = manageable;
             reactor.window_manager.windows.insert(wid, state);
@@ -350,6 +388,9 @@ impl WindowDiscoveryHandler {
         space: SpaceId,
         app_info: &Option<AppInfo>,
     ) -> Result<AppRuleResult, WorkspaceError> {
+        if reactor.window_manager.manually_unmanaged.contains(&wid) {
+            return Ok(AppRuleResult::Unmanaged);
+        }
         let Some(window) = reactor.window_manager.windows.get(&wid) else {
             return Err(WorkspaceError::AssignmentFailed);
         };
@@ -379,7 +420,7 @@ impl WindowDiscoveryHandler {
         assign_result: Result<AppRuleResult, WorkspaceError>,
     ) {
         match assign_result {
-            Ok(AppRuleResult::Managed(_)) => {
+            Ok(AppRuleResult::Managed(_)) | Ok(AppRuleResult::Deferred) => {
                 if let Some(window) = reactor.window_manager.windows.get_mut(&wid) {
                     window.ignore_app_rule = false;
                 }
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
 use objc2_core_foundation::{CGPoint, CGRect};
@@ -18,6 +20,7 @@ use crate::actor::{event_tap, menu_bar, raise_manager, stack_line, window_notify
 use crate::common::collections::{HashMap, HashSet};
 use crate::common::config::{LayoutMode, WindowSnappingSettings};
 use crate::layout_engine::LayoutEngine;
+use crate::model::server::{EventMetricsData, LayoutErrorData, WorkspaceSwitchData};
 use crate::sys::screen::SpaceId;
 use crate::sys::window_server::{WindowServerId, WindowServerInfo};
 
@@ -27,12 +30,25 @@ pub struct WindowManager {
     pub window_ids: HashMap<WindowServerId, WindowId>,
     pub visible_windows: HashSet<WindowServerId>,
     pub observed_window_server_ids: HashSet<WindowServerId>,
+    /// Windows currently dimmed by `inactive_dim`, with the alpha applied.
+    pub dimmed_windows: HashMap<WindowServerId, f64>,
+    /// Floating windows pinned above all others by `ToggleAlwaysOnTop`.
+    pub always_on_top: HashSet<WindowId>,
+    /// Windows unmanaged by `ToggleWindowManaged`. They are treated as if an
+    /// app rule unmanaged them, whatever the rules say, until toggled back.
+    pub manually_unmanaged: HashSet<WindowId>,
+    /// Windows created while new-window focus stealing was off, with their
+    /// creation time; their first focus change is handed back.
+    pub unfocused_new_windows: HashMap<WindowId, Instant>,
 }
 
 /// Manages application state and rules
 pub struct AppManager {
     pub apps: HashMap<pid_t, AppState>,
     pub app_rules_recent_targets: HashMap<crate::sys::window_server::WindowServerId, Instant>,
+    /// Workspace index new windows of a bundle id go to, set at runtime by
+    /// `PinAppToWorkspace`. Takes precedence over `app_rules` and is not saved.
+    pub workspace_pins: HashMap<String, usize>,
 }
 
 impl AppManager {
@@ -40,6 +56,7 @@ impl AppManager {
         AppManager {
             apps: HashMap::default(),
             app_rules_recent_targets: HashMap::default(),
+            workspace_pins: HashMap::default(),
         }
     }
 
@@ -142,9 +159,15 @@ pub struct WorkspaceSwitchManager {
     pub active_workspace_switch: Option<u64>,
     pub pending_workspace_switch_origin: Option<WorkspaceSwitchOrigin>,
     pub pending_workspace_mouse_warp: Option<WindowId>,
+    /// Windows created while a switch was active, placed once it finalizes.
+    pub pending_new_windows: Vec<WindowId>,
+    /// Most recent completed switches, oldest first.
+    pub switch_history: VecDeque<WorkspaceSwitchData>,
 }
 
 impl WorkspaceSwitchManager {
+    const HISTORY_LIMIT: usize = 256;
+
     pub fn start_workspace_switch(&mut self, origin: WorkspaceSwitchOrigin) {
         self.workspace_switch_generation = self.workspace_switch_generation.wrapping_add(1);
         self.active_workspace_switch = Some(self.workspace_switch_generation);
@@ -154,7 +177,17 @@ impl WorkspaceSwitchManager {
 
     pub fn manual_switch_in_progress(&self) -> bool {
         self.workspace_switch_state == WorkspaceSwitchState::Active
-            && self.pending_workspace_switch_origin == Some(WorkspaceSwitchOrigin::Manual)
+            && matches!(
+                self.pending_workspace_switch_origin,
+                Some(WorkspaceSwitchOrigin::Manual | WorkspaceSwitchOrigin::Gesture)
+            )
+    }
+
+    pub fn record_switch(&mut self, entry: WorkspaceSwitchData) {
+        if self.switch_history.len() >= Self::HISTORY_LIMIT {
+            self.switch_history.pop_front();
+        }
+        self.switch_history.push_back(entry);
     }
 
     pub fn mark_workspace_switch_inactive(&mut self) {
@@ -166,7 +199,12 @@ impl WorkspaceSwitchManager {
 /// Manages refocus and cleanup state
 pub struct RefocusManager {
     pub stale_cleanup_state: super::StaleCleanupState,
+    /// Set by `SetStaleCleanup`. Unlike `stale_cleanup_state`, screen changes
+    /// don't touch it.
+    pub stale_cleanup_enabled: bool,
     pub refocus_state: super::RefocusState,
+    /// Window the cursor is resting on and when focus-follows-mouse focuses it.
+    pub pending_mouse_focus: Option<(WindowServerId, Instant)>,
 }
 
 /// Manages communication channels to other actors
@@ -187,6 +225,14 @@ pub struct RecordingManager {
 /// Manages layout engine state
 pub struct LayoutManager {
     pub layout_engine: LayoutEngine,
+    /// The most recent failed layout update, kept for diagnostics.
+    pub last_error: Option<LayoutErrorData>,
+    /// Spaces laid out without gaps by `ToggleGaps`. A space is dropped once it
+    /// is no longer shown on any screen.
+    pub gapless_spaces: HashSet<SpaceId>,
+    /// Set by `ToggleLayoutFreeze`. Layout updates are skipped while frozen so
+    /// windows stay where they are.
+    pub frozen: bool,
 }
 
 pub type LayoutResult = Vec<(SpaceId, Vec<(WindowId, CGRect)>)>;
@@ -260,12 +306,7 @@ impl LayoutManager {
                 continue;
             }
             let display_uuid_opt = screen.display_uuid_owned();
-            let gaps = reactor
-                .config
-                .settings
-                .layout
-                .gaps
-                .effective_for_display(display_uuid_opt.as_deref());
+            let gaps = reactor.effective_gaps_for_space(space);
             reactor
                 .layout_manager
                 .layout_engine
@@ -298,6 +339,22 @@ impl LayoutManager {
                     &active_workspace_windows,
                 );
             }
+            if let Some(fullscreen) =
+                reactor.layout_manager.layout_engine.true_fullscreen_window(space)
+            {
+                // Displays the system doesn't know report empty bounds.
+                let bounds = screen.id.display_bounds();
+                let bounds = if bounds.size.width > 0.0 && bounds.size.height > 0.0 {
+                    bounds
+                } else {
+                    screen.frame
+                };
+                for (wid, rect) in layout.iter_mut() {
+                    if *wid == fullscreen {
+                        *rect = bounds;
+                    }
+                }
+            }
             layout_result.push((space, layout));
         }
 
@@ -324,12 +381,7 @@ impl LayoutManager {
             if let Some(screen) = reactor.space_manager.screen_by_space(space) {
                 let screen_frame = screen.frame;
                 let display_uuid = screen.display_uuid_owned();
-                let gaps = reactor
-                    .config
-                    .settings
-                    .layout
-                    .gaps
-                    .effective_for_display(display_uuid.as_deref());
+                let gaps = reactor.effective_gaps_for_space(space);
                 let active_workspace_for_space_has_fullscreen = active_space == Some(space)
                     && reactor
                         .layout_manager
@@ -348,8 +400,12 @@ impl LayoutManager {
                 if reactor.config.settings.ui.stack_line.enabled
                     && let Some(tx) = &reactor.communication_manager.stack_line_tx
                 {
+                    let engine = &reactor.layout_manager.layout_engine;
+                    let hide_stack_lines = engine.true_fullscreen_window(space).is_some()
+                        || engine.compact_stacks(space);
                     let groups: Vec<crate::actor::stack_line::GroupInfo> = group_infos
                         .iter()
+                        .filter(|_| !hide_stack_lines)
                         .map(|g| crate::actor::stack_line::GroupInfo {
                             node_id: g.node_id,
                             space_id: space,
@@ -437,6 +493,37 @@ pub struct PendingSpaceChangeManager {
     pub topology_relayout_pending: bool,
 }
 
+/// Counters describing the load on the reactor's event channel.
+#[derive(Default)]
+pub struct EventMetrics {
+    processed: AtomicU64,
+    quarantined: AtomicU64,
+    queue_depth: AtomicU64,
+    max_queue_depth: AtomicU64,
+}
+
+impl EventMetrics {
+    pub fn record_processed(&self) { self.processed.fetch_add(1, Ordering::Relaxed); }
+
+    pub fn record_quarantined(&self) { self.quarantined.fetch_add(1, Ordering::Relaxed); }
+
+    /// Record how many events were still queued after taking one.
+    pub fn record_queue_depth(&self, depth: usize) {
+        let depth = depth as u64;
+        self.queue_depth.store(depth, Ordering::Relaxed);
+        self.max_queue_depth.fetch_max(depth, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> EventMetricsData {
+        EventMetricsData {
+            processed: self.processed.load(Ordering::Relaxed),
+            quarantined: self.quarantined.load(Ordering::Relaxed),
+            queue_depth: self.queue_depth.load(Ordering::Relaxed),
+            max_queue_depth: self.max_queue_depth.load(Ordering::Relaxed),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use objc2_core_foundation::{CGPoint, CGRect, CGSize};
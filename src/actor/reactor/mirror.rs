@@ -0,0 +1,100 @@
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+
+use crate::actor::app::WindowId;
+
+/// A floating placeholder window kept at the same relative position and size on
+/// another display as its source window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MirrorLink {
+    pub source: WindowId,
+    pub placeholder: WindowId,
+    pub target_display_uuid: String,
+}
+
+#[derive(Debug, Default)]
+pub struct MirrorManager {
+    links: Vec<MirrorLink>,
+}
+
+impl MirrorManager {
+    /// Register `link`, replacing any link that already uses its source or
+    /// placeholder window.
+    pub fn link(&mut self, link: MirrorLink) {
+        self.unlink(link.source);
+        self.unlink(link.placeholder);
+        self.links.push(link);
+    }
+
+    /// Drop every link `wid` takes part in, as source or placeholder. Returns
+    /// whether anything was removed.
+    pub fn unlink(&mut self, wid: WindowId) -> bool {
+        let before = self.links.len();
+        self.links.retain(|link| link.source != wid && link.placeholder != wid);
+        self.links.len() != before
+    }
+
+    pub fn links_from(&self, source: WindowId) -> impl Iterator<Item = &MirrorLink> + '_ {
+        self.links.iter().filter(move |link| link.source == source)
+    }
+
+    pub fn is_source(&self, wid: WindowId) -> bool {
+        self.links.iter().any(|link| link.source == wid)
+    }
+}
+
+/// Map `frame`, given relative to the screen `from`, to the same relative
+/// position and size on the screen `to`.
+pub fn mirror_frame(frame: CGRect, from: CGRect, to: CGRect) -> CGRect {
+    if from.size.width <= 0.0 || from.size.height <= 0.0 {
+        return CGRect::new(to.origin, frame.size);
+    }
+    let scale_x = to.size.width / from.size.width;
+    let scale_y = to.size.height / from.size.height;
+    CGRect::new(
+        CGPoint::new(
+            to.origin.x + (frame.origin.x - from.origin.x) * scale_x,
+            to.origin.y + (frame.origin.y - from.origin.y) * scale_y,
+        ),
+        CGSize::new(frame.size.width * scale_x, frame.size.height * scale_y),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirror_frame_keeps_relative_position_and_size() {
+        let from = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let to = CGRect::new(CGPoint::new(1000.0, 100.0), CGSize::new(2000.0, 400.0));
+        let frame = CGRect::new(CGPoint::new(250.0, 200.0), CGSize::new(500.0, 400.0));
+
+        assert_eq!(
+            mirror_frame(frame, from, to),
+            CGRect::new(CGPoint::new(1500.0, 200.0), CGSize::new(1000.0, 200.0))
+        );
+    }
+
+    #[test]
+    fn links_are_replaced_and_dropped_by_either_window() {
+        let (source, placeholder, other) =
+            (WindowId::new(1, 1), WindowId::new(2, 1), WindowId::new(3, 1));
+        let mut manager = MirrorManager::default();
+        manager.link(MirrorLink {
+            source,
+            placeholder,
+            target_display_uuid: "A".into(),
+        });
+        manager.link(MirrorLink {
+            source: other,
+            placeholder,
+            target_display_uuid: "B".into(),
+        });
+        assert!(!manager.is_source(source));
+        assert_eq!(manager.links_from(other).count(), 1);
+
+        assert!(manager.unlink(placeholder));
+        assert!(!manager.is_source(other));
+        assert!(!manager.unlink(placeholder));
+    }
+}
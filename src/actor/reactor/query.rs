@@ -6,8 +6,12 @@ use crate::actor::app::WindowId;
 use crate::actor::menu_bar;
 use crate::actor::reactor::{Event, Reactor, Sender};
 use crate::common::collections::HashSet;
+use crate::layout_engine::LayoutCommand;
 use crate::model::server::{
-    ApplicationData, DisplayData, LayoutStateData, WindowData, WorkspaceData, WorkspaceLayoutData,
+    ApplicationData, DisplayData, DisplayTopologyData, DisplayTopologyEntryData,
+    DragSwapTargetData, EffectiveGapsData, FocusedWindowData, LayoutErrorData, LayoutStateData,
+    MetricsData, PreviewFrameData, RuleCoverageData, SelectionPathData, SelectionPathNodeData,
+    StaleCleanupData, WindowData, WorkspaceData, WorkspaceLayoutData, WorkspaceSwitchData,
 };
 use crate::model::virtual_workspace::VirtualWorkspaceId;
 use crate::sys::screen::{ScreenInfo, SpaceId, get_active_space_number, managed_display_space_ids};
@@ -31,8 +35,12 @@ impl ReactorQueryHandle {
         rx.recv().map_err(|_| RecvError)
     }
 
-    pub fn query_workspaces(&self, space_id: Option<SpaceId>) -> Vec<WorkspaceData> {
-        self.send_query(|resp| QueryRequest::Workspaces { space_id, resp })
+    pub fn query_workspaces(
+        &self,
+        space_id: Option<SpaceId>,
+        with_windows: bool,
+    ) -> Vec<WorkspaceData> {
+        self.send_query(|resp| QueryRequest::Workspaces { space_id, with_windows, resp })
             .unwrap_or_default()
     }
 
@@ -41,6 +49,15 @@ impl ReactorQueryHandle {
             .unwrap_or_default()
     }
 
+    pub fn query_windows_in_workspace(
+        &self,
+        space: Option<SpaceId>,
+        workspace_index: usize,
+    ) -> Result<Vec<WindowData>, String> {
+        self.send_query(|resp| QueryRequest::WindowsInWorkspace { space, workspace_index, resp })
+            .unwrap_or_else(|_| Err("Reactor did not respond".to_string()))
+    }
+
     pub fn query_active_workspace(&self, space_id: Option<SpaceId>) -> Option<VirtualWorkspaceId> {
         self.send_query(|resp| QueryRequest::ActiveWorkspace { space_id, resp })
             .ok()
@@ -76,8 +93,78 @@ impl ReactorQueryHandle {
             .flatten()
     }
 
-    pub fn query_metrics(&self) -> serde_json::Value {
-        self.send_query(QueryRequest::Metrics).unwrap_or_else(|_| serde_json::json!({}))
+    pub fn query_metrics(&self) -> Option<MetricsData> {
+        self.send_query(QueryRequest::Metrics).ok()
+    }
+
+    pub fn query_workspace_switch_history(&self) -> Vec<WorkspaceSwitchData> {
+        self.send_query(QueryRequest::WorkspaceSwitchHistory).unwrap_or_default()
+    }
+
+    pub fn query_effective_gaps(&self, space: Option<SpaceId>) -> Option<EffectiveGapsData> {
+        self.send_query(|resp| QueryRequest::EffectiveGaps { space, resp })
+            .ok()
+            .flatten()
+    }
+
+    pub fn query_windows_without_server_id(&self) -> Vec<WindowData> {
+        self.send_query(QueryRequest::WindowsWithoutServerId).unwrap_or_default()
+    }
+
+    pub fn query_focused_window(&self) -> Option<FocusedWindowData> {
+        self.send_query(QueryRequest::FocusedWindow).ok().flatten()
+    }
+
+    pub fn query_new_window_focus_steal(&self) -> Option<bool> {
+        self.send_query(QueryRequest::NewWindowFocusSteal).ok()
+    }
+
+    pub fn query_rule_coverage(&self) -> Vec<RuleCoverageData> {
+        self.send_query(QueryRequest::RuleCoverage).unwrap_or_default()
+    }
+
+    pub fn query_selection_path(&self, space: Option<SpaceId>) -> Option<SelectionPathData> {
+        self.send_query(|resp| QueryRequest::SelectionPath { space, resp })
+            .ok()
+            .flatten()
+    }
+
+    pub fn query_last_layout_error(&self) -> Option<LayoutErrorData> {
+        self.send_query(QueryRequest::LastLayoutError).ok().flatten()
+    }
+
+    pub fn query_stale_cleanup(&self) -> Option<StaleCleanupData> {
+        self.send_query(QueryRequest::StaleCleanup).ok()
+    }
+
+    pub fn query_drag_swap_target(&self) -> Option<DragSwapTargetData> {
+        self.send_query(QueryRequest::DragSwapTarget).ok().flatten()
+    }
+
+    pub fn query_display_topology(&self) -> Option<DisplayTopologyData> {
+        self.send_query(QueryRequest::DisplayTopology).ok()
+    }
+
+    /// Ask the reactor to re-broadcast the current state of every active space.
+    pub fn resync(&self) -> Option<usize> { self.send_query(QueryRequest::Resync).ok() }
+
+    pub fn save_profile(&self, name: String, space: Option<SpaceId>) -> Result<(), String> {
+        self.send_query(|resp| QueryRequest::SaveProfile { name, space, resp })
+            .unwrap_or_else(|_| Err("Reactor did not respond".to_string()))
+    }
+
+    pub fn load_profile(&self, name: String, space: Option<SpaceId>) -> Result<(), String> {
+        self.send_query(|resp| QueryRequest::LoadProfile { name, space, resp })
+            .unwrap_or_else(|_| Err("Reactor did not respond".to_string()))
+    }
+
+    pub fn query_preview_command(
+        &self,
+        space: Option<SpaceId>,
+        command: LayoutCommand,
+    ) -> Result<Vec<PreviewFrameData>, String> {
+        self.send_query(|resp| QueryRequest::PreviewCommand { space, command, resp })
+            .unwrap_or_else(|_| Err("Reactor did not respond".to_string()))
     }
 }
 
@@ -85,12 +172,18 @@ impl ReactorQueryHandle {
 pub enum QueryRequest {
     Workspaces {
         space_id: Option<SpaceId>,
+        with_windows: bool,
         resp: SyncSender<Vec<WorkspaceData>>,
     },
     Windows {
         space_id: Option<SpaceId>,
         resp: SyncSender<Vec<WindowData>>,
     },
+    WindowsInWorkspace {
+        space: Option<SpaceId>,
+        workspace_index: usize,
+        resp: SyncSender<Result<Vec<WindowData>, String>>,
+    },
     ActiveWorkspace {
         space_id: Option<SpaceId>,
         resp: SyncSender<Option<VirtualWorkspaceId>>,
@@ -110,18 +203,54 @@ pub enum QueryRequest {
         space_id: u64,
         resp: SyncSender<Option<LayoutStateData>>,
     },
-    Metrics(SyncSender<serde_json::Value>),
+    Metrics(SyncSender<MetricsData>),
+    WorkspaceSwitchHistory(SyncSender<Vec<WorkspaceSwitchData>>),
+    EffectiveGaps {
+        space: Option<SpaceId>,
+        resp: SyncSender<Option<EffectiveGapsData>>,
+    },
+    SelectionPath {
+        space: Option<SpaceId>,
+        resp: SyncSender<Option<SelectionPathData>>,
+    },
+    WindowsWithoutServerId(SyncSender<Vec<WindowData>>),
+    FocusedWindow(SyncSender<Option<FocusedWindowData>>),
+    NewWindowFocusSteal(SyncSender<bool>),
+    RuleCoverage(SyncSender<Vec<RuleCoverageData>>),
+    LastLayoutError(SyncSender<Option<LayoutErrorData>>),
+    StaleCleanup(SyncSender<StaleCleanupData>),
+    DragSwapTarget(SyncSender<Option<DragSwapTargetData>>),
+    DisplayTopology(SyncSender<DisplayTopologyData>),
+    Resync(SyncSender<usize>),
+    PreviewCommand {
+        space: Option<SpaceId>,
+        command: LayoutCommand,
+        resp: SyncSender<Result<Vec<PreviewFrameData>, String>>,
+    },
+    SaveProfile {
+        name: String,
+        space: Option<SpaceId>,
+        resp: SyncSender<Result<(), String>>,
+    },
+    LoadProfile {
+        name: String,
+        space: Option<SpaceId>,
+        resp: SyncSender<Result<(), String>>,
+    },
 }
 
 impl Reactor {
     pub(super) fn handle_query_request(&mut self, req: QueryRequest) {
         match req {
-            QueryRequest::Workspaces { space_id, resp } => {
-                let _ = resp.send(self.query_workspaces(space_id));
+            QueryRequest::Workspaces { space_id, with_windows, resp } => {
+                let _ = resp.send(self.query_workspaces(space_id, with_windows));
             }
             QueryRequest::Windows { space_id, resp } => {
                 let _ = resp.send(self.query_windows(space_id));
             }
+            QueryRequest::WindowsInWorkspace { space, workspace_index, resp } => {
+                let _ = resp.send(self.query_windows_in_workspace(space, workspace_index));
+            }
             QueryRequest::ActiveWorkspace { space_id, resp } => {
                 let _ = resp.send(self.query_active_workspace(space_id));
             }
@@ -143,6 +272,54 @@ impl Reactor {
             QueryRequest::Metrics(resp) => {
                 let _ = resp.send(self.query_metrics());
             }
+            QueryRequest::WorkspaceSwitchHistory(resp) => {
+                let _ = resp.send(self.query_workspace_switch_history());
+            }
+            QueryRequest::EffectiveGaps { space, resp } => {
+                let _ = resp.send(self.query_effective_gaps(space));
+            }
+            QueryRequest::SelectionPath { space, resp } => {
+                let _ = resp.send(self.query_selection_path(space));
+            }
+            QueryRequest::WindowsWithoutServerId(resp) => {
+                let _ = resp.send(self.query_windows_without_server_id());
+            }
+            QueryRequest::FocusedWindow(resp) => {
+                let _ = resp.send(self.query_focused_window());
+            }
+            QueryRequest::NewWindowFocusSteal(resp) => {
+                let _ = resp.send(self.config.settings.new_window_focus_steal);
+            }
+            QueryRequest::RuleCoverage(resp) => {
+                let _ = resp.send(self.query_rule_coverage());
+            }
+            QueryRequest::LastLayoutError(resp) => {
+                let _ = resp.send(self.layout_manager.last_error.clone());
+            }
+            QueryRequest::StaleCleanup(resp) => {
+                let _ = resp.send(StaleCleanupData {
+                    enabled: self.refocus_manager.stale_cleanup_enabled,
+                    state: self.refocus_manager.stale_cleanup_state,
+                });
+            }
+            QueryRequest::DragSwapTarget(resp) => {
+                let _ = resp.send(self.query_drag_swap_target());
+            }
+            QueryRequest::DisplayTopology(resp) => {
+                let _ = resp.send(self.query_display_topology());
+            }
+            QueryRequest::Resync(resp) => {
+                let _ = resp.send(self.resync_broadcasts());
+            }
+            QueryRequest::PreviewCommand { space, command, resp } => {
+                let _ = resp.send(self.query_preview_command(space, command));
+            }
+            QueryRequest::SaveProfile { name, space, resp } => {
+                let _ = resp.send(self.save_profile(&name, space));
+            }
+            QueryRequest::LoadProfile { name, space, resp } => {
+                let _ = resp.send(self.load_profile(&name, space));
+            }
         }
     }
 
@@ -152,14 +329,43 @@ impl Reactor {
             .or_else(|| self.space_manager.screens.first().and_then(|s| s.space))
     }
 
-    pub fn query_workspaces(&mut self, space_id: Option<SpaceId>) -> Vec<WorkspaceData> {
-        self.handle_workspace_query(space_id)
+    /// Workspaces of `space_id` in display order. Without `with_windows`, the
+    /// windows and their predicted frames aren't computed and `windows` is
+    /// left empty; `window_count` is still filled in.
+    pub fn query_workspaces(
+        &mut self,
+        space_id: Option<SpaceId>,
+        with_windows: bool,
+    ) -> Vec<WorkspaceData> {
+        self.handle_workspace_query(space_id, with_windows)
     }
 
     pub fn query_windows(&self, space_id: Option<SpaceId>) -> Vec<WindowData> {
         self.handle_windows_query(space_id)
     }
 
+    /// Windows of the workspace at `workspace_index` on `space`, including
+    /// those hidden because the workspace isn't active.
+    pub fn query_windows_in_workspace(
+        &mut self,
+        space: Option<SpaceId>,
+        workspace_index: usize,
+    ) -> Result<Vec<WindowData>, String> {
+        let space = space
+            .or_else(|| self.default_query_space())
+            .ok_or_else(|| "Space not found or inactive".to_string())?;
+        let vwm = self.layout_manager.layout_engine.virtual_workspace_manager_mut();
+        let workspaces = vwm.list_workspaces(space);
+        let Some(&(workspace_id, _)) = workspaces.get(workspace_index) else {
+            return Err(format!(
+                "Workspace index {workspace_index} is out of range ({} workspaces)",
+                workspaces.len()
+            ));
+        };
+        let windows = vwm.workspace_windows(space, workspace_id);
+        Ok(windows.into_iter().filter_map(|wid| self.create_window_data(wid)).collect())
+    }
+
     pub fn query_active_workspace(&self, space_id: Option<SpaceId>) -> Option<VirtualWorkspaceId> {
         self.handle_active_workspace_query(space_id)
     }
@@ -184,7 +390,257 @@ impl Reactor {
         self.handle_layout_state_query(space_id)
     }
 
-    pub fn query_metrics(&self) -> serde_json::Value { self.handle_metrics_query() }
+    pub fn query_metrics(&self) -> MetricsData { self.handle_metrics_query() }
+
+    pub fn query_workspace_switch_history(&self) -> Vec<WorkspaceSwitchData> {
+        self.workspace_switch_manager.switch_history.iter().cloned().collect()
+    }
+
+    pub fn query_effective_gaps(&self, space: Option<SpaceId>) -> Option<EffectiveGapsData> {
+        let space = space.or_else(|| self.default_query_space())?;
+        let screen = self.space_manager.screen_by_space(space)?;
+        let gaps = self.layout_manager.layout_engine.gaps_for_active_layout(
+            space,
+            screen.frame,
+            &self.effective_gaps_for_space(space),
+        );
+        Some(EffectiveGapsData {
+            space_id: space.get(),
+            workspace_id: self
+                .layout_manager
+                .layout_engine
+                .active_workspace(space)
+                .map(|id| format!("{:?}", id)),
+            display_uuid: screen.display_uuid_owned(),
+            outer: gaps.outer,
+            inner: gaps.inner,
+        })
+    }
+
+    pub fn query_selection_path(&self, space: Option<SpaceId>) -> Option<SelectionPathData> {
+        let space = space.or_else(|| self.default_query_space())?;
+        let engine = &self.layout_manager.layout_engine;
+        let nodes = engine
+            .selection_path(space)
+            .into_iter()
+            .map(|entry| SelectionPathNodeData {
+                node_id: format!("{:?}", entry.node_id),
+                kind: entry.container_kind,
+                child_count: entry.child_count,
+                selected_index: entry.selected_index,
+                window_id: entry.window_id,
+            })
+            .collect();
+        Some(SelectionPathData {
+            space_id: space.get(),
+            workspace_id: engine.active_workspace(space).map(|id| format!("{:?}", id)),
+            nodes,
+        })
+    }
+
+    /// Save the workspaces of `space` and their layouts as the profile `name`.
+    pub fn save_profile(&mut self, name: &str, space: Option<SpaceId>) -> Result<(), String> {
+        let space = space
+            .or_else(|| self.default_query_space())
+            .ok_or_else(|| "Space not found or inactive".to_string())?;
+        let path = crate::common::config::profile_file(name).map_err(|e| e.to_string())?;
+        self.layout_manager
+            .layout_engine
+            .save_profile(space, &path)
+            .map_err(|e| format!("Failed to save profile {name:?}: {e}"))
+    }
+
+    /// Restore the profile `name` onto `space`, applying its layouts to the
+    /// windows that still exist.
+    pub fn load_profile(&mut self, name: &str, space: Option<SpaceId>) -> Result<(), String> {
+        let space = space
+            .or_else(|| self.default_query_space())
+            .ok_or_else(|| "Space not found or inactive".to_string())?;
+        let path = crate::common::config::profile_file(name).map_err(|e| e.to_string())?;
+        self.layout_manager
+            .layout_engine
+            .load_profile(space, &path)
+            .map_err(|e| format!("Failed to load profile {name:?}: {e}"))?;
+        let _ = self.update_layout_or_warn(false, false);
+        Ok(())
+    }
+
+    /// Frames the active layout of `space` would have after `command`,
+    /// without applying it.
+    pub fn query_preview_command(
+        &mut self,
+        space: Option<SpaceId>,
+        command: LayoutCommand,
+    ) -> Result<Vec<PreviewFrameData>, String> {
+        let space = space
+            .or_else(|| self.default_query_space())
+            .ok_or_else(|| "Space not found or inactive".to_string())?;
+        let screen = self
+            .space_manager
+            .screen_by_space(space)
+            .ok_or_else(|| "Space not found or inactive".to_string())?
+            .frame;
+        let gaps = self.effective_gaps_for_space(space);
+        let stack_line = &self.config.settings.ui.stack_line;
+        let frames = self.layout_manager.layout_engine.preview_command(
+            space,
+            screen,
+            &gaps,
+            stack_line.thickness(),
+            stack_line.horiz_placement,
+            stack_line.vert_placement,
+            command,
+        )?;
+        Ok(frames
+            .into_iter()
+            .map(|(window_id, frame)| PreviewFrameData { window_id, frame })
+            .collect())
+    }
+
+    /// The dragged window and its current swap candidate, or `None` when no
+    /// drag is in progress or nothing would be swapped on drop.
+    pub fn query_drag_swap_target(&self) -> Option<DragSwapTargetData> {
+        let window_id = self.drag_manager.dragged()?;
+        let target_id = self.drag_manager.last_target()?;
+        let target_frame = self.window_manager.windows.get(&target_id)?.frame_monotonic;
+        Some(DragSwapTargetData {
+            window_id,
+            target_id,
+            target_frame,
+        })
+    }
+
+    /// Screens in order with the space each shows and that space's workspaces.
+    pub fn query_display_topology(&mut self) -> DisplayTopologyData {
+        let screens = self.space_manager.screens.clone();
+        let mut active_space_ids = Vec::new();
+        let mut inactive_space_ids = Vec::new();
+        let displays = screens
+            .into_iter()
+            .enumerate()
+            .map(|(index, screen)| {
+                let is_active = screen.space.is_some_and(|space| self.is_space_active(space));
+                if let Some(space) = screen.space {
+                    if is_active {
+                        active_space_ids.push(space.get());
+                    } else {
+                        inactive_space_ids.push(space.get());
+                    }
+                }
+                let workspaces = match screen.space {
+                    Some(space) => self.query_workspaces(Some(space), false),
+                    None => Vec::new(),
+                };
+                DisplayTopologyEntryData {
+                    index,
+                    uuid: screen.display_uuid,
+                    name: screen.name,
+                    screen_id: screen.id.as_u32(),
+                    frame: screen.frame,
+                    space_id: screen.space.map(|space| space.get()),
+                    is_active,
+                    active_workspace_id: workspaces
+                        .iter()
+                        .find(|ws| ws.is_active)
+                        .map(|ws| ws.id.clone()),
+                    workspaces,
+                }
+            })
+            .collect();
+        DisplayTopologyData {
+            displays,
+            active_space_ids,
+            inactive_space_ids,
+        }
+    }
+
+    /// The focused window, or `None` when nothing manageable has focus.
+    pub fn query_focused_window(&mut self) -> Option<FocusedWindowData> {
+        let window_id = self.main_window()?;
+        let window = self.create_window_data(window_id)?;
+        let space = self.best_space_for_window_id(window_id);
+        let workspace = space.and_then(|space| {
+            let vwm = self.layout_manager.layout_engine.virtual_workspace_manager_mut();
+            let workspace_id = vwm.workspace_for_window(space, window_id)?;
+            vwm.list_workspaces(space)
+                .into_iter()
+                .enumerate()
+                .find(|(_, (id, _))| *id == workspace_id)
+                .map(|(index, (id, name))| (id, index, name))
+        });
+        Some(FocusedWindowData {
+            window,
+            space_id: space.map(|space| space.get()),
+            workspace_id: workspace.as_ref().map(|(id, ..)| format!("{:?}", id)),
+            workspace_index: workspace.as_ref().map(|(_, index, _)| *index),
+            workspace_name: workspace.map(|(.., name)| name),
+        })
+    }
+
+    /// Every configured app rule with the manageable windows it matches on its
+    /// own, regardless of whether another rule takes precedence for them.
+    /// Windows kept out of the layout by a `manage = false` rule still count.
+    pub fn query_rule_coverage(&self) -> Vec<RuleCoverageData> {
+        let vwm = self.layout_manager.layout_engine.virtual_workspace_manager();
+        let mut coverage: Vec<RuleCoverageData> = vwm
+            .app_rules()
+            .iter()
+            .enumerate()
+            .map(|(index, rule)| RuleCoverageData {
+                index,
+                rule: rule.clone(),
+                window_count: 0,
+                window_ids: Vec::new(),
+            })
+            .collect();
+
+        let mut windows: Vec<_> = self
+            .window_manager
+            .windows
+            .iter()
+            .filter(|(_, state)| state.is_manageable)
+            .collect();
+        windows.sort_by_key(|(id, _)| **id);
+        for (&id, state) in windows {
+            let app = self.app_manager.apps.get(&id.pid).map(|app| &app.info);
+            let matches = vwm.matching_app_rule_indices(
+                app.and_then(|info| info.bundle_id.as_deref()),
+                app.and_then(|info| info.localized_name.as_deref()),
+                Some(state.info.title.as_str()),
+                state.info.ax_role.as_deref(),
+                state.info.ax_subrole.as_deref(),
+            );
+            for index in matches {
+                coverage[index].window_count += 1;
+                coverage[index].window_ids.push(id);
+            }
+        }
+        coverage
+    }
+
+    /// Every tracked window lacking a window server id, managed or not.
+    pub fn query_windows_without_server_id(&self) -> Vec<WindowData> {
+        let focused = self.main_window();
+        let mut windows: Vec<WindowData> = self
+            .window_manager
+            .windows
+            .iter()
+            .filter(|(_, state)| state.info.sys_id.is_none())
+            .map(|(&id, state)| WindowData {
+                id,
+                is_floating: self.layout_manager.layout_engine.is_window_floating(id),
+                is_focused: focused == Some(id),
+                app_name: self
+                    .app_manager
+                    .apps
+                    .get(&id.pid)
+                    .and_then(|app| app.info.localized_name.clone()),
+                info: state.info.clone(),
+            })
+            .collect();
+        windows.sort_by_key(|window| (window.id.pid, window.id.idx));
+        windows
+    }
 
     pub(super) fn maybe_send_menu_update(&mut self) {
         let menu_tx = match self.menu_manager.menu_tx.as_ref() {
@@ -197,7 +653,7 @@ impl Reactor {
             None => return,
         };
 
-        let workspaces = self.handle_workspace_query(Some(active_space));
+        let workspaces = self.handle_workspace_query(Some(active_space), true);
         let active_space_is_activated = self.is_space_active(active_space);
         let active_workspace = self.layout_manager.layout_engine.active_workspace(active_space);
         let active_workspace_idx =
@@ -214,7 +670,11 @@ impl Reactor {
         }));
     }
 
-    fn handle_workspace_query(&mut self, space_id_param: Option<SpaceId>) -> Vec<WorkspaceData> {
+    fn handle_workspace_query(
+        &mut self,
+        space_id_param: Option<SpaceId>,
+        with_windows: bool,
+    ) -> Vec<WorkspaceData> {
         let mut workspaces = Vec::new();
 
         let space_id = space_id_param.or_else(|| self.default_query_space());
@@ -235,6 +695,38 @@ impl Reactor {
                 false
             };
 
+            let layout_mode = space_id
+                .and_then(|space| {
+                    self.layout_manager
+                        .layout_engine
+                        .virtual_workspace_manager()
+                        .workspace_info(space, *workspace_id)
+                        .map(|ws| ws.layout_mode().to_string())
+                })
+                .unwrap_or_else(|| "unknown".to_string());
+
+            if !with_windows {
+                let window_count = space_id
+                    .and_then(|space| {
+                        self.layout_manager
+                            .layout_engine
+                            .virtual_workspace_manager()
+                            .workspace_info(space, *workspace_id)
+                            .map(|ws| ws.window_count())
+                    })
+                    .unwrap_or(0);
+                workspaces.push(WorkspaceData {
+                    id: format!("{:?}", workspace_id),
+                    name: workspace_name.to_string(),
+                    layout_mode,
+                    is_active,
+                    window_count,
+                    windows: Vec::new(),
+                    index,
+                });
+                continue;
+            }
+
             let workspace_windows_ids: Vec<crate::actor::app::WindowId> =
                 if let Some(space) = space_id {
                     if is_active {
@@ -262,9 +754,7 @@ impl Reactor {
                         .or_else(|| self.space_manager.screens.first().cloned());
 
                     if let Some(screen) = screen_info {
-                        let display_uuid = screen.display_uuid_opt();
-                        let gaps =
-                            self.config.settings.layout.gaps.effective_for_display(display_uuid);
+                        let gaps = self.gaps_for_space_on_display(space, screen.display_uuid_opt());
                         self.layout_manager.layout_engine.calculate_layout_for_workspace(
                             space,
                             *workspace_id,
@@ -299,16 +789,6 @@ impl Reactor {
                 }
             }
 
-            let layout_mode = space_id
-                .and_then(|space| {
-                    self.layout_manager
-                        .layout_engine
-                        .virtual_workspace_manager()
-                        .workspace_info(space, *workspace_id)
-                        .map(|ws| ws.layout_mode().to_string())
-                })
-                .unwrap_or_else(|| "unknown".to_string());
-
             workspaces.push(WorkspaceData {
                 id: format!("{:?}", workspace_id),
                 name: workspace_name.to_string(),
@@ -500,7 +980,7 @@ impl Reactor {
         })
     }
 
-    fn handle_metrics_query(&self) -> serde_json::Value {
+    fn handle_metrics_query(&self) -> MetricsData {
         let stats = self.layout_manager.layout_engine.virtual_workspace_manager().get_stats();
 
         let workspace_stats: crate::common::collections::HashMap<String, usize> = stats
@@ -509,13 +989,14 @@ impl Reactor {
             .map(|(id, count)| (format!("{:?}", id), *count))
             .collect();
 
-        serde_json::json!({
-               "windows_managed": self.window_manager.windows.len(),
-            "workspaces": stats.total_workspaces,
-            "applications": self.app_manager.apps.len(),
-            "screens": self.space_manager.screens.len(),
-            "workspace_stats": workspace_stats,
-        })
+        MetricsData {
+            windows_managed: self.window_manager.windows.len(),
+            workspaces: stats.total_workspaces,
+            applications: self.app_manager.apps.len(),
+            screens: self.space_manager.screens.len(),
+            workspace_stats,
+            events: self.event_metrics.snapshot(),
+        }
     }
 
     pub(crate) fn serialize_state(&mut self) -> Result<String, serde_json::Error> {
@@ -394,6 +394,85 @@ fn workspace_switch_batches_all_windows_with_eui_enabled() {
     );
 }
 
+#[test]
+fn workspace_switches_are_recorded_with_origin() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::SwitchToWorkspace(1),
+    )));
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::GestureSwitchWorkspace { next: false, skip_empty: None },
+    )));
+    // Switching to the already-active workspace is not a switch.
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::SwitchToWorkspace(0),
+    )));
+
+    let history = reactor.query_workspace_switch_history();
+    let origins: Vec<_> = history.iter().map(|entry| entry.origin).collect();
+    assert_eq!(origins, vec![
+        WorkspaceSwitchOrigin::Manual,
+        WorkspaceSwitchOrigin::Gesture
+    ]);
+    assert_eq!(history[0].space_id, space.get());
+    assert_eq!(
+        history[0].to_workspace,
+        history[1].from_workspace.clone().unwrap()
+    );
+    assert_eq!(
+        history[1].to_workspace,
+        history[0].from_workspace.clone().unwrap()
+    );
+}
+
+#[test]
+fn effective_gaps_query_reports_resolved_gaps_for_space() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.layout.gaps.outer.top = 12.0;
+    reactor.config.settings.layout.gaps.inner.horizontal = 6.0;
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+
+    let gaps = reactor.query_effective_gaps(Some(space)).expect("gaps for known space");
+    assert_eq!(gaps.space_id, space.get());
+    assert!(gaps.workspace_id.is_some());
+    assert_eq!(gaps.outer.top, 12.0);
+    assert_eq!(gaps.inner.horizontal, 6.0);
+
+    assert!(reactor.query_effective_gaps(Some(SpaceId::new(99))).is_none());
+
+    // Smart gaps drop the outer gaps around a lone tiled window.
+    reactor.config.settings.layout.gaps.smart_gaps = true;
+    let mut apps = Apps::new();
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+    let gaps = reactor.query_effective_gaps(Some(space)).unwrap();
+    assert_eq!(gaps.outer.top, 0.0);
+    assert_eq!(gaps.inner.horizontal, 6.0);
+
+    // So does turning gaps off for the space.
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::ToggleGaps)));
+    let gaps = reactor.query_effective_gaps(Some(space)).unwrap();
+    assert_eq!(gaps.inner.horizontal, 0.0);
+}
+
 #[test]
 fn windows_discovered_does_not_reintroduce_inactive_workspace_window() {
     let mut apps = Apps::new();
@@ -652,6 +731,69 @@ fn title_change_reapply_does_not_rebalance_when_window_stays_floating() {
     );
 }
 
+#[test]
+fn deferred_untitled_window_joins_layout_once_titled() {
+    let settings = crate::common::config::VirtualWorkspaceSettings {
+        untitled_window_policy: crate::common::config::UntitledWindowPolicy::Defer,
+        ..Default::default()
+    };
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &settings,
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.virtual_workspaces = settings;
+
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+
+    let mut windows = make_windows(2);
+    windows[1].title = String::new();
+    let untitled = WindowId::new(1, 2);
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        windows,
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let tiled = |reactor: &mut Reactor| {
+        reactor
+            .layout_manager
+            .layout_engine
+            .calculate_layout(
+                space,
+                full_screen,
+                &reactor.config.settings.layout.gaps,
+                0.0,
+                crate::common::config::HorizontalPlacement::Top,
+                crate::common::config::VerticalPlacement::Right,
+            )
+            .into_iter()
+            .map(|(wid, _)| wid)
+            .collect::<Vec<_>>()
+    };
+
+    assert!(!tiled(&mut reactor).contains(&untitled));
+    assert!(
+        reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager()
+            .workspace_for_window(space, untitled)
+            .is_none()
+    );
+
+    reactor.handle_event(Event::WindowTitleChanged(untitled, "Now titled".to_string()));
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert!(tiled(&mut reactor).contains(&untitled));
+}
+
 #[test]
 fn menu_open_state_is_cleared_when_owner_deactivates() {
     let mut reactor = Reactor::new_for_test(LayoutEngine::new(
@@ -852,6 +994,49 @@ fn display_index_selector_uses_physical_left_to_right_order() {
     assert_eq!(selected.frame, left);
 }
 
+#[test]
+fn display_cycle_selector_follows_configured_order() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let frames: Vec<_> = [100000., 200000., 300000.]
+        .into_iter()
+        .map(|x| CGRect::new(CGPoint::new(x, 0.), CGSize::new(1000., 1000.)))
+        .collect();
+    reactor.handle_event(screen_params_event(
+        frames.clone(),
+        vec![
+            Some(SpaceId::new(1)),
+            Some(SpaceId::new(2)),
+            Some(SpaceId::new(3)),
+        ],
+        vec![],
+    ));
+    let cycle = |reactor: &Reactor, from: usize, cycle: DisplayCycle| {
+        reactor
+            .screen_for_selector(&DisplaySelector::Cycle(cycle), Some(frames[from].mid()))
+            .map(|screen| screen.frame)
+    };
+
+    // Left to right by default, wrapping at the end.
+    assert_eq!(cycle(&reactor, 0, DisplayCycle::Next), Some(frames[1]));
+    assert_eq!(cycle(&reactor, 2, DisplayCycle::Next), Some(frames[0]));
+    assert_eq!(cycle(&reactor, 0, DisplayCycle::Prev), Some(frames[2]));
+
+    // Listed displays come first; the rest follow in physical order.
+    reactor.config.settings.display_cycle_order =
+        crate::common::config::DisplayCycleOrder::Uuids(vec![
+            "test-display-2".to_string(),
+            "test-display-0".to_string(),
+        ]);
+    assert_eq!(cycle(&reactor, 2, DisplayCycle::Next), Some(frames[0]));
+    assert_eq!(cycle(&reactor, 0, DisplayCycle::Next), Some(frames[1]));
+    assert_eq!(cycle(&reactor, 1, DisplayCycle::Next), Some(frames[2]));
+    assert_eq!(cycle(&reactor, 2, DisplayCycle::Prev), Some(frames[1]));
+}
+
 #[test]
 fn display_churn_quarantine_counters_increment() {
     let mut reactor = Reactor::new_for_test(LayoutEngine::new(
@@ -1016,6 +1201,57 @@ fn fullscreen_space_in_screen_params_does_not_trigger_topology_relayout() {
     );
 }
 
+#[test]
+fn leaving_a_fullscreen_space_broadcasts_fullscreen_exit_once_per_window() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    let fullscreen_space = SpaceId::new(0x400000000 + space.get());
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let (broadcast_tx, mut broadcast_rx) = actor::channel();
+    reactor.communication_manager.event_broadcaster = broadcast_tx;
+
+    let wid = WindowId::new(1, 1);
+    let track = FullscreenWindowTrack {
+        pid: 1,
+        window_id: Some(wid),
+        last_known_user_space: Some(space),
+        _last_seen_fullscreen_space: fullscreen_space,
+    };
+    reactor
+        .space_manager
+        .fullscreen_by_space
+        .insert(space.get(), FullscreenSpaceTrack {
+            windows: vec![track.clone(), track],
+        });
+
+    reactor.handle_event(Event::SpaceChanged(vec![None]));
+    reactor.handle_event(Event::SpaceChanged(vec![Some(space)]));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let mut changes = Vec::new();
+    while let Ok((_, event)) = broadcast_rx.try_recv() {
+        if let BroadcastEvent::WindowFullscreenChanged {
+            window_id,
+            space_id,
+            fullscreen,
+        } = event
+        {
+            changes.push((window_id, space_id, fullscreen));
+        }
+    }
+    assert_eq!(changes, vec![(wid, space, false)]);
+    assert!(reactor.space_manager.fullscreen_by_space.is_empty());
+}
+
 #[test]
 fn fullscreen_screen_params_preserves_window_layout() {
     // Regression test for #308: waking from sleep while a fullscreen video is
@@ -1483,3 +1719,1880 @@ fn discovery_after_display_change_places_window_on_correct_display() {
         "window must be laid out on screen2"
     );
 }
+
+#[test]
+fn stale_pending_space_change_is_force_applied() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+
+    let left = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1280., 800.));
+    let right = CGRect::new(CGPoint::new(1280., 0.), CGSize::new(1280., 800.));
+    reactor.handle_event(screen_params_event(
+        vec![left, right],
+        vec![Some(SpaceId::new(11)), Some(SpaceId::new(22))],
+        vec![],
+    ));
+
+    let fresh = PendingSpaceChange {
+        spaces: vec![Some(SpaceId::new(33))],
+        queued_at: std::time::Instant::now(),
+    };
+    reactor.pending_space_change_manager.pending_space_change = Some(fresh);
+    reactor.try_apply_pending_space_change();
+    assert!(
+        reactor.pending_space_change_manager.pending_space_change.is_some(),
+        "a fresh mismatched change should stay pending"
+    );
+    let max_age =
+        std::time::Duration::from_millis(reactor.config.settings.pending_space_change_max_age_ms);
+    let expiry = reactor.pending_space_change_expiry();
+    assert!(!expiry.is_zero() && expiry <= max_age);
+
+    let stale = PendingSpaceChange {
+        spaces: vec![Some(SpaceId::new(33))],
+        queued_at: std::time::Instant::now() - std::time::Duration::from_secs(60),
+    };
+    reactor.pending_space_change_manager.pending_space_change = Some(stale);
+    assert!(reactor.pending_space_change_expiry().is_zero());
+    // The reactor loop's timer delivers this once the deadline passes.
+    reactor.handle_event(Event::PendingSpaceChangeExpired);
+    assert!(reactor.pending_space_change_manager.pending_space_change.is_none());
+    assert_eq!(reactor.pending_space_change_expiry(), std::time::Duration::MAX);
+    assert_eq!(
+        reactor.raw_spaces_for_current_screens(),
+        vec![Some(SpaceId::new(33)), None],
+        "a stale change should be padded to the screen count and applied"
+    );
+}
+
+#[test]
+fn close_other_windows_only_targets_active_workspace() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(3),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::MoveWindowToWorkspace {
+            workspace: 1,
+            window_id: Some(3),
+        },
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    let _ = apps.requests();
+
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::CloseOtherWindows)));
+
+    let closed: Vec<_> = apps
+        .requests()
+        .into_iter()
+        .filter_map(|req| match req {
+            Request::CloseWindow(wid) => Some(wid),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(closed, vec![WindowId::new(1, 2)]);
+}
+
+fn close_requests(apps: &mut Apps) -> Vec<WindowId> {
+    apps.requests()
+        .into_iter()
+        .filter_map(|req| match req {
+            Request::CloseWindow(wid) => Some(wid),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn close_other_windows_does_nothing_without_a_focused_window() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![screen],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(3)));
+    apps.simulate_until_quiet(&mut reactor);
+    let _ = apps.requests();
+
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::CloseOtherWindows)));
+    assert!(close_requests(&mut apps).is_empty());
+}
+
+#[test]
+fn close_other_windows_does_nothing_when_focus_is_on_another_display() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen1 = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let screen2 = CGRect::new(CGPoint::new(1000., 0.), CGSize::new(1000., 1000.));
+    let space1 = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(
+        vec![screen1, screen2],
+        vec![Some(space1), Some(SpaceId::new(2))],
+        vec![],
+    ));
+    let mut windows = make_windows(3);
+    windows[2].frame.origin = CGPoint::new(1100., 100.);
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        windows,
+        Some(WindowId::new(1, 3)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+    let _ = apps.requests();
+
+    CommandEventHandler::close_other_windows(&mut reactor, space1);
+    assert!(close_requests(&mut apps).is_empty());
+}
+
+#[test]
+fn switching_to_empty_workspace_warps_to_center_when_configured() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.empty_workspace_focus =
+        Some(crate::common::config::EmptyWorkspaceFocus::WarpToCenter);
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let (event_tap_tx, mut event_tap_rx) = actor::channel();
+    reactor.communication_manager.event_tap_tx = Some(event_tap_tx);
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::SwitchToWorkspace(1),
+    )));
+
+    let mut warped_to = None;
+    while let Ok((_, request)) = event_tap_rx.try_recv() {
+        if let crate::actor::event_tap::Request::Warp(point) = request {
+            warped_to = Some(point);
+        }
+    }
+    assert_eq!(warped_to, Some(screen.mid()));
+}
+
+#[test]
+fn windows_without_server_id_are_skipped_when_configured() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.missing_window_server_id =
+        crate::common::config::MissingWindowServerIdPolicy::Skip;
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+
+    let mut windows = make_windows(2);
+    windows[1].sys_id = None;
+    let unidentified = WindowId::new(1, 2);
+    let mut events =
+        apps.make_app_with_opts(1, windows.clone(), Some(WindowId::new(1, 1)), true, false);
+    if let Event::ApplicationLaunched { window_server_info, .. } = &mut events[0] {
+        *window_server_info = windows
+            .iter()
+            .filter_map(|info| {
+                Some(WindowServerInfo {
+                    pid: 1,
+                    id: info.sys_id?,
+                    layer: 0,
+                    frame: info.frame,
+                    min_frame: CGSize::ZERO,
+                    max_frame: CGSize::ZERO,
+                })
+            })
+            .collect();
+    }
+    reactor.handle_events(events);
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert!(
+        reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager()
+            .workspace_for_window(space, unidentified)
+            .is_none()
+    );
+    let reported: Vec<_> =
+        reactor.query_windows_without_server_id().into_iter().map(|w| w.id).collect();
+    assert_eq!(reported, vec![unidentified]);
+}
+
+#[test]
+fn reassign_window_moves_only_that_window_back() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(3),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+
+    for window_id in [2, 3] {
+        reactor.handle_event(Event::Command(Command::Layout(
+            LayoutCommand::MoveWindowToWorkspace {
+                workspace: 1,
+                window_id: Some(window_id),
+            },
+        )));
+        apps.simulate_until_quiet(&mut reactor);
+    }
+
+    let workspace_of = |reactor: &Reactor, idx| {
+        reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager()
+            .workspace_for_window(space, WindowId::new(1, idx))
+    };
+    let active = reactor.layout_manager.layout_engine.active_workspace(space);
+    let moved = workspace_of(&reactor, 2);
+    assert_ne!(moved, active);
+
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::ReassignWindow { wsid: WindowServerId::new(3) },
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert_eq!(workspace_of(&reactor, 3), active);
+    assert_eq!(workspace_of(&reactor, 2), moved);
+}
+
+#[test]
+fn focused_window_query_reports_workspace() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    assert!(reactor.query_focused_window().is_none());
+
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(2),
+        Some(WindowId::new(1, 2)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let focused = reactor.query_focused_window().expect("focused window");
+    assert_eq!(focused.window.id, WindowId::new(1, 2));
+    assert_eq!(focused.space_id, Some(space.get()));
+    assert_eq!(focused.workspace_index, Some(0));
+}
+
+#[test]
+fn new_window_does_not_take_focus_when_focus_steal_is_off() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(1),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+    assert_eq!(
+        reactor.layout_manager.layout_engine.focused_window(),
+        Some(WindowId::new(1, 1))
+    );
+
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::SetNewWindowFocusSteal(false),
+    )));
+    reactor.handle_event(Event::WindowCreated(
+        WindowId::new(1, 2),
+        make_window(2),
+        None,
+        Some(MouseState::Up),
+    ));
+    reactor.handle_event(Event::ApplicationMainWindowChanged(
+        1,
+        Some(WindowId::new(1, 2)),
+        Quiet::No,
+    ));
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert_eq!(
+        reactor.layout_manager.layout_engine.focused_window(),
+        Some(WindowId::new(1, 1))
+    );
+
+    // Only the first focus change is handed back; switching to the new
+    // window and back afterwards follows the user.
+    for wid in [
+        WindowId::new(1, 2),
+        WindowId::new(1, 1),
+        WindowId::new(1, 2),
+    ] {
+        reactor.handle_event(Event::ApplicationMainWindowChanged(1, Some(wid), Quiet::No));
+        apps.simulate_until_quiet(&mut reactor);
+        assert_eq!(reactor.layout_manager.layout_engine.focused_window(), Some(wid));
+    }
+}
+
+#[test]
+fn new_window_takes_focus_once_the_grace_period_has_passed() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(1),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.config.settings.new_window_focus_grace_ms = 0;
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::SetNewWindowFocusSteal(false),
+    )));
+    reactor.handle_event(Event::WindowCreated(
+        WindowId::new(1, 2),
+        make_window(2),
+        None,
+        Some(MouseState::Up),
+    ));
+    reactor.handle_event(Event::ApplicationMainWindowChanged(
+        1,
+        Some(WindowId::new(1, 2)),
+        Quiet::No,
+    ));
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert_eq!(
+        reactor.layout_manager.layout_engine.focused_window(),
+        Some(WindowId::new(1, 2))
+    );
+}
+
+#[test]
+fn layout_preset_applies_only_to_its_space() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let mut compact = crate::common::config::LayoutSettings::default();
+    compact.gaps.outer.top = 4.0;
+    reactor.config.settings.layout_presets.insert("compact".to_string(), compact);
+    reactor.config.settings.layout.gaps.outer.top = 20.0;
+
+    let screens = vec![
+        CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.)),
+        CGRect::new(CGPoint::new(1000., 0.), CGSize::new(1000., 1000.)),
+    ];
+    let (first, second) = (SpaceId::new(1), SpaceId::new(2));
+    reactor.handle_event(screen_params_event(
+        screens,
+        vec![Some(first), Some(second)],
+        vec![],
+    ));
+
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::ApplyLayoutPreset {
+            name: Some("compact".to_string()),
+            space: Some(second),
+        },
+    )));
+    assert_eq!(
+        reactor.query_effective_gaps(Some(first)).unwrap().outer.top,
+        20.0
+    );
+    assert_eq!(
+        reactor.query_effective_gaps(Some(second)).unwrap().outer.top,
+        4.0
+    );
+
+    // Unknown presets leave the space alone.
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::ApplyLayoutPreset {
+            name: Some("missing".to_string()),
+            space: Some(second),
+        },
+    )));
+    assert_eq!(
+        reactor.query_effective_gaps(Some(second)).unwrap().outer.top,
+        4.0
+    );
+
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::ApplyLayoutPreset {
+            name: None,
+            space: Some(second),
+        },
+    )));
+    assert_eq!(
+        reactor.query_effective_gaps(Some(second)).unwrap().outer.top,
+        20.0
+    );
+}
+
+#[test]
+fn rule_coverage_reports_matching_windows_per_rule() {
+    use crate::common::config::{AppWorkspaceRule, VirtualWorkspaceSettings};
+
+    let rule = |app_id: &str, title_substring: Option<&str>| AppWorkspaceRule {
+        app_id: Some(app_id.into()),
+        workspace: None,
+        floating: false,
+        manage: true,
+        app_name: None,
+        title_regex: None,
+        title_substring: title_substring.map(Into::into),
+        ax_role: None,
+        ax_subrole: None,
+        floating_frame: None,
+        min_size: None,
+    };
+    let mut settings = VirtualWorkspaceSettings::default();
+    settings.app_rules = vec![
+        rule("com.testapp1", None),
+        rule("com.testapp1", Some("Window2")),
+        rule("com.example.gone", None),
+    ];
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &settings,
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![screen],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    // The fourth window is not a standard window, so it is never managed.
+    let mut windows = make_windows(4);
+    windows[3].is_standard = false;
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        windows,
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let coverage = reactor.query_rule_coverage();
+    let counts: Vec<_> = coverage.iter().map(|c| (c.index, c.window_count)).collect();
+    assert_eq!(counts, vec![(0, 3), (1, 1), (2, 0)]);
+    assert_eq!(coverage[1].window_ids, vec![WindowId::new(1, 2)]);
+}
+
+#[test]
+fn layout_mode_changes_are_broadcast_on_workspace_switch() {
+    use crate::common::config::LayoutMode;
+
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let (broadcast_tx, mut broadcast_rx) = actor::channel();
+    reactor.communication_manager.event_broadcaster = broadcast_tx;
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let mut mode_changes = move || {
+        let mut modes = Vec::new();
+        while let Ok((_, event)) = broadcast_rx.try_recv() {
+            if let BroadcastEvent::LayoutModeChanged { space_id, mode, .. } = event {
+                modes.push((space_id, mode));
+            }
+        }
+        modes
+    };
+    assert!(mode_changes().iter().all(|&(_, mode)| mode == LayoutMode::Traditional));
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::SetWorkspaceLayout {
+            workspace: Some(1),
+            mode: LayoutMode::Bsp,
+        },
+    )));
+    // The inactive workspace changed, so the space's mode did not.
+    assert!(mode_changes().is_empty());
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::SwitchToWorkspace(1),
+    )));
+    assert_eq!(mode_changes(), vec![(space, LayoutMode::Bsp)]);
+}
+
+#[test]
+fn workspaces_without_windows_report_the_same_ids_and_counts() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::MoveWindowToWorkspace {
+            workspace: 1,
+            window_id: Some(2),
+        },
+    )));
+
+    let list = reactor.query_workspaces(Some(space), false);
+    let full = reactor.query_workspaces(Some(space), true);
+    assert_eq!(list.len(), full.len());
+    for (summary, workspace) in list.iter().zip(&full) {
+        assert_eq!(summary.id, workspace.id);
+        assert_eq!(summary.index, workspace.index);
+        assert_eq!(summary.name, workspace.name);
+        assert_eq!(summary.is_active, workspace.is_active);
+        assert_eq!(summary.window_count, workspace.window_count);
+        assert!(summary.windows.is_empty());
+    }
+    assert!(list[0].is_active);
+    assert_eq!(list[0].window_count, 1);
+    assert_eq!(list[1].window_count, 1);
+}
+
+#[test]
+fn drag_space_resolution_holds_the_settled_space_near_a_shared_edge() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.window_snapping.drag_space_hysteresis = 40.0;
+
+    let screens = vec![
+        CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.)),
+        CGRect::new(CGPoint::new(1000., 0.), CGSize::new(1000., 1000.)),
+    ];
+    let (left, right) = (SpaceId::new(1), SpaceId::new(2));
+    reactor.handle_event(screen_params_event(
+        screens,
+        vec![Some(left), Some(right)],
+        vec![],
+    ));
+
+    // A 200pt-wide window whose center sits at `center_x`.
+    let frame_at =
+        |center_x: f64| CGRect::new(CGPoint::new(center_x - 100., 400.), CGSize::new(200., 200.));
+    let mut session = DragSession {
+        window: WindowId::new(1, 1),
+        last_frame: frame_at(900.),
+        origin_space: Some(left),
+        settled_space: Some(left),
+        layout_dirty: false,
+    };
+
+    // Oscillating just across the edge keeps the drag on the left display.
+    for center_x in [1010., 990., 1030., 995., 1020.] {
+        let resolved = reactor.resolve_drag_space(&session, &frame_at(center_x));
+        assert_eq!(resolved, Some(left), "center at {center_x}");
+        session.settled_space = resolved;
+    }
+
+    // Clearing the margin switches, and the right display is now the sticky one.
+    session.settled_space = reactor.resolve_drag_space(&session, &frame_at(1050.));
+    assert_eq!(session.settled_space, Some(right));
+    for center_x in [990., 1010., 970.] {
+        let resolved = reactor.resolve_drag_space(&session, &frame_at(center_x));
+        assert_eq!(resolved, Some(right), "center at {center_x}");
+        session.settled_space = resolved;
+    }
+    session.settled_space = reactor.resolve_drag_space(&session, &frame_at(950.));
+    assert_eq!(session.settled_space, Some(left));
+
+    // Without hysteresis the space follows the center immediately.
+    reactor.config.settings.window_snapping.drag_space_hysteresis = 0.0;
+    assert_eq!(
+        reactor.resolve_drag_space(&session, &frame_at(1010.)),
+        Some(right)
+    );
+}
+
+#[test]
+fn windows_created_during_a_workspace_switch_join_the_settled_workspace() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let workspaces: Vec<_> = reactor
+        .layout_manager
+        .layout_engine
+        .virtual_workspace_manager_mut()
+        .list_workspaces(space)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+
+    // The switch hides the first window; until its frame changes come back
+    // the switch is still in flight across events.
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::SwitchToWorkspace(1),
+    )));
+    assert!(reactor.workspace_switch_manager.active_workspace_switch.is_some());
+
+    let new_window = WindowId::new(1, 2);
+    reactor.handle_event(Event::WindowCreated(
+        new_window,
+        make_window(2),
+        None,
+        Some(MouseState::Up),
+    ));
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert!(reactor.workspace_switch_manager.active_workspace_switch.is_none());
+    assert!(reactor.workspace_switch_manager.pending_new_windows.is_empty());
+    assert_eq!(
+        reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager()
+            .workspace_for_window(space, new_window),
+        Some(workspaces[1])
+    );
+}
+
+#[test]
+fn pinned_apps_send_new_windows_to_their_workspace_ahead_of_app_rules() {
+    use crate::common::config::{AppWorkspaceRule, VirtualWorkspaceSettings, WorkspaceSelector};
+
+    let mut settings = VirtualWorkspaceSettings::default();
+    settings.app_rules = vec![AppWorkspaceRule {
+        app_id: Some("com.testapp1".into()),
+        workspace: Some(WorkspaceSelector::Index(1)),
+        floating: false,
+        manage: true,
+        app_name: None,
+        title_regex: None,
+        title_substring: None,
+        ax_role: None,
+        ax_subrole: None,
+        floating_frame: None,
+        min_size: None,
+    }];
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &settings,
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::PinAppToWorkspace {
+            bundle_id: "com.testapp1".into(),
+            workspace_index: 2,
+        },
+    )));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let workspaces: Vec<_> = reactor
+        .layout_manager
+        .layout_engine
+        .virtual_workspace_manager_mut()
+        .list_workspaces(space)
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    let workspace_of = |reactor: &Reactor, wid| {
+        reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager()
+            .workspace_for_window(space, wid)
+    };
+    assert_eq!(workspace_of(&reactor, WindowId::new(1, 1)), Some(workspaces[2]));
+
+    // Once unpinned, the configured rule applies again.
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::UnpinApp {
+        bundle_id: "com.testapp1".into(),
+    })));
+    reactor.handle_event(Event::WindowCreated(
+        WindowId::new(1, 2),
+        make_window(2),
+        None,
+        Some(MouseState::Up),
+    ));
+    assert_eq!(workspace_of(&reactor, WindowId::new(1, 2)), Some(workspaces[1]));
+    assert_eq!(workspace_of(&reactor, WindowId::new(1, 1)), Some(workspaces[2]));
+}
+
+#[test]
+fn drag_swap_target_query_reports_the_live_candidate() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![screen],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert!(reactor.query_drag_swap_target().is_none());
+
+    let dragged = WindowId::new(1, 1);
+    let target = WindowId::new(1, 2);
+    let target_frame = reactor.window_manager.windows[&target].frame_monotonic;
+    let chosen = reactor
+        .drag_manager
+        .drag_swap_manager
+        .on_frame_change(dragged, target_frame, &[(target, target_frame)]);
+    assert_eq!(chosen, Some(target));
+
+    let data = reactor.query_drag_swap_target().expect("a candidate is active");
+    assert_eq!(data.window_id, dragged);
+    assert_eq!(data.target_id, target);
+    assert_eq!(data.target_frame, target_frame);
+
+    reactor.drag_manager.reset();
+    assert!(reactor.query_drag_swap_target().is_none());
+}
+
+#[test]
+fn dragging_onto_a_window_broadcasts_start_candidate_and_end() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![screen],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let (broadcast_tx, mut broadcast_rx) = actor::channel();
+    reactor.communication_manager.event_broadcaster = broadcast_tx;
+    let mut drag_events = move || {
+        let mut events = Vec::new();
+        while let Ok((_, event)) = broadcast_rx.try_recv() {
+            if matches!(
+                event,
+                BroadcastEvent::DragStarted { .. }
+                    | BroadcastEvent::DragSwapCandidate { .. }
+                    | BroadcastEvent::DragEnded { .. }
+            ) {
+                events.push(event);
+            }
+        }
+        events
+    };
+
+    let dragged = WindowId::new(1, 1);
+    let target = WindowId::new(1, 2);
+    let target_frame = reactor.window_manager.windows[&target].frame_monotonic;
+    let drag_to = |frame: CGRect| {
+        Event::WindowFrameChanged(dragged, frame, None, Requested(false), Some(MouseState::Down))
+    };
+
+    // Moving the window (same size) over its neighbour starts the drag and
+    // announces the swap candidate.
+    reactor.handle_event(drag_to(target_frame));
+    let events = drag_events();
+    assert!(
+        matches!(
+            events.as_slice(),
+            [
+                BroadcastEvent::DragStarted { window_id },
+                BroadcastEvent::DragSwapCandidate { window_id: candidate_of, target: Some(candidate) },
+            ] if *window_id == dragged && *candidate_of == dragged && *candidate == target
+        ),
+        "unexpected drag broadcasts: {events:?}"
+    );
+
+    // Further movement over the same target does not re-announce it.
+    let mut nudged = target_frame;
+    nudged.origin.x += 1.;
+    reactor.handle_event(drag_to(nudged));
+    assert!(drag_events().is_empty());
+
+    reactor.handle_event(Event::MouseUp);
+    apps.simulate_until_quiet(&mut reactor);
+    let events = drag_events();
+    assert!(
+        matches!(
+            events.as_slice(),
+            [BroadcastEvent::DragEnded { window_id, swapped: true }] if *window_id == dragged
+        ),
+        "unexpected drag broadcasts: {events:?}"
+    );
+}
+
+#[test]
+fn mouse_warp_point_follows_the_configured_target() {
+    let frame = CGRect::new(CGPoint::new(100., 100.), CGSize::new(800., 600.));
+    let warp = |target, cursor| Reactor::mouse_warp_point(frame, target, cursor);
+
+    assert_eq!(warp(MouseFollowsFocusTarget::Center, None), frame.mid());
+    assert_eq!(
+        warp(MouseFollowsFocusTarget::TopLeft, None),
+        CGPoint::new(108., 108.)
+    );
+
+    // Outside the window the cursor is pulled to the closest point just inside it.
+    assert_eq!(
+        warp(
+            MouseFollowsFocusTarget::NearestEdge,
+            Some(CGPoint::new(1200., 400.))
+        ),
+        CGPoint::new(892., 400.)
+    );
+    assert_eq!(
+        warp(MouseFollowsFocusTarget::NearestEdge, Some(CGPoint::new(0., 0.))),
+        CGPoint::new(108., 108.)
+    );
+    // Inside the window the cursor stays put.
+    assert_eq!(
+        warp(
+            MouseFollowsFocusTarget::NearestEdge,
+            Some(CGPoint::new(300., 250.))
+        ),
+        CGPoint::new(300., 250.)
+    );
+    // Without a known cursor position there is nothing to be near; fall back to the center.
+    assert_eq!(warp(MouseFollowsFocusTarget::NearestEdge, None), frame.mid());
+}
+
+#[test]
+fn next_workspace_with_indicator_switches_and_asks_for_the_overlay() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let (stack_line_tx, mut stack_line_rx) = actor::channel();
+    reactor.communication_manager.stack_line_tx = Some(stack_line_tx);
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::NextWorkspaceWithIndicator { skip_empty: None },
+    )));
+
+    let engine = &reactor.layout_manager.layout_engine;
+    assert_eq!(
+        engine.virtual_workspace_manager().active_workspace_idx(space),
+        Some(1)
+    );
+    let mut indicators =
+        std::iter::from_fn(|| stack_line_rx.try_recv().ok()).filter_map(|(_, event)| match event {
+            crate::actor::stack_line::Event::ShowWorkspaceIndicator { screen, label } => {
+                Some((screen, label))
+            }
+            _ => None,
+        });
+    let (shown_on, label) = indicators.next().expect("an indicator should be requested");
+    assert_eq!(shown_on, screen);
+    assert!(label.starts_with('2'), "unexpected label {label:?}");
+    assert!(indicators.next().is_none());
+}
+
+#[test]
+fn windows_in_workspace_query_includes_inactive_workspaces() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::MoveWindowToWorkspace {
+            workspace: 1,
+            window_id: Some(2),
+        },
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let ids = |reactor: &mut Reactor, index| {
+        reactor
+            .query_windows_in_workspace(Some(space), index)
+            .map(|windows| windows.into_iter().map(|w| w.id).collect::<Vec<_>>())
+    };
+    assert_eq!(ids(&mut reactor, 0), Ok(vec![WindowId::new(1, 1)]));
+    assert_eq!(ids(&mut reactor, 1), Ok(vec![WindowId::new(1, 2)]));
+    assert_eq!(ids(&mut reactor, 2), Ok(vec![]));
+    assert!(ids(&mut reactor, 99).is_err());
+}
+
+#[test]
+fn queued_frame_changes_of_one_window_coalesce_to_the_last_frame() {
+    let frame_changed = |wid: WindowId, width: f64| {
+        Event::WindowFrameChanged(
+            wid,
+            CGRect::new(CGPoint::new(0., 0.), CGSize::new(width, 400.)),
+            None,
+            Requested(false),
+            Some(MouseState::Down),
+        )
+    };
+    let resized = WindowId::new(1, 1);
+    let other = WindowId::new(1, 2);
+    let (tx, mut rx) = actor::channel();
+    tx.send(frame_changed(resized, 510.));
+    tx.send(frame_changed(resized, 523.5));
+    tx.send(frame_changed(other, 300.));
+    let mut carried = None;
+
+    let (span, first) = rx.try_recv().unwrap();
+    let (_, event) = Reactor::coalesce_frame_changes(
+        span,
+        first,
+        &mut rx,
+        &mut carried,
+        Duration::from_millis(16),
+    );
+    let Event::WindowFrameChanged(wid, frame, ..) = event else {
+        panic!("expected a frame change, got {event:?}");
+    };
+    assert_eq!(wid, resized);
+    assert_eq!(frame.size.width, 523.5);
+    assert!(
+        matches!(carried, Some((_, Event::WindowFrameChanged(wid, ..))) if wid == other),
+        "the other window's change should be carried over, not dropped"
+    );
+
+    let (span, first) = carried.take().unwrap();
+    tx.send(frame_changed(other, 320.));
+    let (_, event) =
+        Reactor::coalesce_frame_changes(span, first, &mut rx, &mut carried, Duration::ZERO);
+    assert!(matches!(event, Event::WindowFrameChanged(_, frame, ..) if frame.size.width == 300.));
+    assert!(carried.is_none());
+    assert!(rx.try_recv().is_ok(), "a zero interval leaves the queue alone");
+}
+
+#[test]
+fn always_on_top_only_pins_floating_windows_and_clears_when_they_tile() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![screen],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(2),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+    let pip = WindowId::new(1, 1);
+
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::ToggleAlwaysOnTop)));
+    assert!(
+        reactor.window_manager.always_on_top.is_empty(),
+        "tiled windows can't be pinned"
+    );
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::ToggleWindowFloating,
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::ToggleAlwaysOnTop)));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(reactor.window_manager.always_on_top.contains(&pip));
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::ToggleWindowFloating,
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(
+        reactor.window_manager.always_on_top.is_empty(),
+        "a window that goes back to tiling loses always-on-top"
+    );
+}
+
+#[test]
+fn mismatched_space_change_is_deferred_until_a_matching_one() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let left = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1280., 800.));
+    let right = CGRect::new(CGPoint::new(1280., 0.), CGSize::new(1280., 800.));
+    reactor.handle_event(screen_params_event(
+        vec![left, right],
+        vec![Some(SpaceId::new(11)), Some(SpaceId::new(22))],
+        vec![],
+    ));
+
+    reactor.handle_event(Event::SpaceChanged(vec![
+        Some(SpaceId::new(33)),
+        Some(SpaceId::new(44)),
+        Some(SpaceId::new(55)),
+    ]));
+    assert_eq!(
+        reactor.raw_spaces_for_current_screens(),
+        vec![Some(SpaceId::new(11)), Some(SpaceId::new(22))],
+        "an oversize vector must not be zipped onto the current screens"
+    );
+    assert!(reactor.pending_space_change_manager.pending_space_change.is_some());
+
+    reactor.handle_event(Event::SpaceChanged(vec![
+        Some(SpaceId::new(22)),
+        Some(SpaceId::new(11)),
+    ]));
+    assert_eq!(reactor.raw_spaces_for_current_screens(), vec![
+        Some(SpaceId::new(22)),
+        Some(SpaceId::new(11))
+    ]);
+    assert!(
+        reactor.pending_space_change_manager.pending_space_change.is_none(),
+        "a matching vector supersedes the deferred one"
+    );
+}
+
+#[test]
+fn duplicate_space_snapshot_clears_a_deferred_change() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let left = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1280., 800.));
+    let right = CGRect::new(CGPoint::new(1280., 0.), CGSize::new(1280., 800.));
+    let current = vec![Some(SpaceId::new(11)), Some(SpaceId::new(22))];
+    reactor.handle_event(screen_params_event(vec![left, right], current.clone(), vec![]));
+
+    reactor.handle_event(Event::SpaceChanged(vec![Some(SpaceId::new(33))]));
+    assert!(reactor.pending_space_change_manager.pending_space_change.is_some());
+
+    reactor.handle_event(Event::SpaceChanged(current.clone()));
+    assert!(
+        reactor.pending_space_change_manager.pending_space_change.is_none(),
+        "a duplicate of the applied spaces still supersedes the deferred change"
+    );
+
+    // Nothing is left to be force-applied once it would have expired.
+    reactor.config.settings.pending_space_change_max_age_ms = 1;
+    std::thread::sleep(std::time::Duration::from_millis(5));
+    reactor.try_apply_pending_space_change();
+    assert_eq!(reactor.raw_spaces_for_current_screens(), current);
+}
+
+#[test]
+fn stale_oversize_space_change_is_dropped_not_truncated() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let left = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1280., 800.));
+    let right = CGRect::new(CGPoint::new(1280., 0.), CGSize::new(1280., 800.));
+    let current = vec![Some(SpaceId::new(11)), Some(SpaceId::new(22))];
+    reactor.handle_event(screen_params_event(vec![left, right], current.clone(), vec![]));
+
+    reactor.pending_space_change_manager.pending_space_change = Some(PendingSpaceChange {
+        spaces: vec![
+            Some(SpaceId::new(33)),
+            Some(SpaceId::new(44)),
+            Some(SpaceId::new(55)),
+        ],
+        queued_at: std::time::Instant::now() - std::time::Duration::from_secs(60),
+    });
+    reactor.try_apply_pending_space_change();
+    assert!(reactor.pending_space_change_manager.pending_space_change.is_none());
+    assert_eq!(reactor.raw_spaces_for_current_screens(), current);
+}
+
+#[test]
+fn autoraise_skips_blacklisted_apps() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![screen],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(2),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+
+    assert!(reactor.should_raise_on_mouse_over(WindowId::new(1, 2)));
+    reactor.config.settings.autoraise_blacklist = vec!["com.testapp1".to_string()];
+    assert!(!reactor.should_raise_on_mouse_over(WindowId::new(1, 2)));
+}
+
+#[test]
+fn display_topology_lists_screens_in_order_with_their_workspaces() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screens = vec![
+        CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.)),
+        CGRect::new(CGPoint::new(1000., 0.), CGSize::new(800., 600.)),
+    ];
+    let (first, second) = (SpaceId::new(1), SpaceId::new(2));
+    reactor.handle_event(screen_params_event(
+        screens.clone(),
+        vec![Some(first), Some(second)],
+        vec![],
+    ));
+
+    let topology = reactor.query_display_topology();
+    assert_eq!(topology.displays.len(), 2);
+    for (index, (display, frame)) in topology.displays.iter().zip(&screens).enumerate() {
+        assert_eq!(display.index, index);
+        assert_eq!(display.frame, *frame);
+        assert!(!display.workspaces.is_empty());
+        let active: Vec<_> = display.workspaces.iter().filter(|ws| ws.is_active).collect();
+        assert_eq!(active.len(), 1);
+        assert_eq!(display.active_workspace_id.as_ref(), Some(&active[0].id));
+    }
+    assert_eq!(topology.displays[0].space_id, Some(first.get()));
+    assert_eq!(topology.displays[1].space_id, Some(second.get()));
+
+    let mut all_spaces: Vec<u64> = topology
+        .active_space_ids
+        .iter()
+        .chain(&topology.inactive_space_ids)
+        .copied()
+        .collect();
+    all_spaces.sort();
+    assert_eq!(all_spaces, vec![first.get(), second.get()]);
+}
+
+#[test]
+fn toggle_gaps_survives_workspace_switches_but_not_space_changes() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.layout.gaps.outer.top = 20.0;
+    reactor.config.settings.layout.gaps.inner.horizontal = 8.0;
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::ToggleGaps)));
+    let gaps = reactor.query_effective_gaps(Some(space)).unwrap();
+    assert_eq!((gaps.outer.top, gaps.inner.horizontal), (0.0, 0.0));
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::SwitchToWorkspace(1),
+    )));
+    assert_eq!(reactor.query_effective_gaps(Some(space)).unwrap().outer.top, 0.0);
+
+    let other = SpaceId::new(2);
+    reactor.handle_event(Event::SpaceChanged(vec![Some(other)]));
+    reactor.handle_event(Event::SpaceChanged(vec![Some(space)]));
+    assert_eq!(
+        reactor.query_effective_gaps(Some(space)).unwrap().outer.top,
+        20.0
+    );
+
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::ToggleGaps)));
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::ToggleGaps)));
+    assert_eq!(
+        reactor.query_effective_gaps(Some(space)).unwrap().outer.top,
+        20.0
+    );
+}
+
+#[test]
+fn toggle_window_managed_overrides_app_rules_until_toggled_back() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(2),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let wid = WindowId::new(1, 1);
+    let tiled = |reactor: &Reactor| {
+        reactor
+            .layout_manager
+            .layout_engine
+            .windows_in_active_workspace(space)
+            .contains(&wid)
+    };
+    assert!(tiled(&reactor));
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::ToggleWindowManaged,
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(!tiled(&reactor));
+    assert!(reactor.window_manager.windows[&wid].ignore_app_rule);
+
+    // Re-running the app rules (as a title change does) keeps it unmanaged.
+    let app_info = reactor.app_manager.apps[&1].info.clone();
+    reactor.process_windows_for_app_rules(1, vec![wid], app_info);
+    assert!(!tiled(&reactor));
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::ToggleWindowManaged,
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(tiled(&reactor));
+    assert!(!reactor.window_manager.windows[&wid].ignore_app_rule);
+}
+
+#[test]
+fn mirrored_placeholder_follows_the_source_window_onto_another_display() {
+    use crate::actor::reactor::mirror::mirror_frame;
+    use crate::sys::geometry::SameAs;
+
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screens = vec![
+        CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.)),
+        CGRect::new(CGPoint::new(1000., 0.), CGSize::new(2000., 500.)),
+    ];
+    reactor.handle_event(screen_params_event(
+        screens.clone(),
+        vec![Some(SpaceId::new(1)), Some(SpaceId::new(2))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(2),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let (source, placeholder) = (WindowId::new(1, 1), WindowId::new(1, 2));
+    reactor.handle_event(Event::Command(Command::Reactor(
+        ReactorCommand::MirrorToDisplay {
+            selector: DisplaySelector::Uuid("test-display-1".to_string()),
+            placeholder: WindowServerId::new(2),
+        },
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(reactor.layout_manager.layout_engine.is_window_floating(placeholder));
+    let mirrored = |apps: &Apps| {
+        (
+            apps.windows[&placeholder].frame,
+            mirror_frame(apps.windows[&source].frame, screens[0], screens[1]),
+        )
+    };
+    let (actual, expected) = mirrored(&apps);
+    assert!(actual.same_as(expected), "{actual:?} != {expected:?}");
+
+    // The source now has the first screen to itself; moving it is mirrored.
+    reactor.handle_event(Event::WindowFrameChanged(
+        source,
+        CGRect::new(CGPoint::new(100., 200.), CGSize::new(400., 300.)),
+        None,
+        Requested(false),
+        Some(MouseState::Up),
+    ));
+    apps.simulate_until_quiet(&mut reactor);
+    let (actual, expected) = mirrored(&apps);
+    assert!(actual.same_as(expected), "{actual:?} != {expected:?}");
+
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::ClearMirror)));
+    assert!(!reactor.mirror_manager.is_source(source));
+}
+
+#[test]
+fn focus_follows_mouse_waits_for_the_cursor_to_rest_on_a_window() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.focus_follows_mouse_delay_ms = 100;
+    let (raise_manager_tx, mut raise_manager_rx) = actor::channel();
+    reactor.communication_manager.raise_manager_tx = raise_manager_tx;
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![screen],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(3),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+    while raise_manager_rx.try_recv().is_ok() {}
+
+    // Passing over the second window on the way to the third focuses neither
+    // until the cursor settles.
+    reactor.handle_event(Event::MouseMovedOverWindow(WindowServerId::new(2)));
+    reactor.handle_event(Event::MouseMovedOverWindow(WindowServerId::new(3)));
+    assert!(raise_manager_rx.try_recv().is_err());
+    assert!(reactor.mouse_hover_dwell_remaining() <= Duration::from_millis(100));
+
+    reactor.handle_event(Event::MouseHoverSettled(WindowServerId::new(2)));
+    assert!(raise_manager_rx.try_recv().is_err());
+
+    // Settling before the dwell has elapsed is ignored.
+    reactor.handle_event(Event::MouseHoverSettled(WindowServerId::new(3)));
+    assert!(raise_manager_rx.try_recv().is_err());
+    assert_ne!(reactor.mouse_hover_dwell_remaining(), Duration::MAX);
+
+    std::thread::sleep(reactor.mouse_hover_dwell_remaining() + Duration::from_millis(5));
+    reactor.handle_event(Event::MouseHoverSettled(WindowServerId::new(3)));
+    let msg = raise_manager_rx.try_recv().expect("Should have sent an event").1;
+    match msg {
+        raise_manager::Event::RaiseRequest(RaiseRequest { focus_window, .. }) => {
+            assert_eq!(focus_window.map(|(wid, _)| wid), Some(WindowId::new(1, 3)));
+        }
+        _ => panic!("Unexpected event: {msg:?}"),
+    }
+    assert_eq!(reactor.mouse_hover_dwell_remaining(), Duration::MAX);
+
+    // Leaving for the desktop mid-dwell cancels it.
+    reactor.handle_event(Event::MouseMovedOverWindow(WindowServerId::new(2)));
+    reactor.handle_event(Event::MouseHoverCleared);
+    assert_eq!(reactor.mouse_hover_dwell_remaining(), Duration::MAX);
+
+    // Turning focus-follows-mouse off mid-dwell cancels it.
+    reactor.handle_event(Event::MouseMovedOverWindow(WindowServerId::new(2)));
+    reactor.config.settings.focus_follows_mouse = false;
+    reactor.update_focus_follows_mouse_state();
+    assert_eq!(reactor.mouse_hover_dwell_remaining(), Duration::MAX);
+}
+
+#[test]
+fn metrics_count_processed_and_quarantined_events() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.handle_loop_event(Event::MouseUp);
+    reactor.display_topology_manager.begin_churn(
+        3,
+        crate::sys::skylight::DisplayReconfigFlags::ADD,
+        crate::common::collections::HashSet::default(),
+    );
+    reactor.handle_loop_event(Event::WindowFrameChanged(
+        WindowId::new(99, 1),
+        CGRect::new(CGPoint::new(10., 10.), CGSize::new(500., 400.)),
+        None,
+        Requested(false),
+        Some(MouseState::Up),
+    ));
+    reactor.event_metrics.record_queue_depth(5);
+    reactor.event_metrics.record_queue_depth(2);
+
+    let events = reactor.query_metrics().events;
+    assert_eq!(events, crate::model::server::EventMetricsData {
+        processed: 2,
+        quarantined: 1,
+        queue_depth: 2,
+        max_queue_depth: 5,
+    });
+}
+
+#[test]
+fn disabling_stale_cleanup_survives_screen_changes() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::SetStaleCleanup(
+        false,
+    ))));
+    reactor.handle_event(screen_params_event(vec![screen], vec![None], vec![]));
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+
+    assert!(!reactor.refocus_manager.stale_cleanup_enabled);
+    assert_eq!(
+        reactor.refocus_manager.stale_cleanup_state,
+        StaleCleanupState::Enabled
+    );
+
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::SetStaleCleanup(
+        true,
+    ))));
+    assert!(reactor.refocus_manager.stale_cleanup_enabled);
+}
+
+#[test]
+fn closing_the_last_window_keeps_focus_on_its_screen_when_configured() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.keep_focus_on_screen_after_close = true;
+    reactor.config.settings.mouse_follows_focus = true;
+    let left = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let right = CGRect::new(CGPoint::new(1000., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(
+        vec![left, right],
+        vec![Some(space), Some(SpaceId::new(2))],
+        vec![],
+    ));
+    let wid = WindowId::new(1, 1);
+    reactor.handle_events(apps.make_app_with_opts(1, make_windows(1), Some(wid), true, true));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+    assert_eq!(reactor.layout_manager.layout_engine.focused_window(), Some(wid));
+
+    let (event_tap_tx, mut event_tap_rx) = actor::channel();
+    reactor.communication_manager.event_tap_tx = Some(event_tap_tx);
+    reactor.handle_event(Event::WindowDestroyed(wid));
+
+    let mut warped_to = None;
+    while let Ok((_, request)) = event_tap_rx.try_recv() {
+        if let crate::actor::event_tap::Request::Warp(point) = request {
+            warped_to = Some(point);
+        }
+    }
+    assert_eq!(warped_to, Some(left.mid()));
+    assert_eq!(reactor.layout_manager.layout_engine.focused_window(), None);
+}
+
+#[test]
+fn resync_rebroadcasts_the_state_of_active_spaces() {
+    let (broadcast_tx, mut broadcast_rx) = actor::channel();
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        Some(broadcast_tx.clone()),
+    ));
+    reactor.communication_manager.event_broadcaster = broadcast_tx;
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    while broadcast_rx.try_recv().is_ok() {}
+
+    assert_eq!(reactor.resync_broadcasts(), 1);
+
+    let (mut workspaces, mut window_lists, mut titles) = (0, Vec::new(), Vec::new());
+    while let Ok((_, event)) = broadcast_rx.try_recv() {
+        match event {
+            BroadcastEvent::WorkspaceChanged { space_id, .. } => {
+                assert_eq!(space_id, space);
+                workspaces += 1;
+            }
+            BroadcastEvent::WindowsChanged { windows, .. } => window_lists.push(windows.len()),
+            BroadcastEvent::WindowTitleChanged {
+                window_id,
+                previous_title,
+                new_title,
+                ..
+            } => {
+                assert_eq!(previous_title, new_title);
+                titles.push(window_id);
+            }
+            _ => {}
+        }
+    }
+    titles.sort();
+    assert_eq!(workspaces, 1);
+    assert_eq!(window_lists, vec![2]);
+    assert_eq!(titles, vec![WindowId::new(1, 1), WindowId::new(1, 2)]);
+}
+
+#[test]
+fn bound_gestures_run_their_layout_command() {
+    use crate::common::config::Gesture;
+
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor
+        .config
+        .settings
+        .gestures
+        .bindings
+        .insert(Gesture::SwipeUp, LayoutCommand::SwitchToWorkspace(2));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::Gesture(
+        Gesture::SwipeDown,
+    ))));
+    assert!(reactor.query_workspace_switch_history().is_empty());
+
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::Gesture(
+        Gesture::SwipeUp,
+    ))));
+    let history = reactor.query_workspace_switch_history();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].origin, WorkspaceSwitchOrigin::Gesture);
+    assert_eq!(
+        reactor.layout_manager.layout_engine.active_workspace_idx(space),
+        Some(2)
+    );
+}
+
+#[test]
+fn layout_freeze_keeps_windows_in_place_until_unfrozen() {
+    let (broadcast_tx, mut broadcast_rx) = actor::channel();
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.communication_manager.event_broadcaster = broadcast_tx;
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+    let first = WindowId::new(1, 1);
+    let before = apps.windows[&first].frame;
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::ToggleLayoutFreeze,
+    )));
+    reactor.handle_events(apps.make_app(2, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+    assert_eq!(apps.windows[&first].frame, before);
+    assert!(
+        reactor
+            .layout_manager
+            .layout_engine
+            .windows_in_active_workspace(space)
+            .contains(&WindowId::new(2, 1))
+    );
+
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::ToggleLayoutFreeze,
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(apps.windows[&first].frame.size.width < before.size.width);
+
+    let mut frozen_states = Vec::new();
+    while let Ok((_, event)) = broadcast_rx.try_recv() {
+        if let BroadcastEvent::LayoutFreezeChanged { frozen } = event {
+            frozen_states.push(frozen);
+        }
+    }
+    assert_eq!(frozen_states, vec![true, false]);
+}
+
+#[test]
+fn gesture_bound_strip_scroll_does_not_reenter_itself_at_the_edge() {
+    use crate::common::config::{Gesture, LayoutMode, LayoutSettings};
+
+    let mut layout = LayoutSettings::default();
+    layout.mode = LayoutMode::Scrolling;
+    layout.scrolling.gestures.propagate_to_workspace_swipe = true;
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &layout,
+        None,
+    ));
+    reactor.config.settings.layout = layout;
+    reactor.config.settings.gestures.bindings.insert(
+        Gesture::SwipeLeft,
+        LayoutCommand::ScrollStrip { delta: -10.0, source: None },
+    );
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![screen],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    // Scrolling this far past the left edge reports a boundary hit, which maps
+    // back to `swipe_left`; it must not be forwarded again.
+    for _ in 0..2 {
+        reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::Gesture(
+            Gesture::SwipeLeft,
+        ))));
+    }
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(reactor.query_workspace_switch_history().is_empty());
+}
+
+#[test]
+fn sticky_multi_space_policy_floats_windows_flagged_at_discovery() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let multi_space = WindowId::new(1, 1);
+    let single_space = WindowId::new(1, 2);
+    reactor.window_manager.windows.get_mut(&multi_space).unwrap().is_multi_space = true;
+    let added = |wid| LayoutEvent::WindowAdded(space, wid, None);
+
+    // With the default policy nothing changes.
+    reactor.apply_multi_space_window_policy(&added(multi_space));
+    assert!(!reactor.layout_manager.layout_engine.is_window_floating(multi_space));
+
+    reactor.config.settings.multi_space_windows = MultiSpaceWindowPolicy::Sticky;
+    reactor.apply_multi_space_window_policy(&added(multi_space));
+    reactor.apply_multi_space_window_policy(&added(single_space));
+    assert!(reactor.layout_manager.layout_engine.is_window_floating(multi_space));
+    assert!(!reactor.layout_manager.layout_engine.is_window_floating(single_space));
+}
+
+#[test]
+fn a_workspace_layouts_animate_setting_overrides_the_global_one() {
+    use super::animation::AnimationManager;
+    use crate::common::config::LayoutMode;
+
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+    reactor.config.settings.animate = true;
+
+    // Without a layout-specific setting the global one applies.
+    assert!(AnimationManager::animation_enabled_for_space(&reactor, space));
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::SetWorkspaceLayout {
+            workspace: Some(0),
+            mode: LayoutMode::Scrolling,
+        },
+    )));
+    assert!(AnimationManager::animation_enabled_for_space(&reactor, space));
+
+    let space_settings = |animate| {
+        let mut settings = crate::common::config::LayoutSettings::default();
+        settings.scrolling.animate = Some(animate);
+        Some(settings)
+    };
+    reactor
+        .layout_manager
+        .layout_engine
+        .set_space_layout_settings(space, space_settings(false));
+    assert!(!AnimationManager::animation_enabled_for_space(&reactor, space));
+
+    reactor.config.settings.animate = false;
+    reactor
+        .layout_manager
+        .layout_engine
+        .set_space_layout_settings(space, space_settings(true));
+    assert!(AnimationManager::animation_enabled_for_space(&reactor, space));
+}
+
+#[test]
+fn workspace_query_predicts_gapless_frames_after_toggle_gaps() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.layout.gaps.outer.top = 10.;
+    reactor.config.settings.layout.gaps.outer.left = 10.;
+    let screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+
+    reactor.handle_event(screen_params_event(vec![screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::MoveWindowToWorkspace {
+            workspace: 1,
+            window_id: Some(2),
+        },
+    )));
+
+    let predicted_origin = |reactor: &mut Reactor| {
+        let workspaces = reactor.query_workspaces(Some(space), true);
+        assert!(!workspaces[1].is_active);
+        workspaces[1].windows[0].info.frame.origin
+    };
+    assert_eq!(predicted_origin(&mut reactor), CGPoint::new(10., 10.));
+
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::ToggleGaps)));
+    assert_eq!(predicted_origin(&mut reactor), CGPoint::new(0., 0.));
+}
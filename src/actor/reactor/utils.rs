@@ -1,34 +1,47 @@
 use objc2_app_kit::NSNormalWindowLevel;
 
 use crate::common::collections::HashMap;
+use crate::common::config::{MissingWindowServerIdPolicy, MultiSpaceWindowPolicy};
 use crate::sys::window_server::{WindowServerId, WindowServerInfo, window_is_sticky, window_level};
 
+/// Whether macOS shows the window on several spaces ("Assign To: All Desktops").
+pub fn window_is_multi_space(window_server_id: Option<WindowServerId>) -> bool {
+    window_server_id.is_some_and(window_is_sticky)
+}
+
 /// Computes whether a window is manageable based on its properties and window server information.
 ///
 /// A window is manageable if:
 /// - It is not minimized
 /// - Its layer is 0 (if info available)
-/// - It is not sticky
+/// - It is not on several spaces, unless `multi_space` manages those
 /// - Its level is normal (if available)
 /// - It is AX standard and AX root
+/// - It has a window server id, unless `missing_server_id` allows frame-only handling
 pub fn compute_window_manageability(
     window_server_id: Option<WindowServerId>,
     is_minimized: bool,
     is_ax_standard: bool,
     is_ax_root: bool,
     window_server_info: &HashMap<WindowServerId, WindowServerInfo>,
+    missing_server_id: MissingWindowServerIdPolicy,
+    multi_space: MultiSpaceWindowPolicy,
 ) -> bool {
     if is_minimized {
         return false;
     }
 
+    if window_server_id.is_none() && missing_server_id == MissingWindowServerIdPolicy::Skip {
+        return false;
+    }
+
     if let Some(wsid) = window_server_id {
         if let Some(info) = window_server_info.get(&wsid) {
             if info.layer != 0 {
                 return false;
             }
         }
-        if window_is_sticky(wsid) {
+        if multi_space == MultiSpaceWindowPolicy::Ignore && window_is_sticky(wsid) {
             return false;
         }
 
@@ -1,6 +1,7 @@
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use objc2::MainThreadMarker;
 use objc2_app_kit::NSCursor;
@@ -15,9 +16,11 @@ use crate::common::config::{Config, HorizontalPlacement, VerticalPlacement};
 use crate::layout_engine::LayoutKind;
 use crate::model::tree::NodeId;
 use crate::sys::screen::{CoordinateConverter, SpaceId};
+use crate::sys::timer::Timer;
 use crate::ui::stack_line::{
     GroupDisplayData, GroupIndicatorWindow, GroupKind, IndicatorConfig, point_hits_indicator_frame,
 };
+use crate::ui::workspace_indicator::WorkspaceIndicatorWindow;
 
 /// Shared indicator hit-rect state readable from the event tap callback.
 pub type SharedHitRects = Rc<RefCell<Vec<CGRect>>>;
@@ -55,6 +58,12 @@ pub enum Event {
         point: CGPoint,
         hits_indicator: bool,
     },
+    /// Briefly show `label` centered on `screen`. Shown even when the stack
+    /// line itself is disabled.
+    ShowWorkspaceIndicator {
+        screen: CGRect,
+        label: String,
+    },
 }
 
 pub struct StackLine {
@@ -69,6 +78,8 @@ pub struct StackLine {
     group_sigs_by_space: HashMap<SpaceId, Vec<GroupSig>>,
     cursor_over_indicator: bool,
     shared_hit_rects: SharedHitRects,
+    workspace_indicator: Option<WorkspaceIndicatorWindow>,
+    workspace_indicator_hide_at: Option<Instant>,
 }
 
 pub type Sender = actor::Sender<Event>;
@@ -93,6 +104,8 @@ impl StackLine {
             group_sigs_by_space: HashMap::default(),
             cursor_over_indicator: false,
             shared_hit_rects,
+            workspace_indicator: None,
+            workspace_indicator_hide_at: None,
         }
     }
 
@@ -101,9 +114,23 @@ impl StackLine {
             tracing::debug!("stack line disabled at start; will listen for config changes");
         }
 
-        while let Some((span, event)) = self.rx.recv().await {
-            let _guard = span.enter();
-            self.handle_event(event);
+        let mut workspace_indicator_timer = Timer::manual();
+        loop {
+            let until_hide = self
+                .workspace_indicator_hide_at
+                .map_or(Duration::MAX, |at| at.saturating_duration_since(Instant::now()));
+            workspace_indicator_timer.set_next_fire(until_hide);
+
+            tokio::select! {
+                event = self.rx.recv() => {
+                    let Some((span, event)) = event else { break };
+                    let _guard = span.enter();
+                    self.handle_event(event);
+                }
+                _ = workspace_indicator_timer.next() => {
+                    self.hide_expired_workspace_indicator();
+                }
+            }
         }
     }
 
@@ -131,6 +158,7 @@ impl StackLine {
                     | Event::ScreenParametersChanged(_)
                     | Event::MouseDown(_)
                     | Event::MouseMoved { .. }
+                    | Event::ShowWorkspaceIndicator { .. }
             )
         {
             return;
@@ -163,6 +191,43 @@ impl StackLine {
             Event::MouseMoved { point, hits_indicator } => {
                 self.handle_mouse_moved(point, hits_indicator);
             }
+            Event::ShowWorkspaceIndicator { screen, label } => {
+                self.show_workspace_indicator(screen, &label);
+            }
+        }
+    }
+
+    fn show_workspace_indicator(&mut self, screen: CGRect, label: &str) {
+        if self.workspace_indicator.is_none() {
+            match WorkspaceIndicatorWindow::new() {
+                Ok(window) => self.workspace_indicator = Some(window),
+                Err(err) => {
+                    tracing::warn!(?err, "failed to create workspace indicator window");
+                    return;
+                }
+            }
+        }
+        let Some(window) = self.workspace_indicator.as_mut() else {
+            return;
+        };
+        if let Err(err) = window.show(screen, label) {
+            tracing::warn!(?err, "failed to show workspace indicator");
+            return;
+        }
+        let duration =
+            Duration::from_millis(self.config.settings.ui.workspace_indicator.duration_ms);
+        self.workspace_indicator_hide_at = Some(Instant::now() + duration);
+    }
+
+    fn hide_expired_workspace_indicator(&mut self) {
+        if self.workspace_indicator_hide_at.is_none_or(|at| at > Instant::now()) {
+            return;
+        }
+        self.workspace_indicator_hide_at = None;
+        if let Some(window) = &self.workspace_indicator
+            && let Err(err) = window.hide()
+        {
+            tracing::warn!(?err, "failed to hide workspace indicator");
         }
     }
 
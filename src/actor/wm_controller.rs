@@ -307,27 +307,15 @@ impl WmController {
                 )));
             }
             Command(Wm(SwitchToWorkspace(ws_sel))) => {
-                let maybe_index: Option<usize> = match &ws_sel {
-                    WorkspaceSelector::Index(i) => Some(*i),
-                    WorkspaceSelector::Name(name) => self
-                        .config
-                        .config
-                        .virtual_workspaces
-                        .workspace_names
-                        .iter()
-                        .position(|n| n == name),
+                // Names are resolved by the layout engine against the workspaces that exist
+                // on the target space, so they keep working when workspaces are reordered.
+                let cmd = match ws_sel {
+                    WorkspaceSelector::Index(i) => layout::LayoutCommand::SwitchToWorkspace(i),
+                    WorkspaceSelector::Name(name) => {
+                        layout::LayoutCommand::SwitchToWorkspaceNamed { name }
+                    }
                 };
-
-                if let Some(workspace_index) = maybe_index {
-                    self.events_tx.send(reactor::Event::Command(reactor::Command::Layout(
-                        layout::LayoutCommand::SwitchToWorkspace(workspace_index),
-                    )));
-                } else {
-                    tracing::warn!(
-                        "Hotkey requested switch to workspace {:?} but it could not be resolved; ignoring",
-                        ws_sel
-                    );
-                }
+                self.events_tx.send(reactor::Event::Command(reactor::Command::Layout(cmd)));
             }
             Command(Wm(MoveWindowToWorkspace(ws_sel))) => {
                 let maybe_index: Option<usize> = match &ws_sel {
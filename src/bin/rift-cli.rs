@@ -3,10 +3,11 @@ use std::process::{self};
 
 use clap::{Parser, Subcommand};
 use rift_wm::actor::app::WindowId;
-use rift_wm::actor::reactor::{self, DisplaySelector};
-use rift_wm::common::config::LayoutMode;
+use rift_wm::actor::reactor::{self, DisplayCycle, DisplaySelector};
+use rift_wm::common::config::{LayoutMode, MasterStackSide};
 use rift_wm::ipc::{RiftCommand, RiftMachClient, RiftRequest, RiftResponse};
 use rift_wm::layout_engine as layout;
+use rift_wm::sys::screen::SpaceId;
 use rift_wm::sys::window_server::WindowServerId;
 use serde_json::Value;
 
@@ -62,12 +63,22 @@ enum QueryCommands {
     Workspaces {
         #[arg(long)]
         space_id: Option<u64>,
+        /// Only list ids, names and window counts, without window details
+        #[arg(long)]
+        without_windows: bool,
     },
     /// List windows (optionally filtered by space)
     Windows {
         #[arg(long)]
         space_id: Option<u64>,
     },
+    /// List the windows of one workspace, including an inactive one's hidden windows
+    WorkspaceWindows {
+        /// Workspace index (0-based)
+        workspace_index: usize,
+        #[arg(long)]
+        space_id: Option<u64>,
+    },
     /// List connected displays
     Displays,
     /// Get information about a specific window
@@ -85,6 +96,53 @@ enum QueryCommands {
     },
     /// Get performance metrics
     Metrics,
+    /// List recent workspace switches (oldest first)
+    WorkspaceHistory,
+    /// Get the gaps applied to a space's active workspace after all overrides
+    Gaps {
+        #[arg(long)]
+        space_id: Option<u64>,
+    },
+    /// Get the path from the layout root to the selected node
+    SelectionPath {
+        #[arg(long)]
+        space_id: Option<u64>,
+    },
+    /// List windows that never reported a window server id
+    WindowsWithoutServerId,
+    /// Get the focused window with its frame and workspace (null if none)
+    FocusedWindow,
+    /// Get whether new windows are allowed to take focus
+    NewWindowFocusSteal,
+    /// List each app rule with the live windows it matches
+    RuleCoverage,
+    /// Get the most recent failed layout update (null if none)
+    LastLayoutError,
+    /// Get whether stale windows are cleaned up automatically
+    StaleCleanup,
+    /// Get the dragged window and the window it would swap with on drop (null if none)
+    DragSwapTarget,
+    /// Get displays in order with their spaces and workspaces
+    DisplayTopology,
+    /// Show the frames a layout command would produce, without applying it
+    Preview {
+        #[arg(long)]
+        space_id: Option<u64>,
+        /// Layout command as JSON, e.g. '"equalize_sizes"' or '{"set_container_ratio":{"ratio":0.6}}'
+        command: String,
+    },
+    /// Save the workspaces of a space and their layouts as a named profile
+    SaveProfile {
+        name: String,
+        #[arg(long)]
+        space_id: Option<u64>,
+    },
+    /// Restore a named profile onto a space, skipping windows that no longer exist
+    LoadProfile {
+        name: String,
+        #[arg(long)]
+        space_id: Option<u64>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -141,12 +199,30 @@ enum WindowCommands {
     Focus {
         direction: String, // up, down, left, right
     },
+    /// Focus the Nth (0-based) visible window of the active workspace, in layout order
+    FocusIndex { index: usize },
     /// Toggle window floating state
     ToggleFloat,
     /// Toggle fullscreen mode (fills the whole screen, ignores outer gaps)
     ToggleFullscreen,
     /// Toggle fullscreen within configured outer gaps (respects outer gaps / fills tiling area)
     ToggleFullscreenWithinGaps,
+    /// Toggle edge-to-edge fullscreen over the menu bar and Dock, hiding floating windows and stack lines
+    TrueFullscreen,
+    /// Toggle whether rift resizes the focused window (it is still positioned)
+    ToggleIgnoreResize,
+    /// Toggle whether the focused window is shown on every workspace of its display
+    ToggleSticky,
+    /// Resize the focused floating window to the next preset size, centered
+    CycleFloatingPreset,
+    /// Float the focused window as a full-length strip along a screen edge (left, right, up, down)
+    DockToEdge { direction: String },
+    /// Close every window in the active workspace except the focused one
+    CloseOthers,
+    /// Keep the focused floating window above all others (toggle)
+    ToggleAlwaysOnTop,
+    /// Stop or resume managing the focused window, regardless of app rules (toggle)
+    ToggleManaged,
     /// Grow the current window size (increments by ~5%).
     ResizeGrow,
     /// Shrink the current window size (decrements by ~5%).
@@ -154,10 +230,50 @@ enum WindowCommands {
     /// Resize the selected window by a fractional amount.
     /// - Pass a signed floating value: positive to grow, negative to shrink.
     /// - The value is a fraction of the current size (e.g. `0.05` = 5%).
+    /// - Inside a stack or tab group the whole group is resized.
     /// Examples:
     ///   rift-cli execute window resize-by --amount 0.05    # grow by 5%
     ///   rift-cli execute window resize-by --amount -0.10   # shrink by 10%
     ResizeBy { amount: f64 },
+    /// Resize the stack or tab group holding the selected window as a unit.
+    /// Same as `resize-by`, which already treats a group as one unit.
+    ResizeGroup { amount: f64 },
+    /// Set the focused window's size to an absolute ratio in [0, 1]
+    /// - Tree layouts set the split ratio; scrolling sets the column width ratio.
+    /// Examples:
+    ///   rift-cli execute window resize-to --ratio 0.5
+    ResizeTo { ratio: f64 },
+    /// Move the focused window next to a target window, on the given side
+    MoveBeside {
+        /// Window server id of the target window
+        #[arg(long)]
+        target: String,
+        direction: String, // up, down, left, right
+    },
+    /// Cycle between showing all windows, tiled windows only, and floating windows only
+    ToggleFloatingVisibility,
+    /// Allow (true) or prevent (false) new windows from taking focus
+    SetFocusSteal { value: String },
+    /// Turn automatic stale window cleanup on (true) or off (false)
+    SetStaleCleanup { value: String },
+    /// Remove windows whose window server id no longer exists
+    PurgeStale,
+    /// Move the focused window into the workspace of the window under the cursor
+    MatchCursorWorkspace,
+    /// Re-run workspace auto-assignment for a single window
+    Reassign {
+        /// Window server id of the window
+        #[arg(long)]
+        window_id: String,
+    },
+    /// Switch to the workspace of an app's most recently focused window and focus it
+    FocusApp {
+        /// Bundle identifier of the app (e.g. com.apple.Safari)
+        bundle_id: String,
+        /// Launch the app if it isn't running
+        #[arg(long)]
+        launch: bool,
+    },
     /// Close a window by window server identifier
     Close {
         /// Window Id (window server id or idx from window id)
@@ -170,10 +286,14 @@ enum WindowCommands {
 enum WorkspaceCommands {
     /// Switch to next workspace
     Next { skip_empty: Option<bool> },
+    /// Switch to next workspace and briefly show its name in an on-screen overlay
+    NextWithIndicator { skip_empty: Option<bool> },
     /// Switch to previous workspace
     Prev { skip_empty: Option<bool> },
     /// Switch to specific workspace
     Switch { workspace_id: usize },
+    /// Switch to the workspace with the given name
+    SwitchNamed { name: String },
     /// Move current window to workspace
     MoveWindow {
         workspace_id: usize,
@@ -181,8 +301,27 @@ enum WorkspaceCommands {
     },
     /// Create a new workspace
     Create,
+    /// Create workspaces until the current display has at least `count`
+    Ensure { count: usize },
+    /// Delete the workspace at INDEX (0-based) on the current space
+    Delete {
+        index: usize,
+        /// Move its windows to the previous workspace instead of refusing
+        #[arg(long)]
+        force: bool,
+    },
+    /// Move the workspace at index FROM to index TO (0-based) on the current space
+    Move { from: usize, to: usize },
     /// Switch to the last workspace
     Last,
+    /// Move the focused window to the last workspace without switching to it
+    MoveWindowToLast,
+    /// Bring every window of the focused app on this display into the current workspace
+    GatherApp,
+    /// Show or hide the scratchpad on top of the current workspace
+    ToggleScratchpad,
+    /// Move the focused window into the scratchpad
+    MoveWindowToScratchpad,
     /// Set layout mode for a workspace (or active workspace when omitted)
     SetLayout {
         /// Workspace index (0-based). Defaults to active workspace if omitted.
@@ -191,6 +330,17 @@ enum WorkspaceCommands {
         /// Layout mode: traditional, bsp, stack, master_stack, scrolling
         mode: String,
     },
+    /// Send future windows of an app to a workspace index until unpinned (not saved)
+    PinApp {
+        /// Bundle identifier of the app (e.g. com.apple.Safari)
+        bundle_id: String,
+        /// Workspace index (0-based)
+        workspace_index: usize,
+    },
+    /// Remove a pin set with pin-app
+    UnpinApp { bundle_id: String },
+    /// Re-apply app rules and rebuild this display's layouts from the workspace assignments
+    Reconcile,
 }
 
 #[derive(Subcommand)]
@@ -201,26 +351,57 @@ enum LayoutCommands {
     Descend,
     /// Move the selected node in a direction
     MoveNode { direction: String },
+    /// Move the selected node all the way to the edge in a direction
+    MoveNodeToEdge { direction: String },
+    /// Swap the focused window with its neighbor in a direction, crossing
+    /// displays at the edge
+    SwapInDirection { direction: String },
     /// Join the selected window with neighbor in a direction
     JoinWindow { direction: String },
     /// Toggle stacked state for the selected container
     ToggleStack,
+    /// Show every window of the workspace as tabs of one full-screen stack (toggle)
+    ToggleMonocle,
+    /// Move the focused window into the container selected with ascend/descend
+    JoinIntoSelection,
+    /// Pin the focused stack member as the one shown while the stack is unfocused
+    SetStackRepresentative,
     /// Global orientation toggle that works consistently across layout modes (and between splits/stacks)
     ToggleOrientation,
+    /// Rotate the whole layout 90 degrees by flipping every split
+    Rotate,
     /// Unjoin previously joined windows
     Unjoin,
     /// Toggle floating on the focused selection (tree focus)
     ToggleFocusFloat,
+    /// Reset every split ratio in the active layout to an even share
+    EqualizeSizes,
+    /// Give the focused window this share of its parent split (e.g. 0.618)
+    SetContainerRatio { ratio: f64 },
     /// Adjust master ratio by a delta (master/stack layout only)
     AdjustMasterRatio { delta: f64 },
     /// Adjust master count by a delta (master/stack layout only)
     AdjustMasterCount { delta: i32 },
+    /// Step the master count through the configured cycle (master/stack layout only)
+    CycleMasterCount,
     /// Promote the selected window into the master area (master/stack layout only)
     PromoteToMaster,
     /// Swap the first master with the first stack window (master/stack layout only)
     SwapMasterStack,
+    /// Give the stack-area windows equal sizes, keeping the master ratio (master/stack layout only)
+    EqualizeStack,
+    /// Toggle dropping stack offsets and stack-line space on the current space
+    ToggleCompactStacks,
+    /// Toggle all gaps off on the current space, e.g. while screen sharing
+    ToggleGaps,
+    /// Stop moving windows on layout changes, or resume and re-tile
+    ToggleFreeze,
+    /// Put the master area on a side: left, right, top, bottom (master/stack layout only)
+    SetMasterPosition { side: String },
     /// Swap two windows by window id (`WindowId { pid: ..., idx: ... }`)
     SwapWindows { a: String, b: String },
+    /// Swap the focused window with the previously focused window in the workspace
+    SwapWithLast,
     /// Scroll the strip by a normalized delta (scrolling layout only)
     ScrollStrip { delta: f64 },
     /// Snap the strip to the nearest column boundary (scrolling layout only)
@@ -228,6 +409,12 @@ enum LayoutCommands {
     /// Toggle centering of the selected column in scrolling layout.
     /// If invoked again on the same selection, centering is removed.
     CenterSelection,
+    /// Apply a named layout preset to a space (omit the name to use the global settings)
+    ApplyPreset {
+        name: Option<String>,
+        #[arg(long)]
+        space_id: Option<u64>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -316,7 +503,8 @@ enum MissionControlCommands {
 enum DisplayCommands {
     /// Focus a display by direction, index, or UUID.
     Focus {
-        /// Direction relative to the current display (left, right, up, down).
+        /// Direction relative to the current display (left, right, up, down), or
+        /// next/prev to cycle in the configured display order.
         #[arg(long)]
         direction: Option<String>,
         /// Display index (0-based).
@@ -351,18 +539,51 @@ enum DisplayCommands {
         #[arg(long)]
         window_id: Option<u32>,
     },
+    /// Send the focused window to the workspace active on another display.
+    SendToActiveWorkspace {
+        /// Direction relative to the current display (left, right, up, down).
+        #[arg(long)]
+        direction: Option<String>,
+        /// Display index (0-based).
+        #[arg(long)]
+        index: Option<usize>,
+        /// Display UUID.
+        #[arg(long)]
+        uuid: Option<String>,
+        /// Focus the window on its new display.
+        #[arg(long)]
+        follow: bool,
+    },
+    /// Keep a placeholder window on another display at the focused window's
+    /// relative position and size.
+    Mirror {
+        /// Direction relative to the current display (left, right, up, down).
+        #[arg(long)]
+        direction: Option<String>,
+        /// Display index (0-based).
+        #[arg(long)]
+        index: Option<usize>,
+        /// Display UUID.
+        #[arg(long)]
+        uuid: Option<String>,
+        /// Window server id of the placeholder window.
+        #[arg(long)]
+        placeholder: String,
+    },
+    /// Stop mirroring the focused window.
+    ClearMirror,
 }
 
 #[derive(Subcommand)]
 enum SubscribeCommands {
     /// Subscribe to Mach IPC events
     Mach {
-        /// Event to subscribe to (workspace_changed, windows_changed, window_title_changed, stacks_changed, *)
+        /// Event to subscribe to (workspace_changed, windows_changed, window_title_changed, window_fullscreen_changed, stacks_changed, drag_started, drag_swap_candidate, drag_ended, layout_freeze_changed, layout_mode_changed, *)
         event: String,
     },
     /// Subscribe to events via CLI command execution
     Cli {
-        /// Event to subscribe to (workspace_changed, windows_changed, window_title_changed, stacks_changed, *)
+        /// Event to subscribe to (workspace_changed, windows_changed, window_title_changed, window_fullscreen_changed, stacks_changed, drag_started, drag_swap_candidate, drag_ended, layout_freeze_changed, layout_mode_changed, *)
         #[arg(long)]
         event: String,
         /// Command to execute when event occurs
@@ -384,6 +605,8 @@ enum SubscribeCommands {
     },
     /// List current CLI subscriptions
     ListCli,
+    /// Re-send the current workspace and window state to all subscribers
+    Resync,
 }
 
 fn main() {
@@ -470,8 +693,13 @@ fn build_request(command: Commands) -> Result<RiftRequest, String> {
 
 fn build_query_request(query: QueryCommands) -> Result<RiftRequest, String> {
     match query {
-        QueryCommands::Workspaces { space_id } => Ok(RiftRequest::GetWorkspaces { space_id }),
+        QueryCommands::Workspaces { space_id, without_windows } => {
+            Ok(RiftRequest::GetWorkspaces { space_id, without_windows })
+        }
         QueryCommands::Windows { space_id } => Ok(RiftRequest::GetWindows { space_id }),
+        QueryCommands::WorkspaceWindows { workspace_index, space_id } => {
+            Ok(RiftRequest::GetWorkspaceWindows { space_id, workspace_index })
+        }
         QueryCommands::Displays => Ok(RiftRequest::GetDisplays),
         QueryCommands::Window { window_id } => Ok(RiftRequest::GetWindowInfo { window_id }),
         QueryCommands::Applications => Ok(RiftRequest::GetApplications),
@@ -480,6 +708,28 @@ fn build_query_request(query: QueryCommands) -> Result<RiftRequest, String> {
             Ok(RiftRequest::GetWorkspaceLayouts { space_id, workspace_id })
         }
         QueryCommands::Metrics => Ok(RiftRequest::GetMetrics),
+        QueryCommands::WorkspaceHistory => Ok(RiftRequest::GetWorkspaceSwitchHistory),
+        QueryCommands::Gaps { space_id } => Ok(RiftRequest::GetEffectiveGaps { space_id }),
+        QueryCommands::SelectionPath { space_id } => Ok(RiftRequest::GetSelectionPath { space_id }),
+        QueryCommands::WindowsWithoutServerId => Ok(RiftRequest::GetWindowsWithoutServerId),
+        QueryCommands::FocusedWindow => Ok(RiftRequest::GetFocusedWindow),
+        QueryCommands::NewWindowFocusSteal => Ok(RiftRequest::GetNewWindowFocusSteal),
+        QueryCommands::RuleCoverage => Ok(RiftRequest::GetRuleCoverage),
+        QueryCommands::LastLayoutError => Ok(RiftRequest::GetLastLayoutError),
+        QueryCommands::StaleCleanup => Ok(RiftRequest::GetStaleCleanup),
+        QueryCommands::DragSwapTarget => Ok(RiftRequest::GetDragSwapTarget),
+        QueryCommands::DisplayTopology => Ok(RiftRequest::GetDisplayTopology),
+        QueryCommands::Preview { space_id, command } => {
+            let command = serde_json::from_str(&command)
+                .map_err(|e| format!("Invalid layout command JSON: {e}"))?;
+            Ok(RiftRequest::PreviewCommand { space_id, command })
+        }
+        QueryCommands::SaveProfile { name, space_id } => {
+            Ok(RiftRequest::SaveProfile { name, space_id })
+        }
+        QueryCommands::LoadProfile { name, space_id } => {
+            Ok(RiftRequest::LoadProfile { name, space_id })
+        }
     }
 }
 
@@ -492,6 +742,7 @@ fn build_subscribe_request(sub: SubscribeCommands) -> Result<RiftRequest, String
         SubscribeCommands::UnsubMach { event } => Ok(RiftRequest::Unsubscribe { event }),
         SubscribeCommands::UnsubCli { event } => Ok(RiftRequest::UnsubscribeCli { event }),
         SubscribeCommands::ListCli => Ok(RiftRequest::ListCliSubscriptions),
+        SubscribeCommands::Resync => Ok(RiftRequest::Resync),
     }
 }
 
@@ -555,6 +806,11 @@ fn map_window_command(cmd: WindowCommands) -> Result<RiftCommand, String> {
     match cmd {
         WindowCommands::Next => Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::NextWindow))),
         WindowCommands::Prev => Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::PrevWindow))),
+        WindowCommands::FocusIndex { index } => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::FocusIndex {
+                index,
+            })))
+        }
         WindowCommands::Focus { direction } => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::MoveFocus(direction.into()),
         ))),
@@ -567,6 +823,30 @@ fn map_window_command(cmd: WindowCommands) -> Result<RiftCommand, String> {
         WindowCommands::ToggleFullscreenWithinGaps => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::ToggleFullscreenWithinGaps),
         )),
+        WindowCommands::TrueFullscreen => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::TrueFullscreen,
+        ))),
+        WindowCommands::ToggleIgnoreResize => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::ToggleIgnoreResize,
+        ))),
+        WindowCommands::ToggleSticky => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::ToggleWindowSticky,
+        ))),
+        WindowCommands::CycleFloatingPreset => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::CycleFloatingPreset,
+        ))),
+        WindowCommands::DockToEdge { direction } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::DockFloatingToEdge(direction.into())),
+        )),
+        WindowCommands::CloseOthers => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::CloseOtherWindows,
+        ))),
+        WindowCommands::ToggleAlwaysOnTop => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::ToggleAlwaysOnTop,
+        ))),
+        WindowCommands::ToggleManaged => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::ToggleWindowManaged,
+        ))),
         WindowCommands::ResizeGrow => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ResizeWindowGrow,
         ))),
@@ -576,6 +856,61 @@ fn map_window_command(cmd: WindowCommands) -> Result<RiftCommand, String> {
         WindowCommands::ResizeBy { amount } => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ResizeWindowBy { amount },
         ))),
+        WindowCommands::ResizeGroup { amount } => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::ResizeGroup {
+                amount,
+            })))
+        }
+        WindowCommands::ResizeTo { ratio } => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::ResizeWindowTo { ratio },
+        ))),
+        WindowCommands::MoveBeside { target, direction } => {
+            let target_wsid = parse_window_server_id(&target)?;
+            Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+                reactor::ReactorCommand::MoveWindowBeside {
+                    target_wsid,
+                    direction: direction.into(),
+                },
+            )))
+        }
+        WindowCommands::ToggleFloatingVisibility => Ok(RiftCommand::Reactor(
+            reactor::Command::Reactor(reactor::ReactorCommand::ToggleFloatingVisibility),
+        )),
+        WindowCommands::MatchCursorWorkspace => Ok(RiftCommand::Reactor(
+            reactor::Command::Reactor(reactor::ReactorCommand::MatchWorkspaceOfCursorWindow),
+        )),
+        WindowCommands::SetFocusSteal { value } => {
+            let enabled = match value.to_lowercase().as_str() {
+                "true" | "on" => true,
+                "false" | "off" => false,
+                _ => return Err(format!("Invalid boolean value: {}. Use true/false", value)),
+            };
+            Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+                reactor::ReactorCommand::SetNewWindowFocusSteal(enabled),
+            )))
+        }
+        WindowCommands::SetStaleCleanup { value } => {
+            let enabled = match value.to_lowercase().as_str() {
+                "true" | "on" => true,
+                "false" | "off" => false,
+                _ => return Err(format!("Invalid boolean value: {}. Use true/false", value)),
+            };
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(
+                LC::SetStaleCleanup(enabled),
+            )))
+        }
+        WindowCommands::PurgeStale => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::PurgeStaleWindows,
+        ))),
+        WindowCommands::Reassign { window_id } => {
+            let wsid = parse_window_server_id(&window_id)?;
+            Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+                reactor::ReactorCommand::ReassignWindow { wsid },
+            )))
+        }
+        WindowCommands::FocusApp { bundle_id, launch } => Ok(RiftCommand::Reactor(
+            reactor::Command::Reactor(reactor::ReactorCommand::FocusApp { bundle_id, launch }),
+        )),
         WindowCommands::Close { window_id } => {
             let wsid = parse_window_server_id(&window_id)?;
             Ok(RiftCommand::Reactor(reactor::Command::Reactor(
@@ -623,18 +958,39 @@ fn parse_layout_mode(value: &str) -> Result<LayoutMode, String> {
     }
 }
 
+fn parse_master_side(value: &str) -> Result<MasterStackSide, String> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "left" => Ok(MasterStackSide::Left),
+        "right" => Ok(MasterStackSide::Right),
+        "top" => Ok(MasterStackSide::Top),
+        "bottom" => Ok(MasterStackSide::Bottom),
+        other => Err(format!(
+            "Invalid master side '{}'; must be left, right, top, or bottom",
+            other
+        )),
+    }
+}
+
 fn map_workspace_command(cmd: WorkspaceCommands) -> Result<RiftCommand, String> {
     use layout::LayoutCommand as LC;
     match cmd {
         WorkspaceCommands::Next { skip_empty } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::NextWorkspace(skip_empty)),
         )),
+        WorkspaceCommands::NextWithIndicator { skip_empty } => {
+            Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+                reactor::ReactorCommand::NextWorkspaceWithIndicator { skip_empty },
+            )))
+        }
         WorkspaceCommands::Prev { skip_empty } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::PrevWorkspace(skip_empty)),
         )),
         WorkspaceCommands::Switch { workspace_id } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::SwitchToWorkspace(workspace_id)),
         )),
+        WorkspaceCommands::SwitchNamed { name } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::SwitchToWorkspaceNamed { name }),
+        )),
         WorkspaceCommands::MoveWindow { workspace_id, window_id } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::MoveWindowToWorkspace {
                 workspace: workspace_id,
@@ -644,15 +1000,47 @@ fn map_workspace_command(cmd: WorkspaceCommands) -> Result<RiftCommand, String>
         WorkspaceCommands::Create => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::CreateWorkspace,
         ))),
+        WorkspaceCommands::Ensure { count } => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::EnsureWorkspaces { count },
+        ))),
+        WorkspaceCommands::Delete { index, force } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::DeleteWorkspace { index, force }),
+        )),
+        WorkspaceCommands::Move { from, to } => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::MoveWorkspace { from, to },
+        ))),
         WorkspaceCommands::Last => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::SwitchToLastWorkspace,
         ))),
+        WorkspaceCommands::MoveWindowToLast => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::MoveWindowToLastWorkspace,
+        ))),
+        WorkspaceCommands::GatherApp => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::GatherAppWindows,
+        ))),
+        WorkspaceCommands::ToggleScratchpad => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::ToggleScratchpad,
+        ))),
+        WorkspaceCommands::MoveWindowToScratchpad => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::MoveWindowToScratchpad),
+        )),
         WorkspaceCommands::SetLayout { workspace_id, mode } => {
             let mode = parse_layout_mode(&mode)?;
             Ok(RiftCommand::Reactor(reactor::Command::Layout(
                 LC::SetWorkspaceLayout { workspace: workspace_id, mode },
             )))
         }
+        WorkspaceCommands::PinApp { bundle_id, workspace_index } => {
+            Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+                reactor::ReactorCommand::PinAppToWorkspace { bundle_id, workspace_index },
+            )))
+        }
+        WorkspaceCommands::UnpinApp { bundle_id } => Ok(RiftCommand::Reactor(
+            reactor::Command::Reactor(reactor::ReactorCommand::UnpinApp { bundle_id }),
+        )),
+        WorkspaceCommands::Reconcile => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::ReconcileWorkspaces,
+        ))),
     }
 }
 
@@ -664,39 +1052,85 @@ fn map_layout_command(cmd: LayoutCommands) -> Result<RiftCommand, String> {
         LayoutCommands::MoveNode { direction } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::MoveNode(direction.into())),
         )),
+        LayoutCommands::MoveNodeToEdge { direction } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::MoveNodeToEdge(direction.into())),
+        )),
+        LayoutCommands::SwapInDirection { direction } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::SwapInDirection(direction.into())),
+        )),
         LayoutCommands::JoinWindow { direction } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::JoinWindow(direction.into())),
         )),
         LayoutCommands::ToggleStack => {
             Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::ToggleStack)))
         }
+        LayoutCommands::ToggleMonocle => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::ToggleMonocle)))
+        }
+        LayoutCommands::JoinIntoSelection => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::JoinIntoSelection,
+        ))),
+        LayoutCommands::SetStackRepresentative => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::SetStackRepresentative),
+        )),
         LayoutCommands::ToggleOrientation => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ToggleOrientation,
         ))),
+        LayoutCommands::Rotate => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::RotateLayout)))
+        }
         LayoutCommands::Unjoin => {
             Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::UnjoinWindows)))
         }
         LayoutCommands::ToggleFocusFloat => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ToggleFocusFloating,
         ))),
+        LayoutCommands::SetContainerRatio { ratio } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::SetContainerRatio { ratio }),
+        )),
+        LayoutCommands::EqualizeSizes => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::EqualizeSizes)))
+        }
         LayoutCommands::AdjustMasterRatio { delta } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::AdjustMasterRatio { delta }),
         )),
         LayoutCommands::AdjustMasterCount { delta } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::AdjustMasterCount { delta }),
         )),
+        LayoutCommands::CycleMasterCount => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::CycleMasterCount,
+        ))),
         LayoutCommands::PromoteToMaster => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::PromoteToMaster,
         ))),
         LayoutCommands::SwapMasterStack => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::SwapMasterStack,
         ))),
+        LayoutCommands::EqualizeStack => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::EqualizeStack)))
+        }
+        LayoutCommands::ToggleCompactStacks => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::ToggleCompactStacks,
+        ))),
+        LayoutCommands::ToggleGaps => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::ToggleGaps)))
+        }
+        LayoutCommands::ToggleFreeze => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::ToggleLayoutFreeze,
+        ))),
+        LayoutCommands::SetMasterPosition { side } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::SetMasterPosition(parse_master_side(&side)?)),
+        )),
         LayoutCommands::SwapWindows { a, b } => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::SwapWindows(parse_window_id(&a)?, parse_window_id(&b)?),
         ))),
+        LayoutCommands::SwapWithLast => {
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::SwapWithLast)))
+        }
         LayoutCommands::ScrollStrip { delta } => {
             Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::ScrollStrip {
                 delta,
+                source: None,
             })))
         }
         LayoutCommands::SnapStrip => {
@@ -705,6 +1139,12 @@ fn map_layout_command(cmd: LayoutCommands) -> Result<RiftCommand, String> {
         LayoutCommands::CenterSelection => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::CenterSelection,
         ))),
+        LayoutCommands::ApplyPreset { name, space_id } => Ok(RiftCommand::Reactor(
+            reactor::Command::Reactor(reactor::ReactorCommand::ApplyLayoutPreset {
+                name,
+                space: space_id.map(SpaceId::new),
+            }),
+        )),
     }
 }
 
@@ -749,6 +1189,7 @@ fn map_config_command(cmd: ConfigCommands) -> Result<RiftCommand, String> {
                 "ease_in_circ" => AnimationEasing::EaseInCirc,
                 "ease_out_circ" => AnimationEasing::EaseOutCirc,
                 "ease_in_out_circ" => AnimationEasing::EaseInOutCirc,
+                "spring" => AnimationEasing::Spring,
                 _ => return Err(format!("Invalid animation easing: {}", value)),
             };
             ConfigCommand::SetAnimationEasing(easing)
@@ -835,6 +1276,28 @@ fn map_display_command(cmd: DisplayCommands) -> Result<RiftCommand, String> {
                 window_id,
             },
         ))),
+        DisplayCommands::SendToActiveWorkspace { direction, index, uuid, follow } => {
+            Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+                reactor::ReactorCommand::SendToDisplayActiveWorkspace {
+                    selector: build_display_selector(direction, index, uuid)?,
+                    follow,
+                },
+            )))
+        }
+        DisplayCommands::Mirror {
+            direction,
+            index,
+            uuid,
+            placeholder,
+        } => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::MirrorToDisplay {
+                selector: build_display_selector(direction, index, uuid)?,
+                placeholder: parse_window_server_id(&placeholder)?,
+            },
+        ))),
+        DisplayCommands::ClearMirror => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::ClearMirror,
+        ))),
     }
 }
 
@@ -852,6 +1315,11 @@ fn build_display_selector(
     }
 
     if let Some(direction) = direction {
+        match direction.trim().to_ascii_lowercase().as_str() {
+            "next" => return Ok(DisplaySelector::Cycle(DisplayCycle::Next)),
+            "prev" => return Ok(DisplaySelector::Cycle(DisplayCycle::Prev)),
+            _ => {}
+        }
         let parsed_direction = parse_focus_direction(&direction)?;
         Ok(DisplaySelector::Direction(parsed_direction))
     } else if let Some(index) = index {
@@ -7,6 +7,7 @@ use serde_json::Value;
 
 use super::collections::HashMap;
 use crate::actor::wm_controller::WmCommand;
+use crate::layout_engine::{Direction, LayoutCommand};
 use crate::sys::hotkey::{Hotkey, HotkeySpec};
 
 const MAX_WORKSPACES: usize = 32;
@@ -58,6 +59,13 @@ pub enum ConfigCommand {
 
 pub fn data_dir() -> PathBuf { dirs::home_dir().unwrap().join(".rift") }
 pub fn restore_file() -> PathBuf { data_dir().join("layout.ron") }
+/// File a layout profile saved under `name` is kept in.
+pub fn profile_file(name: &str) -> anyhow::Result<PathBuf> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        bail!("invalid profile name {name:?}");
+    }
+    Ok(data_dir().join("profiles").join(format!("{name}.ron")))
+}
 pub fn config_file() -> PathBuf {
     dirs::home_dir().unwrap().join(".config").join("rift").join("config.toml")
 }
@@ -75,6 +83,9 @@ pub struct VirtualWorkspaceSettings {
     pub preserve_focus_per_workspace: bool,
     #[serde(default = "no")]
     pub workspace_auto_back_and_forth: bool,
+    /// Create a workspace when switching by a name that does not exist yet.
+    #[serde(default = "no")]
+    pub create_on_switch: bool,
     #[serde(default = "default_workspace_names")]
     pub workspace_names: Vec<String>,
     #[serde(default)]
@@ -82,9 +93,129 @@ pub struct VirtualWorkspaceSettings {
     #[serde(default)]
     pub reapply_app_rules_on_title_change: bool,
     #[serde(default)]
+    pub untitled_window_policy: UntitledWindowPolicy,
+    /// Corner of the screen windows of inactive workspaces are parked in.
+    #[serde(default)]
+    pub hide_corner: HideCorner,
+    #[serde(default)]
     pub app_rules: Vec<AppWorkspaceRule>,
     #[serde(default)]
     pub workspace_rules: Vec<WorkspaceLayoutRule>,
+    /// Default layout mode per display UUID, used for workspaces no
+    /// `workspace_rules` entry matches.
+    #[serde(default)]
+    pub display_layout_rules: HashMap<String, LayoutMode>,
+}
+
+/// Handling for windows the window server never gave an id. Such windows
+/// can only be placed on a space by their frame and can't be occlusion-checked.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingWindowServerIdPolicy {
+    /// Manage them, resolving their space from the window frame.
+    #[default]
+    Frame,
+    /// Leave them unmanaged, as if they were untracked floating windows.
+    Skip,
+}
+
+/// Physical arrangement `display_cycle_order` can follow.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PhysicalDisplayOrder {
+    #[default]
+    LeftToRight,
+    TopToBottom,
+}
+
+/// Order `focus_display = "next"|"prev"` walks the displays in: a physical
+/// arrangement, or an explicit list of display UUIDs.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+#[serde(untagged)]
+pub enum DisplayCycleOrder {
+    Physical(PhysicalDisplayOrder),
+    Uuids(Vec<String>),
+}
+
+impl Default for DisplayCycleOrder {
+    fn default() -> Self { Self::Physical(PhysicalDisplayOrder::default()) }
+}
+
+/// How windows that macOS reports on more than one space (e.g. "Assign To:
+/// All Desktops") are handled.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MultiSpaceWindowPolicy {
+    /// Leave them unmanaged.
+    #[default]
+    Ignore,
+    /// Manage them as floating windows that stay on the active workspace of
+    /// whichever space they are found on.
+    Sticky,
+}
+
+/// Focus behavior when switching to a workspace with no windows.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptyWorkspaceFocus {
+    /// Leave the cursor where it is; a window under it (if any) gets focus.
+    LeaveCursor,
+    /// Warp the cursor to the center of the workspace's screen.
+    WarpToCenter,
+    /// Focus the desktop (Finder) so no window keeps keyboard focus.
+    FocusDesktop,
+}
+
+/// Where `mouse_follows_focus` places the cursor on the newly focused window.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MouseFollowsFocusTarget {
+    /// The center of the window.
+    #[default]
+    Center,
+    /// Just inside the window's top-left corner.
+    TopLeft,
+    /// The point on the window closest to where the cursor currently is.
+    NearestEdge,
+}
+
+/// How windows that report an empty title are assigned to the layout.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UntitledWindowPolicy {
+    /// Apply app rules exactly as for titled windows.
+    #[default]
+    Rules,
+    /// Always float untitled windows.
+    Float,
+    /// Always tile untitled windows, even if a rule would float them.
+    Tile,
+    /// Keep untitled windows out of the layout until they report a title.
+    Defer,
+}
+
+/// Screen corner windows of inactive workspaces are parked in. The opposite
+/// corner on the same edge is used when the preferred one would overlap
+/// another display.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HideCorner {
+    BottomLeft,
+    #[default]
+    BottomRight,
+    TopLeft,
+    TopRight,
+}
+
+impl HideCorner {
+    pub fn opposite(self) -> Self {
+        match self {
+            HideCorner::BottomLeft => HideCorner::BottomRight,
+            HideCorner::BottomRight => HideCorner::BottomLeft,
+            HideCorner::TopLeft => HideCorner::TopRight,
+            HideCorner::TopRight => HideCorner::TopLeft,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -142,6 +273,71 @@ pub struct AppWorkspaceRule {
     /// non-empty string and will be compared against the accessibility subrole
     /// reported by the AX APIs for a window (exact string match).
     pub ax_subrole: Option<String>,
+
+    /// Optional: Frame a floating window gets the first time it is placed.
+    /// Only used together with `floating = true`.
+    #[serde(default)]
+    pub floating_frame: Option<FloatingRuleFrame>,
+
+    /// Optional: Smallest size tiling may give matching windows. Each side that
+    /// is set replaces the minimum the app reports through accessibility.
+    #[serde(default)]
+    pub min_size: Option<RuleMinSize>,
+}
+
+/// Minimum tiled size from an app rule. Siblings share what is left; when the
+/// minimums don't fit on the screen they are scaled down together.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct RuleMinSize {
+    #[serde(default)]
+    pub w: Option<f64>,
+    #[serde(default)]
+    pub h: Option<f64>,
+}
+
+/// Where an app rule puts a floating window on its display.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct FloatingRuleFrame {
+    /// Offset of the left edge from the display's left edge. Negative values
+    /// are measured from the right edge instead.
+    #[serde(default)]
+    pub x: Option<f64>,
+    /// Offset of the top edge from the display's top edge. Negative values
+    /// are measured from the bottom edge instead.
+    #[serde(default)]
+    pub y: Option<f64>,
+    pub w: f64,
+    pub h: f64,
+    /// Center the window on the display, ignoring `x` and `y`.
+    #[serde(default)]
+    pub center: bool,
+}
+
+impl FloatingRuleFrame {
+    pub fn rect_on(&self, screen: objc2_core_foundation::CGRect) -> objc2_core_foundation::CGRect {
+        use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+
+        let size = CGSize::new(self.w, self.h);
+        let origin = if self.center {
+            CGPoint::new(
+                screen.origin.x + (screen.size.width - self.w) / 2.0,
+                screen.origin.y + (screen.size.height - self.h) / 2.0,
+            )
+        } else {
+            let offset = |value: Option<f64>, start: f64, length: f64| match value {
+                Some(v) if v < 0.0 => start + length + v,
+                Some(v) => start + v,
+                None => start,
+            };
+            CGPoint::new(
+                offset(self.x, screen.origin.x, screen.size.width),
+                offset(self.y, screen.origin.y, screen.size.height),
+            )
+        };
+        CGRect::new(origin, size)
+    }
 }
 
 impl Default for VirtualWorkspaceSettings {
@@ -152,11 +348,15 @@ impl Default for VirtualWorkspaceSettings {
             auto_assign_windows: true,
             preserve_focus_per_workspace: true,
             workspace_auto_back_and_forth: false,
+            create_on_switch: false,
             workspace_names: default_workspace_names(),
             default_workspace: 0,
             reapply_app_rules_on_title_change: false,
+            untitled_window_policy: UntitledWindowPolicy::default(),
+            hide_corner: HideCorner::default(),
             app_rules: Vec::new(),
             workspace_rules: Vec::new(),
+            display_layout_rules: HashMap::default(),
         }
     }
 }
@@ -278,6 +478,27 @@ impl VirtualWorkspaceSettings {
                     issues.push(format!("Duplicate ax_subrole '{}' in rule {}", ax_sub, index));
                 }
             }
+
+            if let Some(frame) = rule.floating_frame {
+                if !rule.floating {
+                    issues.push(format!(
+                        "App rule {} has floating_frame but floating = false",
+                        index
+                    ));
+                }
+                if frame.w <= 0.0 || frame.h <= 0.0 {
+                    issues.push(format!(
+                        "App rule {} floating_frame must have a positive w and h",
+                        index
+                    ));
+                }
+                if !frame.center && (frame.x.is_none() || frame.y.is_none()) {
+                    issues.push(format!(
+                        "App rule {} floating_frame needs x and y unless center = true",
+                        index
+                    ));
+                }
+            }
         }
 
         issues
@@ -344,8 +565,11 @@ unsafe impl Sync for Config {}
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct Settings {
+    /// Whether window frame changes animate. A layout's own `animate` (e.g.
+    /// `layout.scrolling.animate`) wins for workspaces using that layout.
     #[serde(default = "no")]
     pub animate: bool,
+    /// Length of a frame animation, in seconds.
     #[serde(default = "default_animation_duration")]
     pub animation_duration: f64,
     #[serde(default = "default_animation_fps")]
@@ -354,23 +578,75 @@ pub struct Settings {
     pub animation_easing: AnimationEasing,
     #[serde(default = "yes")]
     pub default_disable: bool,
+    /// Display UUIDs whose spaces are never managed, whatever `default_disable`
+    /// and activation toggles say.
+    #[serde(default)]
+    pub disabled_displays: Vec<String>,
     #[serde(default = "yes")]
     pub mouse_follows_focus: bool,
+    /// Where the cursor lands when `mouse_follows_focus` warps it.
+    #[serde(default)]
+    pub mouse_follows_focus_target: MouseFollowsFocusTarget,
     #[serde(default = "yes")]
     pub mouse_hides_on_focus: bool,
+    /// What happens to focus and the cursor after switching to an empty
+    /// workspace. Unset keeps the usual behavior: focus what is under the
+    /// cursor, and otherwise warp per `mouse_follows_focus`.
+    #[serde(default)]
+    pub empty_workspace_focus: Option<EmptyWorkspaceFocus>,
+    /// After closing the last window of a workspace, keep focus on that
+    /// window's screen instead of jumping to a window on another display.
+    #[serde(default)]
+    pub keep_focus_on_screen_after_close: bool,
+    /// How windows that never report a window server id are handled.
+    #[serde(default)]
+    pub missing_window_server_id: MissingWindowServerIdPolicy,
+    /// How windows shown on every space are handled.
+    #[serde(default)]
+    pub multi_space_windows: MultiSpaceWindowPolicy,
+    /// Order the `next`/`prev` display selectors cycle through.
+    #[serde(default)]
+    pub display_cycle_order: DisplayCycleOrder,
+    /// Whether newly created windows may take focus from the focused window.
+    #[serde(default = "yes")]
+    pub new_window_focus_steal: bool,
+    /// How long after creation a new window's first focus change is still
+    /// handed back while `new_window_focus_steal` is off.
+    #[serde(default = "default_new_window_focus_grace_ms")]
+    pub new_window_focus_grace_ms: u64,
     #[serde(default = "yes")]
     pub focus_follows_mouse: bool,
+    /// How long the cursor must rest on a window before focus-follows-mouse
+    /// focuses it. 0 focuses as soon as the cursor enters the window.
+    #[serde(default)]
+    pub focus_follows_mouse_delay_ms: u64,
     /// Hotkey that disables focus-follows-mouse while held.
     /// Accepts either a full hotkey (e.g. "Ctrl + A") or a modifier-only spec (e.g. "Ctrl")
     #[serde(default)]
     pub focus_follows_mouse_disable_hotkey: Option<HotkeySpec>,
+    /// Apps (bundle identifiers) whose windows are never focused just because
+    /// the mouse moved over them, e.g. tool palettes with hover tooltips.
+    #[serde(default)]
+    pub autoraise_blacklist: Vec<String>,
     /// Apps that should not trigger automatic workspace switching when activated.
     /// List of bundle identifiers (e.g., "com.apple.Spotlight") that often
     /// inappropriately steal focus and shouldn't cause workspace switches.
     #[serde(default)]
     pub auto_focus_blacklist: Vec<String>,
+    /// When non-empty, only these apps (bundle identifiers) trigger automatic
+    /// workspace switching when activated. Checked before `auto_focus_blacklist`.
+    #[serde(default)]
+    pub auto_focus_allowlist: Vec<String>,
+    /// Opacity (0.0-1.0) applied to tiled windows other than the focused one.
+    /// Floating and fullscreen windows are never dimmed. 1.0 disables dimming.
+    #[serde(default = "default_inactive_dim")]
+    pub inactive_dim: f64,
     #[serde(default)]
     pub layout: LayoutSettings,
+    /// Named layout settings that can be applied to a single space with
+    /// `apply_layout_preset`, in place of `layout`.
+    #[serde(default)]
+    pub layout_presets: HashMap<String, LayoutSettings>,
     #[serde(default)]
     pub ui: UiSettings,
     /// Trackpad gesture settings
@@ -380,6 +656,34 @@ pub struct Settings {
     #[serde(default)]
     pub window_snapping: WindowSnappingSettings,
 
+    /// Maximum age (in milliseconds) of a space change deferred during Mission Control
+    /// before it is force-applied, even if it no longer matches the screen count.
+    /// Set to 0 to wait indefinitely for a matching space vector.
+    #[serde(default = "default_pending_space_change_max_age_ms")]
+    pub pending_space_change_max_age_ms: u64,
+
+    /// How long (in milliseconds) to hold the post-churn reconcile after displays
+    /// settle. A display churn that begins within this window (e.g. a flaky dock
+    /// connection) is folded into the same cycle and reconciled once at the end.
+    /// Set to 0 to reconcile as soon as each churn settles.
+    #[serde(default)]
+    pub display_churn_coalesce_ms: u64,
+
+    /// Time budget (in milliseconds) for handling a batch of queued events before
+    /// the reactor yields. A batch also ends right after a latency-sensitive event
+    /// such as a mouse release or an app activation. Set to 0 to handle one event
+    /// per batch.
+    #[serde(default = "default_event_batch_budget_ms")]
+    pub event_batch_budget_ms: u64,
+
+    /// How long (in milliseconds) the reactor may spend folding frame changes of
+    /// the same window that are already queued into the latest one, e.g. during
+    /// a live resize. It never waits for further frame changes to arrive. The
+    /// final frame is always applied as reported. Set to 0 to handle every
+    /// frame change.
+    #[serde(default = "default_frame_change_coalesce_ms")]
+    pub frame_change_coalesce_ms: u64,
+
     /// Commands to run on startup (e.g., for subscribing to events)
     #[serde(default)]
     pub run_on_start: Vec<String>,
@@ -417,6 +721,8 @@ pub enum AnimationEasing {
     EaseInCirc,
     EaseOutCirc,
     EaseInOutCirc,
+    /// Overshoots the target slightly and settles back, like a damped spring.
+    Spring,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
@@ -428,6 +734,8 @@ pub struct UiSettings {
     pub stack_line: StackLineSettings,
     #[serde(default)]
     pub mission_control: MissionControlSettings,
+    #[serde(default)]
+    pub workspace_indicator: WorkspaceIndicatorSettings,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -457,6 +765,22 @@ pub struct GestureSettings {
     /// Haptic feedback pattern (generic | alignment | level_change)
     #[serde(default)]
     pub haptic_pattern: HapticPattern,
+    /// Layout commands run by gestures, e.g. `swipe_up = "create_workspace"`.
+    /// Unbound horizontal swipes switch to the next/previous workspace.
+    #[serde(default)]
+    pub bindings: HashMap<Gesture, LayoutCommand>,
+}
+
+/// A trackpad gesture that can be bound to a layout command. Left and right
+/// are swapped by `invert_horizontal_swipe`; scrolling past the end of the
+/// strip counts as the matching horizontal swipe.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Gesture {
+    SwipeLeft,
+    SwipeRight,
+    SwipeUp,
+    SwipeDown,
 }
 
 impl Default for GestureSettings {
@@ -470,15 +794,31 @@ impl Default for GestureSettings {
             distance_pct: default_distance_pct(),
             haptics_enabled: true,
             haptic_pattern: HapticPattern::LevelChange,
+            bindings: HashMap::default(),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default, Copy)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
 #[serde(deny_unknown_fields)]
 pub struct WindowSnappingSettings {
     #[serde(default = "default_drag_swap_fraction")]
     pub drag_swap_fraction: f64,
+    /// How far (in points) a dragged window's center must travel past the edge
+    /// of the display it is settled on before the drag moves to another display.
+    #[serde(default = "default_drag_space_hysteresis")]
+    pub drag_space_hysteresis: f64,
+}
+
+impl Default for WindowSnappingSettings {
+    fn default() -> Self {
+        Self {
+            // Zero lets the drag swap manager fall back to its own threshold
+            // when the section is omitted.
+            drag_swap_fraction: 0.0,
+            drag_space_hysteresis: default_drag_space_hysteresis(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
@@ -550,12 +890,47 @@ pub struct MissionControlSettings {
 
 fn default_mission_control_fade_duration_ms() -> f64 { 180.0 }
 
+/// The overlay shown by `next_workspace_with_indicator`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceIndicatorSettings {
+    /// How long the overlay stays on screen, in milliseconds.
+    #[serde(default = "default_workspace_indicator_duration_ms")]
+    pub duration_ms: u64,
+}
+
+impl Default for WorkspaceIndicatorSettings {
+    fn default() -> Self {
+        Self {
+            duration_ms: default_workspace_indicator_duration_ms(),
+        }
+    }
+}
+
+fn default_workspace_indicator_duration_ms() -> u64 { 700 }
+
+fn default_pending_space_change_max_age_ms() -> u64 { 3000 }
+
+fn default_new_window_focus_grace_ms() -> u64 { 1000 }
+
+fn default_event_batch_budget_ms() -> u64 { 8 }
+
+fn default_frame_change_coalesce_ms() -> u64 { 16 }
+
+fn default_inactive_dim() -> f64 { 1.0 }
+
 fn default_drag_swap_fraction() -> f64 { 0.3 }
 
+fn default_drag_space_hysteresis() -> f64 { 40.0 }
+
 fn default_master_stack_ratio() -> f64 { 0.6 }
 
 fn default_master_stack_count() -> usize { 1 }
 
+fn default_master_stack_count_cycle() -> Vec<usize> { vec![1, 2, 3] }
+
+fn default_master_stack_layout_threshold() -> usize { 3 }
+
 fn default_scrolling_column_width_ratio() -> f64 { 0.7 }
 
 fn default_scrolling_min_column_width_ratio() -> f64 { 0.3 }
@@ -600,6 +975,20 @@ pub struct LayoutSettings {
     /// Scrolling layout configuration (niri-style columns)
     #[serde(default)]
     pub scrolling: ScrollingLayoutSettings,
+    /// Insert new windows next to the window under the cursor instead of the selection
+    #[serde(default)]
+    pub insert_at_cursor: bool,
+    /// Wrap focus around to the other side of the layout at its edges instead of
+    /// moving to another display
+    #[serde(default)]
+    pub focus_wrap: bool,
+    /// How resizing a tiled window takes space from the other windows in its
+    /// container (traditional and bsp layouts)
+    #[serde(default)]
+    pub resize_mode: ResizeMode,
+    /// Floating window configuration
+    #[serde(default)]
+    pub floating: FloatingSettings,
 }
 
 /// Layout mode enum
@@ -619,6 +1008,18 @@ pub enum LayoutMode {
     Scrolling,
 }
 
+/// Which windows give up (or receive) space when a tiled window is resized.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ResizeMode {
+    /// Only the adjacent window on the resized edge changes size.
+    #[default]
+    SiblingOnly,
+    /// Every other window in the container changes size in proportion to its
+    /// current size.
+    Proportional,
+}
+
 impl ToString for LayoutMode {
     fn to_string(&self) -> String {
         match self {
@@ -712,9 +1113,29 @@ pub struct MasterStackSettings {
     /// Which side the master area occupies
     #[serde(default)]
     pub master_side: MasterStackSide,
-    /// Where new windows are inserted when the master area is already full
+    /// Where new windows are inserted when the master area is already full.
+    /// `Master` makes the new window the master, pushing the previous one onto
+    /// the stack.
     #[serde(default = "default_master_stack_new_window_placement")]
     pub new_window_placement: MasterStackNewWindowPlacement,
+    /// Master counts stepped through, wrapping around, by `cycle_master_count`
+    #[serde(default = "default_master_stack_count_cycle")]
+    pub master_count_cycle: Vec<usize>,
+    /// How the stack area arranges its windows. `Tabbed` stacks them on top of
+    /// each other once there are more than `stack_layout_threshold` of them.
+    #[serde(default)]
+    pub stack_layout: MasterStackStackLayout,
+    /// Number of stack windows that are still split evenly under `Tabbed`
+    #[serde(default = "default_master_stack_layout_threshold")]
+    pub stack_layout_threshold: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MasterStackStackLayout {
+    #[default]
+    Split,
+    Tabbed,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
@@ -731,9 +1152,17 @@ pub struct ScrollingGestureSettings {
     /// Enable horizontal scroll gestures to switch columns
     #[serde(default = "no")]
     pub enabled: bool,
-    /// Invert horizontal direction (swap left/right)
+    /// Invert horizontal direction (swap left/right) for trackpad scrolling,
+    /// including momentum after the fingers lift
     #[serde(default)]
     pub invert_horizontal: bool,
+    /// Invert horizontal direction for mouse wheel scrolling
+    #[serde(default)]
+    pub invert_mouse_wheel: bool,
+    /// Scroll the strip with a mouse's horizontal wheel as well. Off by
+    /// default, since it taps every scroll wheel event system-wide.
+    #[serde(default)]
+    pub mouse_wheel_scrolls_strip: bool,
     /// Maximum absolute Y delta allowed for the gesture to count as horizontal
     #[serde(default = "default_swipe_vertical_tolerance")]
     pub vertical_tolerance: f64,
@@ -749,6 +1178,14 @@ pub struct ScrollingGestureSettings {
     /// Amount of overscroll (in steps) required to trigger a workspace switch
     #[serde(default = "default_overscroll_threshold")]
     pub workspace_switch_threshold: f64,
+    /// Overscroll threshold past the left edge of the strip.
+    /// Falls back to `workspace_switch_threshold` when unset.
+    #[serde(default)]
+    pub overscroll_threshold_left: Option<f64>,
+    /// Overscroll threshold past the right edge of the strip.
+    /// Falls back to `workspace_switch_threshold` when unset.
+    #[serde(default)]
+    pub overscroll_threshold_right: Option<f64>,
 }
 
 impl Default for ScrollingGestureSettings {
@@ -756,15 +1193,47 @@ impl Default for ScrollingGestureSettings {
         Self {
             enabled: false,
             invert_horizontal: false,
+            invert_mouse_wheel: false,
+            mouse_wheel_scrolls_strip: false,
             vertical_tolerance: default_swipe_vertical_tolerance(),
             fingers: default_swipe_fingers(),
             distance_pct: default_distance_pct(),
             propagate_to_workspace_swipe: false,
             workspace_switch_threshold: default_overscroll_threshold(),
+            overscroll_threshold_left: None,
+            overscroll_threshold_right: None,
+        }
+    }
+}
+
+impl ScrollingGestureSettings {
+    /// Overscroll needed past the edge hit when moving in `direction`.
+    pub fn overscroll_threshold(&self, direction: Direction) -> f64 {
+        let side = match direction {
+            Direction::Left => self.overscroll_threshold_left,
+            Direction::Right => self.overscroll_threshold_right,
+            Direction::Up | Direction::Down => None,
+        };
+        side.unwrap_or(self.workspace_switch_threshold)
+    }
+
+    /// Whether strip scrolls from `source` run opposite to the raw delta.
+    pub fn inverts(&self, source: ScrollSource) -> bool {
+        match source {
+            ScrollSource::Trackpad => self.invert_horizontal,
+            ScrollSource::MouseWheel => self.invert_mouse_wheel,
         }
     }
 }
 
+/// Input device that drove a strip scroll.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollSource {
+    Trackpad,
+    MouseWheel,
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum StackDefaultOrientation {
@@ -793,6 +1262,31 @@ pub struct StackSettings {
     pub default_orientation: StackDefaultOrientation,
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FloatingSettings {
+    /// Sizes cycled through by `cycle_floating_preset`, as fractions of the screen.
+    /// Each preset is centered on the screen when applied.
+    #[serde(default = "default_floating_presets")]
+    pub presets: Vec<FloatingPreset>,
+    /// Re-stack floating windows in last-focus order whenever a workspace is
+    /// activated, so the most recently focused one is on top.
+    #[serde(default)]
+    pub strict_z_order: bool,
+    /// Width (or height, for the top and bottom edges) of a window docked
+    /// with `dock_floating_to_edge`, in points.
+    #[serde(default = "default_dock_thickness")]
+    pub dock_thickness: f64,
+}
+
+/// A floating window size expressed as fractions of the screen size.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct FloatingPreset {
+    pub width: f64,
+    pub height: f64,
+}
+
 /// Gap configuration for window spacing
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
@@ -803,6 +1297,9 @@ pub struct GapSettings {
     /// Inner gaps (space between windows)
     #[serde(default)]
     pub inner: InnerGaps,
+    /// Drop the outer gaps while a workspace shows a single tile on screen
+    #[serde(default)]
+    pub smart_gaps: bool,
     /// Display-specific gap overrides keyed by display UUID
     #[serde(default)]
     pub per_display: HashMap<String, GapOverride>,
@@ -848,6 +1345,9 @@ pub struct GapOverride {
     /// Override inner gaps completely for the display
     #[serde(default)]
     pub inner: Option<InnerGaps>,
+    /// Override smart gaps for the display
+    #[serde(default)]
+    pub smart_gaps: Option<bool>,
 }
 
 impl Default for StackSettings {
@@ -859,6 +1359,16 @@ impl Default for StackSettings {
     }
 }
 
+impl Default for FloatingSettings {
+    fn default() -> Self {
+        Self {
+            presets: default_floating_presets(),
+            strict_z_order: false,
+            dock_thickness: default_dock_thickness(),
+        }
+    }
+}
+
 impl Default for MasterStackSettings {
     fn default() -> Self {
         Self {
@@ -866,6 +1376,9 @@ impl Default for MasterStackSettings {
             master_count: default_master_stack_count(),
             master_side: MasterStackSide::Left,
             new_window_placement: default_master_stack_new_window_placement(),
+            master_count_cycle: default_master_stack_count_cycle(),
+            stack_layout: MasterStackStackLayout::default(),
+            stack_layout_threshold: default_master_stack_layout_threshold(),
         }
     }
 }
@@ -888,6 +1401,13 @@ impl Settings {
             ));
         }
 
+        if !(0.0..=1.0).contains(&self.inactive_dim) {
+            issues.push(format!(
+                "inactive_dim must be between 0.0 and 1.0, got {}",
+                self.inactive_dim
+            ));
+        }
+
         issues.extend(self.layout.validate());
 
         if self.gestures.swipe_vertical_tolerance < 0.0 {
@@ -913,6 +1433,27 @@ impl LayoutSettings {
 
         issues.extend(self.scrolling.validate());
 
+        issues.extend(self.floating.validate());
+
+        issues
+    }
+}
+
+impl FloatingSettings {
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        for (idx, preset) in self.presets.iter().enumerate() {
+            if !(preset.width > 0.0 && preset.width <= 1.0)
+                || !(preset.height > 0.0 && preset.height <= 1.0)
+            {
+                issues.push(format!(
+                    "layout.floating.presets[{idx}] width/height must be in (0.0, 1.0], got {}x{}",
+                    preset.width, preset.height
+                ));
+            }
+        }
+
         issues
     }
 }
@@ -1033,6 +1574,7 @@ impl GapSettings {
         let mut resolved = GapSettings {
             outer: self.outer.clone(),
             inner: self.inner.clone(),
+            smart_gaps: self.smart_gaps,
             per_display: HashMap::default(),
         };
         if let Some(uuid) = display_uuid {
@@ -1043,6 +1585,9 @@ impl GapSettings {
                 if let Some(inner_override) = &overrides.inner {
                     resolved.inner = inner_override.clone();
                 }
+                if let Some(smart_gaps) = overrides.smart_gaps {
+                    resolved.smart_gaps = smart_gaps;
+                }
             }
         }
         resolved
@@ -1107,6 +1652,15 @@ fn yes() -> bool { true }
 
 fn default_stack_offset() -> f64 { 40.0 }
 
+fn default_dock_thickness() -> f64 { 300.0 }
+
+fn default_floating_presets() -> Vec<FloatingPreset> {
+    [(0.5, 0.5), (0.7, 0.7), (0.9, 0.9)]
+        .into_iter()
+        .map(|(width, height)| FloatingPreset { width, height })
+        .collect()
+}
+
 pub fn default_stack_orientation() -> StackDefaultOrientation {
     StackDefaultOrientation::Perpendicular
 }
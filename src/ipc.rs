@@ -257,10 +257,20 @@ impl MachHandler {
                 let data = state.list_cli_subscriptions();
                 RiftResponse::Success { data }
             }
-
-            RiftRequest::GetWorkspaces { space_id } => {
-                let workspaces =
-                    self.reactor.query_workspaces(space_id.map(crate::sys::screen::SpaceId::new));
+            RiftRequest::Resync => match self.reactor.resync() {
+                Some(spaces) => RiftResponse::Success {
+                    data: serde_json::json!({ "resynced_spaces": spaces }),
+                },
+                None => RiftResponse::Error {
+                    error: serde_json::json!({ "message": "Reactor did not respond" }),
+                },
+            },
+
+            RiftRequest::GetWorkspaces { space_id, without_windows } => {
+                let workspaces = self.reactor.query_workspaces(
+                    space_id.map(crate::sys::screen::SpaceId::new),
+                    !without_windows,
+                );
                 RiftResponse::Success {
                     data: serde_json::to_value(workspaces).unwrap(),
                 }
@@ -282,6 +292,20 @@ impl MachHandler {
                 }
             }
 
+            RiftRequest::GetWorkspaceWindows { space_id, workspace_index } => {
+                match self.reactor.query_windows_in_workspace(
+                    space_id.map(crate::sys::screen::SpaceId::new),
+                    workspace_index,
+                ) {
+                    Ok(windows) => RiftResponse::Success {
+                        data: serde_json::to_value(windows).unwrap(),
+                    },
+                    Err(message) => RiftResponse::Error {
+                        error: serde_json::json!({ "message": message }),
+                    },
+                }
+            }
+
             RiftRequest::GetWindowInfo { window_id } => {
                 let window_id = match crate::actor::app::WindowId::from_debug_string(&window_id) {
                     Some(wid) => wid,
@@ -330,9 +354,154 @@ impl MachHandler {
                 }
             }
 
-            RiftRequest::GetMetrics => {
-                let metrics = self.reactor.query_metrics();
-                RiftResponse::Success { data: metrics }
+            RiftRequest::GetMetrics => match self.reactor.query_metrics() {
+                Some(metrics) => RiftResponse::Success {
+                    data: serde_json::to_value(metrics).unwrap(),
+                },
+                None => RiftResponse::Error {
+                    error: serde_json::json!({ "message": "Reactor did not respond" }),
+                },
+            },
+
+            RiftRequest::GetWorkspaceSwitchHistory => {
+                let history = self.reactor.query_workspace_switch_history();
+                RiftResponse::Success {
+                    data: serde_json::to_value(history).unwrap(),
+                }
+            }
+
+            RiftRequest::GetEffectiveGaps { space_id } => {
+                match self
+                    .reactor
+                    .query_effective_gaps(space_id.map(crate::sys::screen::SpaceId::new))
+                {
+                    Some(gaps) => RiftResponse::Success {
+                        data: serde_json::to_value(gaps).unwrap(),
+                    },
+                    None => RiftResponse::Error {
+                        error: serde_json::json!({ "message": "Space not found or inactive" }),
+                    },
+                }
+            }
+
+            RiftRequest::GetWindowsWithoutServerId => {
+                let windows = self.reactor.query_windows_without_server_id();
+                RiftResponse::Success {
+                    data: serde_json::to_value(windows).unwrap(),
+                }
+            }
+
+            RiftRequest::GetFocusedWindow => {
+                let window = self.reactor.query_focused_window();
+                RiftResponse::Success {
+                    data: serde_json::to_value(window).unwrap(),
+                }
+            }
+
+            RiftRequest::GetNewWindowFocusSteal => {
+                match self.reactor.query_new_window_focus_steal() {
+                    Some(enabled) => RiftResponse::Success {
+                        data: serde_json::json!({ "enabled": enabled }),
+                    },
+                    None => RiftResponse::Error {
+                        error: serde_json::json!({ "message": "Reactor did not respond" }),
+                    },
+                }
+            }
+
+            RiftRequest::GetRuleCoverage => {
+                let coverage = self.reactor.query_rule_coverage();
+                RiftResponse::Success {
+                    data: serde_json::to_value(coverage).unwrap(),
+                }
+            }
+
+            RiftRequest::GetLastLayoutError => {
+                let error = self.reactor.query_last_layout_error();
+                RiftResponse::Success {
+                    data: serde_json::to_value(error).unwrap(),
+                }
+            }
+
+            RiftRequest::GetStaleCleanup => match self.reactor.query_stale_cleanup() {
+                Some(data) => RiftResponse::Success {
+                    data: serde_json::to_value(data).unwrap(),
+                },
+                None => RiftResponse::Error {
+                    error: serde_json::json!({ "message": "Reactor did not respond" }),
+                },
+            },
+
+            RiftRequest::GetDragSwapTarget => {
+                let target = self.reactor.query_drag_swap_target();
+                RiftResponse::Success {
+                    data: serde_json::to_value(target).unwrap(),
+                }
+            }
+
+            RiftRequest::GetDisplayTopology => match self.reactor.query_display_topology() {
+                Some(topology) => RiftResponse::Success {
+                    data: serde_json::to_value(topology).unwrap(),
+                },
+                None => RiftResponse::Error {
+                    error: serde_json::json!({ "message": "Reactor did not respond" }),
+                },
+            },
+
+            RiftRequest::PreviewCommand { space_id, command } => {
+                match self
+                    .reactor
+                    .query_preview_command(space_id.map(crate::sys::screen::SpaceId::new), command)
+                {
+                    Ok(frames) => RiftResponse::Success {
+                        data: serde_json::to_value(frames).unwrap(),
+                    },
+                    Err(message) => RiftResponse::Error {
+                        error: serde_json::json!({ "message": message }),
+                    },
+                }
+            }
+
+            RiftRequest::SaveProfile { name, space_id } => {
+                match self
+                    .reactor
+                    .save_profile(name.clone(), space_id.map(crate::sys::screen::SpaceId::new))
+                {
+                    Ok(()) => RiftResponse::Success {
+                        data: serde_json::json!({ "saved_profile": name }),
+                    },
+                    Err(message) => RiftResponse::Error {
+                        error: serde_json::json!({ "message": message }),
+                    },
+                }
+            }
+
+            RiftRequest::LoadProfile { name, space_id } => {
+                match self
+                    .reactor
+                    .load_profile(name.clone(), space_id.map(crate::sys::screen::SpaceId::new))
+                {
+                    Ok(()) => RiftResponse::Success {
+                        data: serde_json::json!({ "loaded_profile": name }),
+                    },
+                    Err(message) => RiftResponse::Error {
+                        error: serde_json::json!({ "message": message }),
+                    },
+                }
+            }
+
+            RiftRequest::GetSelectionPath { space_id } => {
+                match self
+                    .reactor
+                    .query_selection_path(space_id.map(crate::sys::screen::SpaceId::new))
+                {
+                    Some(path) => RiftResponse::Success {
+                        data: serde_json::to_value(path).unwrap(),
+                    },
+                    None => RiftResponse::Error {
+                        error: serde_json::json!({ "message": "Space not found or inactive" }),
+                    },
+                }
             }
 
             RiftRequest::GetConfig => {
@@ -51,6 +51,24 @@ impl CliExecutor for DefaultCliExecutor {
                     env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
                 }
             }
+            BroadcastEvent::WorkspaceActivated {
+                space_id,
+                display_uuid,
+                workspace_id,
+                workspace_index,
+                workspace_name,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "workspace_activated".into());
+                env_vars.insert("RIFT_WORKSPACE_ID".into(), workspace_id.to_string());
+                env_vars.insert("RIFT_WORKSPACE_NAME".into(), workspace_name.clone());
+                if let Some(workspace_index) = workspace_index {
+                    env_vars.insert("RIFT_WORKSPACE_INDEX".into(), workspace_index.to_string());
+                }
+                env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                if let Some(display_uuid) = display_uuid.as_ref() {
+                    env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+                }
+            }
             BroadcastEvent::WindowsChanged {
                 workspace_id,
                 workspace_name,
@@ -92,6 +110,16 @@ impl CliExecutor for DefaultCliExecutor {
                     env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
                 }
             }
+            BroadcastEvent::WindowFullscreenChanged {
+                window_id,
+                space_id,
+                fullscreen,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "window_fullscreen_changed".into());
+                env_vars.insert("RIFT_WINDOW_ID".into(), window_id.to_debug_string());
+                env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                env_vars.insert("RIFT_WINDOW_FULLSCREEN".into(), fullscreen.to_string());
+            }
             BroadcastEvent::StacksChanged {
                 workspace_id,
                 workspace_index,
@@ -117,6 +145,34 @@ impl CliExecutor for DefaultCliExecutor {
                     env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
                 }
             }
+            BroadcastEvent::DragStarted { window_id } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "drag_started".into());
+                env_vars.insert("RIFT_WINDOW_ID".into(), window_id.to_debug_string());
+            }
+            BroadcastEvent::DragSwapCandidate { window_id, target } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "drag_swap_candidate".into());
+                env_vars.insert("RIFT_WINDOW_ID".into(), window_id.to_debug_string());
+                if let Some(target) = target {
+                    env_vars.insert("RIFT_DRAG_TARGET_ID".into(), target.to_debug_string());
+                }
+            }
+            BroadcastEvent::DragEnded { window_id, swapped } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "drag_ended".into());
+                env_vars.insert("RIFT_WINDOW_ID".into(), window_id.to_debug_string());
+                env_vars.insert("RIFT_DRAG_SWAPPED".into(), swapped.to_string());
+            }
+            BroadcastEvent::LayoutFreezeChanged { frozen } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "layout_freeze_changed".into());
+                env_vars.insert("RIFT_LAYOUT_FROZEN".into(), frozen.to_string());
+            }
+            BroadcastEvent::LayoutModeChanged { space_id, display_uuid, mode } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "layout_mode_changed".into());
+                env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                env_vars.insert("RIFT_LAYOUT_MODE".into(), mode.to_string());
+                if let Some(display_uuid) = display_uuid.as_ref() {
+                    env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+                }
+            }
         }
 
         let event_json = match serde_json::to_string(event) {
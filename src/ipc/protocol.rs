@@ -7,11 +7,19 @@ use serde_json::Value;
 pub enum RiftRequest {
     GetWorkspaces {
         space_id: Option<u64>,
+        /// Leave `windows` empty and only report ids, names and window counts.
+        #[serde(default)]
+        without_windows: bool,
     },
     GetDisplays,
     GetWindows {
         space_id: Option<u64>,
     },
+    /// Windows of one workspace by index, whether or not it is active.
+    GetWorkspaceWindows {
+        space_id: Option<u64>,
+        workspace_index: usize,
+    },
     GetWindowInfo {
         window_id: String,
     },
@@ -24,6 +32,36 @@ pub enum RiftRequest {
     },
     GetApplications,
     GetMetrics,
+    GetWorkspaceSwitchHistory,
+    GetEffectiveGaps {
+        space_id: Option<u64>,
+    },
+    GetSelectionPath {
+        space_id: Option<u64>,
+    },
+    GetWindowsWithoutServerId,
+    GetFocusedWindow,
+    GetNewWindowFocusSteal,
+    GetRuleCoverage,
+    GetLastLayoutError,
+    GetStaleCleanup,
+    GetDragSwapTarget,
+    GetDisplayTopology,
+    /// Frames the layout would have after a command, without applying it.
+    PreviewCommand {
+        space_id: Option<u64>,
+        command: crate::layout_engine::LayoutCommand,
+    },
+    /// Save the workspaces of a space and their layouts under a name.
+    SaveProfile {
+        name: String,
+        space_id: Option<u64>,
+    },
+    /// Restore a saved profile onto a space.
+    LoadProfile {
+        name: String,
+        space_id: Option<u64>,
+    },
     GetConfig,
     ExecuteCommand {
         command: String,
@@ -44,6 +82,9 @@ pub enum RiftRequest {
         event: String,
     },
     ListCliSubscriptions,
+    /// Re-send the current workspace and window state of every active space to
+    /// subscribers.
+    Resync,
 }
 
 #[non_exhaustive]
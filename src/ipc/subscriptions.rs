@@ -184,9 +184,16 @@ impl ServerState {
     fn forward_event_to_subscribers(&self, event: BroadcastEvent) {
         let event_name = match &event {
             BroadcastEvent::WorkspaceChanged { .. } => "workspace_changed",
+            BroadcastEvent::WorkspaceActivated { .. } => "workspace_activated",
             BroadcastEvent::WindowsChanged { .. } => "windows_changed",
             BroadcastEvent::WindowTitleChanged { .. } => "window_title_changed",
+            BroadcastEvent::WindowFullscreenChanged { .. } => "window_fullscreen_changed",
             BroadcastEvent::StacksChanged { .. } => "stacks_changed",
+            BroadcastEvent::DragStarted { .. } => "drag_started",
+            BroadcastEvent::DragSwapCandidate { .. } => "drag_swap_candidate",
+            BroadcastEvent::DragEnded { .. } => "drag_ended",
+            BroadcastEvent::LayoutFreezeChanged { .. } => "layout_freeze_changed",
+            BroadcastEvent::LayoutModeChanged { .. } => "layout_mode_changed",
         };
 
         let mut targets: HashSet<ClientPort> = HashSet::default();
@@ -232,9 +239,16 @@ impl ServerState {
     fn forward_event_to_cli_subscribers(&self, event: BroadcastEvent) {
         let event_name = match &event {
             BroadcastEvent::WorkspaceChanged { .. } => "workspace_changed",
+            BroadcastEvent::WorkspaceActivated { .. } => "workspace_activated",
             BroadcastEvent::WindowsChanged { .. } => "windows_changed",
             BroadcastEvent::WindowTitleChanged { .. } => "window_title_changed",
+            BroadcastEvent::WindowFullscreenChanged { .. } => "window_fullscreen_changed",
             BroadcastEvent::StacksChanged { .. } => "stacks_changed",
+            BroadcastEvent::DragStarted { .. } => "drag_started",
+            BroadcastEvent::DragSwapCandidate { .. } => "drag_swap_candidate",
+            BroadcastEvent::DragEnded { .. } => "drag_ended",
+            BroadcastEvent::LayoutFreezeChanged { .. } => "layout_freeze_changed",
+            BroadcastEvent::LayoutModeChanged { .. } => "layout_mode_changed",
         };
 
         // Collect relevant subscriptions without full HashMap clone
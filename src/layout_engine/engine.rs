@@ -1,10 +1,12 @@
+use std::borrow::Cow;
 use std::cmp::Ordering;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
+use super::workspaces::SpaceLayoutInfo;
 use super::{Direction, FloatingManager, LayoutId, LayoutSystemKind, WorkspaceLayouts};
 use crate::actor::app::{AppInfo, WindowId, pid_t};
 use crate::actor::broadcast::{BroadcastEvent, BroadcastSender};
@@ -15,6 +17,7 @@ use crate::layout_engine::systems::WindowLayoutConstraints;
 use crate::model::virtual_workspace::{
     AppRuleAssignment, AppRuleResult, VirtualWorkspace, VirtualWorkspaceId, VirtualWorkspaceManager,
 };
+use crate::sys::geometry::CGRectExt;
 use crate::sys::screen::SpaceId;
 
 #[derive(Debug, Clone)]
@@ -27,6 +30,17 @@ pub struct GroupContainerInfo {
     pub window_ids: Vec<crate::actor::app::WindowId>,
 }
 
+/// One node on the path from a layout's root to its selected leaf.
+#[derive(Debug, Clone)]
+pub struct SelectionPathEntry {
+    pub node_id: crate::model::tree::NodeId,
+    /// Container kind, or `None` for the selected window leaf.
+    pub container_kind: Option<super::LayoutKind>,
+    pub child_count: usize,
+    pub selected_index: Option<usize>,
+    pub window_id: Option<crate::actor::app::WindowId>,
+}
+
 #[derive(Debug, Default)]
 struct WindowRemovalImpact {
     active_space: Option<SpaceId>,
@@ -45,29 +59,111 @@ impl WindowRemovalImpact {
 pub enum LayoutCommand {
     NextWindow,
     PrevWindow,
+    /// Focus and raise the `index`-th (0-based) visible window of the active
+    /// workspace, in layout order. Out-of-range indices are ignored.
+    FocusIndex {
+        index: usize,
+    },
     MoveFocus(#[serde(rename = "direction")] Direction),
     Ascend,
     Descend,
     MoveNode(Direction),
+    /// Swap the focused window with its neighbor in a direction. At the edge
+    /// of the layout, swap with the focused window of the adjacent display.
+    SwapInDirection(Direction),
+    /// Move the selected node as far as it can go in a direction, without
+    /// crossing onto another space.
+    MoveNodeToEdge(Direction),
 
     JoinWindow(Direction),
+    /// Move the focused window into the container selected with
+    /// `Ascend`/`Descend`, as a new tab or split of it (Traditional and BSP).
+    JoinIntoSelection,
     ToggleStack,
+    /// Show every window of the active workspace as tabs of one full-screen
+    /// stack, or go back to the previous layout (Traditional only).
+    ToggleMonocle,
+    /// Pin the focused member of a stacked group as the one the group shows
+    /// while focus is elsewhere.
+    SetStackRepresentative,
     ToggleOrientation,
+    /// Flip the orientation of every split in the active layout (rotate 90 degrees).
+    RotateLayout,
     UnjoinWindows,
     ToggleFocusFloating,
     ToggleWindowFloating,
     ToggleFullscreen,
     ToggleFullscreenWithinGaps,
+    /// Toggle an edge-to-edge fullscreen of the focused window that covers
+    /// the menu bar and Dock areas and hides floating windows and stack lines
+    /// on its space, without creating a native fullscreen space.
+    TrueFullscreen,
+    /// Resize the focused floating window to the next configured size preset,
+    /// centered on its screen.
+    CycleFloatingPreset,
+    /// Float the focused window and stretch it along the full length of a
+    /// screen edge, `floating.dock_thickness` thick.
+    DockFloatingToEdge(Direction),
+    /// Ask every window in the active workspace except the focused one to close.
+    CloseOtherWindows,
+    /// Keep the focused floating window above all other windows, even while
+    /// others are focused. Toggling again restores its normal level.
+    ToggleAlwaysOnTop,
+    /// Re-run app rules for every window on the current space and rebuild the
+    /// tiling trees from the workspace assignments, dropping stale entries.
+    ReconcileWorkspaces,
+    /// Drop all outer and inner gaps on the current space until toggled again
+    /// or the space leaves its screen.
+    ToggleGaps,
+    /// Stop managing the focused window, as if an app rule excluded it, or
+    /// manage it again. App rules don't override the choice.
+    ToggleWindowManaged,
+    /// Turn automatic removal of stale windows on or off.
+    SetStaleCleanup(bool),
+    /// Remove every window whose window server id no longer exists, e.g.
+    /// ghosts left behind by an app that crashed.
+    PurgeStaleWindows,
+    /// Stop moving windows on layout updates, or resume and re-tile. The
+    /// layout trees keep tracking windows while frozen.
+    ToggleLayoutFreeze,
+    /// Toggle whether Rift resizes the focused window. Ignored windows are still
+    /// positioned, but keep their own size; any slack is left as gap.
+    ToggleIgnoreResize,
+    /// Toggle whether the focused window stays visible on every workspace of
+    /// its space.
+    ToggleWindowSticky,
 
     ResizeWindowGrow,
     ResizeWindowShrink,
     ResizeWindowBy {
         amount: f64,
     },
+    /// Resize the stacked group holding the focused window against its
+    /// siblings. `ResizeWindowBy` already treats a group as one unit, so this
+    /// is the same resize under the name users look for.
+    ResizeGroup {
+        amount: f64,
+    },
+    /// Reset every split ratio in the active layout to an even share.
+    EqualizeSizes,
+    /// Give the focused window this share of its parent split (Traditional
+    /// and BSP only).
+    SetContainerRatio {
+        ratio: f64,
+    },
+    /// `SetContainerRatio` that also sets the column width ratio in Scrolling.
+    /// Windows whose resize is ignored are left alone.
+    ResizeWindowTo {
+        ratio: f64,
+    },
 
-    /// Scroll the strip by a normalized delta (scaled by column step width)
+    /// Scroll the strip by a normalized delta (scaled by column step width).
+    /// Gesture-driven scrolls name their input source so that device's
+    /// inversion setting applies; without one the delta is used as given.
     ScrollStrip {
         delta: f64,
+        #[serde(default)]
+        source: Option<crate::common::config::ScrollSource>,
     },
     /// Snap the strip to the nearest column boundary
     SnapStrip,
@@ -78,18 +174,52 @@ pub enum LayoutCommand {
     NextWorkspace(Option<bool>),
     PrevWorkspace(Option<bool>),
     SwitchToWorkspace(usize),
+    /// Switch to the workspace with this name on the current space.
+    SwitchToWorkspaceNamed {
+        name: String,
+    },
     MoveWindowToWorkspace {
         workspace: usize,
         window_id: Option<u32>,
     },
+    /// Move the focused window to the previously active workspace without switching to it.
+    MoveWindowToLastWorkspace,
+    /// Pull every window of the focused window's app on the current space into
+    /// the active workspace. Floating windows stay floating.
+    GatherAppWindows,
     SetWorkspaceLayout {
         workspace: Option<usize>,
         mode: LayoutMode,
     },
     CreateWorkspace,
+    /// Create workspaces on the current space until it has at least `count`.
+    /// Never removes any, and stops at the workspace limit.
+    EnsureWorkspaces {
+        count: usize,
+    },
+    /// Delete the workspace at `index` on the current space. A workspace with
+    /// windows is kept unless `force` is set, which first moves its windows
+    /// to the previous workspace (the next one, for the first).
+    DeleteWorkspace {
+        index: usize,
+        #[serde(default)]
+        force: bool,
+    },
+    /// Move the workspace at index `from` to index `to` on the current space,
+    /// shifting the ones in between. Index-based switching follows the new order.
+    MoveWorkspace {
+        from: usize,
+        to: usize,
+    },
     SwitchToLastWorkspace,
+    /// Show or hide the scratchpad windows, centered on top of the current workspace.
+    ToggleScratchpad,
+    /// Move the focused window into the space's hidden scratchpad.
+    MoveWindowToScratchpad,
 
     SwapWindows(crate::actor::app::WindowId, crate::actor::app::WindowId),
+    /// Swap the focused window with the one focused before it in the same workspace.
+    SwapWithLast,
 
     AdjustMasterRatio {
         delta: f64,
@@ -97,8 +227,35 @@ pub enum LayoutCommand {
     AdjustMasterCount {
         delta: i32,
     },
+    /// Step the master count through `master_stack.master_count_cycle`.
+    CycleMasterCount,
     PromoteToMaster,
     SwapMasterStack,
+    /// Reset the stack-area windows to equal sizes, keeping the master ratio.
+    EqualizeStack,
+    /// Put the master area on the given side of the active workspace.
+    SetMasterPosition(crate::common::config::MasterStackSide),
+}
+
+/// Which category of windows is shown on a space; the other is parked in the
+/// hidden corner.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowVisibilityMode {
+    #[default]
+    All,
+    TiledOnly,
+    FloatingOnly,
+}
+
+impl WindowVisibilityMode {
+    fn next(self) -> Self {
+        match self {
+            WindowVisibilityMode::All => WindowVisibilityMode::TiledOnly,
+            WindowVisibilityMode::TiledOnly => WindowVisibilityMode::FloatingOnly,
+            WindowVisibilityMode::FloatingOnly => WindowVisibilityMode::All,
+        }
+    }
 }
 
 #[non_exhaustive]
@@ -120,7 +277,9 @@ pub enum LayoutEvent {
         Option<AppInfo>,
     ),
     AppClosed(pid_t),
-    WindowAdded(SpaceId, WindowId),
+    /// A window was created; the optional anchor is a managed window the new one
+    /// should be placed next to (e.g. the window under the cursor).
+    WindowAdded(SpaceId, WindowId, Option<WindowId>),
     WindowRemoved(WindowId),
     WindowRemovedPreserveFloating(WindowId),
     WindowFocused(SpaceId, WindowId),
@@ -141,23 +300,116 @@ pub struct EventResponse {
     pub boundary_hit: Option<Direction>,
 }
 
+/// Version written by [`LayoutEngine::save`] and [`LayoutEngine::save_profile`].
+/// Bump it whenever the saved shape changes in a way `#[serde(default)]`
+/// cannot absorb, and teach [`LayoutEngine::migrate`] how to read the
+/// previous one.
+pub const LAYOUT_STATE_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+#[serde(rename = "LayoutState")]
+struct SavedLayoutStateRef<'a> {
+    version: u32,
+    engine: &'a LayoutEngine,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "LayoutState")]
+struct SavedLayoutState {
+    #[allow(dead_code)]
+    version: u32,
+    engine: LayoutEngine,
+}
+
+/// Reads only the version of a saved layout state, skipping the rest.
+#[derive(Deserialize)]
+#[serde(rename = "LayoutState")]
+struct SavedLayoutVersion {
+    version: u32,
+}
+
+/// Workspaces of one space, written by [`LayoutEngine::save_profile`].
+#[derive(Serialize)]
+#[serde(rename = "LayoutProfile")]
+struct SavedProfileRef<'a> {
+    version: u32,
+    active_index: Option<usize>,
+    workspaces: Vec<SavedProfileWorkspaceRef<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename = "ProfileWorkspace")]
+struct SavedProfileWorkspaceRef<'a> {
+    workspace: &'a VirtualWorkspace,
+    layouts: Option<&'a SpaceLayoutInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "LayoutProfile")]
+struct SavedProfile {
+    version: u32,
+    active_index: Option<usize>,
+    workspaces: Vec<SavedProfileWorkspace>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "ProfileWorkspace")]
+struct SavedProfileWorkspace {
+    workspace: VirtualWorkspace,
+    layouts: Option<SpaceLayoutInfo>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct LayoutEngine {
+    #[serde(default)]
     workspace_layouts: WorkspaceLayouts,
+    #[serde(default)]
     floating: FloatingManager,
     #[serde(skip)]
     focused_window: Option<WindowId>,
     #[serde(skip)]
     window_layout_constraints: HashMap<WindowId, WindowLayoutConstraints>,
+    /// Windows the user asked Rift to keep at their own size, with whether
+    /// the app reports them resizable. Their constraints are stored as not
+    /// resizable, so layout passes can read them as they are.
+    #[serde(skip)]
+    ignore_resize_windows: HashMap<WindowId, bool>,
+    #[serde(default)]
     virtual_workspace_manager: VirtualWorkspaceManager,
     #[serde(skip)]
     layout_settings: LayoutSettings,
+    /// Layout settings applied to a single space in place of `layout_settings`.
+    #[serde(skip)]
+    space_layout_settings: HashMap<SpaceId, LayoutSettings>,
     #[serde(skip)]
     broadcast_tx: Option<BroadcastSender>,
     #[serde(skip)]
     space_display_map: HashMap<SpaceId, Option<String>>,
     #[serde(skip)]
     display_last_space: HashMap<String, SpaceId>,
+    /// Display each space last had `display_layout_rules` applied for.
+    #[serde(skip)]
+    display_layout_applied: HashMap<SpaceId, String>,
+    #[serde(skip)]
+    window_visibility: HashMap<SpaceId, WindowVisibilityMode>,
+    #[serde(skip)]
+    floating_preset_index: HashMap<WindowId, usize>,
+    /// Window shown edge to edge on each space by `TrueFullscreen`.
+    #[serde(skip)]
+    true_fullscreen: HashMap<SpaceId, WindowId>,
+    /// Spaces laid out without stack offsets or stack-line space by
+    /// `ToggleCompactStacks`.
+    #[serde(skip)]
+    compact_stacks: HashSet<SpaceId>,
+    /// Workspaces in monocle mode, with the layout to restore and the
+    /// monocle layout standing in for it.
+    #[serde(skip)]
+    monocle: HashMap<VirtualWorkspaceId, (LayoutId, LayoutId)>,
+    /// Order in which windows were last focused; higher is more recent.
+    #[serde(skip)]
+    focus_order: HashMap<WindowId, u64>,
+    #[serde(skip)]
+    focus_counter: u64,
 }
 
 impl LayoutEngine {
@@ -233,8 +485,8 @@ impl LayoutEngine {
             return false;
         };
         workspace.layout_mode = mode;
-        workspace.layout_system =
-            VirtualWorkspace::create_layout_system(mode, &self.layout_settings);
+        let settings = self.space_layout_settings.get(&space).unwrap_or(&self.layout_settings);
+        workspace.layout_system = VirtualWorkspace::create_layout_system(mode, settings);
 
         let new_layout = workspace.layout_system.create_layout();
         self.workspace_layouts
@@ -303,6 +555,43 @@ impl LayoutEngine {
         Self::response_for_raised_windows(visible_windows)
     }
 
+    fn toggle_monocle(
+        &mut self,
+        space: SpaceId,
+        workspace_id: VirtualWorkspaceId,
+        layout: LayoutId,
+    ) -> EventResponse {
+        let exiting = self.monocle.remove(&workspace_id);
+        let LayoutSystemKind::Traditional(tree) = self.workspace_tree_mut(workspace_id) else {
+            return EventResponse::default();
+        };
+
+        let Some((saved, monocle)) = exiting else {
+            let monocle = tree.create_monocle_layout(layout);
+            self.workspace_layouts.swap_active(space, workspace_id, monocle);
+            self.workspace_layouts.mark_last_saved(space, workspace_id, monocle);
+            self.monocle.insert(workspace_id, (layout, monocle));
+            let windows = self.workspace_tree(workspace_id).visible_windows_in_layout(monocle);
+            return Self::response_for_raised_windows(windows);
+        };
+
+        if !tree.has_layout(saved) {
+            return EventResponse::default();
+        }
+        if layout != monocle {
+            // The monocle layout was replaced (e.g. by a screen size change);
+            // keep whatever is showing now.
+            tree.remove_layout(saved);
+            return EventResponse::default();
+        }
+        tree.restore_from_monocle(saved, monocle);
+        tree.remove_layout(monocle);
+        self.workspace_layouts.swap_active(space, workspace_id, saved);
+        self.workspace_layouts.mark_last_saved(space, workspace_id, saved);
+        let windows = self.workspace_tree(workspace_id).visible_windows_in_layout(saved);
+        Self::response_for_raised_windows(windows)
+    }
+
     fn collect_group_containers_for_space(
         &self,
         space: SpaceId,
@@ -316,7 +605,10 @@ impl LayoutEngine {
         let Some((ws_id, layout_id)) = self.workspace_and_layout(space) else {
             return Vec::new();
         };
-        let stack_offset = self.layout_settings.stack.stack_offset;
+        let gaps = self.smart_gaps_for_layout(space, ws_id, layout_id, screen, gaps);
+        let gaps = gaps.as_ref();
+        let stack_offset = self.stack_offset_for_space(space);
+        let stack_line_thickness = self.stack_line_thickness_for_space(space, stack_line_thickness);
         match self.workspace_tree(ws_id) {
             LayoutSystemKind::Traditional(s) => {
                 if selection_path_only {
@@ -397,17 +689,84 @@ impl LayoutEngine {
         self.layout_settings = settings.clone();
 
         for (_, ws) in self.virtual_workspace_manager.workspaces.iter_mut() {
-            match &mut ws.layout_system {
-                LayoutSystemKind::Stack(system) => {
-                    system.update_settings(settings.stack.default_orientation);
-                }
-                LayoutSystemKind::MasterStack(system) => {
-                    system.update_settings(settings.master_stack.clone());
-                }
-                LayoutSystemKind::Scrolling(system) => {
-                    system.update_settings(&settings.scrolling);
-                }
-                _ => {}
+            let settings = self.space_layout_settings.get(&ws.space).unwrap_or(settings);
+            Self::apply_layout_settings(&mut ws.layout_system, settings);
+        }
+    }
+
+    /// Lay out `space` with `settings` instead of the global layout settings.
+    /// Passing `None` puts the space back on the global settings.
+    pub fn set_space_layout_settings(&mut self, space: SpaceId, settings: Option<LayoutSettings>) {
+        match settings {
+            Some(settings) => {
+                self.space_layout_settings.insert(space, settings);
+            }
+            None => {
+                self.space_layout_settings.remove(&space);
+            }
+        }
+
+        let settings = self.space_layout_settings.get(&space).unwrap_or(&self.layout_settings);
+        for (_, ws) in self.virtual_workspace_manager.workspaces.iter_mut() {
+            if ws.space == space {
+                Self::apply_layout_settings(&mut ws.layout_system, settings);
+            }
+        }
+    }
+
+    /// Layout settings applied to `space` in place of the global ones, if any.
+    pub fn space_layout_settings(&self, space: SpaceId) -> Option<&LayoutSettings> {
+        self.space_layout_settings.get(&space)
+    }
+
+    fn layout_settings_for_space(&self, space: SpaceId) -> &LayoutSettings {
+        self.space_layout_settings(space).unwrap_or(&self.layout_settings)
+    }
+
+    /// Toggle compact stacks on `space`, returning whether they are now on.
+    pub fn toggle_compact_stacks(&mut self, space: SpaceId) -> bool {
+        if self.compact_stacks.remove(&space) {
+            false
+        } else {
+            self.compact_stacks.insert(space);
+            true
+        }
+    }
+
+    pub fn compact_stacks(&self, space: SpaceId) -> bool { self.compact_stacks.contains(&space) }
+
+    fn stack_offset_for_space(&self, space: SpaceId) -> f64 {
+        if self.compact_stacks(space) {
+            0.0
+        } else {
+            self.layout_settings_for_space(space).stack.stack_offset
+        }
+    }
+
+    fn stack_line_thickness_for_space(&self, space: SpaceId, thickness: f64) -> f64 {
+        if self.compact_stacks(space) {
+            0.0
+        } else {
+            thickness
+        }
+    }
+
+    fn apply_layout_settings(system: &mut LayoutSystemKind, settings: &LayoutSettings) {
+        match system {
+            LayoutSystemKind::Stack(system) => {
+                system.update_settings(settings.stack.default_orientation);
+            }
+            LayoutSystemKind::MasterStack(system) => {
+                system.update_settings(settings.master_stack.clone());
+            }
+            LayoutSystemKind::Scrolling(system) => {
+                system.update_settings(&settings.scrolling);
+            }
+            LayoutSystemKind::Traditional(system) => {
+                system.update_settings(settings.resize_mode);
+            }
+            LayoutSystemKind::Bsp(system) => {
+                system.update_settings(settings.resize_mode);
             }
         }
     }
@@ -421,10 +780,12 @@ impl LayoutEngine {
         // Re-apply workspace layout rules to already-existing workspaces on hot reload.
         let spaces = self.virtual_workspace_manager.initialized_spaces();
         for space in spaces {
+            let display_uuid = self.display_uuid_for_space(space);
             let workspaces = self.virtual_workspace_manager.list_workspaces(space).to_vec();
             for (index, (workspace_id, name)) in workspaces.iter().enumerate() {
-                let desired_mode =
-                    self.virtual_workspace_manager.desired_layout_mode_for_workspace(index, name);
+                let desired_mode = self
+                    .virtual_workspace_manager
+                    .desired_layout_mode_for_workspace(index, name, display_uuid.as_deref());
                 let current_mode = self
                     .virtual_workspace_manager
                     .workspace_info(space, *workspace_id)
@@ -468,7 +829,9 @@ impl LayoutEngine {
     pub fn layout_specific_animate_settings(&self, space: SpaceId) -> Option<bool> {
         if let Some(ws_id) = self.virtual_workspace_manager.active_workspace(space) {
             match self.workspace_tree(ws_id) {
-                LayoutSystemKind::Scrolling(_) => self.layout_settings.scrolling.animate,
+                LayoutSystemKind::Scrolling(_) => {
+                    self.layout_settings_for_space(space).scrolling.animate
+                }
                 _ => None,
             }
         } else {
@@ -484,6 +847,177 @@ impl LayoutEngine {
             .collect()
     }
 
+    /// Reassign `window_id` on `op_space` to `target_workspace_id` without changing which
+    /// workspace is active, keeping last-focused bookkeeping of both workspaces in sync.
+    pub fn move_window_to_workspace_id(
+        &mut self,
+        op_space: SpaceId,
+        window_id: WindowId,
+        target_workspace_id: VirtualWorkspaceId,
+    ) -> EventResponse {
+        let Some(current_workspace_id) =
+            self.virtual_workspace_manager.workspace_for_window(op_space, window_id)
+        else {
+            return EventResponse::default();
+        };
+
+        if current_workspace_id == target_workspace_id {
+            return EventResponse::default();
+        }
+
+        let is_floating = self.floating.is_floating(window_id);
+
+        if is_floating {
+            self.floating.remove_active_for_window(window_id);
+        } else {
+            self.remove_window_from_all_tiling_trees(window_id);
+        }
+
+        let assigned = self.virtual_workspace_manager.assign_window_to_workspace(
+            op_space,
+            window_id,
+            target_workspace_id,
+        );
+        if !assigned {
+            if is_floating {
+                self.floating.add_active(op_space, window_id.pid, window_id);
+            } else if let Some(prev_layout) =
+                self.workspace_layouts.active(op_space, current_workspace_id)
+            {
+                self.workspace_tree_mut(current_workspace_id)
+                    .add_window_after_selection(prev_layout, window_id);
+            }
+            return EventResponse::default();
+        }
+
+        if !is_floating {
+            if let Some(target_layout) =
+                self.workspace_layouts.active(op_space, target_workspace_id)
+            {
+                self.workspace_tree_mut(target_workspace_id)
+                    .add_window_after_selection(target_layout, window_id);
+            }
+        }
+
+        let active_workspace = self.virtual_workspace_manager.active_workspace(op_space);
+
+        if Some(target_workspace_id) == active_workspace {
+            if is_floating {
+                self.floating.add_active(op_space, window_id.pid, window_id);
+            }
+            self.broadcast_windows_changed(op_space);
+            return EventResponse {
+                focus_window: Some(window_id),
+                raise_windows: vec![],
+                boundary_hit: None,
+            };
+        }
+
+        self.virtual_workspace_manager.set_last_focused_window(
+            op_space,
+            target_workspace_id,
+            Some(window_id),
+        );
+
+        if Some(current_workspace_id) == active_workspace {
+            self.focused_window = None;
+            self.virtual_workspace_manager.set_last_focused_window(
+                op_space,
+                current_workspace_id,
+                None,
+            );
+
+            let remaining_windows =
+                self.virtual_workspace_manager.windows_in_active_workspace(op_space);
+            if let Some(&new_focus) = remaining_windows.first() {
+                self.broadcast_windows_changed(op_space);
+                return EventResponse {
+                    focus_window: Some(new_focus),
+                    raise_windows: vec![],
+                    boundary_hit: None,
+                };
+            }
+        }
+
+        self.broadcast_windows_changed(op_space);
+        EventResponse::default()
+    }
+
+    /// Activate `workspace_id` on `space`, or bounce back to the last workspace when it is
+    /// already active and `workspace_auto_back_and_forth` is enabled.
+    fn switch_to_workspace(
+        &mut self,
+        space: SpaceId,
+        workspace_id: VirtualWorkspaceId,
+    ) -> EventResponse {
+        if self.virtual_workspace_manager.active_workspace(space) == Some(workspace_id) {
+            // Check if workspace_auto_back_and_forth is enabled
+            if self.virtual_workspace_manager.workspace_auto_back_and_forth() {
+                // Switch to last workspace instead
+                if let Some(last_workspace) = self.virtual_workspace_manager.last_workspace(space) {
+                    self.virtual_workspace_manager.set_active_workspace(space, last_workspace);
+                    self.update_active_floating_windows(space);
+                    self.broadcast_workspace_changed(space);
+                    self.broadcast_workspace_activated(space);
+                    self.broadcast_windows_changed(space);
+                    return self.refocus_workspace(space, last_workspace);
+                }
+            }
+            return EventResponse::default();
+        }
+        self.virtual_workspace_manager.set_active_workspace(space, workspace_id);
+
+        self.update_active_floating_windows(space);
+
+        self.broadcast_workspace_changed(space);
+        self.broadcast_workspace_activated(space);
+        self.broadcast_windows_changed(space);
+
+        self.refocus_workspace(space, workspace_id)
+    }
+
+    fn delete_workspace(&mut self, space: SpaceId, index: usize, force: bool) -> EventResponse {
+        let previous = self.virtual_workspace_manager.list_workspaces(space);
+        let Some(&(workspace_id, _)) = previous.get(index) else {
+            warn!("Cannot delete workspace {}: index out of range", index);
+            return EventResponse::default();
+        };
+        if previous.len() <= 1 {
+            warn!("Cannot delete the only workspace of space {:?}", space);
+            return EventResponse::default();
+        }
+        let neighbor = previous[if index > 0 { index - 1 } else { 1 }].0;
+        let windows = self.virtual_workspace_manager.workspace_windows(space, workspace_id);
+        if !windows.is_empty() && !force {
+            warn!(
+                "Not deleting workspace {}: it has {} window(s); use force to move them",
+                index,
+                windows.len()
+            );
+            return EventResponse::default();
+        }
+
+        let mut response = EventResponse::default();
+        if self.virtual_workspace_manager.active_workspace(space) == Some(workspace_id) {
+            response = self.switch_to_workspace(space, neighbor);
+        }
+        for wid in windows {
+            let moved = self.move_window_to_workspace_id(space, wid, neighbor);
+            if response.focus_window.is_none() {
+                response.focus_window = moved.focus_window;
+            }
+        }
+        if !self.virtual_workspace_manager.remove_workspace(space, workspace_id) {
+            warn!("Failed to delete workspace {}", index);
+            return response;
+        }
+        self.workspace_layouts.remove_workspace(space, workspace_id);
+        self.monocle.remove(&workspace_id);
+        self.reapply_workspace_layout_rules_after_move(space, &previous);
+        self.broadcast_workspace_changed(space);
+        response
+    }
+
     fn refocus_workspace(
         &mut self,
         space: SpaceId,
@@ -538,9 +1072,22 @@ impl LayoutEngine {
                 .set_last_focused_window(space, workspace_id, None);
         }
 
+        // Re-stack the workspace's floating windows so the most recently
+        // focused one ends up on top.
+        let raise_windows = if self.layout_settings.floating.strict_z_order {
+            let floating_windows = self.active_floating_windows_in_workspace(space);
+            self.floating
+                .in_focus_order(&floating_windows)
+                .into_iter()
+                .filter(|wid| Some(*wid) != focus_window)
+                .collect()
+        } else {
+            vec![]
+        };
+
         EventResponse {
             focus_window,
-            raise_windows: vec![],
+            raise_windows,
             boundary_hit: None,
         }
     }
@@ -702,6 +1249,24 @@ impl LayoutEngine {
             if let Some(prev_wid) = previous_selection {
                 let _ = self.workspace_tree_mut(ws_id).select_window(layout, prev_wid);
             }
+            if self.layout_settings_for_space(space).focus_wrap {
+                let (wrapped_raw, raise_windows) =
+                    self.workspace_tree_mut(ws_id).wrap_focus(layout, direction);
+                let focus_window = self.filter_active_workspace_window(space, wrapped_raw);
+                if focus_window.is_some() {
+                    let raise_windows = self.filter_active_workspace_windows(space, raise_windows);
+                    let response = EventResponse {
+                        focus_window,
+                        raise_windows,
+                        boundary_hit: None,
+                    };
+                    self.apply_focus_response(space, ws_id, layout, &response);
+                    return response;
+                }
+                if let Some(prev_wid) = previous_selection {
+                    let _ = self.workspace_tree_mut(ws_id).select_window(layout, prev_wid);
+                }
+            }
             if let Some(new_space) = self.next_space_for_direction(
                 space,
                 direction,
@@ -841,6 +1406,20 @@ impl LayoutEngine {
     }
 
     fn remove_window_internal(&mut self, wid: WindowId, preserve_floating: bool) {
+        let removal = self.forget_window(wid, preserve_floating);
+
+        if let Some(space) = removal.active_space {
+            self.broadcast_windows_changed(space);
+        }
+
+        if removal.changes_layout() {
+            self.rebalance_all_layouts();
+        }
+    }
+
+    /// Drop every trace of `wid` without broadcasting or rebalancing, leaving
+    /// that to the caller.
+    fn forget_window(&mut self, wid: WindowId, preserve_floating: bool) -> WindowRemovalImpact {
         let removal = self.remove_window_layout_membership(wid);
 
         if preserve_floating {
@@ -858,14 +1437,11 @@ impl LayoutEngine {
             self.focused_window = None;
         }
         self.window_layout_constraints.remove(&wid);
-
-        if let Some(space) = removal.active_space {
-            self.broadcast_windows_changed(space);
-        }
-
-        if removal.changes_layout() {
-            self.rebalance_all_layouts();
-        }
+        self.ignore_resize_windows.remove(&wid);
+        self.floating_preset_index.remove(&wid);
+        self.focus_order.remove(&wid);
+        self.true_fullscreen.retain(|_, fullscreen| *fullscreen != wid);
+        removal
     }
 
     fn remove_window_layout_membership(&mut self, wid: WindowId) -> WindowRemovalImpact {
@@ -892,7 +1468,12 @@ impl LayoutEngine {
         WindowRemovalImpact { active_space, tiled_workspaces }
     }
 
-    fn add_window_to_layout(&mut self, space: SpaceId, wid: WindowId) -> bool {
+    fn add_window_to_layout(
+        &mut self,
+        space: SpaceId,
+        wid: WindowId,
+        anchor: Option<WindowId>,
+    ) -> bool {
         let active_space_before = self.space_with_window(wid);
 
         let assigned_workspace =
@@ -914,9 +1495,14 @@ impl LayoutEngine {
         if should_be_floating {
             self.floating.add_active(space, wid.pid, wid);
         } else if let Some(layout) = self.workspace_layouts.active(space, assigned_workspace) {
-            if !self.workspace_tree(assigned_workspace).contains_window(layout, wid) {
-                self.workspace_tree_mut(assigned_workspace)
-                    .add_window_after_selection(layout, wid);
+            let tree = self.workspace_tree_mut(assigned_workspace);
+            // The window may already have been inserted at the selection by the
+            // app-rule pass; insert_window_beside moves it next to the anchor.
+            let placed = anchor.is_some_and(|anchor| {
+                tree.insert_window_beside(layout, wid, anchor, Direction::Right)
+            });
+            if !placed && !tree.contains_window(layout, wid) {
+                tree.add_window_after_selection(layout, wid);
             }
         } else {
             warn!(
@@ -1021,29 +1607,150 @@ impl LayoutEngine {
         changed_layouts
     }
 
-    pub fn update_space_display(&mut self, space: SpaceId, display_uuid: Option<String>) {
-        if let Some(uuid) = display_uuid {
-            self.space_display_map.insert(space, Some(uuid.clone()));
-            self.display_last_space.insert(uuid, space);
-        } else {
-            self.space_display_map.remove(&space);
+    /// Drop the `stale` windows, then make every tiling tree on `space` hold
+    /// exactly the tiled windows the virtual workspace manager assigns to its
+    /// workspace, for the apps in `pids` and any app already assigned or
+    /// visible there. Everything is applied as one update. Returns the windows
+    /// that were added to a tree they were missing from.
+    pub fn reconcile_workspace_trees(
+        &mut self,
+        space: SpaceId,
+        pids: impl IntoIterator<Item = pid_t>,
+        stale: &[WindowId],
+    ) -> Vec<WindowId> {
+        let mut stale_changed_layout = false;
+        for &wid in stale {
+            stale_changed_layout |= self.forget_window(wid, false).changes_layout();
         }
-    }
 
-    pub fn last_space_for_display_uuid(&self, display_uuid: &str) -> Option<SpaceId> {
-        self.display_last_space.get(display_uuid).copied()
-    }
-
-    pub fn display_seen_before(&self, display_uuid: &str) -> bool {
-        self.display_last_space.contains_key(display_uuid)
-    }
-
-    fn display_uuid_for_space(&self, space: SpaceId) -> Option<String> {
-        self.space_display_map.get(&space).and_then(|uuid| uuid.clone())
-    }
+        let layouts = self.workspace_layouts.active_layouts_for_space(space);
+        let mut all_pids: HashSet<pid_t> = pids.into_iter().collect();
+        for &(ws_id, layout) in &layouts {
+            all_pids.extend(
+                self.virtual_workspace_manager
+                    .workspace_windows(space, ws_id)
+                    .into_iter()
+                    .chain(self.workspace_tree(ws_id).visible_windows_in_layout(layout))
+                    .map(|wid| wid.pid),
+            );
+        }
 
-    /// Returns the last known space associated with the given display UUID.
-    /// Useful when the OS recreates spaces (e.g. after sleep/resume) and we
+        let mut relocated = Vec::new();
+        let mut changed_layouts = Vec::new();
+        for (ws_id, layout) in layouts {
+            let assigned = self.virtual_workspace_manager.workspace_windows(space, ws_id);
+            for &pid in &all_pids {
+                let mut desired: Vec<WindowId> = assigned
+                    .iter()
+                    .copied()
+                    .filter(|wid| wid.pid == pid && !self.floating.is_floating(*wid))
+                    .collect();
+                desired.sort_unstable();
+                let mut current = self.workspace_tree(ws_id).windows_for_app(layout, pid);
+                current.sort_unstable();
+                if desired == current {
+                    continue;
+                }
+                relocated.extend(desired.iter().filter(|wid| !current.contains(wid)));
+                self.workspace_tree_mut(ws_id).set_windows_for_app(layout, pid, desired);
+                if !changed_layouts.contains(&(ws_id, layout)) {
+                    changed_layouts.push((ws_id, layout));
+                }
+            }
+        }
+
+        if !changed_layouts.is_empty() || !stale.is_empty() {
+            self.broadcast_windows_changed(space);
+        }
+        for (ws_id, layout) in changed_layouts {
+            self.workspace_tree_mut(ws_id).rebalance(layout);
+        }
+        if stale_changed_layout {
+            self.rebalance_all_layouts();
+        }
+        relocated
+    }
+
+    pub fn update_space_display(&mut self, space: SpaceId, display_uuid: Option<String>) {
+        if let Some(uuid) = display_uuid {
+            self.space_display_map.insert(space, Some(uuid.clone()));
+            self.display_last_space.insert(uuid, space);
+            self.apply_display_layout_rules(space);
+        } else {
+            self.space_display_map.remove(&space);
+        }
+    }
+
+    /// Give each reordered workspace of `space` the layout mode its new index
+    /// calls for. Workspaces whose mode was changed away from the one their old
+    /// index called for are left alone.
+    fn reapply_workspace_layout_rules_after_move(
+        &mut self,
+        space: SpaceId,
+        previous: &[(VirtualWorkspaceId, String)],
+    ) {
+        let display_uuid = self.display_uuid_for_space(space);
+        let workspaces = self.virtual_workspace_manager.list_workspaces(space);
+        for (index, (workspace_id, name)) in workspaces.iter().enumerate() {
+            let Some(old_index) = previous.iter().position(|(id, _)| id == workspace_id) else {
+                continue;
+            };
+            if old_index == index {
+                continue;
+            }
+            let vwm = &self.virtual_workspace_manager;
+            let old_mode =
+                vwm.desired_layout_mode_for_workspace(old_index, name, display_uuid.as_deref());
+            let new_mode =
+                vwm.desired_layout_mode_for_workspace(index, name, display_uuid.as_deref());
+            let current_mode = vwm.workspace_info(space, *workspace_id).map(|ws| ws.layout_mode());
+            if current_mode == Some(old_mode) && new_mode != old_mode {
+                let _ = self.switch_workspace_layout_mode(space, *workspace_id, new_mode);
+            }
+        }
+    }
+
+    /// Switch the workspaces of `space` to its display's default layout mode
+    /// the first time the space is seen on that display. Workspaces whose
+    /// mode was changed away from the rule-derived one are left alone.
+    fn apply_display_layout_rules(&mut self, space: SpaceId) {
+        let Some(uuid) = self.display_uuid_for_space(space) else {
+            return;
+        };
+        if self.display_layout_applied.get(&space) == Some(&uuid) {
+            return;
+        }
+        self.display_layout_applied.insert(space, uuid.clone());
+        if !self.virtual_workspace_manager.display_layout_rules.contains_key(&uuid) {
+            return;
+        }
+
+        let workspaces = self.virtual_workspace_manager.list_workspaces(space);
+        for (index, (workspace_id, name)) in workspaces.iter().enumerate() {
+            let vwm = &self.virtual_workspace_manager;
+            let rule_mode = vwm.desired_layout_mode_for_workspace(index, name, None);
+            let display_mode = vwm.desired_layout_mode_for_workspace(index, name, Some(&uuid));
+            let current_mode = vwm.workspace_info(space, *workspace_id).map(|ws| ws.layout_mode());
+            if current_mode == Some(rule_mode) && rule_mode != display_mode {
+                let _ = self.switch_workspace_layout_mode(space, *workspace_id, display_mode);
+            }
+        }
+    }
+
+    pub fn last_space_for_display_uuid(&self, display_uuid: &str) -> Option<SpaceId> {
+        self.display_last_space.get(display_uuid).copied()
+    }
+
+    pub fn display_seen_before(&self, display_uuid: &str) -> bool {
+        self.display_last_space.contains_key(display_uuid)
+    }
+
+    fn display_uuid_for_space(&self, space: SpaceId) -> Option<String> {
+        self.space_display_map.get(&space).and_then(|uuid| uuid.clone())
+    }
+
+    /// Returns the last known space associated with the given display UUID.
+    /// Useful when the OS recreates spaces (e.g. after sleep/resume) and we
     /// want to migrate layout state to the new space id.
     pub fn space_for_display_uuid(&self, display_uuid: &str) -> Option<SpaceId> {
         self.space_display_map.iter().find_map(|(space, uuid_opt)| match uuid_opt {
@@ -1065,6 +1772,12 @@ impl LayoutEngine {
         if let Some(uuid) = self.space_display_map.remove(&old_space) {
             self.space_display_map.insert(new_space, uuid);
         }
+        if let Some(uuid) = self.display_layout_applied.remove(&old_space) {
+            self.display_layout_applied.insert(new_space, uuid);
+        }
+        if let Some(settings) = self.space_layout_settings.remove(&old_space) {
+            self.space_layout_settings.insert(new_space, settings);
+        }
 
         for (_uuid, space) in self.display_last_space.iter_mut() {
             if *space == old_space {
@@ -1096,11 +1809,21 @@ impl LayoutEngine {
             floating: FloatingManager::new(),
             focused_window: None,
             window_layout_constraints: HashMap::default(),
+            ignore_resize_windows: HashMap::default(),
             virtual_workspace_manager,
             layout_settings: layout_settings.clone(),
+            space_layout_settings: HashMap::default(),
             broadcast_tx,
             space_display_map: HashMap::default(),
             display_last_space: HashMap::default(),
+            display_layout_applied: HashMap::default(),
+            window_visibility: HashMap::default(),
+            floating_preset_index: HashMap::default(),
+            true_fullscreen: HashMap::default(),
+            compact_stacks: HashSet::default(),
+            monocle: HashMap::default(),
+            focus_order: HashMap::default(),
+            focus_counter: 0,
         }
     }
 
@@ -1140,6 +1863,7 @@ impl LayoutEngine {
                     let tree = &mut self.virtual_workspace_manager.workspaces[id].layout_system;
                     self.workspace_layouts.ensure_active_for_workspace(space, size, id, tree);
                 }
+                self.apply_display_layout_rules(space);
             }
             LayoutEvent::WindowsOnScreenUpdated(space, pid, windows_with_titles, app_info) => {
                 self.debug_tree(space);
@@ -1166,24 +1890,44 @@ impl LayoutEngine {
                     max_size,
                 ) in windows_with_titles
                 {
+                    let title_ref = title_opt.as_deref();
+                    let ax_role_ref = ax_role_opt.as_deref();
+                    let ax_subrole_ref = ax_subrole_opt.as_deref();
+
+                    let rule_min_size = self.virtual_workspace_manager.app_rule_min_size(
+                        app_bundle_id,
+                        app_name,
+                        title_ref,
+                        ax_role_ref,
+                        ax_subrole_ref,
+                    );
+                    let min_width = rule_min_size
+                        .and_then(|min| min.w)
+                        .unwrap_or_else(|| min_size.map_or(0.0, |s| s.width));
+                    let min_height = rule_min_size
+                        .and_then(|min| min.h)
+                        .unwrap_or_else(|| min_size.map_or(0.0, |s| s.height));
+                    let is_resizable = match self.ignore_resize_windows.get_mut(&wid) {
+                        Some(reported) => {
+                            *reported = is_resizable;
+                            false
+                        }
+                        None => is_resizable,
+                    };
                     self.window_layout_constraints.insert(
                         wid,
                         WindowLayoutConstraints {
                             is_resizable,
                             locked_width: size_hint.width,
                             locked_height: size_hint.height,
-                            min_width: min_size.map_or(0.0, |s| s.width),
-                            min_height: min_size.map_or(0.0, |s| s.height),
+                            min_width,
+                            min_height,
                             max_width: max_size.map_or(0.0, |s| s.width),
                             max_height: max_size.map_or(0.0, |s| s.height),
                         }
                         .normalized(),
                     );
 
-                    let title_ref = title_opt.as_deref();
-                    let ax_role_ref = ax_role_opt.as_deref();
-                    let ax_subrole_ref = ax_subrole_opt.as_deref();
-
                     let was_floating = self.floating.is_floating(wid);
                     let assignment = match self
                         .virtual_workspace_manager
@@ -1197,7 +1941,7 @@ impl LayoutEngine {
                             ax_subrole_ref,
                         ) {
                         Ok(AppRuleResult::Managed(decision)) => Some(decision),
-                        Ok(AppRuleResult::Unmanaged) => None,
+                        Ok(AppRuleResult::Unmanaged) | Ok(AppRuleResult::Deferred) => None,
                         Err(_) => {
                             match self.virtual_workspace_manager.auto_assign_window(wid, space) {
                                 Ok(ws) => Some(AppRuleAssignment {
@@ -1262,13 +2006,15 @@ impl LayoutEngine {
                 }
                 self.floating.remove_all_for_pid(pid);
                 self.window_layout_constraints.retain(|wid, _| wid.pid != pid);
+                self.ignore_resize_windows.retain(|wid, _| wid.pid != pid);
+                self.focus_order.retain(|wid, _| wid.pid != pid);
 
                 self.virtual_workspace_manager.remove_windows_for_app(pid);
                 self.virtual_workspace_manager.remove_app_floating_positions(pid);
             }
-            LayoutEvent::WindowAdded(space, wid) => {
+            LayoutEvent::WindowAdded(space, wid, anchor) => {
                 self.debug_tree(space);
-                if self.add_window_to_layout(space, wid) {
+                if self.add_window_to_layout(space, wid, anchor) {
                     self.broadcast_windows_changed(space);
                 }
             }
@@ -1280,6 +2026,8 @@ impl LayoutEngine {
             }
             LayoutEvent::WindowFocused(space, wid) => {
                 self.focused_window = Some(wid);
+                self.focus_counter += 1;
+                self.focus_order.insert(wid, self.focus_counter);
                 if self.floating.is_floating(wid) {
                     self.floating.set_last_focus(Some(wid));
                 } else {
@@ -1300,6 +2048,15 @@ impl LayoutEngine {
                 new_frame,
                 screens,
             } => {
+                if self.is_window_resize_locked(wid) {
+                    // The app owns this window's size; remember it instead of
+                    // redistributing the layout around it.
+                    if let Some(constraints) = self.window_layout_constraints.get_mut(&wid) {
+                        constraints.locked_width = new_frame.size.width;
+                        constraints.locked_height = new_frame.size.height;
+                    }
+                    return EventResponse::default();
+                }
                 for (space, screen_frame, display_uuid) in screens {
                     let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
                         debug!(
@@ -1308,8 +2065,10 @@ impl LayoutEngine {
                         );
                         continue;
                     };
-                    let gaps =
-                        self.layout_settings.gaps.effective_for_display(display_uuid.as_deref());
+                    let gaps = self
+                        .layout_settings_for_space(space)
+                        .gaps
+                        .effective_for_display(display_uuid.as_deref());
                     self.workspace_tree_mut(ws_id).on_window_resized(
                         layout,
                         wid,
@@ -1399,6 +2158,26 @@ impl LayoutEngine {
             return EventResponse::default();
         }
 
+        if let LayoutCommand::ToggleIgnoreResize = &command {
+            let Some(wid) = self.focused_window else {
+                return EventResponse::default();
+            };
+            let constraints = self.window_layout_constraints.get_mut(&wid);
+            if let Some(reported) = self.ignore_resize_windows.remove(&wid) {
+                if let Some(constraints) = constraints {
+                    constraints.is_resizable = reported;
+                }
+                debug!("Window {:?} is resized by the layout again", wid);
+            } else {
+                let reported = constraints.map_or(true, |constraints| {
+                    std::mem::replace(&mut constraints.is_resizable, false)
+                });
+                self.ignore_resize_windows.insert(wid, reported);
+                debug!("Ignoring resize for window {:?}", wid);
+            }
+            return EventResponse::default();
+        }
+
         let Some(space) = space else {
             return EventResponse::default();
         };
@@ -1455,16 +2234,54 @@ impl LayoutEngine {
         match command {
             LayoutCommand::ToggleWindowFloating => unreachable!(),
             LayoutCommand::ToggleFocusFloating => unreachable!(),
+            LayoutCommand::ToggleIgnoreResize => unreachable!(),
+
+            LayoutCommand::ToggleWindowSticky => {
+                if let Some(wid) = self.focused_window {
+                    let sticky = self.virtual_workspace_manager.toggle_window_sticky(space, wid);
+                    debug!(?wid, sticky, "Toggled sticky window");
+                }
+                EventResponse::default()
+            }
 
             LayoutCommand::SwapWindows(a, b) => {
                 let _ = self.workspace_tree_mut(workspace_id).swap_windows(layout, a, b);
 
                 EventResponse::default()
             }
+            LayoutCommand::SwapWithLast => {
+                if is_floating {
+                    return EventResponse::default();
+                }
+                let Some(workspace) =
+                    self.virtual_workspace_manager.workspace_info(space, workspace_id)
+                else {
+                    return EventResponse::default();
+                };
+                let (Some(current), Some(previous)) =
+                    (workspace.last_focused(), workspace.previous_focused())
+                else {
+                    return EventResponse::default();
+                };
+                if !workspace.contains_window(previous) || self.floating.is_floating(previous) {
+                    return EventResponse::default();
+                }
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                let _ =
+                    self.workspace_tree_mut(workspace_id).swap_windows(layout, current, previous);
+                EventResponse::default()
+            }
             LayoutCommand::NextWindow | LayoutCommand::PrevWindow => {
                 let forward = matches!(command, LayoutCommand::NextWindow);
+                let in_monocle =
+                    self.monocle.get(&workspace_id).is_some_and(|&(_, monocle)| monocle == layout);
                 let windows = if is_floating {
                     self.active_floating_windows_in_workspace(space)
+                } else if in_monocle
+                    && let LayoutSystemKind::Traditional(tree) = self.workspace_tree(workspace_id)
+                {
+                    // Only the selected tab is visible; cycle through all of them.
+                    self.filter_active_workspace_windows(space, tree.windows_in_layout(layout))
                 } else {
                     self.filter_active_workspace_windows(
                         space,
@@ -1488,6 +2305,22 @@ impl LayoutEngine {
                     EventResponse::default()
                 }
             }
+            LayoutCommand::FocusIndex { index } => {
+                let windows = self.filter_active_workspace_windows(
+                    space,
+                    self.workspace_tree(workspace_id).visible_windows_in_layout(layout),
+                );
+                let Some(&wid) = windows.get(index) else {
+                    return EventResponse::default();
+                };
+                let response = EventResponse {
+                    focus_window: Some(wid),
+                    raise_windows: vec![wid],
+                    boundary_hit: None,
+                };
+                self.apply_focus_response(space, workspace_id, layout, &response);
+                response
+            }
             LayoutCommand::MoveFocus(direction) => {
                 debug!(
                     "MoveFocus command received, direction: {:?}, is_floating: {}",
@@ -1543,9 +2376,96 @@ impl LayoutEngine {
                 }
                 EventResponse::default()
             }
+            LayoutCommand::SwapInDirection(direction) => {
+                if is_floating {
+                    return EventResponse::default();
+                }
+                let Some(current) = self.workspace_tree(workspace_id).selected_window(layout)
+                else {
+                    return EventResponse::default();
+                };
+                let neighbor = self.filter_active_workspace_window(
+                    space,
+                    self.workspace_tree(workspace_id).window_in_direction(layout, direction),
+                );
+                if let Some(neighbor) = neighbor {
+                    self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                    let tree = self.workspace_tree_mut(workspace_id);
+                    let _ = tree.swap_windows(layout, current, neighbor);
+                    let _ = tree.select_window(layout, current);
+                    return EventResponse::default();
+                }
+
+                let Some(new_space) = self.next_space_for_direction(
+                    space,
+                    direction,
+                    visible_spaces,
+                    visible_space_centers,
+                ) else {
+                    return EventResponse::default();
+                };
+                let Some((new_ws_id, new_layout)) = self.workspace_and_layout(new_space) else {
+                    debug!(
+                        "No active workspace/layout for adjacent space {:?}; skipping cross-space swap",
+                        new_space
+                    );
+                    return EventResponse::default();
+                };
+                let Some(other) = self.filter_active_workspace_window(
+                    new_space,
+                    self.workspace_tree(new_ws_id).selected_window(new_layout),
+                ) else {
+                    return EventResponse::default();
+                };
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                self.workspace_layouts.mark_last_saved(new_space, new_ws_id, new_layout);
+
+                // Insert each window next to the one it replaces before removing
+                // that one, so both land in the other's slot.
+                let tree = self.workspace_tree_mut(workspace_id);
+                let _ = tree.select_window(layout, current);
+                tree.add_window_after_selection(layout, other);
+                tree.remove_window(current);
+                let _ = tree.select_window(layout, other);
+                let tree = self.workspace_tree_mut(new_ws_id);
+                let _ = tree.select_window(new_layout, other);
+                tree.add_window_after_selection(new_layout, current);
+                tree.remove_window(other);
+                let _ = tree.select_window(new_layout, current);
+
+                self.virtual_workspace_manager.assign_window_to_workspace(
+                    space,
+                    other,
+                    workspace_id,
+                );
+                self.virtual_workspace_manager
+                    .assign_window_to_workspace(new_space, current, new_ws_id);
+                EventResponse::default()
+            }
+            LayoutCommand::MoveNodeToEdge(direction) => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                // Every step moves the node closer to the edge, so the tree
+                // size bounds the walk; the guard only protects against a
+                // layout system that keeps reporting progress.
+                let max_steps =
+                    self.workspace_tree(workspace_id).visible_windows_in_layout(layout).len() * 4
+                        + 4;
+                let tree = self.workspace_tree_mut(workspace_id);
+                let mut steps = 0;
+                while steps < max_steps && tree.move_selection(layout, direction) {
+                    steps += 1;
+                }
+                if steps == max_steps {
+                    warn!("move_node_to_edge gave up after {steps} steps");
+                }
+                EventResponse::default()
+            }
             LayoutCommand::ToggleFullscreen => {
+                let before = self.workspace_tree(workspace_id).fullscreen_windows(layout);
                 let raise_windows =
                     self.workspace_tree_mut(workspace_id).toggle_fullscreen_of_selection(layout);
+                let after = self.workspace_tree(workspace_id).fullscreen_windows(layout);
+                self.broadcast_fullscreen_changes(space, &before, &after);
                 if raise_windows.is_empty() {
                     EventResponse::default()
                 } else {
@@ -1556,10 +2476,30 @@ impl LayoutEngine {
                     }
                 }
             }
+            LayoutCommand::TrueFullscreen => {
+                if self.true_fullscreen.remove(&space).is_some() {
+                    return EventResponse::default();
+                }
+                let Some(wid) = self.focused_window.filter(|&wid| {
+                    self.virtual_workspace_manager.workspace_for_window(space, wid)
+                        == Some(workspace_id)
+                }) else {
+                    return EventResponse::default();
+                };
+                self.true_fullscreen.insert(space, wid);
+                EventResponse {
+                    raise_windows: vec![wid],
+                    focus_window: None,
+                    boundary_hit: None,
+                }
+            }
             LayoutCommand::ToggleFullscreenWithinGaps => {
+                let before = self.workspace_tree(workspace_id).fullscreen_windows(layout);
                 let raise_windows = self
                     .workspace_tree_mut(workspace_id)
                     .toggle_fullscreen_within_gaps_of_selection(layout);
+                let after = self.workspace_tree(workspace_id).fullscreen_windows(layout);
+                self.broadcast_fullscreen_changes(space, &before, &after);
                 if raise_windows.is_empty() {
                     EventResponse::default()
                 } else {
@@ -1574,22 +2514,71 @@ impl LayoutEngine {
             LayoutCommand::NextWorkspace(_)
             | LayoutCommand::PrevWorkspace(_)
             | LayoutCommand::SwitchToWorkspace(_)
+            | LayoutCommand::SwitchToWorkspaceNamed { .. }
             | LayoutCommand::MoveWindowToWorkspace { .. }
+            | LayoutCommand::MoveWindowToLastWorkspace
+            | LayoutCommand::GatherAppWindows
             | LayoutCommand::SetWorkspaceLayout { .. }
             | LayoutCommand::CreateWorkspace
-            | LayoutCommand::SwitchToLastWorkspace => EventResponse::default(),
+            | LayoutCommand::EnsureWorkspaces { .. }
+            | LayoutCommand::DeleteWorkspace { .. }
+            | LayoutCommand::MoveWorkspace { .. }
+            | LayoutCommand::SwitchToLastWorkspace
+            | LayoutCommand::CycleFloatingPreset
+            | LayoutCommand::DockFloatingToEdge(_)
+            | LayoutCommand::CloseOtherWindows
+            | LayoutCommand::ToggleAlwaysOnTop
+            | LayoutCommand::ReconcileWorkspaces
+            | LayoutCommand::ToggleGaps
+            | LayoutCommand::ToggleWindowManaged
+            | LayoutCommand::SetStaleCleanup(_)
+            | LayoutCommand::PurgeStaleWindows
+            | LayoutCommand::ToggleLayoutFreeze => EventResponse::default(),
             LayoutCommand::JoinWindow(direction) => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
                 self.workspace_tree_mut(workspace_id)
                     .join_selection_with_direction(layout, direction);
                 EventResponse::default()
             }
+            LayoutCommand::JoinIntoSelection => {
+                let Some(wid) = self.focused_window.filter(|_| !is_floating) else {
+                    return EventResponse::default();
+                };
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                let joined = match self.workspace_tree_mut(workspace_id) {
+                    LayoutSystemKind::Traditional(tree) => {
+                        let node = tree.selected_node(layout);
+                        tree.join_into(layout, node, wid)
+                    }
+                    LayoutSystemKind::Bsp(tree) => tree
+                        .selected_node(layout)
+                        .is_some_and(|node| tree.join_into(layout, node, wid)),
+                    _ => false,
+                };
+                if !joined {
+                    return EventResponse::default();
+                }
+                EventResponse {
+                    focus_window: Some(wid),
+                    ..EventResponse::default()
+                }
+            }
             LayoutCommand::ToggleStack => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
                 let default_orientation: crate::common::config::StackDefaultOrientation =
-                    self.layout_settings.stack.default_orientation;
+                    self.layout_settings_for_space(space).stack.default_orientation;
                 self.toggle_stack_for_workspace(workspace_id, layout, default_orientation)
             }
+            LayoutCommand::ToggleMonocle => self.toggle_monocle(space, workspace_id, layout),
+            LayoutCommand::SetStackRepresentative => {
+                if is_floating {
+                    return EventResponse::default();
+                }
+                if self.workspace_tree_mut(workspace_id).set_stack_representative(layout) {
+                    self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                }
+                EventResponse::default()
+            }
             LayoutCommand::UnjoinWindows => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
                 self.workspace_tree_mut(workspace_id).unjoin_selection(layout);
@@ -1598,7 +2587,8 @@ impl LayoutEngine {
             LayoutCommand::ToggleOrientation => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
 
-                let default_orientation = self.layout_settings.stack.default_orientation;
+                let default_orientation =
+                    self.layout_settings_for_space(space).stack.default_orientation;
                 let tree = self.workspace_tree_mut(workspace_id);
                 match tree {
                     LayoutSystemKind::Traditional(s) => {
@@ -1618,6 +2608,11 @@ impl LayoutEngine {
                     }
                 }
             }
+            LayoutCommand::RotateLayout => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                self.workspace_tree_mut(workspace_id).rotate_all(layout);
+                EventResponse::default()
+            }
             LayoutCommand::ResizeWindowGrow => {
                 if is_floating {
                     return EventResponse::default();
@@ -1638,7 +2633,7 @@ impl LayoutEngine {
                 self.workspace_tree_mut(workspace_id).resize_selection_by(layout, resize_amount);
                 EventResponse::default()
             }
-            LayoutCommand::ResizeWindowBy { amount } => {
+            LayoutCommand::ResizeWindowBy { amount } | LayoutCommand::ResizeGroup { amount } => {
                 if is_floating {
                     return EventResponse::default();
                 }
@@ -1647,6 +2642,33 @@ impl LayoutEngine {
                 self.workspace_tree_mut(workspace_id).resize_selection_by(layout, amount);
                 EventResponse::default()
             }
+            LayoutCommand::SetContainerRatio { ratio }
+            | LayoutCommand::ResizeWindowTo { ratio } => {
+                let resize_to = matches!(command, LayoutCommand::ResizeWindowTo { .. });
+                if is_floating
+                    || !ratio.is_finite()
+                    || (resize_to
+                        && self.focused_window.is_some_and(|wid| self.is_window_resize_locked(wid)))
+                {
+                    return EventResponse::default();
+                }
+                let ratio = ratio.clamp(0.05, 0.95);
+                let changed = match self.workspace_tree_mut(workspace_id) {
+                    LayoutSystemKind::Scrolling(system) if resize_to => {
+                        system.set_selected_column_width_ratio(layout, ratio)
+                    }
+                    tree => tree.set_selection_ratio(layout, ratio),
+                };
+                if changed {
+                    self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::EqualizeSizes => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                self.workspace_tree_mut(workspace_id).equalize(layout);
+                EventResponse::default()
+            }
             LayoutCommand::AdjustMasterRatio { delta } => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
                 if let LayoutSystemKind::MasterStack(s) = self.workspace_tree_mut(workspace_id) {
@@ -1661,6 +2683,13 @@ impl LayoutEngine {
                 }
                 EventResponse::default()
             }
+            LayoutCommand::CycleMasterCount => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                if let LayoutSystemKind::MasterStack(s) = self.workspace_tree_mut(workspace_id) {
+                    s.cycle_master_count(layout);
+                }
+                EventResponse::default()
+            }
             LayoutCommand::PromoteToMaster => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
                 if let LayoutSystemKind::MasterStack(s) = self.workspace_tree_mut(workspace_id) {
@@ -1675,10 +2704,24 @@ impl LayoutEngine {
                 }
                 EventResponse::default()
             }
-            LayoutCommand::ScrollStrip { delta } => {
+            LayoutCommand::EqualizeStack => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                if let LayoutSystemKind::MasterStack(s) = self.workspace_tree_mut(workspace_id) {
+                    s.equalize_stack(layout);
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::SetMasterPosition(side) => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                if let LayoutSystemKind::MasterStack(s) = self.workspace_tree_mut(workspace_id) {
+                    s.set_master_side(layout, side);
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::ScrollStrip { delta, source } => {
                 let mut resp = EventResponse::default();
                 if let LayoutSystemKind::Scrolling(system) = self.workspace_tree_mut(workspace_id) {
-                    resp.boundary_hit = system.scroll_by_delta(layout, delta);
+                    resp.boundary_hit = system.scroll_by_delta(layout, delta, source);
                 }
                 resp
             }
@@ -1694,10 +2737,114 @@ impl LayoutEngine {
                 }
                 EventResponse::default()
             }
+            LayoutCommand::MoveWindowToScratchpad => {
+                let Some(wid) = self.focused_window else {
+                    return EventResponse::default();
+                };
+                let scratchpad = self.virtual_workspace_manager.scratchpad_workspace(space);
+                if self.virtual_workspace_manager.workspace_for_window(space, wid)
+                    == Some(scratchpad)
+                {
+                    return EventResponse::default();
+                }
+
+                if is_floating {
+                    self.floating.remove_active_for_window(wid);
+                } else {
+                    self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                    self.remove_window_from_all_tiling_trees(wid);
+                    self.workspace_tree_mut(workspace_id).rebalance(layout);
+                }
+
+                if !self
+                    .virtual_workspace_manager
+                    .assign_window_to_workspace(space, wid, scratchpad)
+                {
+                    return EventResponse::default();
+                }
+
+                self.focused_window = None;
+                let focus_window = self.workspace_tree(workspace_id).selected_window(layout);
+                let response = EventResponse {
+                    raise_windows: vec![],
+                    focus_window,
+                    boundary_hit: None,
+                };
+                self.apply_focus_response(space, workspace_id, layout, &response);
+                self.broadcast_windows_changed(space);
+                response
+            }
+            LayoutCommand::ToggleScratchpad => {
+                let visible = self.virtual_workspace_manager.toggle_scratchpad_visible(space);
+                let scratchpad_windows = self.virtual_workspace_manager.scratchpad_windows(space);
+                if visible {
+                    let mut raise_windows = scratchpad_windows;
+                    let focus_window = raise_windows.pop();
+                    self.focused_window = focus_window.or(self.focused_window);
+                    EventResponse {
+                        raise_windows,
+                        focus_window,
+                        boundary_hit: None,
+                    }
+                } else {
+                    if self.focused_window.is_some_and(|wid| scratchpad_windows.contains(&wid)) {
+                        self.focused_window = None;
+                    }
+                    let focus_window = self.workspace_tree(workspace_id).selected_window(layout);
+                    let response = EventResponse {
+                        raise_windows: vec![],
+                        focus_window,
+                        boundary_hit: None,
+                    };
+                    self.apply_focus_response(space, workspace_id, layout, &response);
+                    response
+                }
+            }
         }
     }
 
-    pub fn calculate_layout(
+    /// Whether the user asked Rift to keep `wid` at its own size.
+    fn is_window_resize_locked(&self, wid: WindowId) -> bool {
+        self.ignore_resize_windows.contains_key(&wid)
+    }
+
+    /// Whether `command` only rearranges the active layout, so it can be
+    /// previewed on a scratch copy without touching other state. The master
+    /// ratio, count and side live in the MasterStack system's settings and
+    /// apply to every layout, so commands changing them are left out.
+    fn is_previewable(command: &LayoutCommand) -> bool {
+        matches!(
+            command,
+            LayoutCommand::MoveNode(_)
+                | LayoutCommand::SwapInDirection(_)
+                | LayoutCommand::MoveNodeToEdge(_)
+                | LayoutCommand::JoinWindow(_)
+                | LayoutCommand::ToggleStack
+                | LayoutCommand::SetStackRepresentative
+                | LayoutCommand::ToggleOrientation
+                | LayoutCommand::RotateLayout
+                | LayoutCommand::UnjoinWindows
+                | LayoutCommand::ToggleFullscreen
+                | LayoutCommand::ToggleFullscreenWithinGaps
+                | LayoutCommand::ResizeWindowGrow
+                | LayoutCommand::ResizeWindowShrink
+                | LayoutCommand::ResizeWindowBy { .. }
+                | LayoutCommand::ResizeGroup { .. }
+                | LayoutCommand::EqualizeSizes
+                | LayoutCommand::SetContainerRatio { .. }
+                | LayoutCommand::ResizeWindowTo { .. }
+                | LayoutCommand::SwapWindows(..)
+                | LayoutCommand::SwapWithLast
+                | LayoutCommand::PromoteToMaster
+                | LayoutCommand::SwapMasterStack
+                | LayoutCommand::EqualizeStack
+        )
+    }
+
+    /// Apply `command` to a scratch copy of the active layout of `space` and
+    /// return the tiled frames it would produce. The real layout is left
+    /// untouched and nothing is raised or broadcast.
+    pub fn preview_command(
         &mut self,
         space: SpaceId,
         screen: CGRect,
@@ -1705,17 +2852,125 @@ impl LayoutEngine {
         stack_line_thickness: f64,
         stack_line_horiz: crate::common::config::HorizontalPlacement,
         stack_line_vert: crate::common::config::VerticalPlacement,
-    ) -> Vec<(WindowId, CGRect)> {
-        let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
-            return Vec::new();
+        command: LayoutCommand,
+    ) -> Result<Vec<(WindowId, CGRect)>, String> {
+        if !Self::is_previewable(&command) {
+            return Err(format!("{command:?} is unsupported in preview"));
+        }
+        let Some((workspace_id, layout)) = self.workspace_and_layout(space) else {
+            return Err(format!("No active layout for space {space:?}"));
+        };
+        // On MasterStack, equalizing also resets the system-wide master ratio.
+        if matches!(command, LayoutCommand::EqualizeSizes)
+            && matches!(
+                self.workspace_tree(workspace_id),
+                LayoutSystemKind::MasterStack(_)
+            )
+        {
+            return Err(format!("{command:?} is unsupported in preview on master_stack"));
+        }
+
+        let scratch = self.workspace_tree_mut(workspace_id).clone_layout(layout);
+        let last_saved = self.workspace_layouts.last_saved(space, workspace_id);
+        self.workspace_layouts.swap_active(space, workspace_id, scratch);
+        let broadcast_tx = self.broadcast_tx.take();
+
+        // Only `space` is visible, so nothing spills onto another display.
+        let _ = self.handle_command(Some(space), &[space], &HashMap::default(), command);
+        let frames = self.calculate_layout(
+            space,
+            screen,
+            gaps,
+            stack_line_thickness,
+            stack_line_horiz,
+            stack_line_vert,
+        );
+
+        self.broadcast_tx = broadcast_tx;
+        self.workspace_layouts.swap_active(space, workspace_id, layout);
+        self.workspace_layouts.restore_last_saved(space, workspace_id, last_saved);
+        self.workspace_tree_mut(workspace_id).remove_layout(scratch);
+        Ok(frames)
+    }
+
+    /// With smart gaps on, a layout showing a single tile on `screen` gets no
+    /// outer gaps. The tiles are read off the frames the layout calculates, so
+    /// a stack counts once and Scrolling columns off screen don't count.
+    /// Floating windows live outside the tree and don't count either.
+    fn smart_gaps_for_layout<'a>(
+        &self,
+        space: SpaceId,
+        workspace_id: crate::model::VirtualWorkspaceId,
+        layout: LayoutId,
+        screen: CGRect,
+        gaps: &'a crate::common::config::GapSettings,
+    ) -> Cow<'a, crate::common::config::GapSettings> {
+        if !gaps.smart_gaps {
+            return Cow::Borrowed(gaps);
+        }
+        let shown: Vec<CGRect> = self
+            .workspace_tree(workspace_id)
+            .calculate_layout(
+                layout,
+                screen,
+                self.stack_offset_for_space(space),
+                &self.window_layout_constraints,
+                gaps,
+                0.0,
+                Default::default(),
+                Default::default(),
+            )
+            .into_iter()
+            .map(|(_, frame)| frame)
+            .filter(|frame| frame.intersection(&screen).area() > 0.0)
+            .collect();
+        // Stacked windows overlap one another; tiles side by side don't.
+        let single_tile = shown.iter().enumerate().all(|(idx, frame)| {
+            shown[idx + 1..].iter().all(|other| frame.intersection(other).area() > 0.0)
+        });
+        if !single_tile {
+            return Cow::Borrowed(gaps);
+        }
+        let mut gaps = gaps.clone();
+        gaps.outer = Default::default();
+        Cow::Owned(gaps)
+    }
+
+    /// `gaps` as the active layout of `space` is laid out with on `screen`,
+    /// after smart gaps are applied.
+    pub fn gaps_for_active_layout(
+        &self,
+        space: SpaceId,
+        screen: CGRect,
+        gaps: &crate::common::config::GapSettings,
+    ) -> crate::common::config::GapSettings {
+        match self.workspace_and_layout(space) {
+            Some((ws_id, layout)) => {
+                self.smart_gaps_for_layout(space, ws_id, layout, screen, gaps).into_owned()
+            }
+            None => gaps.clone(),
+        }
+    }
+
+    pub fn calculate_layout(
+        &mut self,
+        space: SpaceId,
+        screen: CGRect,
+        gaps: &crate::common::config::GapSettings,
+        stack_line_thickness: f64,
+        stack_line_horiz: crate::common::config::HorizontalPlacement,
+        stack_line_vert: crate::common::config::VerticalPlacement,
+    ) -> Vec<(WindowId, CGRect)> {
+        let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
+            return Vec::new();
         };
         self.workspace_tree(ws_id).calculate_layout(
             layout,
             screen,
-            self.layout_settings.stack.stack_offset,
+            self.stack_offset_for_space(space),
             &self.window_layout_constraints,
-            gaps,
-            stack_line_thickness,
+            &self.smart_gaps_for_layout(space, ws_id, layout, screen, gaps),
+            self.stack_line_thickness_for_space(space, stack_line_thickness),
             stack_line_horiz,
             stack_line_vert,
         )
@@ -1735,8 +2990,6 @@ impl LayoutEngine {
     where
         F: Fn(WindowId) -> Option<CGRect>,
     {
-        use crate::model::HideCorner;
-
         let mut positions = HashMap::default();
         let window_size = |wid| {
             get_window_frame(wid)
@@ -1796,10 +3049,10 @@ impl LayoutEngine {
                 let tiled_positions = self.workspace_tree(active_workspace_id).calculate_layout(
                     layout,
                     screen,
-                    self.layout_settings.stack.stack_offset,
+                    self.stack_offset_for_space(space),
                     &self.window_layout_constraints,
-                    gaps,
-                    stack_line_thickness,
+                    &self.smart_gaps_for_layout(space, active_workspace_id, layout, screen, gaps),
+                    self.stack_line_thickness_for_space(space, stack_line_thickness),
                     stack_line_horiz,
                     stack_line_vert,
                 );
@@ -1809,6 +3062,20 @@ impl LayoutEngine {
                 }
             }
 
+            for (wid, frame) in self
+                .virtual_workspace_manager
+                .take_pending_rule_frames(space, active_workspace_id)
+            {
+                if self.floating.is_floating(wid) {
+                    self.virtual_workspace_manager.store_floating_position(
+                        space,
+                        active_workspace_id,
+                        wid,
+                        frame.rect_on(screen),
+                    );
+                }
+            }
+
             let floating_positions = self
                 .virtual_workspace_manager
                 .get_workspace_floating_positions(space, active_workspace_id);
@@ -1848,8 +3115,47 @@ impl LayoutEngine {
             }
         }
 
+        let visibility = self.window_visibility(space);
+        if visibility != WindowVisibilityMode::All {
+            let show_floating = visibility == WindowVisibilityMode::FloatingOnly;
+            for (&wid, rect) in positions.iter_mut() {
+                if self.floating.is_floating(wid) == show_floating {
+                    continue;
+                }
+                let app_bundle_id = self.get_app_bundle_id_for_window(wid);
+                *rect = self.virtual_workspace_manager.calculate_hidden_position_multi(
+                    screen,
+                    rect.size,
+                    self.virtual_workspace_manager.hide_corner,
+                    app_bundle_id.as_deref(),
+                    all_screens,
+                );
+            }
+        }
+
+        if let Some(fullscreen) = self.true_fullscreen_window(space) {
+            for (&wid, rect) in positions.iter_mut() {
+                if wid == fullscreen || !self.floating.is_floating(wid) {
+                    continue;
+                }
+                let app_bundle_id = self.get_app_bundle_id_for_window(wid);
+                *rect = self.virtual_workspace_manager.calculate_hidden_position_multi(
+                    screen,
+                    rect.size,
+                    self.virtual_workspace_manager.hide_corner,
+                    app_bundle_id.as_deref(),
+                    all_screens,
+                );
+            }
+        }
+
+        let scratchpad_visible = self.virtual_workspace_manager.is_scratchpad_visible(space);
+        let scratchpad_windows = self.virtual_workspace_manager.scratchpad_windows(space);
         let hidden_windows = self.virtual_workspace_manager.windows_in_inactive_workspaces(space);
         for wid in hidden_windows {
+            if self.virtual_workspace_manager.is_window_sticky(space, wid) {
+                continue;
+            }
             let original_frame = get_window_frame(wid);
 
             if self.floating.is_floating(wid) {
@@ -1872,13 +3178,18 @@ impl LayoutEngine {
                 }
             }
 
+            if scratchpad_visible && scratchpad_windows.contains(&wid) {
+                positions.insert(wid, center_rect(window_size(wid)));
+                continue;
+            }
+
             let original_size =
                 original_frame.map(|f| f.size).unwrap_or_else(|| CGSize::new(500.0, 500.0));
             let app_bundle_id = self.get_app_bundle_id_for_window(wid);
             let hidden_rect = self.virtual_workspace_manager.calculate_hidden_position_multi(
                 screen,
                 original_size,
-                HideCorner::BottomRight,
+                self.virtual_workspace_manager.hide_corner,
                 app_bundle_id.as_deref(),
                 all_screens,
             );
@@ -1888,6 +3199,20 @@ impl LayoutEngine {
         positions.into_iter().collect()
     }
 
+    /// Nodes from the root of the active layout on `space` down to its selection.
+    /// Empty for layout modes that are not tree based.
+    pub fn selection_path(&self, space: SpaceId) -> Vec<SelectionPathEntry> {
+        let Some((ws_id, layout_id)) = self.workspace_and_layout(space) else {
+            return Vec::new();
+        };
+        match self.workspace_tree(ws_id) {
+            LayoutSystemKind::Traditional(s) => s.selection_path(layout_id),
+            LayoutSystemKind::Stack(s) => s.selection_path(layout_id),
+            LayoutSystemKind::MasterStack(s) => s.selection_path(layout_id),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn collect_group_containers_in_selection_path(
         &mut self,
         space: SpaceId,
@@ -1908,6 +3233,38 @@ impl LayoutEngine {
         )
     }
 
+    pub fn fullscreen_windows_in_active_workspace(&self, space: SpaceId) -> Vec<WindowId> {
+        let Some((ws_id, layout_id)) = self.workspace_and_layout(space) else {
+            return vec![];
+        };
+        self.workspace_tree(ws_id).fullscreen_windows(layout_id)
+    }
+
+    /// Tiled windows of the active workspace on `space` that `inactive_dim`
+    /// applies to: everything but `focused`, floating and fullscreen windows.
+    pub fn dimmable_windows_in_active_workspace(
+        &self,
+        space: SpaceId,
+        focused: Option<WindowId>,
+    ) -> Vec<WindowId> {
+        let fullscreen = self.fullscreen_windows_in_active_workspace(space);
+        self.windows_in_active_workspace(space)
+            .into_iter()
+            .filter(|&wid| {
+                Some(wid) != focused && !self.is_window_floating(wid) && !fullscreen.contains(&wid)
+            })
+            .collect()
+    }
+
+    /// The window `TrueFullscreen` shows edge to edge on `space`, if it is in
+    /// the active workspace.
+    pub fn true_fullscreen_window(&self, space: SpaceId) -> Option<WindowId> {
+        let wid = *self.true_fullscreen.get(&space)?;
+        let active = self.virtual_workspace_manager.active_workspace(space)?;
+        (self.virtual_workspace_manager.workspace_for_window(space, wid) == Some(active))
+            .then_some(wid)
+    }
+
     pub fn active_workspace_for_space_has_fullscreen(&mut self, space: SpaceId) -> bool {
         let Some((ws_id, layout_id)) = self.workspace_and_layout(space) else {
             return false;
@@ -1951,10 +3308,10 @@ impl LayoutEngine {
             let tiled_positions = self.workspace_tree(workspace_id).calculate_layout(
                 layout,
                 screen,
-                self.layout_settings.stack.stack_offset,
+                self.stack_offset_for_space(space),
                 &self.window_layout_constraints,
-                gaps,
-                stack_line_thickness,
+                &self.smart_gaps_for_layout(space, workspace_id, layout, screen, gaps),
+                self.stack_line_thickness_for_space(space, stack_line_thickness),
                 stack_line_horiz,
                 stack_line_vert,
             );
@@ -2008,18 +3365,181 @@ impl LayoutEngine {
         }
     }
 
-    pub fn load(_path: PathBuf) -> anyhow::Result<Self> {
-        Ok(Self::new(
-            &VirtualWorkspaceSettings::default(),
-            &LayoutSettings::default(),
-            None,
-        ))
+    /// Load saved layout state from `path`. A missing, unreadable or
+    /// incompatible file is logged and yields a fresh engine instead of an error.
+    pub fn load(path: PathBuf) -> anyhow::Result<Self> {
+        let fresh = || {
+            Self::new(
+                &VirtualWorkspaceSettings::default(),
+                &LayoutSettings::default(),
+                None,
+            )
+        };
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(fresh()),
+            Err(e) => {
+                warn!(
+                    "Could not read layout state from {}: {e}; starting fresh",
+                    path.display()
+                );
+                return Ok(fresh());
+            }
+        };
+        match Self::migrate(&contents) {
+            Ok(engine) => Ok(engine),
+            Err(e) => {
+                warn!(
+                    "Discarding layout state from {}: {e}; starting fresh",
+                    path.display()
+                );
+                Ok(fresh())
+            }
+        }
+    }
+
+    /// Parse saved layout state of any known version into the current shape.
+    fn migrate(contents: &str) -> anyhow::Result<Self> {
+        // Version 0 predates the envelope and is a bare serialized engine.
+        let version = ron::from_str::<SavedLayoutVersion>(contents).map_or(0, |v| v.version);
+        match version {
+            0 => Ok(ron::from_str::<LayoutEngine>(contents)?),
+            LAYOUT_STATE_VERSION => Ok(ron::from_str::<SavedLayoutState>(contents)?.engine),
+            newer => anyhow::bail!(
+                "layout state version {newer} is newer than supported version {LAYOUT_STATE_VERSION}"
+            ),
+        }
     }
 
-    pub fn save(&self, _path: PathBuf) -> std::io::Result<()> { Ok(()) }
+    pub fn save(&self, path: PathBuf) -> std::io::Result<()> {
+        let state = SavedLayoutStateRef {
+            version: LAYOUT_STATE_VERSION,
+            engine: self,
+        };
+        let contents = ron::ser::to_string(&state).map_err(std::io::Error::other)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)
+    }
 
     pub fn serialize_to_string(&self) -> String { ron::ser::to_string(&self).unwrap() }
 
+    /// Write the workspaces of `space`, with their layout trees, to `path` as a
+    /// profile [`Self::load_profile`] can restore.
+    pub fn save_profile(&mut self, space: SpaceId, path: &Path) -> anyhow::Result<()> {
+        let workspaces = self.virtual_workspace_manager.list_workspaces(space);
+        if workspaces.is_empty() {
+            anyhow::bail!("space {space:?} has no workspaces");
+        }
+        let active = self.virtual_workspace_manager.active_workspace(space);
+        let profile = SavedProfileRef {
+            version: LAYOUT_STATE_VERSION,
+            active_index: workspaces.iter().position(|&(id, _)| Some(id) == active),
+            workspaces: workspaces
+                .iter()
+                .map(|&(id, _)| SavedProfileWorkspaceRef {
+                    workspace: &self.virtual_workspace_manager.workspaces[id],
+                    layouts: self.workspace_layouts.info(space, id),
+                })
+                .collect(),
+        };
+        let contents = ron::ser::to_string(&profile)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Restore the workspaces of `space` from a profile written by
+    /// [`Self::save_profile`], replacing the current ones in order. Saved
+    /// windows that are no longer on the space are dropped, and windows the
+    /// profile doesn't know stay on the workspace they are on.
+    pub fn load_profile(&mut self, space: SpaceId, path: &Path) -> anyhow::Result<()> {
+        let profile: SavedProfile = ron::from_str(&std::fs::read_to_string(path)?)?;
+        if profile.version > LAYOUT_STATE_VERSION {
+            anyhow::bail!(
+                "profile version {} is newer than supported version {LAYOUT_STATE_VERSION}",
+                profile.version
+            );
+        }
+        let live: Vec<VirtualWorkspaceId> = self
+            .virtual_workspace_manager
+            .list_workspaces(space)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        if live.is_empty() {
+            anyhow::bail!("space {space:?} has no workspaces");
+        }
+        let present: HashSet<WindowId> = self
+            .virtual_workspace_manager
+            .window_to_workspace
+            .iter()
+            .filter(|&(&(window_space, _), workspace)| {
+                window_space == space && live.contains(workspace)
+            })
+            .map(|(&(_, wid), _)| wid)
+            .collect();
+
+        // Saved trees bring their own copy of each tiled window.
+        for saved in &profile.workspaces {
+            for wid in saved.workspace.windows().filter(|wid| present.contains(wid)) {
+                if !self.floating.is_floating(wid) {
+                    self.remove_window_from_all_tiling_trees(wid);
+                }
+            }
+        }
+        for (&workspace_id, saved) in live.iter().zip(profile.workspaces) {
+            self.virtual_workspace_manager.restore_workspace(
+                workspace_id,
+                saved.workspace,
+                &present,
+            );
+            self.workspace_layouts.set_info(space, workspace_id, saved.layouts);
+            self.monocle.remove(&workspace_id);
+        }
+
+        for &wid in &present {
+            let Some(workspace_id) =
+                self.virtual_workspace_manager.workspace_for_window(space, wid)
+            else {
+                continue;
+            };
+            if !self.virtual_workspace_manager.workspaces[workspace_id].contains_window(wid) {
+                self.virtual_workspace_manager
+                    .assign_window_to_workspace(space, wid, workspace_id);
+            }
+            if self.floating.is_floating(wid) {
+                self.workspace_tree_mut(workspace_id).remove_window(wid);
+                continue;
+            }
+            if self.workspace_layouts.active(space, workspace_id).is_none() {
+                let tree =
+                    &mut self.virtual_workspace_manager.workspaces[workspace_id].layout_system;
+                self.workspace_layouts.ensure_active_for_workspace(
+                    space,
+                    CGSize::new(1000.0, 1000.0),
+                    workspace_id,
+                    tree,
+                );
+            }
+            let Some(layout) = self.workspace_layouts.active(space, workspace_id) else {
+                continue;
+            };
+            let tree = self.workspace_tree_mut(workspace_id);
+            if !tree.contains_window(layout, wid) {
+                tree.add_window_after_selection(layout, wid);
+            }
+        }
+
+        if let Some(&workspace_id) = profile.active_index.and_then(|index| live.get(index)) {
+            self.virtual_workspace_manager.set_active_workspace(space, workspace_id);
+        }
+        Ok(())
+    }
+
     #[cfg(test)]
     pub(crate) fn selected_window(&mut self, space: SpaceId) -> Option<WindowId> {
         let (ws_id, layout) = self.workspace_and_layout(space)?;
@@ -2046,6 +3566,7 @@ impl LayoutEngine {
                         self.update_active_floating_windows(space);
 
                         self.broadcast_workspace_changed(space);
+                        self.broadcast_workspace_activated(space);
                         self.broadcast_windows_changed(space);
 
                         return self.refocus_workspace(space, next_workspace);
@@ -2067,6 +3588,7 @@ impl LayoutEngine {
                         self.update_active_floating_windows(space);
 
                         self.broadcast_workspace_changed(space);
+                        self.broadcast_workspace_activated(space);
                         self.broadcast_windows_changed(space);
 
                         return self.refocus_workspace(space, prev_workspace);
@@ -2077,35 +3599,36 @@ impl LayoutEngine {
             LayoutCommand::SwitchToWorkspace(workspace_index) => {
                 let workspaces = self.virtual_workspace_manager_mut().list_workspaces(space);
                 if let Some((workspace_id, _)) = workspaces.get(*workspace_index) {
-                    let workspace_id = *workspace_id;
-                    if self.virtual_workspace_manager.active_workspace(space) == Some(workspace_id)
-                    {
-                        // Check if workspace_auto_back_and_forth is enabled
-                        if self.virtual_workspace_manager.workspace_auto_back_and_forth() {
-                            // Switch to last workspace instead
-                            if let Some(last_workspace) =
-                                self.virtual_workspace_manager.last_workspace(space)
-                            {
-                                self.virtual_workspace_manager
-                                    .set_active_workspace(space, last_workspace);
-                                self.update_active_floating_windows(space);
-                                self.broadcast_workspace_changed(space);
-                                self.broadcast_windows_changed(space);
-                                return self.refocus_workspace(space, last_workspace);
+                    return self.switch_to_workspace(space, *workspace_id);
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::SwitchToWorkspaceNamed { name } => {
+                let workspaces = self.virtual_workspace_manager_mut().list_workspaces(space);
+                let existing = workspaces
+                    .iter()
+                    .find(|(_, ws_name)| ws_name == name)
+                    .map(|(workspace_id, _)| *workspace_id);
+                let workspace_id = match existing {
+                    Some(workspace_id) => workspace_id,
+                    None if self.virtual_workspace_manager.create_on_switch => {
+                        match self
+                            .virtual_workspace_manager
+                            .create_workspace(space, Some(name.clone()))
+                        {
+                            Ok(workspace_id) => workspace_id,
+                            Err(e) => {
+                                warn!("Failed to create workspace {:?}: {:?}", name, e);
+                                return EventResponse::default();
                             }
                         }
+                    }
+                    None => {
+                        warn!("No workspace named {:?} on space {:?}", name, space);
                         return EventResponse::default();
                     }
-                    self.virtual_workspace_manager.set_active_workspace(space, workspace_id);
-
-                    self.update_active_floating_windows(space);
-
-                    self.broadcast_workspace_changed(space);
-                    self.broadcast_windows_changed(space);
-
-                    return self.refocus_workspace(space, workspace_id);
-                }
-                EventResponse::default()
+                };
+                self.switch_to_workspace(space, workspace_id)
             }
             LayoutCommand::MoveWindowToWorkspace {
                 workspace: workspace_index,
@@ -2134,92 +3657,18 @@ impl LayoutEngine {
                 let Some((target_workspace_id, _)) = workspaces.get(*workspace_index) else {
                     return EventResponse::default();
                 };
-                let target_workspace_id = *target_workspace_id;
-
-                let Some(current_workspace_id) =
-                    self.virtual_workspace_manager.workspace_for_window(op_space, focused_window)
-                else {
+                self.move_window_to_workspace_id(op_space, focused_window, *target_workspace_id)
+            }
+            LayoutCommand::MoveWindowToLastWorkspace => {
+                let Some(window_id) = self.focused_window else {
                     return EventResponse::default();
                 };
-
-                if current_workspace_id == target_workspace_id {
-                    return EventResponse::default();
-                }
-
-                let is_floating = self.floating.is_floating(focused_window);
-
-                if is_floating {
-                    self.floating.remove_active_for_window(focused_window);
-                } else {
-                    self.remove_window_from_all_tiling_trees(focused_window);
-                }
-
-                let assigned = self.virtual_workspace_manager.assign_window_to_workspace(
-                    op_space,
-                    focused_window,
-                    target_workspace_id,
-                );
-                if !assigned {
-                    if is_floating {
-                        self.floating.add_active(op_space, focused_window.pid, focused_window);
-                    } else if let Some(prev_layout) =
-                        self.workspace_layouts.active(op_space, current_workspace_id)
-                    {
-                        self.workspace_tree_mut(current_workspace_id)
-                            .add_window_after_selection(prev_layout, focused_window);
-                    }
+                let op_space = self.space_with_window(window_id).unwrap_or(space);
+                let Some(last_workspace) = self.virtual_workspace_manager.last_workspace(op_space)
+                else {
                     return EventResponse::default();
-                }
-
-                if !is_floating {
-                    if let Some(target_layout) =
-                        self.workspace_layouts.active(op_space, target_workspace_id)
-                    {
-                        self.workspace_tree_mut(target_workspace_id)
-                            .add_window_after_selection(target_layout, focused_window);
-                    }
-                }
-
-                let active_workspace = self.virtual_workspace_manager.active_workspace(op_space);
-
-                if Some(target_workspace_id) == active_workspace {
-                    if is_floating {
-                        self.floating.add_active(op_space, focused_window.pid, focused_window);
-                    }
-                    self.broadcast_windows_changed(op_space);
-                    return EventResponse {
-                        focus_window: Some(focused_window),
-                        raise_windows: vec![],
-                        boundary_hit: None,
-                    };
-                } else if Some(current_workspace_id) == active_workspace {
-                    self.focused_window = None;
-                    self.virtual_workspace_manager.set_last_focused_window(
-                        op_space,
-                        current_workspace_id,
-                        None,
-                    );
-
-                    let remaining_windows =
-                        self.virtual_workspace_manager.windows_in_active_workspace(op_space);
-                    if let Some(&new_focus) = remaining_windows.first() {
-                        self.broadcast_windows_changed(op_space);
-                        return EventResponse {
-                            focus_window: Some(new_focus),
-                            raise_windows: vec![],
-                            boundary_hit: None,
-                        };
-                    }
-                }
-
-                self.virtual_workspace_manager.set_last_focused_window(
-                    op_space,
-                    target_workspace_id,
-                    Some(focused_window),
-                );
-
-                self.broadcast_windows_changed(op_space);
-                EventResponse::default()
+                };
+                self.move_window_to_workspace_id(op_space, window_id, last_workspace)
             }
             LayoutCommand::CreateWorkspace => {
                 match self.virtual_workspace_manager.create_workspace(space, None) {
@@ -2232,6 +3681,66 @@ impl LayoutEngine {
                 }
                 EventResponse::default()
             }
+            LayoutCommand::EnsureWorkspaces { count } => {
+                let mut created = false;
+                while self.virtual_workspace_manager.list_workspaces(space).len() < *count {
+                    if let Err(e) = self.virtual_workspace_manager.create_workspace(space, None) {
+                        warn!("Stopped creating workspaces: {:?}", e);
+                        break;
+                    }
+                    created = true;
+                }
+                if created {
+                    self.broadcast_workspace_changed(space);
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::GatherAppWindows => {
+                let (Some(focused), Some(active)) = (
+                    self.focused_window,
+                    self.virtual_workspace_manager.active_workspace(space),
+                ) else {
+                    return EventResponse::default();
+                };
+                let vwm = &self.virtual_workspace_manager;
+                let mut gathered: Vec<WindowId> = vwm
+                    .window_to_workspace
+                    .iter()
+                    .filter(|&(&(window_space, wid), &workspace_id)| {
+                        window_space == space
+                            && wid.pid == focused.pid
+                            && workspace_id != active
+                            && !vwm.is_scratchpad_workspace(workspace_id)
+                    })
+                    .map(|(&(_, wid), _)| wid)
+                    .collect();
+                if gathered.is_empty() {
+                    return EventResponse::default();
+                }
+                gathered.sort_unstable();
+                for &wid in &gathered {
+                    let _ = self.move_window_to_workspace_id(space, wid, active);
+                }
+                self.rebalance_all_layouts();
+                EventResponse {
+                    raise_windows: gathered,
+                    focus_window: Some(focused),
+                    boundary_hit: None,
+                }
+            }
+            LayoutCommand::DeleteWorkspace { index, force } => {
+                self.delete_workspace(space, *index, *force)
+            }
+            LayoutCommand::MoveWorkspace { from, to } => {
+                let previous = self.virtual_workspace_manager.list_workspaces(space);
+                if !self.virtual_workspace_manager.move_workspace(space, *from, *to) {
+                    warn!("Cannot move workspace {} to {}: index out of range", from, to);
+                    return EventResponse::default();
+                }
+                self.reapply_workspace_layout_rules_after_move(space, &previous);
+                self.broadcast_workspace_changed(space);
+                EventResponse::default()
+            }
             LayoutCommand::SwitchToLastWorkspace => {
                 if let Some(last_workspace) = self.virtual_workspace_manager.last_workspace(space) {
                     self.virtual_workspace_manager.set_active_workspace(space, last_workspace);
@@ -2239,6 +3748,7 @@ impl LayoutEngine {
                     self.update_active_floating_windows(space);
 
                     self.broadcast_workspace_changed(space);
+                    self.broadcast_workspace_activated(space);
                     self.broadcast_windows_changed(space);
 
                     return self.refocus_workspace(space, last_workspace);
@@ -2443,13 +3953,215 @@ impl LayoutEngine {
         }
     }
 
-    pub fn workspace_name(
-        &self,
-        space: SpaceId,
-        workspace_id: crate::model::VirtualWorkspaceId,
-    ) -> Option<String> {
-        self.virtual_workspace_manager
-            .workspace_info(space, workspace_id)
+    /// Resize the focused floating window to the next size preset, centered on
+    /// `screen`. Tiled windows are left alone.
+    pub fn cycle_floating_preset(&mut self, space: SpaceId, screen: CGRect) -> EventResponse {
+        let Some(wid) = self.focused_window.filter(|wid| self.floating.is_floating(*wid)) else {
+            return EventResponse::default();
+        };
+        let Some(ws_id) = self.virtual_workspace_manager.workspace_for_window(space, wid) else {
+            return EventResponse::default();
+        };
+        let presets = &self.layout_settings.floating.presets;
+        if presets.is_empty() {
+            return EventResponse::default();
+        }
+
+        let idx = self.floating_preset_index.get(&wid).map_or(0, |idx| (idx + 1) % presets.len());
+        let preset = presets[idx];
+        self.floating_preset_index.insert(wid, idx);
+
+        let size = CGSize::new(
+            screen.size.width * preset.width,
+            screen.size.height * preset.height,
+        );
+        let center = screen.mid();
+        let rect = CGRect::new(
+            CGPoint::new(center.x - size.width / 2.0, center.y - size.height / 2.0),
+            size,
+        );
+        self.virtual_workspace_manager.store_floating_position(space, ws_id, wid, rect);
+
+        EventResponse {
+            raise_windows: vec![wid],
+            focus_window: None,
+            boundary_hit: None,
+        }
+    }
+
+    /// Float the focused window and dock it to the `direction` edge of
+    /// `screen`, spanning that edge's full length.
+    pub fn dock_floating_to_edge(
+        &mut self,
+        space: SpaceId,
+        screen: CGRect,
+        direction: Direction,
+    ) -> EventResponse {
+        let Some(wid) = self.focused_window else {
+            return EventResponse::default();
+        };
+        let Some(ws_id) = self.virtual_workspace_manager.workspace_for_window(space, wid) else {
+            return EventResponse::default();
+        };
+        self.float_window(space, wid);
+        self.floating.set_last_focus(Some(wid));
+
+        let thickness = self.layout_settings_for_space(space).floating.dock_thickness.max(1.0);
+        let width = thickness.min(screen.size.width);
+        let height = thickness.min(screen.size.height);
+        let rect = match direction {
+            Direction::Left => CGRect::new(screen.origin, CGSize::new(width, screen.size.height)),
+            Direction::Right => CGRect::new(
+                CGPoint::new(screen.max().x - width, screen.origin.y),
+                CGSize::new(width, screen.size.height),
+            ),
+            Direction::Up => CGRect::new(screen.origin, CGSize::new(screen.size.width, height)),
+            Direction::Down => CGRect::new(
+                CGPoint::new(screen.origin.x, screen.max().y - height),
+                CGSize::new(screen.size.width, height),
+            ),
+        };
+        self.virtual_workspace_manager.store_floating_position(space, ws_id, wid, rect);
+
+        EventResponse {
+            raise_windows: vec![wid],
+            focus_window: None,
+            boundary_hit: None,
+        }
+    }
+
+    pub fn window_visibility(&self, space: SpaceId) -> WindowVisibilityMode {
+        self.window_visibility.get(&space).copied().unwrap_or_default()
+    }
+
+    /// Advance the space through all / tiled-only / floating-only and move focus
+    /// into the category that stays visible.
+    pub fn cycle_window_visibility(&mut self, space: SpaceId) -> EventResponse {
+        let mode = self.window_visibility(space).next();
+        if mode == WindowVisibilityMode::All {
+            self.window_visibility.remove(&space);
+        } else {
+            self.window_visibility.insert(space, mode);
+        }
+
+        let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
+            return EventResponse::default();
+        };
+        let focus_window = match mode {
+            WindowVisibilityMode::All => None,
+            WindowVisibilityMode::TiledOnly => self.workspace_tree(ws_id).selected_window(layout),
+            WindowVisibilityMode::FloatingOnly => {
+                let floating = self.active_floating_windows_in_workspace(space);
+                self.floating
+                    .last_focus()
+                    .filter(|wid| floating.contains(wid))
+                    .or_else(|| floating.last().copied())
+            }
+        };
+        let response = EventResponse {
+            raise_windows: vec![],
+            focus_window,
+            boundary_hit: None,
+        };
+        self.apply_focus_response(space, ws_id, layout, &response);
+        response
+    }
+
+    /// Move `window_id` so it becomes the immediate `direction` neighbor of
+    /// `target` in the target's workspace, pulling it out of its current
+    /// workspace (or the floating layer) first.
+    pub fn move_window_beside(
+        &mut self,
+        window_id: WindowId,
+        target: WindowId,
+        direction: Direction,
+    ) -> EventResponse {
+        if window_id == target || self.floating.is_floating(target) {
+            return EventResponse::default();
+        }
+        let Some(target_workspace_id) =
+            self.virtual_workspace_manager.workspace_for_window_any(target)
+        else {
+            return EventResponse::default();
+        };
+        let Some(target_space) = self
+            .virtual_workspace_manager
+            .workspaces
+            .get(target_workspace_id)
+            .map(|ws| ws.space)
+        else {
+            return EventResponse::default();
+        };
+        let Some(target_layout) = self.workspace_layouts.active(target_space, target_workspace_id)
+        else {
+            return EventResponse::default();
+        };
+        if !self.workspace_tree(target_workspace_id).contains_window(target_layout, target) {
+            return EventResponse::default();
+        }
+
+        let source_space = self
+            .virtual_workspace_manager
+            .workspace_for_window_any(window_id)
+            .and_then(|ws_id| self.virtual_workspace_manager.workspaces.get(ws_id))
+            .map(|ws| ws.space);
+        if !self.virtual_workspace_manager.assign_window_to_workspace(
+            target_space,
+            window_id,
+            target_workspace_id,
+        ) {
+            return EventResponse::default();
+        }
+
+        if self.floating.is_floating(window_id) {
+            self.floating.remove_active_for_window(window_id);
+            self.floating.remove_floating(window_id);
+        } else {
+            self.remove_window_from_all_tiling_trees(window_id);
+        }
+
+        self.workspace_layouts
+            .mark_last_saved(target_space, target_workspace_id, target_layout);
+        let tree = self.workspace_tree_mut(target_workspace_id);
+        if !tree.insert_window_beside(target_layout, window_id, target, direction) {
+            tree.add_window_after_selection(target_layout, window_id);
+        }
+
+        self.virtual_workspace_manager.set_last_focused_window(
+            target_space,
+            target_workspace_id,
+            Some(window_id),
+        );
+        if let Some(source_space) = source_space
+            && source_space != target_space
+        {
+            self.broadcast_windows_changed(source_space);
+        }
+        self.broadcast_windows_changed(target_space);
+
+        if self.virtual_workspace_manager.active_workspace(target_space)
+            == Some(target_workspace_id)
+        {
+            EventResponse {
+                raise_windows: vec![window_id],
+                focus_window: Some(window_id),
+                boundary_hit: None,
+            }
+        } else {
+            if self.focused_window == Some(window_id) {
+                self.focused_window = None;
+            }
+            EventResponse::default()
+        }
+    }
+
+    pub fn workspace_name(
+        &self,
+        space: SpaceId,
+        workspace_id: crate::model::VirtualWorkspaceId,
+    ) -> Option<String> {
+        self.virtual_workspace_manager
+            .workspace_info(space, workspace_id)
             .map(|ws| ws.name.clone())
     }
 
@@ -2465,12 +4177,109 @@ impl LayoutEngine {
         self.floating.is_floating(window_id)
     }
 
+    pub fn focused_window(&self) -> Option<WindowId> { self.focused_window }
+
+    pub fn clear_focused_window(&mut self) { self.focused_window = None; }
+
+    /// The window among `windows` that was focused most recently, if any of
+    /// them has been focused.
+    pub fn most_recently_focused(
+        &self,
+        windows: impl IntoIterator<Item = WindowId>,
+    ) -> Option<WindowId> {
+        windows
+            .into_iter()
+            .filter_map(|wid| self.focus_order.get(&wid).map(|order| (*order, wid)))
+            .max_by_key(|(order, _)| *order)
+            .map(|(_, wid)| wid)
+    }
+
     fn update_active_floating_windows(&mut self, space: SpaceId) {
+        // Every workspace switch comes through here, so sticky windows are
+        // carried over before the floating set is rebuilt.
+        self.carry_sticky_windows(space);
         let windows_in_workspace =
             self.virtual_workspace_manager.windows_in_active_workspace(space);
         self.floating.rebuild_active_for_workspace(space, windows_in_workspace);
     }
 
+    /// Float `wid` and make it sticky on `space`, for windows macOS shows on
+    /// every space.
+    pub fn float_sticky_window(&mut self, space: SpaceId, wid: WindowId) {
+        if !self.virtual_workspace_manager.is_window_sticky(space, wid) {
+            self.virtual_workspace_manager.toggle_window_sticky(space, wid);
+        }
+        self.float_window(space, wid);
+    }
+
+    /// Float `wid` and store `rect` as its position in its workspace on
+    /// `space`. Returns false if the window has no workspace there.
+    pub fn place_floating_window(&mut self, space: SpaceId, wid: WindowId, rect: CGRect) -> bool {
+        let Some(ws_id) = self.virtual_workspace_manager.workspace_for_window(space, wid) else {
+            return false;
+        };
+        self.float_window(space, wid);
+        self.virtual_workspace_manager.store_floating_position(space, ws_id, wid, rect);
+        true
+    }
+
+    fn float_window(&mut self, space: SpaceId, wid: WindowId) {
+        if !self.floating.is_floating(wid) {
+            self.remove_window_from_all_tiling_trees(wid);
+            self.floating.add_floating(wid);
+        }
+        self.update_active_floating_windows(space);
+    }
+
+    /// Move the sticky windows of `space` into its active workspace, keeping
+    /// the tiled selection and each floating window's position.
+    fn carry_sticky_windows(&mut self, space: SpaceId) {
+        let Some(active) = self.virtual_workspace_manager.active_workspace(space) else {
+            return;
+        };
+        for wid in self.virtual_workspace_manager.sticky_windows(space) {
+            let Some(current) = self.virtual_workspace_manager.workspace_for_window(space, wid)
+            else {
+                continue;
+            };
+            if current == active {
+                continue;
+            }
+            let is_floating = self.floating.is_floating(wid);
+            let floating_position = self
+                .virtual_workspace_manager
+                .get_workspace_floating_positions(space, current)
+                .into_iter()
+                .find_map(|(w, rect)| (w == wid).then_some(rect));
+
+            if !is_floating {
+                self.remove_window_from_all_tiling_trees(wid);
+            }
+            if !self.virtual_workspace_manager.assign_window_to_workspace(space, wid, active) {
+                warn!(?wid, "Failed to carry sticky window to the active workspace");
+                if !is_floating && let Some(layout) = self.workspace_layouts.active(space, current)
+                {
+                    self.workspace_tree_mut(current).add_window_after_selection(layout, wid);
+                }
+                continue;
+            }
+
+            if is_floating {
+                if let Some(rect) = floating_position {
+                    self.virtual_workspace_manager
+                        .store_floating_position(space, active, wid, rect);
+                }
+            } else if let Some(layout) = self.workspace_layouts.active(space, active) {
+                let tree = self.workspace_tree_mut(active);
+                let selected = tree.selected_window(layout);
+                tree.add_window_after_selection(layout, wid);
+                if let Some(selected) = selected {
+                    let _ = tree.select_window(layout, selected);
+                }
+            }
+        }
+    }
+
     pub fn store_floating_window_positions(
         &mut self,
         space: SpaceId,
@@ -2480,6 +4289,13 @@ impl LayoutEngine {
             .store_current_floating_positions(space, floating_positions);
     }
 
+    /// Re-send the active workspace and its windows for `space_id`, so
+    /// subscribers that connected late can catch up.
+    pub fn broadcast_workspace_state(&self, space_id: SpaceId) {
+        self.broadcast_workspace_changed(space_id);
+        self.broadcast_windows_changed(space_id);
+    }
+
     fn broadcast_workspace_changed(&self, space_id: SpaceId) {
         if let Some(ref broadcast_tx) = self.broadcast_tx {
             if let Some((active_workspace_id, active_workspace_name)) =
@@ -2496,6 +4312,23 @@ impl LayoutEngine {
         }
     }
 
+    fn broadcast_workspace_activated(&self, space_id: SpaceId) {
+        let Some(ref broadcast_tx) = self.broadcast_tx else {
+            return;
+        };
+        let Some((workspace_id, workspace_name)) = self.active_workspace_id_and_name(space_id)
+        else {
+            return;
+        };
+        let _ = broadcast_tx.send(BroadcastEvent::WorkspaceActivated {
+            space_id,
+            display_uuid: self.display_uuid_for_space(space_id),
+            workspace_id,
+            workspace_index: self.active_workspace_idx(space_id),
+            workspace_name,
+        });
+    }
+
     fn broadcast_windows_changed(&self, space_id: SpaceId) {
         if let Some(ref broadcast_tx) = self.broadcast_tx {
             if let Some((workspace_id, workspace_name)) =
@@ -2522,6 +4355,28 @@ impl LayoutEngine {
         }
     }
 
+    /// Broadcast a `WindowFullscreenChanged` for every window whose fullscreen state
+    /// differs between `before` and `after`.
+    fn broadcast_fullscreen_changes(
+        &self,
+        space_id: SpaceId,
+        before: &[WindowId],
+        after: &[WindowId],
+    ) {
+        let Some(ref broadcast_tx) = self.broadcast_tx else {
+            return;
+        };
+        let entered = after.iter().filter(|wid| !before.contains(wid)).map(|wid| (*wid, true));
+        let exited = before.iter().filter(|wid| !after.contains(wid)).map(|wid| (*wid, false));
+        for (window_id, fullscreen) in entered.chain(exited) {
+            let _ = broadcast_tx.send(BroadcastEvent::WindowFullscreenChanged {
+                window_id,
+                space_id,
+                fullscreen,
+            });
+        }
+    }
+
     pub fn debug_log_workspace_stats(&self) {
         let stats = self.virtual_workspace_manager.get_stats();
         info!(
@@ -2654,6 +4509,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn resize_group_resizes_like_resize_window_by() {
+        let space = SpaceId::new(103);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let window_info = |wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None);
+        let resized = |command| {
+            let mut engine = test_engine();
+            let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+            let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+                space,
+                1,
+                vec![
+                    window_info(WindowId::new(1, 1)),
+                    window_info(WindowId::new(1, 2)),
+                ],
+                None,
+            ));
+            let _ = engine.handle_command(Some(space), &[space], &HashMap::default(), command);
+            let mut frames = engine.calculate_layout(
+                space,
+                screen,
+                &LayoutSettings::default().gaps,
+                0.0,
+                Default::default(),
+                Default::default(),
+            );
+            frames.sort_by_key(|(wid, _)| *wid);
+            frames
+        };
+
+        let by_group = resized(LayoutCommand::ResizeGroup { amount: 0.2 });
+        assert_eq!(by_group, resized(LayoutCommand::ResizeWindowBy { amount: 0.2 }));
+        assert_ne!(by_group, resized(LayoutCommand::ResizeWindowBy { amount: 0.0 }));
+    }
+
     #[test]
     fn tiled_membership_sync_does_not_rebalance_other_spaces() {
         let mut engine = test_engine();
@@ -2713,434 +4603,632 @@ mod tests {
     }
 
     #[test]
-    fn move_focus_to_uninitialized_adjacent_space_does_not_panic() {
+    fn toggle_ignore_resize_keeps_window_size() {
         let mut engine = test_engine();
-        let current_space = SpaceId::new(50);
-        let adjacent_space = SpaceId::new(51);
-        let screen_size = CGSize::new(1920.0, 1080.0);
-        let visible_spaces = vec![current_space, adjacent_space];
-        let mut visible_space_centers = HashMap::default();
-        visible_space_centers.insert(current_space, CGPoint::new(0.0, 0.0));
-        visible_space_centers.insert(adjacent_space, CGPoint::new(1920.0, 0.0));
+        let space = SpaceId::new(60);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let visible_spaces = vec![space];
+        let visible_space_centers = HashMap::default();
+        let window_a = WindowId::new(1, 1);
+        let window_b = WindowId::new(1, 2);
+        let gaps = LayoutSettings::default().gaps;
 
-        let _ = engine.handle_event(LayoutEvent::SpaceExposed(current_space, screen_size));
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            vec![
+                (
+                    window_a,
+                    None,
+                    None,
+                    None,
+                    true,
+                    CGSize::new(300.0, 200.0),
+                    None,
+                    None,
+                ),
+                (
+                    window_b,
+                    None,
+                    None,
+                    None,
+                    true,
+                    CGSize::new(0.0, 0.0),
+                    None,
+                    None,
+                ),
+            ],
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, window_a));
 
-        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+        let width_of = |engine: &mut LayoutEngine| {
+            engine
+                .calculate_layout(space, screen, &gaps, 0.0, Default::default(), Default::default())
+                .into_iter()
+                .find(|(wid, _)| *wid == window_a)
+                .map(|(_, frame)| frame.size.width)
+                .expect("window should be laid out")
+        };
+
+        assert!((width_of(&mut engine) - 500.0).abs() < 1.0);
+
+        let toggle = |engine: &mut LayoutEngine| {
             engine.handle_command(
-                Some(current_space),
+                Some(space),
                 &visible_spaces,
                 &visible_space_centers,
-                LayoutCommand::MoveFocus(Direction::Right),
+                LayoutCommand::ToggleIgnoreResize,
             )
-        }));
+        };
 
-        assert!(
-            result.is_ok(),
-            "cross-space move focus should not panic when adjacent space is not initialized"
-        );
+        let _ = toggle(&mut engine);
+        assert!(width_of(&mut engine) <= 300.0 + 0.5);
+
+        let _ = toggle(&mut engine);
+        assert!((width_of(&mut engine) - 500.0).abs() < 1.0);
     }
 
     #[test]
-    fn update_virtual_workspace_settings_reapplies_workspace_rules() {
+    fn equalize_sizes_evens_out_horizontal_split() {
         let mut engine = test_engine();
-        let space = SpaceId::new(7);
-        let workspace_list = engine.virtual_workspace_manager_mut().list_workspaces(space);
-        let (workspace_id, workspace_name) = workspace_list[0].clone();
-        assert_eq!(
-            engine
-                .virtual_workspace_manager()
-                .workspace_info(space, workspace_id)
-                .map(|ws| ws.layout_mode()),
-            Some(LayoutMode::Traditional)
-        );
-
-        let mut settings = VirtualWorkspaceSettings::default();
-        settings.workspace_rules = vec![WorkspaceLayoutRule {
-            workspace: WorkspaceSelector::Name(workspace_name),
-            layout: LayoutMode::Scrolling,
-        }];
+        let space = SpaceId::new(61);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(900.0, 800.0));
+        let visible_spaces = vec![space];
+        let visible_space_centers = HashMap::default();
+        let windows = [
+            WindowId::new(1, 1),
+            WindowId::new(1, 2),
+            WindowId::new(1, 3),
+        ];
+        let gaps = LayoutSettings::default().gaps;
 
-        engine.update_virtual_workspace_settings(&settings);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, windows[0]));
+
+        let widths = |engine: &mut LayoutEngine| {
+            let frames: HashMap<_, _> = engine
+                .calculate_layout(space, screen, &gaps, 0.0, Default::default(), Default::default())
+                .into_iter()
+                .collect();
+            windows.map(|wid| frames[&wid].size.width)
+        };
 
-        assert_eq!(
-            engine
-                .virtual_workspace_manager()
-                .workspace_info(space, workspace_id)
-                .map(|ws| ws.layout_mode()),
-            Some(LayoutMode::Scrolling)
+        let _ = engine.handle_command(
+            Some(space),
+            &visible_spaces,
+            &visible_space_centers,
+            LayoutCommand::ResizeWindowBy { amount: 0.3 },
+        );
+        let skewed = widths(&mut engine);
+        assert!(
+            (skewed[0] - skewed[1]).abs() > 1.0,
+            "resize should skew the split"
+        );
+
+        let _ = engine.handle_command(
+            Some(space),
+            &visible_spaces,
+            &visible_space_centers,
+            LayoutCommand::EqualizeSizes,
         );
+        for width in widths(&mut engine) {
+            assert!((width - 300.0).abs() < 1.0, "expected even widths, got {width}");
+        }
     }
 
     #[test]
-    fn set_workspace_layout_for_inactive_workspace_does_not_raise_active_windows() {
+    fn set_container_ratio_sets_absolute_share_of_split() {
         let mut engine = test_engine();
-        let space = SpaceId::new(8);
-        let window_id = WindowId::new(999, 1);
+        let space = SpaceId::new(62);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(900.0, 800.0));
+        let windows = [WindowId::new(1, 1), WindowId::new(1, 2)];
+        let gaps = LayoutSettings::default().gaps;
 
-        let _ = engine.virtual_workspace_manager_mut().list_workspaces(space);
-        let _ = engine.virtual_workspace_manager_mut().auto_assign_window(window_id, space);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, windows[1]));
 
-        let response =
-            engine.handle_virtual_workspace_command(space, &LayoutCommand::SetWorkspaceLayout {
-                workspace: Some(1),
-                mode: LayoutMode::Bsp,
-            });
+        let width_after = |engine: &mut LayoutEngine, ratio: f64| {
+            let _ = engine.handle_command(
+                Some(space),
+                &[space],
+                &HashMap::default(),
+                LayoutCommand::SetContainerRatio { ratio },
+            );
+            let frames: HashMap<_, _> = engine
+                .calculate_layout(space, screen, &gaps, 0.0, Default::default(), Default::default())
+                .into_iter()
+                .collect();
+            frames[&windows[1]].size.width
+        };
 
-        assert!(response.raise_windows.is_empty());
-        assert_eq!(response.focus_window, None);
+        let golden = width_after(&mut engine, 0.618);
+        assert!(
+            (golden - 556.2).abs() < 1.0,
+            "expected golden split, got {golden}"
+        );
+        // Repeating the command doesn't nudge the split any further.
+        assert!((width_after(&mut engine, 0.618) - golden).abs() < 0.01);
+        let clamped = width_after(&mut engine, 2.0);
+        assert!(
+            (clamped - 855.0).abs() < 1.0,
+            "expected clamped split, got {clamped}"
+        );
     }
 
     #[test]
-    fn move_window_to_space_detaches_window_when_source_mapping_is_stale() {
+    fn resize_window_to_skips_windows_whose_resize_is_ignored() {
         let mut engine = test_engine();
-        let source = SpaceId::new(70);
-        let target = SpaceId::new(71);
-        let screen_size = CGSize::new(1920.0, 1080.0);
-        let window_id = WindowId::new(4242, 1);
+        let space = SpaceId::new(94);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(900.0, 800.0));
+        let windows = [WindowId::new(1, 1), WindowId::new(1, 2)];
+        let gaps = LayoutSettings::default().gaps;
 
-        let _ = engine.handle_event(LayoutEvent::SpaceExposed(source, screen_size));
-        let _ = engine.handle_event(LayoutEvent::SpaceExposed(target, screen_size));
-
-        let source_workspace = engine
-            .virtual_workspace_manager()
-            .active_workspace(source)
-            .expect("source active workspace");
-        let target_workspace = engine
-            .virtual_workspace_manager()
-            .active_workspace(target)
-            .expect("target active workspace");
-        let source_layout = engine
-            .workspace_layouts
-            .active(source, source_workspace)
-            .expect("source active layout");
-        let target_layout = engine
-            .workspace_layouts
-            .active(target, target_workspace)
-            .expect("target active layout");
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, windows[1]));
+
+        let width_after = |engine: &mut LayoutEngine, command: LayoutCommand| {
+            let _ = engine.handle_command(Some(space), &[space], &HashMap::default(), command);
+            let frames: HashMap<_, _> = engine
+                .calculate_layout(space, screen, &gaps, 0.0, Default::default(), Default::default())
+                .into_iter()
+                .collect();
+            frames[&windows[1]].size.width
+        };
 
+        let resized = width_after(&mut engine, LayoutCommand::ResizeWindowTo { ratio: 0.618 });
         assert!(
-            engine.virtual_workspace_manager_mut().assign_window_to_workspace(
-                source,
-                window_id,
-                source_workspace
-            )
-        );
-        engine
-            .workspace_tree_mut(source_workspace)
-            .add_window_after_selection(source_layout, window_id);
-        assert!(
-            engine
-                .workspace_tree(source_workspace)
-                .contains_window(source_layout, window_id)
+            (resized - 556.2).abs() < 1.0,
+            "expected golden split, got {resized}"
         );
 
-        // Create an inconsistent state: workspace mapping points to target, but source tree
-        // still contains the window. Cross-space move must still detach from source tree.
+        let locked = width_after(&mut engine, LayoutCommand::ToggleIgnoreResize);
+        let after = width_after(&mut engine, LayoutCommand::ResizeWindowTo { ratio: 0.3 });
         assert!(
-            engine.virtual_workspace_manager_mut().assign_window_to_workspace(
-                target,
-                window_id,
-                target_workspace
-            )
-        );
-        assert_eq!(
-            engine.virtual_workspace_manager().workspace_for_window(source, window_id),
-            None
+            (after - locked).abs() < 0.01,
+            "locked window was resized to {after}"
         );
+    }
+
+    #[test]
+    fn reconcile_workspace_trees_follows_workspace_assignments() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(95);
+        let screen = CGSize::new(900.0, 800.0);
+        let windows = [WindowId::new(1, 1), WindowId::new(1, 2)];
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+
+        let workspaces: Vec<_> = engine
+            .virtual_workspace_manager_mut()
+            .list_workspaces(space)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        let layout_of = |engine: &LayoutEngine, ws| {
+            engine.workspace_layouts.active(space, ws).expect("active layout")
+        };
+        let (first, second) = (workspaces[0], workspaces[1]);
+        let (first_layout, second_layout) = (layout_of(&engine, first), layout_of(&engine, second));
+
+        // The assignment moves on, but the first tree keeps the window.
         assert!(
             engine
-                .workspace_tree(source_workspace)
-                .contains_window(source_layout, window_id)
+                .virtual_workspace_manager_mut()
+                .assign_window_to_workspace(space, windows[1], second)
         );
+        assert!(engine.workspace_tree(first).contains_window(first_layout, windows[1]));
 
-        let _ = engine.move_window_to_space(source, target, screen_size, window_id);
+        assert_eq!(engine.reconcile_workspace_trees(space, [1], &[]), vec![
+            windows[1]
+        ]);
+        assert!(!engine.workspace_tree(first).contains_window(first_layout, windows[1]));
+        assert!(engine.workspace_tree(second).contains_window(second_layout, windows[1]));
+        assert!(engine.workspace_tree(first).contains_window(first_layout, windows[0]));
 
-        assert!(
-            !engine
-                .workspace_tree(source_workspace)
-                .contains_window(source_layout, window_id)
-        );
+        assert!(engine.reconcile_workspace_trees(space, [1], &[]).is_empty());
+
+        // A window that went away is dropped from its tree and assignment.
+        let relocated = engine.reconcile_workspace_trees(space, [1], &[windows[0]]);
+        assert!(relocated.is_empty());
+        assert!(!engine.workspace_tree(first).contains_window(first_layout, windows[0]));
         assert!(
             engine
-                .workspace_tree(target_workspace)
-                .contains_window(target_layout, window_id)
-        );
-        assert_eq!(
-            engine.virtual_workspace_manager().workspace_for_window(target, window_id),
-            Some(target_workspace)
+                .virtual_workspace_manager
+                .workspace_for_window(space, windows[0])
+                .is_none()
         );
     }
 
     #[test]
-    fn locked_tiled_windows_stay_within_screen_bounds() {
+    fn focus_index_focuses_the_nth_visible_window() {
         let mut engine = test_engine();
-        let space = SpaceId::new(90);
-        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 800.0));
-        let pid: pid_t = 4242;
-
-        let locked = WindowId::new(pid, 100);
-        let other_a = WindowId::new(pid, 101);
-        let other_b = WindowId::new(pid, 102);
+        let space = SpaceId::new(96);
+        let windows = [
+            WindowId::new(1, 1),
+            WindowId::new(1, 2),
+            WindowId::new(1, 3),
+        ];
 
-        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1200.0, 800.0)));
         let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
             space,
-            pid,
-            vec![
-                (
-                    locked,
-                    None,
-                    None,
-                    None,
-                    false,
-                    // Intentionally impossible size for this screen; layout should still keep
-                    // tiled results bounded instead of force-applying this at the end.
-                    CGSize::new(1600.0, 900.0),
-                    None,
-                    None,
-                ),
-                (
-                    other_a,
-                    None,
-                    None,
-                    None,
-                    true,
-                    CGSize::new(600.0, 600.0),
-                    None,
-                    None,
-                ),
-                (
-                    other_b,
-                    None,
-                    None,
-                    None,
-                    true,
-                    CGSize::new(600.0, 600.0),
-                    None,
-                    None,
-                ),
-            ],
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
             None,
         ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, windows[0]));
 
-        let gaps = engine.layout_settings.gaps.effective_for_display(None);
-        let positions = engine.calculate_layout_with_virtual_workspaces(
-            space,
-            screen,
-            &gaps,
-            0.0,
-            Default::default(),
-            Default::default(),
-            |_| None,
-            &[screen],
+        let response = engine.handle_command(
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::FocusIndex { index: 2 },
         );
-        let frames: HashMap<WindowId, CGRect> = positions.into_iter().collect();
-        let locked_frame = frames
-            .get(&locked)
-            .copied()
-            .expect("locked tiled window should have a calculated frame");
+        assert_eq!(response.focus_window, Some(windows[2]));
+        assert_eq!(response.raise_windows, vec![windows[2]]);
 
-        let epsilon = 0.5;
-        let max_x = screen.origin.x + screen.size.width + epsilon;
-        let max_y = screen.origin.y + screen.size.height + epsilon;
-        assert!(locked_frame.origin.x >= screen.origin.x - epsilon);
-        assert!(locked_frame.origin.y >= screen.origin.y - epsilon);
-        assert!(locked_frame.origin.x + locked_frame.size.width <= max_x);
-        assert!(locked_frame.origin.y + locked_frame.size.height <= max_y);
+        let response = engine.handle_command(
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::FocusIndex { index: 3 },
+        );
+        assert_eq!(response.focus_window, None, "out-of-range indices are a no-op");
     }
 
     #[test]
-    fn repeated_windows_on_screen_update_does_not_rebalance_unchanged_tiled_layout() {
+    fn gather_app_windows_pulls_the_focused_app_into_the_active_workspace() {
         let mut engine = test_engine();
-        let space = SpaceId::new(91);
-        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
-        let pid: pid_t = 5150;
+        let space = SpaceId::new(98);
+        let browser = [
+            WindowId::new(1, 1),
+            WindowId::new(1, 2),
+            WindowId::new(1, 3),
+        ];
+        let other = WindowId::new(2, 1);
 
-        let windows = vec![
-            (
-                WindowId::new(pid, 1),
-                None,
-                None,
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1200.0, 800.0)));
+        for (pid, windows) in [(1, &browser[..]), (2, &[other][..])] {
+            let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+                space,
+                pid,
+                windows
+                    .iter()
+                    .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                    .collect(),
                 None,
-                true,
-                CGSize::new(500.0, 500.0),
+            ));
+        }
+        for (wid, workspace) in [(browser[1], 1), (browser[2], 2), (other, 1)] {
+            let _ = engine.handle_event(LayoutEvent::WindowFocused(space, wid));
+            let _ = engine.handle_virtual_workspace_command(
+                space,
+                &LayoutCommand::MoveWindowToWorkspace { workspace, window_id: None },
+            );
+        }
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, browser[0]));
+
+        let response =
+            engine.handle_virtual_workspace_command(space, &LayoutCommand::GatherAppWindows);
+        assert_eq!(response.raise_windows, vec![browser[1], browser[2]]);
+
+        let mut active = engine.windows_in_active_workspace(space);
+        active.sort_unstable();
+        assert_eq!(active, browser.to_vec(), "other apps' windows are left behind");
+    }
+
+    #[test]
+    fn workspace_activated_is_broadcast_only_on_switches() {
+        let (broadcast_tx, mut broadcast_rx) = crate::actor::channel();
+        let mut engine = LayoutEngine::new(
+            &VirtualWorkspaceSettings::default(),
+            &LayoutSettings::default(),
+            Some(broadcast_tx),
+        );
+        let space = SpaceId::new(100);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1000.0, 800.0)));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            vec![(
+                WindowId::new(1, 1),
                 None,
                 None,
-            ),
-            (
-                WindowId::new(pid, 2),
                 None,
+                true,
+                CGSize::new(0.0, 0.0),
                 None,
                 None,
-                true,
-                CGSize::new(500.0, 500.0),
-                None,
-                None,
-            ),
-            (
-                WindowId::new(pid, 3),
-                None,
-                None,
-                None,
-                true,
-                CGSize::new(500.0, 500.0),
-                None,
-                None,
-            ),
-        ];
-
-        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
-        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
-            space,
-            pid,
-            windows.clone(),
+            )],
             None,
         ));
-        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, WindowId::new(pid, 1)));
-        let gaps = engine.layout_settings.gaps.clone();
+        let mut activations = move || {
+            let mut activated = Vec::new();
+            while let Ok((_, event)) = broadcast_rx.try_recv() {
+                if let BroadcastEvent::WorkspaceActivated { workspace_index, .. } = event {
+                    activated.push(workspace_index);
+                }
+            }
+            activated
+        };
+        assert!(activations().is_empty());
 
-        let baseline = engine.calculate_layout(
-            space,
-            screen,
-            &gaps,
-            0.0,
-            Default::default(),
-            Default::default(),
-        );
+        let _ =
+            engine.handle_virtual_workspace_command(space, &LayoutCommand::SwitchToWorkspace(1));
+        assert_eq!(activations(), vec![Some(1)]);
 
-        let _ = engine.handle_command(
-            Some(space),
-            &[space],
-            &HashMap::default(),
-            LayoutCommand::MoveNode(Direction::Up),
+        let _ = engine.handle_virtual_workspace_command(space, &LayoutCommand::CreateWorkspace);
+        let _ =
+            engine.handle_virtual_workspace_command(space, &LayoutCommand::SwitchToWorkspace(1));
+        assert!(activations().is_empty());
+
+        let _ = engine
+            .handle_virtual_workspace_command(space, &LayoutCommand::NextWorkspace(Some(false)));
+        assert_eq!(activations(), vec![Some(2)]);
+    }
+
+    #[test]
+    fn ensure_workspaces_only_ever_adds_and_broadcasts_once() {
+        let (broadcast_tx, mut broadcast_rx) = crate::actor::channel();
+        let mut engine = LayoutEngine::new(
+            &VirtualWorkspaceSettings::default(),
+            &LayoutSettings::default(),
+            Some(broadcast_tx),
         );
+        let space = SpaceId::new(105);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1000.0, 800.0)));
+        let mut changes = move || {
+            let mut count = 0;
+            while let Ok((_, event)) = broadcast_rx.try_recv() {
+                if matches!(event, BroadcastEvent::WorkspaceChanged { .. }) {
+                    count += 1;
+                }
+            }
+            count
+        };
+        let _ = changes();
+        let initial = engine.virtual_workspace_manager_mut().list_workspaces(space).len();
 
-        let modified = engine.calculate_layout(
-            space,
-            screen,
-            &gaps,
-            0.0,
-            Default::default(),
-            Default::default(),
+        let ensure = |count| LayoutCommand::EnsureWorkspaces { count };
+        let _ = engine.handle_virtual_workspace_command(space, &ensure(initial + 2));
+        assert_eq!(
+            engine.virtual_workspace_manager_mut().list_workspaces(space).len(),
+            initial + 2
         );
-        assert_ne!(baseline, modified);
+        assert_eq!(changes(), 1);
 
-        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(space, pid, windows, None));
+        let _ = engine.handle_virtual_workspace_command(space, &ensure(1));
+        assert_eq!(
+            engine.virtual_workspace_manager_mut().list_workspaces(space).len(),
+            initial + 2
+        );
+        assert_eq!(changes(), 0);
 
+        let _ = engine.handle_virtual_workspace_command(space, &ensure(usize::MAX));
         assert_eq!(
-            engine.calculate_layout(
-                space,
-                screen,
-                &gaps,
-                0.0,
-                Default::default(),
-                Default::default(),
-            ),
-            modified
+            engine.virtual_workspace_manager_mut().list_workspaces(space).len(),
+            32
         );
     }
 
     #[test]
-    fn removing_unknown_window_does_not_rebalance_layout() {
+    fn delete_workspace_refuses_non_empty_workspaces_unless_forced() {
         let mut engine = test_engine();
-        let space = SpaceId::new(92);
-        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
-        let pid: pid_t = 5151;
+        let space = SpaceId::new(106);
+        let wid = WindowId::new(1, 1);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1000.0, 800.0)));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            vec![(wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None)],
+            None,
+        ));
+        let ids = |engine: &mut LayoutEngine| -> Vec<_> {
+            engine
+                .virtual_workspace_manager_mut()
+                .list_workspaces(space)
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect()
+        };
+        let before = ids(&mut engine);
+        assert!(before.len() >= 3);
+        assert_eq!(engine.active_workspace(space), Some(before[0]));
+
+        let delete = |index, force| LayoutCommand::DeleteWorkspace { index, force };
+        let _ = engine.handle_virtual_workspace_command(space, &delete(2, false));
+        let after = ids(&mut engine);
+        assert_eq!(after.len(), before.len() - 1);
+        assert!(!after.contains(&before[2]));
+
+        let _ = engine.handle_virtual_workspace_command(space, &delete(0, false));
+        assert_eq!(ids(&mut engine), after);
+
+        let _ = engine.handle_virtual_workspace_command(space, &delete(0, true));
+        assert_eq!(ids(&mut engine), after[1..]);
+        assert_eq!(engine.active_workspace(space), Some(before[1]));
+        let vwm = engine.virtual_workspace_manager();
+        assert_eq!(vwm.workspace_for_window(space, wid), Some(before[1]));
+        assert_eq!(engine.windows_in_active_workspace(space), vec![wid]);
+    }
 
-        let windows = vec![
-            (
-                WindowId::new(pid, 1),
-                None,
-                None,
-                None,
-                true,
-                CGSize::new(500.0, 500.0),
-                None,
-                None,
-            ),
-            (
-                WindowId::new(pid, 2),
-                None,
-                None,
-                None,
-                true,
-                CGSize::new(500.0, 500.0),
-                None,
-                None,
-            ),
-            (
-                WindowId::new(pid, 3),
-                None,
-                None,
-                None,
-                true,
-                CGSize::new(500.0, 500.0),
-                None,
-                None,
-            ),
-        ];
+    #[test]
+    fn move_workspace_reorders_and_keeps_the_active_workspace() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(97);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1200.0, 800.0)));
+        let before: Vec<_> = engine
+            .virtual_workspace_manager_mut()
+            .list_workspaces(space)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert!(before.len() >= 3);
+        let active = engine.active_workspace(space);
+
+        let _ = engine.handle_virtual_workspace_command(space, &LayoutCommand::MoveWorkspace {
+            from: 0,
+            to: 2,
+        });
+        let after: Vec<_> = engine
+            .virtual_workspace_manager_mut()
+            .list_workspaces(space)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(&after[..3], &[before[1], before[2], before[0]]);
+        assert_eq!(engine.active_workspace(space), active);
+
+        let _ = engine.handle_virtual_workspace_command(space, &LayoutCommand::MoveWorkspace {
+            from: 0,
+            to: before.len(),
+        });
+        let unchanged: Vec<_> = engine
+            .virtual_workspace_manager_mut()
+            .list_workspaces(space)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(unchanged, after, "out-of-range moves are ignored");
+    }
 
-        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
-        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(space, pid, windows, None));
-        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, WindowId::new(pid, 1)));
-        let gaps = engine.layout_settings.gaps.clone();
+    #[test]
+    fn preview_command_leaves_real_layout_untouched() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(63);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(900.0, 800.0));
+        let windows = [WindowId::new(1, 1), WindowId::new(1, 2)];
+        let gaps = LayoutSettings::default().gaps;
 
-        let _ = engine.handle_command(
-            Some(space),
-            &[space],
-            &HashMap::default(),
-            LayoutCommand::MoveNode(Direction::Up),
-        );
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, windows[0]));
 
-        let modified = engine.calculate_layout(
+        let width = |frames: Vec<(WindowId, CGRect)>| {
+            frames.into_iter().find(|(wid, _)| *wid == windows[0]).unwrap().1.size.width
+        };
+        let before = width(engine.calculate_layout(
             space,
             screen,
             &gaps,
             0.0,
             Default::default(),
             Default::default(),
-        );
-
-        let _ = engine.handle_event(LayoutEvent::WindowRemoved(WindowId::new(9999, 1)));
+        ));
 
-        assert_eq!(
-            engine.calculate_layout(
+        let preview = engine
+            .preview_command(
                 space,
                 screen,
                 &gaps,
                 0.0,
                 Default::default(),
                 Default::default(),
-            ),
-            modified
+                LayoutCommand::SetContainerRatio { ratio: 0.25 },
+            )
+            .unwrap();
+        assert!((width(preview) - 225.0).abs() < 1.0);
+
+        let after = width(engine.calculate_layout(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        ));
+        assert_eq!(before, after);
+
+        assert!(
+            engine
+                .preview_command(
+                    space,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                    LayoutCommand::NextWorkspace(None),
+                )
+                .is_err()
         );
     }
 
     #[test]
-    fn duplicate_window_added_is_treated_as_noop_for_active_layout() {
-        let mut engine = test_engine();
-        let space = SpaceId::new(93);
-        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
-        let pid: pid_t = 5152;
-        let wid = WindowId::new(pid, 1);
+    fn master_stack_preview_does_not_touch_shared_settings() {
+        let settings = LayoutSettings {
+            mode: crate::common::config::LayoutMode::MasterStack,
+            ..Default::default()
+        };
+        let mut engine = LayoutEngine::new(&VirtualWorkspaceSettings::default(), &settings, None);
+        let space = SpaceId::new(64);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let windows: Vec<_> = (1..=3).map(|idx| WindowId::new(1, idx)).collect();
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
 
         let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
         let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
             space,
-            pid,
-            vec![(
-                wid,
-                None,
-                None,
-                None,
-                true,
-                CGSize::new(500.0, 500.0),
-                None,
-                None,
-            )],
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
             None,
         ));
-        let gaps = engine.layout_settings.gaps.clone();
+
+        let master = |frames: &[(WindowId, CGRect)]| {
+            frames.iter().find(|(_, rect)| rect.origin.x == 0.0).map(|(wid, _)| *wid)
+        };
         let before = engine.calculate_layout(
             space,
             screen,
@@ -3149,18 +5237,2031 @@ mod tests {
             Default::default(),
             Default::default(),
         );
+        let stacked = *windows.iter().find(|&&wid| master(&before) != Some(wid)).unwrap();
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, stacked));
 
-        assert!(!engine.add_window_to_layout(space, wid));
-        assert_eq!(
-            engine.calculate_layout(
+        let preview = engine
+            .preview_command(
                 space,
                 screen,
                 &gaps,
                 0.0,
                 Default::default(),
                 Default::default(),
-            ),
-            before
+                LayoutCommand::PromoteToMaster,
+            )
+            .unwrap();
+        assert_eq!(master(&preview), Some(stacked));
+
+        for command in [
+            LayoutCommand::AdjustMasterRatio { delta: 0.2 },
+            LayoutCommand::AdjustMasterCount { delta: 1 },
+            LayoutCommand::CycleMasterCount,
+            LayoutCommand::SetMasterPosition(crate::common::config::MasterStackSide::Right),
+            LayoutCommand::EqualizeSizes,
+        ] {
+            assert!(
+                engine
+                    .preview_command(
+                        space,
+                        screen,
+                        &gaps,
+                        0.0,
+                        Default::default(),
+                        Default::default(),
+                        command,
+                    )
+                    .is_err()
+            );
+        }
+
+        let after = engine.calculate_layout(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+        assert_eq!(before, after);
+        assert_ne!(master(&after), Some(stacked));
+    }
+
+    #[test]
+    fn scratchpad_hides_window_and_shows_it_centered() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(62);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let visible_spaces = vec![space];
+        let visible_space_centers = HashMap::default();
+        let tiled = WindowId::new(1, 1);
+        let dropdown = WindowId::new(1, 2);
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            [tiled, dropdown]
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, dropdown));
+
+        let _ = engine.handle_command(
+            Some(space),
+            &visible_spaces,
+            &visible_space_centers,
+            LayoutCommand::MoveWindowToScratchpad,
+        );
+
+        let frames = |engine: &mut LayoutEngine| -> HashMap<WindowId, CGRect> {
+            engine
+                .calculate_layout_with_virtual_workspaces(
+                    space,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                    |_| Some(CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(400.0, 300.0))),
+                    &[screen],
+                )
+                .into_iter()
+                .collect()
+        };
+
+        let is_hidden = |engine: &LayoutEngine, rect: &CGRect| {
+            engine
+                .virtual_workspace_manager
+                .is_hidden_position_multi(&screen, rect, None, &[screen])
+        };
+
+        let hidden = frames(&mut engine);
+        assert!(
+            is_hidden(&engine, &hidden[&dropdown]),
+            "scratchpad window should be parked in the hidden corner"
+        );
+        assert!(
+            (hidden[&tiled].size.width - screen.size.width).abs() < 50.0,
+            "remaining tiled window should take the freed space"
+        );
+
+        let _ = engine.handle_command(
+            Some(space),
+            &visible_spaces,
+            &visible_space_centers,
+            LayoutCommand::ToggleScratchpad,
         );
+        let shown = frames(&mut engine);
+        let center = shown[&dropdown].mid();
+        assert!((center.x - screen.mid().x).abs() < 1.0);
+        assert!((center.y - screen.mid().y).abs() < 1.0);
+
+        let _ = engine.handle_command(
+            Some(space),
+            &visible_spaces,
+            &visible_space_centers,
+            LayoutCommand::ToggleScratchpad,
+        );
+        let hidden_again = frames(&mut engine);
+        assert!(is_hidden(&engine, &hidden_again[&dropdown]));
+    }
+
+    #[test]
+    fn move_focus_to_uninitialized_adjacent_space_does_not_panic() {
+        let mut engine = test_engine();
+        let current_space = SpaceId::new(50);
+        let adjacent_space = SpaceId::new(51);
+        let screen_size = CGSize::new(1920.0, 1080.0);
+        let visible_spaces = vec![current_space, adjacent_space];
+        let mut visible_space_centers = HashMap::default();
+        visible_space_centers.insert(current_space, CGPoint::new(0.0, 0.0));
+        visible_space_centers.insert(adjacent_space, CGPoint::new(1920.0, 0.0));
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(current_space, screen_size));
+
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            engine.handle_command(
+                Some(current_space),
+                &visible_spaces,
+                &visible_space_centers,
+                LayoutCommand::MoveFocus(Direction::Right),
+            )
+        }));
+
+        assert!(
+            result.is_ok(),
+            "cross-space move focus should not panic when adjacent space is not initialized"
+        );
+    }
+
+    #[test]
+    fn update_virtual_workspace_settings_reapplies_workspace_rules() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(7);
+        let workspace_list = engine.virtual_workspace_manager_mut().list_workspaces(space);
+        let (workspace_id, workspace_name) = workspace_list[0].clone();
+        assert_eq!(
+            engine
+                .virtual_workspace_manager()
+                .workspace_info(space, workspace_id)
+                .map(|ws| ws.layout_mode()),
+            Some(LayoutMode::Traditional)
+        );
+
+        let mut settings = VirtualWorkspaceSettings::default();
+        settings.workspace_rules = vec![WorkspaceLayoutRule {
+            workspace: WorkspaceSelector::Name(workspace_name),
+            layout: LayoutMode::Scrolling,
+        }];
+
+        engine.update_virtual_workspace_settings(&settings);
+
+        assert_eq!(
+            engine
+                .virtual_workspace_manager()
+                .workspace_info(space, workspace_id)
+                .map(|ws| ws.layout_mode()),
+            Some(LayoutMode::Scrolling)
+        );
+    }
+
+    #[test]
+    fn set_workspace_layout_for_inactive_workspace_does_not_raise_active_windows() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(8);
+        let window_id = WindowId::new(999, 1);
+
+        let _ = engine.virtual_workspace_manager_mut().list_workspaces(space);
+        let _ = engine.virtual_workspace_manager_mut().auto_assign_window(window_id, space);
+
+        let response =
+            engine.handle_virtual_workspace_command(space, &LayoutCommand::SetWorkspaceLayout {
+                workspace: Some(1),
+                mode: LayoutMode::Bsp,
+            });
+
+        assert!(response.raise_windows.is_empty());
+        assert_eq!(response.focus_window, None);
+    }
+
+    #[test]
+    fn move_window_to_space_detaches_window_when_source_mapping_is_stale() {
+        let mut engine = test_engine();
+        let source = SpaceId::new(70);
+        let target = SpaceId::new(71);
+        let screen_size = CGSize::new(1920.0, 1080.0);
+        let window_id = WindowId::new(4242, 1);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(source, screen_size));
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(target, screen_size));
+
+        let source_workspace = engine
+            .virtual_workspace_manager()
+            .active_workspace(source)
+            .expect("source active workspace");
+        let target_workspace = engine
+            .virtual_workspace_manager()
+            .active_workspace(target)
+            .expect("target active workspace");
+        let source_layout = engine
+            .workspace_layouts
+            .active(source, source_workspace)
+            .expect("source active layout");
+        let target_layout = engine
+            .workspace_layouts
+            .active(target, target_workspace)
+            .expect("target active layout");
+
+        assert!(
+            engine.virtual_workspace_manager_mut().assign_window_to_workspace(
+                source,
+                window_id,
+                source_workspace
+            )
+        );
+        engine
+            .workspace_tree_mut(source_workspace)
+            .add_window_after_selection(source_layout, window_id);
+        assert!(
+            engine
+                .workspace_tree(source_workspace)
+                .contains_window(source_layout, window_id)
+        );
+
+        // Create an inconsistent state: workspace mapping points to target, but source tree
+        // still contains the window. Cross-space move must still detach from source tree.
+        assert!(
+            engine.virtual_workspace_manager_mut().assign_window_to_workspace(
+                target,
+                window_id,
+                target_workspace
+            )
+        );
+        assert_eq!(
+            engine.virtual_workspace_manager().workspace_for_window(source, window_id),
+            None
+        );
+        assert!(
+            engine
+                .workspace_tree(source_workspace)
+                .contains_window(source_layout, window_id)
+        );
+
+        let _ = engine.move_window_to_space(source, target, screen_size, window_id);
+
+        assert!(
+            !engine
+                .workspace_tree(source_workspace)
+                .contains_window(source_layout, window_id)
+        );
+        assert!(
+            engine
+                .workspace_tree(target_workspace)
+                .contains_window(target_layout, window_id)
+        );
+        assert_eq!(
+            engine.virtual_workspace_manager().workspace_for_window(target, window_id),
+            Some(target_workspace)
+        );
+    }
+
+    #[test]
+    fn locked_tiled_windows_stay_within_screen_bounds() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(90);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 800.0));
+        let pid: pid_t = 4242;
+
+        let locked = WindowId::new(pid, 100);
+        let other_a = WindowId::new(pid, 101);
+        let other_b = WindowId::new(pid, 102);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            pid,
+            vec![
+                (
+                    locked,
+                    None,
+                    None,
+                    None,
+                    false,
+                    // Intentionally impossible size for this screen; layout should still keep
+                    // tiled results bounded instead of force-applying this at the end.
+                    CGSize::new(1600.0, 900.0),
+                    None,
+                    None,
+                ),
+                (
+                    other_a,
+                    None,
+                    None,
+                    None,
+                    true,
+                    CGSize::new(600.0, 600.0),
+                    None,
+                    None,
+                ),
+                (
+                    other_b,
+                    None,
+                    None,
+                    None,
+                    true,
+                    CGSize::new(600.0, 600.0),
+                    None,
+                    None,
+                ),
+            ],
+            None,
+        ));
+
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+        let positions = engine.calculate_layout_with_virtual_workspaces(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+            |_| None,
+            &[screen],
+        );
+        let frames: HashMap<WindowId, CGRect> = positions.into_iter().collect();
+        let locked_frame = frames
+            .get(&locked)
+            .copied()
+            .expect("locked tiled window should have a calculated frame");
+
+        let epsilon = 0.5;
+        let max_x = screen.origin.x + screen.size.width + epsilon;
+        let max_y = screen.origin.y + screen.size.height + epsilon;
+        assert!(locked_frame.origin.x >= screen.origin.x - epsilon);
+        assert!(locked_frame.origin.y >= screen.origin.y - epsilon);
+        assert!(locked_frame.origin.x + locked_frame.size.width <= max_x);
+        assert!(locked_frame.origin.y + locked_frame.size.height <= max_y);
+    }
+
+    #[test]
+    fn repeated_windows_on_screen_update_does_not_rebalance_unchanged_tiled_layout() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(91);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
+        let pid: pid_t = 5150;
+
+        let windows = vec![
+            (
+                WindowId::new(pid, 1),
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(500.0, 500.0),
+                None,
+                None,
+            ),
+            (
+                WindowId::new(pid, 2),
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(500.0, 500.0),
+                None,
+                None,
+            ),
+            (
+                WindowId::new(pid, 3),
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(500.0, 500.0),
+                None,
+                None,
+            ),
+        ];
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            pid,
+            windows.clone(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, WindowId::new(pid, 1)));
+        let gaps = engine.layout_settings.gaps.clone();
+
+        let baseline = engine.calculate_layout(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+
+        let _ = engine.handle_command(
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::MoveNode(Direction::Up),
+        );
+
+        let modified = engine.calculate_layout(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+        assert_ne!(baseline, modified);
+
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(space, pid, windows, None));
+
+        assert_eq!(
+            engine.calculate_layout(
+                space,
+                screen,
+                &gaps,
+                0.0,
+                Default::default(),
+                Default::default(),
+            ),
+            modified
+        );
+    }
+
+    #[test]
+    fn removing_unknown_window_does_not_rebalance_layout() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(92);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
+        let pid: pid_t = 5151;
+
+        let windows = vec![
+            (
+                WindowId::new(pid, 1),
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(500.0, 500.0),
+                None,
+                None,
+            ),
+            (
+                WindowId::new(pid, 2),
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(500.0, 500.0),
+                None,
+                None,
+            ),
+            (
+                WindowId::new(pid, 3),
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(500.0, 500.0),
+                None,
+                None,
+            ),
+        ];
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(space, pid, windows, None));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, WindowId::new(pid, 1)));
+        let gaps = engine.layout_settings.gaps.clone();
+
+        let _ = engine.handle_command(
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::MoveNode(Direction::Up),
+        );
+
+        let modified = engine.calculate_layout(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+
+        let _ = engine.handle_event(LayoutEvent::WindowRemoved(WindowId::new(9999, 1)));
+
+        assert_eq!(
+            engine.calculate_layout(
+                space,
+                screen,
+                &gaps,
+                0.0,
+                Default::default(),
+                Default::default(),
+            ),
+            modified
+        );
+    }
+
+    #[test]
+    fn move_node_to_edge_moves_selection_past_every_sibling() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(67);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 800.0));
+        let windows: Vec<_> = (1..=4).map(|idx| WindowId::new(1, idx)).collect();
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, windows[0]));
+
+        let order = |engine: &LayoutEngine| {
+            let (ws_id, layout) = engine.workspace_and_layout(space).unwrap();
+            engine.workspace_tree(ws_id).visible_windows_in_layout(layout)
+        };
+        assert_eq!(order(&engine).first(), Some(&windows[0]));
+
+        let _ = engine.handle_command(
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::MoveNodeToEdge(Direction::Right),
+        );
+        assert_eq!(order(&engine).last(), Some(&windows[0]));
+        assert_eq!(order(&engine).len(), windows.len());
+    }
+
+    #[test]
+    fn dock_floating_to_edge_floats_window_as_full_length_strip() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(70);
+        let screen = CGRect::new(CGPoint::new(0.0, 25.0), CGSize::new(1000.0, 800.0));
+        let docked = WindowId::new(1, 1);
+        let tiled = WindowId::new(1, 2);
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            [docked, tiled]
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, docked));
+        let frames = |engine: &mut LayoutEngine| -> HashMap<WindowId, CGRect> {
+            engine
+                .calculate_layout_with_virtual_workspaces(
+                    space,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                    |_| None,
+                    &[screen],
+                )
+                .into_iter()
+                .collect()
+        };
+
+        let response = engine.dock_floating_to_edge(space, screen, Direction::Right);
+        assert_eq!(response.raise_windows, vec![docked]);
+        assert!(engine.is_window_floating(docked));
+        let frames_after = frames(&mut engine);
+        assert_eq!(
+            frames_after[&docked],
+            CGRect::new(CGPoint::new(700.0, 25.0), CGSize::new(300.0, 800.0))
+        );
+        // The remaining tiled window still gets the whole screen.
+        assert_eq!(frames_after[&tiled].size, screen.size);
+
+        let _ = engine.dock_floating_to_edge(space, screen, Direction::Up);
+        assert_eq!(
+            frames(&mut engine)[&docked],
+            CGRect::new(screen.origin, CGSize::new(1000.0, 300.0))
+        );
+
+        // A per-space layout override takes precedence over the global thickness.
+        let mut space_settings = engine.layout_settings.clone();
+        space_settings.floating.dock_thickness = 200.0;
+        engine.set_space_layout_settings(space, Some(space_settings));
+        let _ = engine.dock_floating_to_edge(space, screen, Direction::Left);
+        assert_eq!(
+            frames(&mut engine)[&docked],
+            CGRect::new(screen.origin, CGSize::new(200.0, 800.0))
+        );
+    }
+
+    #[test]
+    fn most_recently_focused_prefers_the_latest_focus_among_candidates() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(93);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
+        let pid: pid_t = 5252;
+
+        let windows = (1..=3)
+            .map(|idx| {
+                (
+                    WindowId::new(pid, idx),
+                    None,
+                    None,
+                    None,
+                    true,
+                    CGSize::new(500.0, 500.0),
+                    None,
+                    None,
+                )
+            })
+            .collect();
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(space, pid, windows, None));
+
+        let candidates = [WindowId::new(pid, 1), WindowId::new(pid, 2)];
+        assert_eq!(engine.most_recently_focused(candidates), None);
+
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, WindowId::new(pid, 2)));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, WindowId::new(pid, 1)));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, WindowId::new(pid, 3)));
+        assert_eq!(
+            engine.most_recently_focused(candidates),
+            Some(WindowId::new(pid, 1))
+        );
+
+        let _ = engine.handle_event(LayoutEvent::WindowRemoved(WindowId::new(pid, 1)));
+        assert_eq!(
+            engine.most_recently_focused(candidates),
+            Some(WindowId::new(pid, 2))
+        );
+    }
+
+    #[test]
+    fn app_rule_floating_frame_applies_only_on_first_placement() {
+        use crate::common::config::{AppWorkspaceRule, FloatingRuleFrame};
+
+        let mut settings = VirtualWorkspaceSettings::default();
+        settings.app_rules = vec![AppWorkspaceRule {
+            app_id: None,
+            workspace: None,
+            floating: true,
+            manage: true,
+            app_name: None,
+            title_regex: None,
+            title_substring: Some("Calculator".into()),
+            ax_role: None,
+            ax_subrole: None,
+            floating_frame: Some(FloatingRuleFrame {
+                x: Some(-400.0),
+                y: Some(0.0),
+                w: 400.0,
+                h: 500.0,
+                center: false,
+            }),
+            min_size: None,
+        }];
+        let mut engine = LayoutEngine::new(&settings, &LayoutSettings::default(), None);
+        let space = SpaceId::new(68);
+        let screen = CGRect::new(CGPoint::new(0.0, 25.0), CGSize::new(1000.0, 800.0));
+        let calculator = WindowId::new(1, 1);
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+        let windows_updated = || {
+            LayoutEvent::WindowsOnScreenUpdated(
+                space,
+                1,
+                vec![(
+                    calculator,
+                    Some("Calculator".to_string()),
+                    None,
+                    None,
+                    true,
+                    CGSize::new(0.0, 0.0),
+                    None,
+                    None,
+                )],
+                None,
+            )
+        };
+        let calculator_frame = |engine: &mut LayoutEngine| {
+            let frames: HashMap<WindowId, CGRect> = engine
+                .calculate_layout_with_virtual_workspaces(
+                    space,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                    |_| None,
+                    &[screen],
+                )
+                .into_iter()
+                .collect();
+            frames[&calculator]
+        };
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(windows_updated());
+        assert!(engine.is_window_floating(calculator));
+        assert_eq!(
+            calculator_frame(&mut engine),
+            CGRect::new(CGPoint::new(600.0, 25.0), CGSize::new(400.0, 500.0))
+        );
+
+        // Once the user moves it, relayouts and rule re-evaluation leave it be.
+        let moved = CGRect::new(CGPoint::new(100.0, 100.0), CGSize::new(400.0, 500.0));
+        let workspace = engine.active_workspace(space).unwrap();
+        engine
+            .virtual_workspace_manager
+            .store_floating_position(space, workspace, calculator, moved);
+        let _ = engine.handle_event(windows_updated());
+        assert_eq!(calculator_frame(&mut engine), moved);
+    }
+
+    #[test]
+    fn app_rule_min_size_overrides_the_reported_minimum_when_tiling() {
+        use crate::common::config::{AppWorkspaceRule, RuleMinSize};
+
+        let mut settings = VirtualWorkspaceSettings::default();
+        settings.app_rules = vec![AppWorkspaceRule {
+            app_id: None,
+            workspace: None,
+            floating: false,
+            manage: true,
+            app_name: None,
+            title_regex: None,
+            title_substring: Some("IDE".into()),
+            ax_role: None,
+            ax_subrole: None,
+            floating_frame: None,
+            min_size: Some(RuleMinSize { w: Some(900.0), h: None }),
+        }];
+        let mut engine = LayoutEngine::new(&settings, &LayoutSettings::default(), None);
+        let space = SpaceId::new(99);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 800.0));
+        let ide = WindowId::new(1, 1);
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            vec![
+                (
+                    ide,
+                    Some("IDE".to_string()),
+                    None,
+                    None,
+                    true,
+                    CGSize::new(0.0, 0.0),
+                    Some(CGSize::new(300.0, 200.0)),
+                    None,
+                ),
+                (
+                    WindowId::new(1, 2),
+                    None,
+                    None,
+                    None,
+                    true,
+                    CGSize::new(0.0, 0.0),
+                    None,
+                    None,
+                ),
+                (
+                    WindowId::new(1, 3),
+                    None,
+                    None,
+                    None,
+                    true,
+                    CGSize::new(0.0, 0.0),
+                    None,
+                    None,
+                ),
+            ],
+            None,
+        ));
+
+        let frames: HashMap<WindowId, CGRect> = engine
+            .calculate_layout_with_virtual_workspaces(
+                space,
+                screen,
+                &gaps,
+                0.0,
+                Default::default(),
+                Default::default(),
+                |_| None,
+                &[screen],
+            )
+            .into_iter()
+            .collect();
+        assert!(frames[&ide].size.width >= 900.0 - 1.0);
+        assert!(!engine.is_window_floating(ide));
+    }
+
+    #[test]
+    fn toggle_monocle_tabs_every_window_and_restores_the_layout() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(69);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 800.0));
+        let windows = [
+            WindowId::new(1, 1),
+            WindowId::new(1, 2),
+            WindowId::new(1, 3),
+        ];
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, windows[0]));
+        let command = |engine: &mut LayoutEngine, cmd| {
+            engine.handle_command(Some(space), &[space], &HashMap::default(), cmd)
+        };
+        let frames = |engine: &mut LayoutEngine| -> HashMap<WindowId, CGRect> {
+            engine
+                .calculate_layout(space, screen, &gaps, 0.0, Default::default(), Default::default())
+                .into_iter()
+                .collect()
+        };
+        let tiled = frames(&mut engine);
+
+        let _ = command(&mut engine, LayoutCommand::ToggleMonocle);
+        let groups = engine.collect_group_containers(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].total_count, windows.len());
+
+        // NextWindow walks through every tab, not just the one on screen.
+        let mut focused = vec![];
+        for _ in 0..windows.len() {
+            let response = command(&mut engine, LayoutCommand::NextWindow);
+            let wid = response.focus_window.expect("next tab should take focus");
+            let _ = engine.handle_event(LayoutEvent::WindowFocused(space, wid));
+            focused.push(wid);
+        }
+        focused.sort();
+        assert_eq!(focused, windows.to_vec());
+
+        let _ = command(&mut engine, LayoutCommand::ToggleMonocle);
+        assert!(
+            engine
+                .collect_group_containers(
+                    space,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                )
+                .is_empty()
+        );
+        assert_eq!(frames(&mut engine), tiled);
+    }
+
+    #[test]
+    fn compact_stacks_drop_stack_offsets_until_toggled_off() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(67);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let windows = [WindowId::new(1, 1), WindowId::new(1, 2)];
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_command(
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::ToggleStack,
+        );
+
+        let frames = |engine: &mut LayoutEngine| {
+            let frames: HashMap<WindowId, CGRect> = engine
+                .calculate_layout(space, screen, &gaps, 6.0, Default::default(), Default::default())
+                .into_iter()
+                .collect();
+            (frames[&windows[0]], frames[&windows[1]])
+        };
+
+        let (first, second) = frames(&mut engine);
+        assert_ne!(first, second);
+
+        assert!(engine.toggle_compact_stacks(space));
+        let (first, second) = frames(&mut engine);
+        assert_eq!(first, second);
+
+        assert!(!engine.toggle_compact_stacks(space));
+        let (first, second) = frames(&mut engine);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn smart_gaps_drop_outer_gaps_for_a_single_tiled_window() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(66);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let tiled = WindowId::new(1, 1);
+        let floating = WindowId::new(1, 2);
+        let mut gaps = engine.layout_settings.gaps.effective_for_display(None);
+        gaps.outer.top = 20.0;
+        gaps.outer.left = 20.0;
+        gaps.outer.bottom = 20.0;
+        gaps.outer.right = 20.0;
+        gaps.smart_gaps = true;
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            [tiled, floating]
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, floating));
+        let toggle_floating = |engine: &mut LayoutEngine| {
+            engine.handle_command(
+                Some(space),
+                &[space],
+                &HashMap::default(),
+                LayoutCommand::ToggleWindowFloating,
+            )
+        };
+        let _ = toggle_floating(&mut engine);
+
+        let tiled_frame = |engine: &mut LayoutEngine| {
+            let frames: HashMap<WindowId, CGRect> = engine
+                .calculate_layout(space, screen, &gaps, 0.0, Default::default(), Default::default())
+                .into_iter()
+                .collect();
+            frames[&tiled]
+        };
+
+        // The floating window doesn't count, so the lone tiled window is full-bleed.
+        assert_eq!(tiled_frame(&mut engine), screen);
+
+        let _ = toggle_floating(&mut engine);
+        let frame = tiled_frame(&mut engine);
+        assert_eq!(frame.origin.x, 20.0);
+        assert_eq!(frame.origin.y, 20.0);
+    }
+
+    #[test]
+    fn smart_gaps_count_a_stack_as_a_single_tile() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(66);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let windows = [WindowId::new(1, 1), WindowId::new(1, 2)];
+        let mut gaps = engine.layout_settings.gaps.effective_for_display(None);
+        gaps.outer.top = 20.0;
+        gaps.outer.left = 20.0;
+        gaps.outer.bottom = 20.0;
+        gaps.outer.right = 20.0;
+        gaps.smart_gaps = true;
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+
+        let top_left = |engine: &mut LayoutEngine| {
+            let frames = engine.calculate_layout(
+                space,
+                screen,
+                &gaps,
+                0.0,
+                Default::default(),
+                Default::default(),
+            );
+            assert_eq!(frames.len(), 2);
+            let x = frames.iter().map(|(_, f)| f.origin.x).fold(f64::INFINITY, f64::min);
+            let y = frames.iter().map(|(_, f)| f.origin.y).fold(f64::INFINITY, f64::min);
+            (x, y)
+        };
+
+        // Two tiles side by side keep their outer gaps.
+        assert_eq!(top_left(&mut engine), (20.0, 20.0));
+        assert_eq!(
+            engine.gaps_for_active_layout(space, screen, &gaps).outer.left,
+            20.0
+        );
+
+        let _ = engine.handle_command(
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::ToggleStack,
+        );
+
+        // Stacked, both windows share one tile, so it goes full-bleed.
+        assert_eq!(top_left(&mut engine), (0.0, 0.0));
+        assert_eq!(
+            engine.gaps_for_active_layout(space, screen, &gaps).outer.left,
+            0.0
+        );
+    }
+
+    #[test]
+    fn dimmable_windows_skip_focused_floating_and_fullscreen_windows() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(66);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let [focused, floating, fullscreen, tiled] = [1, 2, 3, 4].map(|idx| WindowId::new(1, idx));
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            [focused, floating, fullscreen, tiled]
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        for (wid, command) in [
+            (floating, LayoutCommand::ToggleWindowFloating),
+            (fullscreen, LayoutCommand::ToggleFullscreen),
+        ] {
+            let _ = engine.handle_event(LayoutEvent::WindowFocused(space, wid));
+            let _ = engine.handle_command(Some(space), &[space], &HashMap::default(), command);
+        }
+
+        assert_eq!(
+            engine.dimmable_windows_in_active_workspace(space, Some(focused)),
+            vec![tiled]
+        );
+        let mut unfocused = engine.dimmable_windows_in_active_workspace(space, None);
+        unfocused.sort();
+        assert_eq!(unfocused, vec![focused, tiled]);
+    }
+
+    #[test]
+    fn true_fullscreen_hides_floating_windows_until_toggled_off() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(65);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let tiled = WindowId::new(1, 1);
+        let floating = WindowId::new(1, 2);
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            [tiled, floating]
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, floating));
+        let _ = engine.handle_command(
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::ToggleWindowFloating,
+        );
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, tiled));
+
+        let floating_hidden = |engine: &mut LayoutEngine| {
+            let frames: HashMap<WindowId, CGRect> = engine
+                .calculate_layout_with_virtual_workspaces(
+                    space,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                    |_| {
+                        Some(CGRect::new(
+                            CGPoint::new(100.0, 100.0),
+                            CGSize::new(300.0, 200.0),
+                        ))
+                    },
+                    &[screen],
+                )
+                .into_iter()
+                .collect();
+            engine.virtual_workspace_manager.is_hidden_position_multi(
+                &screen,
+                &frames[&floating],
+                None,
+                &[screen],
+            )
+        };
+        let toggle = |engine: &mut LayoutEngine| {
+            engine.handle_command(
+                Some(space),
+                &[space],
+                &HashMap::default(),
+                LayoutCommand::TrueFullscreen,
+            )
+        };
+
+        assert!(!floating_hidden(&mut engine));
+
+        let response = toggle(&mut engine);
+        assert_eq!(response.raise_windows, vec![tiled]);
+        assert_eq!(engine.true_fullscreen_window(space), Some(tiled));
+        assert!(floating_hidden(&mut engine));
+
+        let _ = toggle(&mut engine);
+        assert_eq!(engine.true_fullscreen_window(space), None);
+        assert!(!floating_hidden(&mut engine));
+    }
+
+    #[test]
+    fn cycling_window_visibility_hides_one_category_at_a_time() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(64);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let visible_spaces = vec![space];
+        let visible_space_centers = HashMap::default();
+        let tiled = WindowId::new(1, 1);
+        let floating = WindowId::new(1, 2);
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            [tiled, floating]
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, floating));
+        let _ = engine.handle_command(
+            Some(space),
+            &visible_spaces,
+            &visible_space_centers,
+            LayoutCommand::ToggleWindowFloating,
+        );
+
+        let hidden = |engine: &mut LayoutEngine| -> (bool, bool) {
+            let frames: HashMap<WindowId, CGRect> = engine
+                .calculate_layout_with_virtual_workspaces(
+                    space,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                    |_| {
+                        Some(CGRect::new(
+                            CGPoint::new(100.0, 100.0),
+                            CGSize::new(300.0, 200.0),
+                        ))
+                    },
+                    &[screen],
+                )
+                .into_iter()
+                .collect();
+            let is_hidden = |wid: WindowId| {
+                engine.virtual_workspace_manager.is_hidden_position_multi(
+                    &screen,
+                    &frames[&wid],
+                    None,
+                    &[screen],
+                )
+            };
+            (is_hidden(tiled), is_hidden(floating))
+        };
+
+        assert_eq!(hidden(&mut engine), (false, false));
+
+        let response = engine.cycle_window_visibility(space);
+        assert_eq!(engine.window_visibility(space), WindowVisibilityMode::TiledOnly);
+        assert_eq!(response.focus_window, Some(tiled));
+        assert_eq!(hidden(&mut engine), (false, true));
+
+        let response = engine.cycle_window_visibility(space);
+        assert_eq!(
+            engine.window_visibility(space),
+            WindowVisibilityMode::FloatingOnly
+        );
+        assert_eq!(response.focus_window, Some(floating));
+        assert_eq!(hidden(&mut engine), (true, false));
+
+        let _ = engine.cycle_window_visibility(space);
+        assert_eq!(engine.window_visibility(space), WindowVisibilityMode::All);
+        assert_eq!(hidden(&mut engine), (false, false));
+    }
+
+    #[test]
+    fn window_added_with_anchor_is_placed_next_to_anchor() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(63);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 800.0));
+        let gaps = engine.layout_settings.gaps.clone();
+        let existing = [
+            WindowId::new(1, 1),
+            WindowId::new(1, 2),
+            WindowId::new(1, 3),
+        ];
+        let new_window = WindowId::new(2, 1);
+        let window_entry = |wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            existing.iter().map(|&wid| window_entry(wid)).collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, existing[2]));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            2,
+            vec![window_entry(new_window)],
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowAdded(space, new_window, Some(existing[0])));
+
+        let mut frames = engine.calculate_layout(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+        frames.sort_by(|a, b| a.1.origin.x.total_cmp(&b.1.origin.x));
+        let order: Vec<_> = frames.into_iter().map(|(wid, _)| wid).collect();
+        assert_eq!(order, vec![existing[0], new_window, existing[1], existing[2]]);
+    }
+
+    #[test]
+    fn duplicate_window_added_is_treated_as_noop_for_active_layout() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(93);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
+        let pid: pid_t = 5152;
+        let wid = WindowId::new(pid, 1);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            pid,
+            vec![(
+                wid,
+                None,
+                None,
+                None,
+                true,
+                CGSize::new(500.0, 500.0),
+                None,
+                None,
+            )],
+            None,
+        ));
+        let gaps = engine.layout_settings.gaps.clone();
+        let before = engine.calculate_layout(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+
+        assert!(!engine.add_window_to_layout(space, wid, None));
+        assert_eq!(
+            engine.calculate_layout(
+                space,
+                screen,
+                &gaps,
+                0.0,
+                Default::default(),
+                Default::default(),
+            ),
+            before
+        );
+    }
+
+    #[test]
+    fn workspace_activation_restacks_floating_windows_in_focus_order() {
+        let mut settings = LayoutSettings::default();
+        settings.floating.strict_z_order = true;
+        let mut engine = LayoutEngine::new(&VirtualWorkspaceSettings::default(), &settings, None);
+        let space = SpaceId::new(69);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let windows = [
+            WindowId::new(1, 1),
+            WindowId::new(1, 2),
+            WindowId::new(1, 3),
+        ];
+        let centers = HashMap::default();
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        for &wid in &windows {
+            let _ = engine.handle_event(LayoutEvent::WindowFocused(space, wid));
+            let _ = engine.handle_command(
+                Some(space),
+                &[space],
+                &centers,
+                LayoutCommand::ToggleWindowFloating,
+            );
+        }
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, windows[0]));
+
+        let _ =
+            engine.handle_virtual_workspace_command(space, &LayoutCommand::SwitchToWorkspace(1));
+        let response =
+            engine.handle_virtual_workspace_command(space, &LayoutCommand::SwitchToWorkspace(0));
+
+        assert_eq!(response.focus_window, Some(windows[0]));
+        assert_eq!(response.raise_windows, vec![windows[1], windows[2]]);
+    }
+
+    #[test]
+    fn sticky_window_follows_workspace_switches() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(70);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let sticky = WindowId::new(1, 1);
+        let other = WindowId::new(1, 2);
+        let centers = HashMap::default();
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            [sticky, other]
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, sticky));
+        let _ = engine.handle_command(
+            Some(space),
+            &[space],
+            &centers,
+            LayoutCommand::ToggleWindowSticky,
+        );
+
+        let _ =
+            engine.handle_virtual_workspace_command(space, &LayoutCommand::SwitchToWorkspace(1));
+        let active = engine.active_workspace(space);
+        assert_eq!(
+            engine.virtual_workspace_manager.workspace_for_window(space, sticky),
+            active
+        );
+        assert_ne!(
+            engine.virtual_workspace_manager.workspace_for_window(space, other),
+            active
+        );
+
+        let layout = engine.calculate_layout_with_virtual_workspaces(
+            space,
+            screen,
+            &crate::common::config::GapSettings::default(),
+            0.0,
+            Default::default(),
+            Default::default(),
+            |_| None,
+            &[screen],
+        );
+        let sticky_frame = layout.iter().find(|(wid, _)| *wid == sticky).map(|(_, rect)| *rect);
+        assert_eq!(sticky_frame.map(|rect| rect.origin), Some(screen.origin));
+    }
+
+    #[test]
+    fn saved_layout_state_round_trips_with_version() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(69);
+        let wid = WindowId::new(1, 1);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1000.0, 800.0)));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            vec![(wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None)],
+            None,
+        ));
+        let workspace = engine.virtual_workspace_manager.workspace_for_window(space, wid);
+        assert!(workspace.is_some());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("layout.ron");
+        engine.save(path.clone()).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.contains(&format!("version:{LAYOUT_STATE_VERSION}")));
+
+        let loaded = LayoutEngine::load(path).unwrap();
+        assert_eq!(
+            loaded.virtual_workspace_manager.workspace_for_window(space, wid),
+            workspace
+        );
+    }
+
+    #[test]
+    fn unversioned_layout_state_is_migrated_on_load() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(70);
+        let wid = WindowId::new(1, 1);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1000.0, 800.0)));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            vec![(wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None)],
+            None,
+        ));
+        let workspace = engine.virtual_workspace_manager.workspace_for_window(space, wid);
+        assert!(workspace.is_some());
+
+        // Before versioning the engine itself was written, without an envelope.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("layout.ron");
+        std::fs::write(&path, engine.serialize_to_string()).unwrap();
+        let loaded = LayoutEngine::load(path).unwrap();
+        assert_eq!(
+            loaded.virtual_workspace_manager.workspace_for_window(space, wid),
+            workspace
+        );
+
+        // Fields missing from an older format fall back to their defaults.
+        let sparse = dir.path().join("sparse.ron");
+        std::fs::write(&sparse, "()").unwrap();
+        let loaded = LayoutEngine::load(sparse).unwrap();
+        assert!(loaded.virtual_workspace_manager.workspace_for_window(space, wid).is_none());
+    }
+
+    #[test]
+    fn unreadable_layout_state_starts_fresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = LayoutEngine::load(dir.path().join("missing.ron")).unwrap();
+        assert!(
+            missing
+                .virtual_workspace_manager
+                .workspace_for_window(SpaceId::new(1), WindowId::new(1, 1))
+                .is_none()
+        );
+
+        let newer = dir.path().join("newer.ron");
+        std::fs::write(
+            &newer,
+            format!("(version:{},engine:())", LAYOUT_STATE_VERSION + 1),
+        )
+        .unwrap();
+        assert!(LayoutEngine::load(newer).is_ok());
+
+        let garbage = dir.path().join("garbage.ron");
+        std::fs::write(&garbage, "not ron at all").unwrap();
+        assert!(LayoutEngine::load(garbage).is_ok());
+    }
+
+    #[test]
+    fn loading_a_profile_restores_workspaces_for_windows_that_still_exist() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(102);
+        let windows: Vec<WindowId> = (1..=3).map(|idx| WindowId::new(1, idx)).collect();
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1000.0, 800.0)));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let first = engine.virtual_workspace_manager.workspace_for_window(space, windows[0]);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("work.ron");
+        engine.save_profile(space, &path).unwrap();
+
+        // Rearrange: one window moves away, one closes and a new one opens.
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, windows[1]));
+        let _ =
+            engine.handle_virtual_workspace_command(space, &LayoutCommand::MoveWindowToWorkspace {
+                workspace: 1,
+                window_id: None,
+            });
+        let _ = engine.handle_event(LayoutEvent::WindowRemoved(windows[2]));
+        let added = WindowId::new(1, 4);
+        let _ = engine.handle_event(LayoutEvent::WindowAdded(space, added, None));
+
+        engine.load_profile(space, &path).unwrap();
+
+        let vwm = &engine.virtual_workspace_manager;
+        assert_eq!(vwm.workspace_for_window(space, windows[1]), first);
+        assert_eq!(vwm.workspace_for_window(space, added), first);
+        assert_eq!(vwm.workspace_for_window(space, windows[2]), None);
+        let first = first.unwrap();
+        assert!(!vwm.workspaces[first].contains_window(windows[2]));
+        let layout = engine.workspace_layouts.active(space, first).unwrap();
+        let mut tiled = engine.workspace_tree(first).visible_windows_in_layout(layout);
+        tiled.sort();
+        assert_eq!(tiled, vec![windows[0], windows[1], added]);
+
+        assert!(engine.load_profile(space, &dir.path().join("missing.ron")).is_err());
+    }
+
+    #[test]
+    fn swap_in_direction_crosses_to_the_adjacent_display_at_the_edge() {
+        let mut engine = test_engine();
+        let (left, right) = (SpaceId::new(103), SpaceId::new(104));
+        let visible_spaces = [left, right];
+        let mut centers = HashMap::default();
+        centers.insert(left, CGPoint::new(500.0, 400.0));
+        centers.insert(right, CGPoint::new(1500.0, 400.0));
+        let (a, b, c) = (WindowId::new(1, 1), WindowId::new(1, 2), WindowId::new(2, 1));
+        for (space, pid, windows) in [(left, 1, vec![a, b]), (right, 2, vec![c])] {
+            let _ =
+                engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1000.0, 800.0)));
+            let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+                space,
+                pid,
+                windows
+                    .iter()
+                    .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                    .collect(),
+                None,
+            ));
+        }
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(right, c));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(left, a));
+        let tiled = |engine: &LayoutEngine, space| {
+            let (ws, layout) = engine.workspace_and_layout(space).unwrap();
+            engine.workspace_tree(ws).visible_windows_in_layout(layout)
+        };
+
+        let swap_right = LayoutCommand::SwapInDirection(Direction::Right);
+        let _ = engine.handle_command(Some(left), &visible_spaces, &centers, swap_right.clone());
+        assert_eq!(tiled(&engine, left), vec![b, a]);
+
+        let _ = engine.handle_command(Some(left), &visible_spaces, &centers, swap_right);
+        assert_eq!(tiled(&engine, left), vec![b, c]);
+        assert_eq!(tiled(&engine, right), vec![a]);
+        let (right_ws, right_layout) = engine.workspace_and_layout(right).unwrap();
+        assert_eq!(
+            engine.workspace_tree(right_ws).selected_window(right_layout),
+            Some(a)
+        );
+        let vwm = &engine.virtual_workspace_manager;
+        assert_eq!(vwm.workspace_for_window(right, a), Some(right_ws));
+        assert_eq!(
+            vwm.workspace_for_window(left, c),
+            engine.workspace_and_layout(left).map(|(ws, _)| ws)
+        );
+    }
+
+    #[test]
+    fn cycle_master_count_wraps_through_configured_counts() {
+        let settings = LayoutSettings {
+            mode: crate::common::config::LayoutMode::MasterStack,
+            ..Default::default()
+        };
+        let mut engine = LayoutEngine::new(&VirtualWorkspaceSettings::default(), &settings, None);
+        let space = SpaceId::new(70);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let windows: Vec<_> = (1..=4).map(|idx| WindowId::new(1, idx)).collect();
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            windows
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+        let master_count = |engine: &mut LayoutEngine| {
+            let _ = engine.handle_command(
+                Some(space),
+                &[space],
+                &HashMap::default(),
+                LayoutCommand::CycleMasterCount,
+            );
+            let layout = engine.calculate_layout(
+                space,
+                screen,
+                &gaps,
+                0.0,
+                Default::default(),
+                Default::default(),
+            );
+            layout.iter().filter(|(_, rect)| rect.origin.x == 0.0).count()
+        };
+
+        assert_eq!(master_count(&mut engine), 2);
+        assert_eq!(master_count(&mut engine), 3);
+        assert_eq!(master_count(&mut engine), 1);
+    }
+
+    #[test]
+    fn master_placement_makes_new_windows_the_master() {
+        let settings = LayoutSettings {
+            mode: crate::common::config::LayoutMode::MasterStack,
+            ..Default::default()
+        };
+        let mut engine = LayoutEngine::new(&VirtualWorkspaceSettings::default(), &settings, None);
+        let space = SpaceId::new(101);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+        let (first, second, third) =
+            (WindowId::new(1, 1), WindowId::new(1, 2), WindowId::new(2, 1));
+        let master = |engine: &mut LayoutEngine| {
+            engine
+                .calculate_layout(space, screen, &gaps, 0.0, Default::default(), Default::default())
+                .into_iter()
+                .filter(|(_, rect)| rect.origin.x == 0.0)
+                .map(|(wid, _)| wid)
+                .collect::<Vec<_>>()
+        };
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            vec![(first, None, None, None, true, CGSize::new(0.0, 0.0), None, None)],
+            None,
+        ));
+        assert_eq!(master(&mut engine), vec![first]);
+
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            [first, second]
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        assert_eq!(master(&mut engine), vec![second]);
+
+        let _ = engine.handle_event(LayoutEvent::WindowAdded(space, third, Some(first)));
+        assert_eq!(master(&mut engine), vec![third]);
+    }
+
+    #[test]
+    fn swap_with_last_exchanges_focused_and_previous_windows() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(71);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 800.0));
+        let (a, b, c) = (WindowId::new(1, 1), WindowId::new(1, 2), WindowId::new(1, 3));
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            [a, b, c]
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+        let frames = |engine: &mut LayoutEngine| -> HashMap<WindowId, CGRect> {
+            engine
+                .calculate_layout(space, screen, &gaps, 0.0, Default::default(), Default::default())
+                .into_iter()
+                .collect()
+        };
+        let before = frames(&mut engine);
+
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, a));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, b));
+        let _ = engine.handle_command(
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::SwapWithLast,
+        );
+
+        let after = frames(&mut engine);
+        assert_eq!(after[&a], before[&b]);
+        assert_eq!(after[&b], before[&a]);
+        assert_eq!(after[&c], before[&c]);
+    }
+
+    #[test]
+    fn display_layout_rules_pick_default_mode_per_display() {
+        let mut settings = VirtualWorkspaceSettings::default();
+        settings
+            .display_layout_rules
+            .insert("ultrawide".to_string(), LayoutMode::Scrolling);
+        let mut engine = LayoutEngine::new(&settings, &LayoutSettings::default(), None);
+        let ultrawide = SpaceId::new(1);
+        let laptop = SpaceId::new(2);
+        let size = CGSize::new(1000.0, 1000.0);
+
+        for space in [ultrawide, laptop] {
+            let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, size));
+        }
+        engine.update_space_display(ultrawide, Some("ultrawide".to_string()));
+        engine.update_space_display(laptop, Some("laptop".to_string()));
+
+        assert_eq!(engine.active_layout_mode_at(ultrawide), LayoutMode::Scrolling);
+        assert_eq!(engine.active_layout_mode_at(laptop), LayoutMode::Traditional);
+
+        // A mode chosen by hand survives later display updates.
+        let _ = engine.handle_virtual_workspace_command(
+            ultrawide,
+            &LayoutCommand::SetWorkspaceLayout {
+                workspace: None,
+                mode: LayoutMode::Bsp,
+            },
+        );
+        engine.update_space_display(ultrawide, Some("ultrawide".to_string()));
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(ultrawide, size));
+        assert_eq!(engine.active_layout_mode_at(ultrawide), LayoutMode::Bsp);
+    }
+
+    #[test]
+    fn floating_window_keeps_a_separate_rect_per_workspace() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(69);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let wid = WindowId::new(1, 1);
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+        let rect_a = CGRect::new(CGPoint::new(50.0, 60.0), CGSize::new(300.0, 200.0));
+        let rect_b = CGRect::new(CGPoint::new(400.0, 300.0), CGSize::new(350.0, 250.0));
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            vec![(wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None)],
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, wid));
+        let _ = engine.handle_command(
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::ToggleWindowFloating,
+        );
+        let workspaces = engine.virtual_workspace_manager_mut().list_workspaces(space);
+        let (first, second) = (workspaces[0].0, workspaces[1].0);
+        engine
+            .virtual_workspace_manager
+            .store_floating_position(space, first, wid, rect_a);
+
+        let move_to = |engine: &mut LayoutEngine, workspace: usize| {
+            let _ = engine.handle_event(LayoutEvent::WindowFocused(space, wid));
+            let _ = engine.handle_virtual_workspace_command(
+                space,
+                &LayoutCommand::MoveWindowToWorkspace { workspace, window_id: None },
+            );
+            let _ = engine.handle_virtual_workspace_command(
+                space,
+                &LayoutCommand::SwitchToWorkspace(workspace),
+            );
+        };
+        let frame = |engine: &mut LayoutEngine| {
+            engine
+                .calculate_layout_with_virtual_workspaces(
+                    space,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                    |_| None,
+                    &[screen],
+                )
+                .into_iter()
+                .find_map(|(w, rect)| (w == wid).then_some(rect))
+        };
+
+        move_to(&mut engine, 1);
+        engine
+            .virtual_workspace_manager
+            .store_floating_position(space, second, wid, rect_b);
+        assert_eq!(frame(&mut engine), Some(rect_b));
+        // The rect left behind on the first workspace is remembered but not applied there.
+        assert!(
+            engine
+                .calculate_layout_for_workspace(
+                    space,
+                    first,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                )
+                .is_empty()
+        );
+
+        move_to(&mut engine, 0);
+        assert_eq!(frame(&mut engine), Some(rect_a));
+        assert_eq!(
+            engine.virtual_workspace_manager.get_floating_position(space, first, wid),
+            Some(rect_a)
+        );
+        assert_eq!(
+            engine.virtual_workspace_manager.get_floating_position(space, second, wid),
+            Some(rect_b)
+        );
+    }
+
+    #[test]
+    fn move_window_to_last_workspace_keeps_active_workspace() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(68);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let first = WindowId::new(1, 1);
+        let second = WindowId::new(1, 2);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            [first, second]
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, second));
+        let workspaces = engine.virtual_workspace_manager_mut().list_workspaces(space);
+        let (origin, other) = (workspaces[0].0, workspaces[1].0);
+
+        // Without a previous workspace there is nowhere to send the window.
+        let _ = engine
+            .handle_virtual_workspace_command(space, &LayoutCommand::MoveWindowToLastWorkspace);
+        assert_eq!(
+            engine.virtual_workspace_manager.workspace_for_window(space, second),
+            Some(origin)
+        );
+
+        let _ =
+            engine.handle_virtual_workspace_command(space, &LayoutCommand::SwitchToWorkspace(1));
+        let _ =
+            engine.handle_virtual_workspace_command(space, &LayoutCommand::SwitchToWorkspace(0));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, second));
+        let _ = engine
+            .handle_virtual_workspace_command(space, &LayoutCommand::MoveWindowToLastWorkspace);
+
+        assert_eq!(engine.active_workspace(space), Some(origin));
+        assert_eq!(
+            engine.virtual_workspace_manager.workspace_for_window(space, second),
+            Some(other)
+        );
+        assert_eq!(
+            engine.virtual_workspace_manager.last_focused_window(space, other),
+            Some(second)
+        );
+        assert_eq!(
+            engine.virtual_workspace_manager.workspace_for_window(space, first),
+            Some(origin)
+        );
+    }
+
+    #[test]
+    fn switch_to_workspace_named_resolves_names_and_optionally_creates() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(67);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+
+        let workspaces = engine.virtual_workspace_manager_mut().list_workspaces(space);
+        let (second_id, second_name) = workspaces[1].clone();
+        let _ = engine
+            .handle_virtual_workspace_command(space, &LayoutCommand::SwitchToWorkspaceNamed {
+                name: second_name,
+            });
+        assert_eq!(engine.active_workspace(space), Some(second_id));
+
+        let missing = LayoutCommand::SwitchToWorkspaceNamed { name: "scratch".to_string() };
+        let _ = engine.handle_virtual_workspace_command(space, &missing);
+        assert_eq!(engine.active_workspace(space), Some(second_id));
+        assert_eq!(
+            engine.virtual_workspace_manager_mut().list_workspaces(space).len(),
+            workspaces.len()
+        );
+
+        engine.virtual_workspace_manager.create_on_switch = true;
+        let _ = engine.handle_virtual_workspace_command(space, &missing);
+        let after = engine.virtual_workspace_manager_mut().list_workspaces(space);
+        let (created_id, _) = after
+            .iter()
+            .find(|(_, name)| name == "scratch")
+            .cloned()
+            .expect("workspace should be created on switch");
+        assert_eq!(engine.active_workspace(space), Some(created_id));
+    }
+
+    #[test]
+    fn cycle_floating_preset_resizes_focused_floating_window() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(66);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let visible_spaces = vec![space];
+        let visible_space_centers = HashMap::default();
+        let tiled = WindowId::new(1, 1);
+        let floating = WindowId::new(1, 2);
+        let gaps = engine.layout_settings.gaps.effective_for_display(None);
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+            space,
+            1,
+            [tiled, floating]
+                .iter()
+                .map(|&wid| (wid, None, None, None, true, CGSize::new(0.0, 0.0), None, None))
+                .collect(),
+            None,
+        ));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, floating));
+        let _ = engine.handle_command(
+            Some(space),
+            &visible_spaces,
+            &visible_space_centers,
+            LayoutCommand::ToggleWindowFloating,
+        );
+
+        let frame_of = |engine: &mut LayoutEngine, wid: WindowId| -> CGRect {
+            engine
+                .calculate_layout_with_virtual_workspaces(
+                    space,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                    |_| Some(CGRect::new(CGPoint::new(10.0, 10.0), CGSize::new(300.0, 200.0))),
+                    &[screen],
+                )
+                .into_iter()
+                .find(|(w, _)| *w == wid)
+                .map(|(_, rect)| rect)
+                .unwrap()
+        };
+
+        let _ = engine.cycle_floating_preset(space, screen);
+        let first = frame_of(&mut engine, floating);
+        assert_eq!(first.size, CGSize::new(500.0, 400.0));
+        assert!((first.mid().x - screen.mid().x).abs() < 1.0);
+        assert!((first.mid().y - screen.mid().y).abs() < 1.0);
+
+        let _ = engine.cycle_floating_preset(space, screen);
+        assert_eq!(frame_of(&mut engine, floating).size, CGSize::new(700.0, 560.0));
+
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, tiled));
+        let before = frame_of(&mut engine, tiled);
+        let response = engine.cycle_floating_preset(space, screen);
+        assert!(response.raise_windows.is_empty());
+        assert_eq!(frame_of(&mut engine, tiled), before);
     }
 }
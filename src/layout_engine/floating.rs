@@ -10,6 +10,9 @@ pub(crate) struct FloatingManager {
     #[serde(skip)]
     active_floating_windows: HashMap<SpaceId, HashMap<pid_t, HashSet<WindowId>>>,
     last_floating_focus: Option<WindowId>,
+    /// Floating windows ordered by when they last had focus, most recent last.
+    #[serde(skip)]
+    focus_order: Vec<WindowId>,
 }
 
 impl FloatingManager {
@@ -26,6 +29,7 @@ impl FloatingManager {
     pub(crate) fn remove_floating(&mut self, window_id: WindowId) {
         self.floating_windows.remove(&window_id);
         self.remove_active_entries(window_id);
+        self.focus_order.retain(|&wid| wid != window_id);
         if self.last_floating_focus == Some(window_id) {
             self.last_floating_focus = None;
         }
@@ -70,12 +74,28 @@ impl FloatingManager {
 
     pub(crate) fn set_last_focus(&mut self, wid: Option<WindowId>) {
         self.last_floating_focus = wid;
+        if let Some(wid) = wid {
+            self.focus_order.retain(|&w| w != wid);
+            self.focus_order.push(wid);
+        }
+    }
+
+    /// Sort `windows` bottom-to-top by focus recency; never-focused windows come
+    /// first and `last_focus()` ends up on top.
+    pub(crate) fn in_focus_order(&self, windows: &[WindowId]) -> Vec<WindowId> {
+        let mut ordered = windows.to_vec();
+        ordered.sort_by_key(|wid| {
+            let rank = self.focus_order.iter().position(|w| w == wid);
+            (Some(*wid) == self.last_floating_focus, rank)
+        });
+        ordered
     }
 
     pub(crate) fn last_focus(&self) -> Option<WindowId> { self.last_floating_focus }
 
     pub(crate) fn remove_all_for_pid(&mut self, pid: pid_t) {
         let _ = self.floating_windows.remove_all_for_pid(pid);
+        self.focus_order.retain(|wid| wid.pid != pid);
 
         for space_map in self.active_floating_windows.values_mut() {
             space_map.remove(&pid);
@@ -93,6 +113,8 @@ impl FloatingManager {
         space: SpaceId,
         windows_in_workspace: Vec<WindowId>,
     ) {
+        let floating_windows = &self.floating_windows;
+        self.focus_order.retain(|wid| floating_windows.contains(wid));
         let space_map = self.active_floating_windows.entry(space).or_default();
         space_map.clear();
         for wid in windows_in_workspace.into_iter().filter(|&w| self.floating_windows.contains(&w))
@@ -118,6 +118,13 @@ pub trait LayoutSystem: Serialize + for<'de> Deserialize<'de> {
         direction: Direction,
     ) -> (Option<WindowId>, Vec<WindowId>);
     fn window_in_direction(&self, layout: LayoutId, direction: Direction) -> Option<WindowId>;
+    /// Focus the window on the opposite edge of the layout, for when `move_focus`
+    /// in `direction` has nowhere to go.
+    fn wrap_focus(
+        &mut self,
+        layout: LayoutId,
+        direction: Direction,
+    ) -> (Option<WindowId>, Vec<WindowId>);
     fn add_window_after_selection(&mut self, layout: LayoutId, wid: WindowId);
     fn remove_window(&mut self, wid: WindowId);
     fn remove_windows_for_app(&mut self, pid: pid_t);
@@ -137,6 +144,16 @@ pub trait LayoutSystem: Serialize + for<'de> Deserialize<'de> {
     );
 
     fn swap_windows(&mut self, layout: LayoutId, a: WindowId, b: WindowId) -> bool;
+    /// Insert `wid` as the immediate neighbor of `target` on the given side,
+    /// removing it from its current position in this system first. Returns
+    /// false if `target` is not in the layout.
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        wid: WindowId,
+        target: WindowId,
+        direction: Direction,
+    ) -> bool;
 
     fn move_selection(&mut self, layout: LayoutId, direction: Direction) -> bool;
     fn move_selection_to_layout_after_selection(
@@ -149,6 +166,8 @@ pub trait LayoutSystem: Serialize + for<'de> Deserialize<'de> {
     fn toggle_fullscreen_of_selection(&mut self, layout: LayoutId) -> Vec<WindowId>;
     fn toggle_fullscreen_within_gaps_of_selection(&mut self, layout: LayoutId) -> Vec<WindowId>;
     fn has_any_fullscreen_node(&self, layout: LayoutId) -> bool;
+    /// Windows currently shown fullscreen (or fullscreen within gaps) in the layout.
+    fn fullscreen_windows(&self, layout: LayoutId) -> Vec<WindowId>;
 
     fn join_selection_with_direction(&mut self, layout: LayoutId, direction: Direction);
     fn apply_stacking_to_parent_of_selection(
@@ -162,10 +181,23 @@ pub trait LayoutSystem: Serialize + for<'de> Deserialize<'de> {
         default_orientation: crate::common::config::StackDefaultOrientation,
     ) -> Vec<WindowId>;
     fn parent_of_selection_is_stacked(&self, layout: LayoutId) -> bool;
+    /// Pin the selected member of the enclosing stacked group so the group
+    /// shows it whenever focus is elsewhere. Returns false if the selection
+    /// isn't inside a stacked group.
+    fn set_stack_representative(&mut self, layout: LayoutId) -> bool;
     fn unjoin_selection(&mut self, _layout: LayoutId);
     fn resize_selection_by(&mut self, layout: LayoutId, amount: f64);
     fn rebalance(&mut self, layout: LayoutId);
+    /// Reset every split in the layout to an even share, keeping the tree
+    /// structure and selection intact.
+    fn equalize(&mut self, layout: LayoutId);
+    /// Give the selection `ratio` of its nearest adjustable parent split.
+    /// Returns false when the selection has no such split.
+    fn set_selection_ratio(&mut self, layout: LayoutId, ratio: f64) -> bool;
     fn toggle_tile_orientation(&mut self, layout: LayoutId);
+    /// Flip the orientation of every split in the layout, transposing the
+    /// whole arrangement.
+    fn rotate_all(&mut self, layout: LayoutId);
 }
 
 mod traditional;
@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::actor::app::{WindowId, pid_t};
 use crate::common::collections::{HashMap, HashSet};
+use crate::common::config::ResizeMode;
 use crate::layout_engine::systems::constraints::{AxisConstraints, solve_axis_lengths};
 use crate::layout_engine::systems::{LayoutSystem, WindowLayoutConstraints};
 use crate::layout_engine::utils::compute_tiling_area;
@@ -35,6 +36,8 @@ pub struct BspLayoutSystem {
     tree: Tree<Components>,
     kind: slotmap::SecondaryMap<NodeId, NodeKind>,
     window_to_node: HashMap<WindowId, NodeId>,
+    #[serde(skip)]
+    resize_mode: ResizeMode,
 }
 
 impl BspLayoutSystem {
@@ -76,6 +79,22 @@ impl BspLayoutSystem {
         None
     }
 
+    /// The leaf on the far side of the outermost split along `direction`'s axis.
+    fn wrap_leaf(&self, from_leaf: NodeId, direction: Direction) -> Option<NodeId> {
+        let outermost = from_leaf.ancestors(&self.tree.map).skip(1).filter(|&node| {
+            matches!(
+                self.kind.get(node),
+                Some(NodeKind::Split { orientation, .. }) if *orientation == direction.orientation()
+            )
+        });
+        let split = outermost.last()?;
+        let target = match direction {
+            Direction::Left | Direction::Up => split.last_child(&self.tree.map),
+            Direction::Right | Direction::Down => split.first_child(&self.tree.map),
+        }?;
+        Some(self.find_closest_leaf_in_direction(target, direction))
+    }
+
     fn find_closest_leaf_in_direction(&self, root: NodeId, direction: Direction) -> NodeId {
         match self.kind.get(root) {
             Some(NodeKind::Leaf { .. }) => root,
@@ -183,11 +202,108 @@ impl Default for BspLayoutSystem {
             tree: Tree::with_observer(Components::default()),
             kind: Default::default(),
             window_to_node: Default::default(),
+            resize_mode: ResizeMode::default(),
         }
     }
 }
 
 impl BspLayoutSystem {
+    pub fn update_settings(&mut self, resize_mode: ResizeMode) { self.resize_mode = resize_mode; }
+
+    /// Splits of one orientation nested directly inside each other behave
+    /// like a single container; this returns the topmost split of the run
+    /// that `split` belongs to.
+    fn split_run_root(&self, split: NodeId, orientation: Orientation) -> NodeId {
+        let mut root = split;
+        while let Some(parent) = root.parent(&self.tree.map)
+            && matches!(
+                self.kind.get(parent),
+                Some(NodeKind::Split { orientation: o, .. }) if *o == orientation
+            )
+        {
+            root = parent;
+        }
+        root
+    }
+
+    /// Collect the members of the run below `node` with the fraction of the
+    /// run's length each one takes up.
+    fn split_run_members(
+        &self,
+        node: NodeId,
+        orientation: Orientation,
+        fraction: f64,
+        out: &mut Vec<(NodeId, f64)>,
+    ) {
+        if let Some(NodeKind::Split { orientation: o, ratio }) = self.kind.get(node)
+            && *o == orientation
+        {
+            let ratio = f64::from(*ratio);
+            let mut children = node.children(&self.tree.map);
+            if let (Some(first), Some(second)) = (children.next(), children.next()) {
+                self.split_run_members(first, orientation, fraction * ratio, out);
+                self.split_run_members(second, orientation, fraction * (1.0 - ratio), out);
+                return;
+            }
+        }
+        out.push((node, fraction));
+    }
+
+    /// Rewrite the split ratios of the run below `node` so its members take
+    /// up `fractions`, returning the fraction covered by `node`.
+    fn apply_split_run_fractions(
+        &mut self,
+        node: NodeId,
+        orientation: Orientation,
+        fractions: &HashMap<NodeId, f64>,
+    ) -> f64 {
+        if let Some(NodeKind::Split { orientation: o, .. }) = self.kind.get(node)
+            && *o == orientation
+        {
+            let children: Vec<_> = node.children(&self.tree.map).collect();
+            if let [first, second] = children[..] {
+                let a = self.apply_split_run_fractions(first, orientation, fractions);
+                let b = self.apply_split_run_fractions(second, orientation, fractions);
+                if let Some(NodeKind::Split { ratio, .. }) = self.kind.get_mut(node)
+                    && a + b > f64::EPSILON
+                {
+                    *ratio = (a / (a + b)).clamp(0.05, 0.95) as f32;
+                }
+                return a + b;
+            }
+        }
+        fractions.get(&node).copied().unwrap_or(0.0)
+    }
+
+    /// Resize `member`, a child of `split`, across the whole run of
+    /// `orientation` splits around it. `resize` maps the member's current
+    /// fraction of the run to the new one; every other member of the run is
+    /// scaled to share the rest in its current proportions.
+    fn resize_split_run_member(
+        &mut self,
+        split: NodeId,
+        member: NodeId,
+        orientation: Orientation,
+        resize: impl FnOnce(f64) -> f64,
+    ) {
+        let root = self.split_run_root(split, orientation);
+        let mut members = Vec::new();
+        self.split_run_members(root, orientation, 1.0, &mut members);
+        let Some(old) = members.iter().find(|(node, _)| *node == member).map(|(_, f)| *f) else {
+            return;
+        };
+        if 1.0 - old <= f64::EPSILON {
+            return;
+        }
+        let new = resize(old).clamp(0.05, 0.95);
+        let scale = (1.0 - new) / (1.0 - old);
+        let fractions: HashMap<NodeId, f64> = members
+            .into_iter()
+            .map(|(node, f)| (node, if node == member { new } else { f * scale }))
+            .collect();
+        self.apply_split_run_fractions(root, orientation, &fractions);
+    }
+
     fn index_window(&mut self, wid: WindowId, node: NodeId) {
         debug_assert!(
             matches!(self.kind.get(node), Some(NodeKind::Leaf { .. })),
@@ -296,6 +412,50 @@ impl BspLayoutSystem {
         }
     }
 
+    fn collect_fullscreen_windows(&self, node: NodeId, out: &mut Vec<WindowId>) {
+        match self.kind.get(node) {
+            Some(NodeKind::Leaf {
+                window: Some(w),
+                fullscreen,
+                fullscreen_within_gaps,
+                ..
+            }) => {
+                if *fullscreen || *fullscreen_within_gaps {
+                    out.push(*w);
+                }
+            }
+            Some(NodeKind::Split { .. }) => {
+                for child in node.children(&self.tree.map) {
+                    self.collect_fullscreen_windows(child, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn equalize_node(&mut self, node: NodeId) {
+        if let Some(NodeKind::Split { ratio, .. }) = self.kind.get_mut(node) {
+            *ratio = 0.5;
+            let children: Vec<_> = node.children(&self.tree.map).collect();
+            for child in children {
+                self.equalize_node(child);
+            }
+        }
+    }
+
+    fn rotate_node(&mut self, node: NodeId) {
+        if let Some(NodeKind::Split { orientation, .. }) = self.kind.get_mut(node) {
+            *orientation = match *orientation {
+                Orientation::Horizontal => Orientation::Vertical,
+                Orientation::Vertical => Orientation::Horizontal,
+            };
+            let children: Vec<_> = node.children(&self.tree.map).collect();
+            for child in children {
+                self.rotate_node(child);
+            }
+        }
+    }
+
     fn find_layout_root(&self, mut node: NodeId) -> NodeId {
         while let Some(p) = node.parent(&self.tree.map) {
             node = p;
@@ -368,6 +528,60 @@ impl BspLayoutSystem {
         parent_id
     }
 
+    pub(crate) fn selected_node(&self, layout: LayoutId) -> Option<NodeId> {
+        self.selection_of_layout(layout)
+    }
+
+    /// Move `wid` next to the split `node`: the split's current contents are
+    /// pushed down one level and the window becomes their sibling, so it joins
+    /// the group `node` addressed. Returns false if `node` is not a split of
+    /// `layout`, `wid` is not in the layout, or `wid` is already a direct
+    /// child of `node`.
+    pub(crate) fn join_into(&mut self, layout: LayoutId, node: NodeId, wid: WindowId) -> bool {
+        let Some(state) = self.layouts.get(layout).copied() else {
+            return false;
+        };
+        if !matches!(self.kind.get(node), Some(NodeKind::Split { .. }))
+            || !self.belongs_to_layout(state, node)
+        {
+            return false;
+        }
+        let Some(leaf) = self.node_for_window_mut(wid) else {
+            return false;
+        };
+        let leaf_parent = leaf.parent(&self.tree.map);
+        if !self.belongs_to_layout(state, leaf) || leaf_parent == Some(node) {
+            return false;
+        }
+
+        // Removing the leaf folds its sibling into their parent; if that
+        // sibling was the target, the target now lives at the parent.
+        let sibling =
+            leaf_parent.and_then(|p| p.children(&self.tree.map).find(|&child| child != leaf));
+        if let Some(NodeKind::Leaf { window, .. }) = self.kind.get_mut(leaf) {
+            *window = None;
+        }
+        self.unindex_window(wid);
+        let parent = self.cleanup_after_removal(leaf);
+        let target = if sibling == Some(node) { parent } else { node };
+
+        let Some(NodeKind::Split { orientation, ratio }) = self.kind.get(target).cloned() else {
+            return false;
+        };
+        let inner = self.tree.mk_node().into_id();
+        self.kind.insert(inner, NodeKind::Split { orientation, ratio });
+        let children: Vec<_> = target.children(&self.tree.map).collect();
+        for child in children {
+            child.detach(&mut self.tree).push_back(inner);
+        }
+        let new_leaf = self.make_leaf(Some(wid));
+        inner.detach(&mut self.tree).push_back(target);
+        new_leaf.detach(&mut self.tree).push_back(target);
+        self.kind.insert(target, NodeKind::Split { orientation, ratio: 0.5 });
+        self.tree.data.selection.select(&self.tree.map, new_leaf);
+        true
+    }
+
     fn selection_of_layout(&self, layout: crate::layout_engine::LayoutId) -> Option<NodeId> {
         self.layouts
             .get(layout)
@@ -918,6 +1132,67 @@ mod tests {
             "orthogonal max-only constraint should not change the parent split allocation"
         );
     }
+
+    /// Lays out three windows side by side, then widens the middle one by
+    /// 100px to the right. Returns the frames, left to right, before and after.
+    fn resize_middle_of_three(mode: ResizeMode) -> ([CGRect; 3], [CGRect; 3]) {
+        let mut system = BspLayoutSystem::default();
+        system.update_settings(mode);
+        let layout = system.create_layout();
+        let windows = [w(111), w(112), w(113)];
+        for wid in windows {
+            system.add_window_after_selection(layout, wid);
+        }
+        // The third window splits vertically; flip it so all three share a row.
+        system.toggle_tile_orientation(layout);
+
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(900.0, 800.0));
+        let frames = |system: &BspLayoutSystem| {
+            let mut frames = system.calculate_layout(
+                layout,
+                screen,
+                0.0,
+                &HashMap::default(),
+                &Default::default(),
+                0.0,
+                Default::default(),
+                Default::default(),
+            );
+            frames.sort_by(|a, b| a.1.origin.x.total_cmp(&b.1.origin.x));
+            [frames[0], frames[1], frames[2]]
+        };
+
+        let before = frames(&system);
+        assert!(before.iter().all(|(_, f)| (f.size.height - 800.0).abs() < 1.0));
+        let (middle, old_frame) = before[1];
+        let mut new_frame = old_frame;
+        new_frame.size.width += 100.0;
+        system.on_window_resized(layout, middle, old_frame, new_frame, screen, &Default::default());
+
+        (before.map(|(_, f)| f), frames(&system).map(|(_, f)| f))
+    }
+
+    #[test]
+    fn sibling_only_resize_takes_space_from_one_neighbor() {
+        let (before, after) = resize_middle_of_three(ResizeMode::SiblingOnly);
+        assert!((after[1].size.width - before[1].size.width - 100.0).abs() < 1.0);
+        let left_changed = (after[0].size.width - before[0].size.width).abs() > 1.0;
+        let right_changed = (after[2].size.width - before[2].size.width).abs() > 1.0;
+        assert!(
+            left_changed != right_changed,
+            "exactly one neighbor should yield space"
+        );
+    }
+
+    #[test]
+    fn proportional_resize_shrinks_both_neighbors_evenly() {
+        let (before, after) = resize_middle_of_three(ResizeMode::Proportional);
+        assert!((after[1].size.width - before[1].size.width - 100.0).abs() < 1.0);
+        let left_scale = after[0].size.width / before[0].size.width;
+        let right_scale = after[2].size.width / before[2].size.width;
+        assert!(left_scale < 1.0 && right_scale < 1.0);
+        assert!((left_scale - right_scale).abs() < 0.01);
+    }
 }
 
 impl LayoutSystem for BspLayoutSystem {
@@ -1086,6 +1361,30 @@ impl LayoutSystem for BspLayoutSystem {
             .and_then(|state| self.window_in_direction_from(state.root, direction))
     }
 
+    fn wrap_focus(
+        &mut self,
+        layout: LayoutId,
+        direction: Direction,
+    ) -> (Option<WindowId>, Vec<WindowId>) {
+        let raise_windows = self.visible_windows_in_layout(layout);
+        let Some(current_sel) = self.selection_of_layout(layout) else {
+            return (None, vec![]);
+        };
+        let current_leaf = self.descend_to_leaf(current_sel);
+        let Some(target_leaf) = self.wrap_leaf(current_leaf, direction) else {
+            return (None, vec![]);
+        };
+        if target_leaf == current_leaf {
+            return (None, vec![]);
+        }
+        self.tree.data.selection.select(&self.tree.map, target_leaf);
+        let focus = match self.kind.get(target_leaf) {
+            Some(NodeKind::Leaf { window, .. }) => *window,
+            _ => None,
+        };
+        (focus, raise_windows)
+    }
+
     fn add_window_after_selection(&mut self, layout: LayoutId, wid: WindowId) {
         if self.layouts.get(layout).is_some() {
             // Try smart insertion first (with preselection support)
@@ -1264,6 +1563,16 @@ impl LayoutSystem for BspLayoutSystem {
                         }
                     };
 
+                    if self.resize_mode == ResizeMode::Proportional {
+                        let orientation = *orientation;
+                        if old_len > 0.0 {
+                            self.resize_split_run_member(parent, current, orientation, |f| {
+                                f * new_len / old_len
+                            });
+                        }
+                        break;
+                    }
+
                     let current_ratio = f64::from(*ratio);
                     let is_first = Some(current) == parent.first_child(&self.tree.map);
                     let denom = if is_first {
@@ -1327,6 +1636,24 @@ impl LayoutSystem for BspLayoutSystem {
         true
     }
 
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        wid: WindowId,
+        target: WindowId,
+        direction: Direction,
+    ) -> bool {
+        if wid == target || !self.contains_window(layout, target) {
+            return false;
+        }
+        self.remove_window(wid);
+        let Some(target_node) = self.node_for_window_mut(target) else {
+            return false;
+        };
+        self.split_leaf_in_direction(target_node, direction, wid);
+        true
+    }
+
     fn swap_windows(&mut self, layout: LayoutId, a: WindowId, b: WindowId) -> bool {
         let Some(node_a) = self.node_for_window_mut(a) else {
             return false;
@@ -1466,6 +1793,14 @@ impl LayoutSystem for BspLayoutSystem {
         }
     }
 
+    fn fullscreen_windows(&self, layout: LayoutId) -> Vec<WindowId> {
+        let mut out = Vec::new();
+        if let Some(state) = self.layouts.get(layout).copied() {
+            self.collect_fullscreen_windows(state.root, &mut out);
+        }
+        out
+    }
+
     fn join_selection_with_direction(&mut self, layout: LayoutId, direction: Direction) {
         let Some(sel) = self.selection_of_layout(layout) else {
             return;
@@ -1514,6 +1849,8 @@ impl LayoutSystem for BspLayoutSystem {
 
     fn parent_of_selection_is_stacked(&self, _layout: LayoutId) -> bool { false }
 
+    fn set_stack_representative(&mut self, _layout: LayoutId) -> bool { false }
+
     fn unstack_parent_of_selection(
         &mut self,
         _: LayoutId,
@@ -1572,7 +1909,12 @@ impl LayoutSystem for BspLayoutSystem {
         };
 
         while let Some(parent) = node.parent(&self.tree.map) {
-            if let Some(NodeKind::Split { ratio, .. }) = self.kind.get_mut(parent) {
+            if let Some(NodeKind::Split { orientation, ratio }) = self.kind.get_mut(parent) {
+                if self.resize_mode == ResizeMode::Proportional {
+                    let orientation = *orientation;
+                    self.resize_split_run_member(parent, node, orientation, |f| f + amount * 0.5);
+                    break;
+                }
                 let is_first = Some(node) == parent.first_child(&self.tree.map);
                 let delta = (amount as f32) * 0.5;
                 if is_first {
@@ -1588,6 +1930,30 @@ impl LayoutSystem for BspLayoutSystem {
 
     fn rebalance(&mut self, _layout: LayoutId) {}
 
+    fn equalize(&mut self, layout: LayoutId) {
+        let Some(state) = self.layouts.get(layout) else {
+            return;
+        };
+        let root = state.root;
+        self.equalize_node(root);
+    }
+
+    fn set_selection_ratio(&mut self, layout: LayoutId, ratio: f64) -> bool {
+        let Some(mut node) = self.selection_of_layout(layout) else {
+            return false;
+        };
+        while let Some(parent) = node.parent(&self.tree.map) {
+            let is_first = Some(node) == parent.first_child(&self.tree.map);
+            if let Some(NodeKind::Split { ratio: split_ratio, .. }) = self.kind.get_mut(parent) {
+                let ratio = ratio as f32;
+                *split_ratio = if is_first { ratio } else { 1.0 - ratio };
+                return true;
+            }
+            node = parent;
+        }
+        false
+    }
+
     fn toggle_tile_orientation(&mut self, layout: LayoutId) {
         let sel_snapshot = self.selection_of_layout(layout);
 
@@ -1622,4 +1988,12 @@ impl LayoutSystem for BspLayoutSystem {
             }
         }
     }
+
+    fn rotate_all(&mut self, layout: LayoutId) {
+        let Some(state) = self.layouts.get(layout) else {
+            return;
+        };
+        let root = state.root;
+        self.rotate_node(root);
+    }
 }
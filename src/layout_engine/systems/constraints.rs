@@ -1,3 +1,5 @@
+use tracing::debug;
+
 #[derive(Debug, Clone, Copy, Default)]
 pub(crate) struct AxisConstraints {
     pub min: f64,
@@ -63,6 +65,11 @@ pub(crate) fn solve_axis_lengths(items: &[AxisConstraints], usable: f64) -> Vec<
     let min_sum: f64 = min_indices.iter().map(|&idx| mins[idx]).sum();
     let remaining_for_mins = (usable - fixed_sum).max(0.0);
     if min_sum > remaining_for_mins && min_sum > 0.0 {
+        debug!(
+            min_sum,
+            available = remaining_for_mins,
+            "Window minimum sizes don't fit; scaling them down"
+        );
         let scale = remaining_for_mins / min_sum;
         for &idx in &min_indices {
             mins[idx] *= scale;
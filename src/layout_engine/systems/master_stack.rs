@@ -5,7 +5,9 @@ use objc2_core_foundation::CGRect;
 use serde::{Deserialize, Serialize};
 
 use crate::actor::app::WindowId;
-use crate::common::config::{MasterStackNewWindowPlacement, MasterStackSettings, MasterStackSide};
+use crate::common::config::{
+    MasterStackNewWindowPlacement, MasterStackSettings, MasterStackSide, MasterStackStackLayout,
+};
 use crate::layout_engine::systems::WindowLayoutConstraints;
 use crate::layout_engine::utils::compute_tiling_area;
 use crate::layout_engine::{
@@ -17,6 +19,16 @@ use crate::model::tree::NodeId;
 pub struct MasterStackLayoutSystem {
     inner: TraditionalLayoutSystem,
     settings: MasterStackSettings,
+    /// Ratio picked with `adjust_master_ratio`/`set_master_ratio`, until
+    /// `equalize` goes back to `settings.master_ratio`.
+    #[serde(default)]
+    master_ratio_override: Option<f64>,
+    /// Side picked with `set_master_side`. Kept when the settings are reloaded.
+    #[serde(default)]
+    master_side_override: Option<MasterStackSide>,
+    /// Side of layouts turned by `rotate_all`, ahead of the override above.
+    #[serde(default)]
+    rotated_sides: slotmap::SecondaryMap<LayoutId, MasterStackSide>,
 }
 
 impl Default for MasterStackLayoutSystem {
@@ -28,6 +40,9 @@ impl MasterStackLayoutSystem {
         Self {
             inner: TraditionalLayoutSystem::default(),
             settings,
+            master_ratio_override: None,
+            master_side_override: None,
+            rotated_sides: slotmap::SecondaryMap::new(),
         }
     }
 
@@ -35,10 +50,17 @@ impl MasterStackLayoutSystem {
         if self.settings == settings {
             return;
         }
-        let old_master_first = self.master_first();
-        self.settings = settings;
         let layouts: Vec<_> = self.inner.layout_roots.keys().collect();
-        for layout in layouts {
+        self.rebuild_layouts_after(layouts, |system| system.settings = settings);
+    }
+
+    /// Apply `change`, then rebuild `layouts` so their windows keep the same
+    /// master/stack order on whichever side the master ends up.
+    fn rebuild_layouts_after(&mut self, layouts: Vec<LayoutId>, change: impl FnOnce(&mut Self)) {
+        let old_master_first: Vec<_> =
+            layouts.iter().map(|&layout| self.master_first(layout)).collect();
+        change(self);
+        for (layout, old_master_first) in layouts.into_iter().zip(old_master_first) {
             if let Some(windows) =
                 self.windows_in_layout_by_container_with_order(layout, old_master_first)
             {
@@ -49,23 +71,31 @@ impl MasterStackLayoutSystem {
         }
     }
 
-    fn root_orientation(&self) -> Orientation {
-        match self.settings.master_side {
+    fn master_side(&self, layout: LayoutId) -> MasterStackSide {
+        self.rotated_sides
+            .get(layout)
+            .copied()
+            .or(self.master_side_override)
+            .unwrap_or(self.settings.master_side)
+    }
+
+    fn root_orientation(&self, layout: LayoutId) -> Orientation {
+        match self.master_side(layout) {
             MasterStackSide::Left | MasterStackSide::Right => Orientation::Horizontal,
             MasterStackSide::Top | MasterStackSide::Bottom => Orientation::Vertical,
         }
     }
 
-    fn container_orientation(&self) -> Orientation {
-        match self.root_orientation() {
+    fn container_orientation(&self, layout: LayoutId) -> Orientation {
+        match self.root_orientation(layout) {
             Orientation::Horizontal => Orientation::Vertical,
             Orientation::Vertical => Orientation::Horizontal,
         }
     }
 
-    fn master_first(&self) -> bool {
+    fn master_first(&self, layout: LayoutId) -> bool {
         matches!(
-            self.settings.master_side,
+            self.master_side(layout),
             MasterStackSide::Left | MasterStackSide::Top
         )
     }
@@ -78,7 +108,7 @@ impl MasterStackLayoutSystem {
     }
 
     fn windows_in_layout_by_container(&self, layout: LayoutId) -> Vec<WindowId> {
-        self.windows_in_layout_by_container_with_order(layout, self.master_first())
+        self.windows_in_layout_by_container_with_order(layout, self.master_first(layout))
             .unwrap_or_else(|| self.all_windows_in_layout(layout))
     }
 
@@ -138,14 +168,15 @@ impl MasterStackLayoutSystem {
         candidate.traverse_preorder(map).find_map(|node| self.inner.window_at(node))
     }
 
-    fn create_containers(&mut self, root: NodeId) -> (NodeId, NodeId) {
-        self.inner.set_layout(root, LayoutKind::from(self.root_orientation()));
-        let container_kind = LayoutKind::from(self.container_orientation());
+    fn create_containers(&mut self, layout: LayoutId) -> (NodeId, NodeId) {
+        let root = self.inner.root(layout);
+        self.inner.set_layout(root, LayoutKind::from(self.root_orientation(layout)));
+        let container_kind = LayoutKind::from(self.container_orientation(layout));
         let first = self.inner.tree.mk_node().push_back(root);
         self.inner.set_layout(first, container_kind);
         let second = self.inner.tree.mk_node().push_back(root);
         self.inner.set_layout(second, container_kind);
-        if self.master_first() {
+        if self.master_first(layout) {
             (first, second)
         } else {
             (second, first)
@@ -153,7 +184,7 @@ impl MasterStackLayoutSystem {
     }
 
     fn apply_master_ratio(&mut self, root: NodeId, master: NodeId, stack: NodeId) {
-        let ratio = self.settings.master_ratio.clamp(0.05, 0.95) as f32;
+        let ratio = self.master_ratio().clamp(0.05, 0.95) as f32;
         let total = 2.0_f32;
         let master_size = (ratio * total).max(0.05);
         let stack_size = (total - master_size).max(0.05);
@@ -173,21 +204,22 @@ impl MasterStackLayoutSystem {
         }
         let children: Vec<_> = root.children(self.inner.map()).collect();
         if children.len() != 2 {
-            let (master, stack) = self.create_containers(root);
+            let (master, stack) = self.create_containers(layout);
             self.apply_master_ratio(root, master, stack);
             return (root, master, stack);
         }
         let first = children[0];
         let second = children[1];
-        self.inner.set_layout(root, LayoutKind::from(self.root_orientation()));
-        let container_kind = LayoutKind::from(self.container_orientation());
+        self.inner.set_layout(root, LayoutKind::from(self.root_orientation(layout)));
+        let container_kind = LayoutKind::from(self.container_orientation(layout));
         self.inner.set_layout(first, container_kind);
         self.inner.set_layout(second, container_kind);
-        let (master, stack) = if self.master_first() {
+        let (master, stack) = if self.master_first(layout) {
             (first, second)
         } else {
             (second, first)
         };
+        self.apply_stack_layout(layout, stack);
         self.apply_master_ratio(root, master, stack);
         (root, master, stack)
     }
@@ -204,7 +236,7 @@ impl MasterStackLayoutSystem {
         for child in children {
             child.detach(&mut self.inner.tree).remove();
         }
-        let (master, stack) = self.create_containers(root);
+        let (master, stack) = self.create_containers(layout);
         for (idx, wid) in windows.iter().enumerate() {
             let target = if idx < self.settings.master_count {
                 master
@@ -261,6 +293,19 @@ impl MasterStackLayoutSystem {
                 }
             }
         }
+
+        self.apply_stack_layout(layout, stack);
+    }
+
+    fn apply_stack_layout(&mut self, layout: LayoutId, stack: NodeId) {
+        let tabbed = self.settings.stack_layout == MasterStackStackLayout::Tabbed
+            && self.windows_in_container(stack).len() > self.settings.stack_layout_threshold;
+        let kind = if tabbed {
+            LayoutKind::stack_with_offset(self.container_orientation(layout))
+        } else {
+            LayoutKind::from(self.container_orientation(layout))
+        };
+        self.inner.set_layout(stack, kind);
     }
 
     fn move_window_to_container(
@@ -332,12 +377,24 @@ impl MasterStackLayoutSystem {
         self.enforce_master_count(layout, master, stack);
     }
 
-    pub fn adjust_master_ratio(&mut self, _layout: LayoutId, delta: f64) {
-        let next = (self.settings.master_ratio + delta).clamp(0.05, 0.95);
-        if (next - self.settings.master_ratio).abs() < f64::EPSILON {
+    fn master_ratio(&self) -> f64 {
+        self.master_ratio_override.unwrap_or(self.settings.master_ratio)
+    }
+
+    pub fn adjust_master_ratio(&mut self, layout: LayoutId, delta: f64) {
+        self.set_master_ratio(layout, self.master_ratio() + delta);
+    }
+
+    pub fn set_master_ratio(&mut self, _layout: LayoutId, ratio: f64) {
+        let next = ratio.clamp(0.05, 0.95);
+        if (next - self.master_ratio()).abs() < f64::EPSILON {
             return;
         }
-        self.settings.master_ratio = next;
+        self.master_ratio_override = Some(next);
+        self.normalize_all_layouts();
+    }
+
+    fn normalize_all_layouts(&mut self) {
         let layouts: Vec<_> = self.inner.layout_roots.keys().collect();
         for layout in layouts {
             self.normalize_layout(layout);
@@ -357,6 +414,34 @@ impl MasterStackLayoutSystem {
         }
     }
 
+    /// Step the master count to the entry after the current one in
+    /// `master_count_cycle`, wrapping around. A count missing from the list
+    /// jumps to its first entry.
+    pub fn cycle_master_count(&mut self, layout: LayoutId) {
+        let cycle: Vec<usize> =
+            self.settings.master_count_cycle.iter().copied().filter(|&n| n >= 1).collect();
+        let Some(&first) = cycle.first() else {
+            return;
+        };
+        let current = self.settings.master_count;
+        let next = cycle
+            .iter()
+            .position(|&n| n == current)
+            .map_or(first, |idx| cycle[(idx + 1) % cycle.len()]);
+        self.adjust_master_count(layout, next as i32 - current as i32);
+    }
+
+    /// Move the master area to `side` in every layout of the workspace,
+    /// keeping the current ratio, count and window order. The side sticks
+    /// until it is set again, even if the settings are reloaded.
+    pub fn set_master_side(&mut self, _layout: LayoutId, side: MasterStackSide) {
+        let layouts: Vec<_> = self.inner.layout_roots.keys().collect();
+        self.rebuild_layouts_after(layouts, |system| {
+            system.master_side_override = Some(side);
+            system.rotated_sides.clear();
+        });
+    }
+
     pub fn promote_to_master(&mut self, layout: LayoutId) {
         let (_root, master, stack) = self.ensure_structure(layout);
         let Some(wid) = self.inner.selected_window(layout) else {
@@ -372,6 +457,13 @@ impl MasterStackLayoutSystem {
         self.enforce_master_count(layout, master, stack);
     }
 
+    /// Give every window in the stack area an equal share of it, leaving the
+    /// master ratio alone.
+    pub fn equalize_stack(&mut self, layout: LayoutId) {
+        let (_root, _master, stack) = self.ensure_structure(layout);
+        self.inner.rebalance_node(stack);
+    }
+
     pub fn swap_master_stack(&mut self, layout: LayoutId) {
         let (_root, master, stack) = self.ensure_structure(layout);
         let (Some(master_wid), Some(stack_wid)) = (
@@ -387,6 +479,13 @@ impl MasterStackLayoutSystem {
         }
     }
 
+    pub(crate) fn selection_path(
+        &self,
+        layout: LayoutId,
+    ) -> Vec<crate::layout_engine::engine::SelectionPathEntry> {
+        self.inner.selection_path(layout)
+    }
+
     pub(crate) fn collect_group_containers_in_selection_path(
         &self,
         layout: LayoutId,
@@ -434,19 +533,25 @@ impl LayoutSystem for MasterStackLayoutSystem {
     fn create_layout(&mut self) -> LayoutId {
         let layout = self.inner.create_layout();
         let root = self.inner.root(layout);
-        let (master, stack) = self.create_containers(root);
+        let (master, stack) = self.create_containers(layout);
         self.apply_master_ratio(root, master, stack);
         layout
     }
 
     fn clone_layout(&mut self, layout: LayoutId) -> LayoutId {
         let cloned = self.inner.clone_layout(layout);
+        if let Some(&side) = self.rotated_sides.get(layout) {
+            self.rotated_sides.insert(cloned, side);
+        }
         let (_root, master, stack) = self.ensure_structure(cloned);
         self.enforce_master_count(cloned, master, stack);
         cloned
     }
 
-    fn remove_layout(&mut self, layout: LayoutId) { self.inner.remove_layout(layout); }
+    fn remove_layout(&mut self, layout: LayoutId) {
+        self.rotated_sides.remove(layout);
+        self.inner.remove_layout(layout);
+    }
 
     fn draw_tree(&self, layout: LayoutId) -> String {
         let root = self.inner.root(layout);
@@ -457,7 +562,7 @@ impl LayoutSystem for MasterStackLayoutSystem {
         if children.iter().any(|&child| self.inner.tree.data.window.at(child).is_some()) {
             return self.inner.draw_tree(layout);
         }
-        let (master, stack) = if self.master_first() {
+        let (master, stack) = if self.master_first(layout) {
             (children[0], children[1])
         } else {
             (children[1], children[0])
@@ -482,7 +587,7 @@ impl LayoutSystem for MasterStackLayoutSystem {
         let root = self.inner.root(layout);
         let children: Vec<_> = root.children(self.inner.map()).collect();
         if children.len() == 2 && children.iter().all(|&c| self.inner.window_at(c).is_none()) {
-            let (master, stack) = if self.master_first() {
+            let (master, stack) = if self.master_first(layout) {
                 (children[0], children[1])
             } else {
                 (children[1], children[0])
@@ -544,6 +649,14 @@ impl LayoutSystem for MasterStackLayoutSystem {
         self.inner.window_in_direction(layout, direction)
     }
 
+    fn wrap_focus(
+        &mut self,
+        layout: LayoutId,
+        direction: Direction,
+    ) -> (Option<WindowId>, Vec<WindowId>) {
+        self.inner.wrap_focus(layout, direction)
+    }
+
     fn add_window_after_selection(&mut self, layout: LayoutId, wid: WindowId) {
         let (_root, master, stack) = self.ensure_structure(layout);
         let master_windows = self.windows_in_container(master);
@@ -660,13 +773,42 @@ impl LayoutSystem for MasterStackLayoutSystem {
         self.inner.swap_windows(layout, a, b)
     }
 
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        wid: WindowId,
+        target: WindowId,
+        direction: Direction,
+    ) -> bool {
+        if wid == target || !self.inner.contains_window(layout, target) {
+            return false;
+        }
+        let mut windows: Vec<_> = self
+            .windows_in_layout_by_container(layout)
+            .into_iter()
+            .filter(|&w| w != wid)
+            .collect();
+        let Some(idx) = windows.iter().position(|&w| w == target) else {
+            return false;
+        };
+        self.inner.remove_window(wid);
+        let insert_at = match direction {
+            Direction::Left | Direction::Up => idx,
+            Direction::Right | Direction::Down => idx + 1,
+        };
+        windows.insert(insert_at, wid);
+        self.rebuild_layout_with_windows(layout, &windows);
+        let _ = self.inner.select_window(layout, wid);
+        true
+    }
+
     fn move_selection(&mut self, layout: LayoutId, direction: Direction) -> bool {
         let (_root, master, stack) = self.ensure_structure(layout);
         let Some(container) = self.focused_container(layout, master, stack) else {
             return false;
         };
-        let container_axis = self.container_orientation();
-        let (towards_master, towards_stack) = match self.settings.master_side {
+        let container_axis = self.container_orientation(layout);
+        let (towards_master, towards_stack) = match self.master_side(layout) {
             MasterStackSide::Left => (direction == Direction::Left, direction == Direction::Right),
             MasterStackSide::Right => (direction == Direction::Right, direction == Direction::Left),
             MasterStackSide::Top => (direction == Direction::Up, direction == Direction::Down),
@@ -726,6 +868,10 @@ impl LayoutSystem for MasterStackLayoutSystem {
         self.inner.has_any_fullscreen_node(layout)
     }
 
+    fn fullscreen_windows(&self, layout: LayoutId) -> Vec<WindowId> {
+        self.inner.fullscreen_windows(layout)
+    }
+
     fn join_selection_with_direction(&mut self, layout: LayoutId, direction: Direction) {
         let _ = direction;
         self.normalize_layout(layout);
@@ -755,6 +901,10 @@ impl LayoutSystem for MasterStackLayoutSystem {
         self.inner.parent_of_selection_is_stacked(layout)
     }
 
+    fn set_stack_representative(&mut self, layout: LayoutId) -> bool {
+        self.inner.set_stack_representative(layout)
+    }
+
     fn unjoin_selection(&mut self, layout: LayoutId) { self.normalize_layout(layout); }
 
     fn resize_selection_by(&mut self, layout: LayoutId, amount: f64) {
@@ -764,5 +914,197 @@ impl LayoutSystem for MasterStackLayoutSystem {
 
     fn rebalance(&mut self, layout: LayoutId) { self.normalize_layout(layout); }
 
+    // The master ratio is shared by every layout of the workspace, so going
+    // back to the configured one relays them all.
+    fn equalize(&mut self, layout: LayoutId) {
+        self.inner.rebalance(layout);
+        self.master_ratio_override = None;
+        self.normalize_all_layouts();
+    }
+
+    // The master/stack split is owned by `master_ratio`.
+    fn set_selection_ratio(&mut self, _layout: LayoutId, _ratio: f64) -> bool { false }
+
     fn toggle_tile_orientation(&mut self, layout: LayoutId) { self.normalize_layout(layout); }
+
+    fn rotate_all(&mut self, layout: LayoutId) {
+        let side = match self.master_side(layout) {
+            MasterStackSide::Left => MasterStackSide::Top,
+            MasterStackSide::Top => MasterStackSide::Left,
+            MasterStackSide::Right => MasterStackSide::Bottom,
+            MasterStackSide::Bottom => MasterStackSide::Right,
+        };
+        self.rebuild_layouts_after(vec![layout], |system| {
+            system.rotated_sides.insert(layout, side);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use objc2_core_foundation::{CGPoint, CGSize};
+
+    use super::*;
+    use crate::common::collections::HashMap;
+    use crate::common::config::GapSettings;
+
+    fn w(idx: u32) -> WindowId { WindowId::new(1, idx) }
+
+    #[test]
+    fn equalize_goes_back_to_the_configured_master_ratio() {
+        let mut system = MasterStackLayoutSystem::default();
+        let layout = system.create_layout();
+        for idx in 1..=3 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 900.0));
+        let frames = |system: &MasterStackLayoutSystem| {
+            system.calculate_layout(
+                layout,
+                screen,
+                0.0,
+                &HashMap::default(),
+                &GapSettings::default(),
+                0.0,
+                Default::default(),
+                Default::default(),
+            )
+        };
+
+        let configured = frames(&system);
+        system.adjust_master_ratio(layout, 0.2);
+        assert_ne!(frames(&system), configured);
+
+        system.equalize(layout);
+        assert_eq!(frames(&system), configured);
+    }
+
+    #[test]
+    fn master_side_set_at_runtime_survives_a_settings_reload() {
+        let mut system = MasterStackLayoutSystem::default();
+        let layout = system.create_layout();
+        for idx in 1..=3 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+
+        system.set_master_side(layout, MasterStackSide::Right);
+        system.update_settings(MasterStackSettings {
+            master_ratio: 0.6,
+            ..MasterStackSettings::default()
+        });
+
+        assert_eq!(system.master_side(layout), MasterStackSide::Right);
+        let (root, master, _) = system.ensure_structure(layout);
+        assert_eq!(root.children(system.inner.map()).last(), Some(master));
+    }
+
+    #[test]
+    fn rotate_all_only_turns_the_given_layout() {
+        let mut system = MasterStackLayoutSystem::default();
+        let rotated = system.create_layout();
+        let other = system.create_layout();
+        for layout in [rotated, other] {
+            for idx in 1..=3 {
+                system.add_window_after_selection(layout, w(idx));
+            }
+        }
+
+        system.rotate_all(rotated);
+        assert_eq!(system.master_side(rotated), MasterStackSide::Top);
+        assert_eq!(system.master_side(other), MasterStackSide::Left);
+        let (root, _, _) = system.ensure_structure(rotated);
+        assert_eq!(system.inner.layout(root), LayoutKind::Vertical);
+        let (root, _, _) = system.ensure_structure(other);
+        assert_eq!(system.inner.layout(root), LayoutKind::Horizontal);
+
+        let cloned = system.clone_layout(rotated);
+        assert_eq!(system.master_side(cloned), MasterStackSide::Top);
+
+        system.rotate_all(rotated);
+        assert_eq!(system.master_side(rotated), MasterStackSide::Left);
+    }
+
+    #[test]
+    fn equalize_stack_evens_out_stack_windows_and_keeps_master() {
+        let mut system = MasterStackLayoutSystem::default();
+        let layout = system.create_layout();
+        for idx in 1..=4 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+        let (_root, master, stack) = system.ensure_structure(layout);
+        let master_windows = system.windows_in_container(master);
+        let stack_windows = system.windows_in_container(stack);
+        assert!(stack_windows.len() >= 2);
+
+        let first = stack.first_child(system.inner.map()).unwrap();
+        system.inner.tree.data.layout.info[first].size += 1.5;
+        system.inner.tree.data.layout.info[stack].total += 1.5;
+
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 900.0));
+        let frames = |system: &MasterStackLayoutSystem| -> HashMap<WindowId, CGRect> {
+            system
+                .calculate_layout(
+                    layout,
+                    screen,
+                    0.0,
+                    &HashMap::default(),
+                    &GapSettings::default(),
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                )
+                .into_iter()
+                .collect()
+        };
+        let stack_sizes_even = |frames: &HashMap<WindowId, CGRect>| {
+            let first = frames[&stack_windows[0]].size;
+            stack_windows.iter().all(|wid| {
+                let size = frames[wid].size;
+                (size.width - first.width).abs() < 1.0 && (size.height - first.height).abs() < 1.0
+            })
+        };
+
+        let before = frames(&system);
+        assert!(!stack_sizes_even(&before));
+
+        system.equalize_stack(layout);
+        let after = frames(&system);
+        assert!(stack_sizes_even(&after));
+        for wid in &master_windows {
+            assert_eq!(before[wid], after[wid]);
+        }
+    }
+
+    #[test]
+    fn tabbed_stack_layout_kicks_in_past_the_threshold_and_survives_promotion() {
+        let mut system = MasterStackLayoutSystem::new(MasterStackSettings {
+            stack_layout: MasterStackStackLayout::Tabbed,
+            stack_layout_threshold: 2,
+            new_window_placement: MasterStackNewWindowPlacement::Stack,
+            ..MasterStackSettings::default()
+        });
+        let layout = system.create_layout();
+        for idx in 1..=3 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+        let (_root, _master, stack) = system.ensure_structure(layout);
+        assert!(!system.inner.layout(stack).is_stacked());
+
+        system.add_window_after_selection(layout, w(4));
+        let (_root, master, stack) = system.ensure_structure(layout);
+        assert_eq!(system.windows_in_container(stack).len(), 3);
+        assert!(system.inner.layout(stack).is_stacked());
+
+        assert!(system.select_window(layout, w(4)));
+        system.promote_to_master(layout);
+        let (_root, master_after, stack_after) = system.ensure_structure(layout);
+        assert_eq!((master_after, stack_after), (master, stack));
+        assert_eq!(system.windows_in_container(master), vec![w(4)]);
+        assert!(system.windows_in_container(stack).contains(&w(1)));
+        assert!(system.inner.layout(stack).is_stacked());
+
+        system.remove_window(w(2));
+        let (_root, _master, stack) = system.ensure_structure(layout);
+        assert!(!system.inner.layout(stack).is_stacked());
+    }
 }
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::actor::app::{WindowId, pid_t};
 use crate::common::collections::{HashMap, HashSet};
-use crate::common::config::{ScrollingFocusNavigationStyle, ScrollingLayoutSettings};
+use crate::common::config::{ScrollSource, ScrollingFocusNavigationStyle, ScrollingLayoutSettings};
 use crate::layout_engine::systems::constraints::{AxisConstraints, solve_axis_lengths};
 use crate::layout_engine::systems::{LayoutSystem, WindowLayoutConstraints};
 use crate::layout_engine::utils::compute_tiling_area;
@@ -341,10 +341,22 @@ impl ScrollingLayoutSystem {
         (widths, starts)
     }
 
-    pub fn scroll_by_delta(&mut self, layout: LayoutId, delta: f64) -> Option<Direction> {
+    /// Scroll the strip by `delta` column steps. Deltas that come from an input
+    /// device are flipped when that device's inversion is configured.
+    pub fn scroll_by_delta(
+        &mut self,
+        layout: LayoutId,
+        delta: f64,
+        source: Option<ScrollSource>,
+    ) -> Option<Direction> {
         let min_ratio = self.settings.min_column_width_ratio;
         let max_ratio = self.settings.max_column_width_ratio;
-        let threshold = self.settings.gestures.workspace_switch_threshold;
+        let gestures = self.settings.gestures;
+        let delta = if source.is_some_and(|source| gestures.inverts(source)) {
+            -delta
+        } else {
+            delta
+        };
         let Some(state) = self.layout_state_mut(layout) else {
             return None;
         };
@@ -376,30 +388,38 @@ impl ScrollingLayoutSystem {
         let next = next_raw.clamp(min_offset, max_offset);
         state.scroll_offset_px.store(next.to_bits(), Ordering::Relaxed);
 
-        if next_raw < min_offset && delta < 0.0 {
-            let overscroll = (min_offset - next_raw) / step;
-            let accum =
-                f64::from_bits(state.overscroll_accumulation.load(Ordering::Relaxed)) + overscroll;
-            if accum >= threshold {
-                state.overscroll_accumulation.store(0.0f64.to_bits(), Ordering::Relaxed);
-                Some(Direction::Left)
-            } else {
-                state.overscroll_accumulation.store(accum.to_bits(), Ordering::Relaxed);
-                None
-            }
+        // The accumulator is signed: negative while pushing past the left edge,
+        // positive past the right edge. Reversing direction starts over so a
+        // back-and-forth wiggle never adds up to a workspace swipe.
+        let accumulated = f64::from_bits(state.overscroll_accumulation.load(Ordering::Relaxed));
+        let (direction, overscroll, prior) = if next_raw < min_offset && delta < 0.0 {
+            (
+                Direction::Left,
+                (min_offset - next_raw) / step,
+                (-accumulated).max(0.0),
+            )
         } else if next_raw > max_offset && delta > 0.0 {
-            let overscroll = (next_raw - max_offset) / step;
-            let accum =
-                f64::from_bits(state.overscroll_accumulation.load(Ordering::Relaxed)) + overscroll;
-            if accum >= threshold {
-                state.overscroll_accumulation.store(0.0f64.to_bits(), Ordering::Relaxed);
-                Some(Direction::Right)
-            } else {
-                state.overscroll_accumulation.store(accum.to_bits(), Ordering::Relaxed);
-                None
-            }
+            (
+                Direction::Right,
+                (next_raw - max_offset) / step,
+                accumulated.max(0.0),
+            )
         } else {
             state.overscroll_accumulation.store(0.0f64.to_bits(), Ordering::Relaxed);
+            return None;
+        };
+
+        let accum = prior + overscroll;
+        if accum >= gestures.overscroll_threshold(direction) {
+            state.overscroll_accumulation.store(0.0f64.to_bits(), Ordering::Relaxed);
+            Some(direction)
+        } else {
+            let signed = if direction == Direction::Left {
+                -accum
+            } else {
+                accum
+            };
+            state.overscroll_accumulation.store(signed.to_bits(), Ordering::Relaxed);
             None
         }
     }
@@ -454,6 +474,31 @@ impl ScrollingLayoutSystem {
         state.request_center_on_selected();
     }
 
+    /// Give the selected column this absolute width ratio, within the
+    /// configured bounds. Returns false without a selection.
+    pub fn set_selected_column_width_ratio(&mut self, layout: LayoutId, ratio: f64) -> bool {
+        let min_ratio = self.settings.min_column_width_ratio;
+        let max_ratio = self.settings.max_column_width_ratio;
+        let niri_navigation = matches!(
+            self.settings.focus_navigation_style,
+            ScrollingFocusNavigationStyle::Niri
+        );
+        let Some(state) = self.layout_state_mut(layout) else {
+            return false;
+        };
+        let Some((col_idx, _)) = state.selected_location() else {
+            return false;
+        };
+        let clamped = ratio.clamp(min_ratio, max_ratio).max(0.05);
+        state.columns[col_idx].width_offset = clamped - state.column_width_ratio;
+        if niri_navigation {
+            state.reveal_selected_without_direction();
+        } else {
+            state.align_scroll_to_selected();
+        }
+        true
+    }
+
     fn layout_state(&self, layout: LayoutId) -> Option<&LayoutState> { self.layouts.get(layout) }
 
     fn layout_state_mut(&mut self, layout: LayoutId) -> Option<&mut LayoutState> {
@@ -913,6 +958,45 @@ impl LayoutSystem for ScrollingLayoutSystem {
         (new_sel, raise)
     }
 
+    fn wrap_focus(
+        &mut self,
+        layout: LayoutId,
+        direction: Direction,
+    ) -> (Option<WindowId>, Vec<WindowId>) {
+        let niri_navigation = matches!(
+            self.settings.focus_navigation_style,
+            ScrollingFocusNavigationStyle::Niri
+        );
+        let Some(state) = self.layout_state_mut(layout) else {
+            return (None, vec![]);
+        };
+        let Some((col_idx, row_idx)) = state.selected_location() else {
+            return (None, vec![]);
+        };
+        let (target_col, target_row) = match direction {
+            Direction::Left => (state.columns.len() - 1, row_idx),
+            Direction::Right => (0, row_idx),
+            Direction::Up => (col_idx, state.columns[col_idx].windows.len() - 1),
+            Direction::Down => (col_idx, 0),
+        };
+        let column = &state.columns[target_col];
+        let Some(&new_sel) =
+            column.windows.get(target_row.min(column.windows.len().saturating_sub(1)))
+        else {
+            return (None, vec![]);
+        };
+        if state.selected == Some(new_sel) {
+            return (None, vec![]);
+        }
+        state.selected = Some(new_sel);
+        if niri_navigation {
+            state.reveal_selected_without_direction();
+        } else {
+            state.align_scroll_to_selected();
+        }
+        (Some(new_sel), state.columns[target_col].windows.clone())
+    }
+
     fn window_in_direction(&self, layout: LayoutId, direction: Direction) -> Option<WindowId> {
         let state = self.layout_state(layout)?;
         let (col_idx, row_idx) = state.selected_location()?;
@@ -1099,6 +1183,44 @@ impl LayoutSystem for ScrollingLayoutSystem {
         }
     }
 
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        wid: WindowId,
+        target: WindowId,
+        direction: Direction,
+    ) -> bool {
+        if wid == target || !self.contains_window(layout, target) {
+            return false;
+        }
+        self.remove_window(wid);
+        let Some(state) = self.layout_state_mut(layout) else {
+            return false;
+        };
+        let Some((col_idx, row_idx)) = state.locate(target) else {
+            return false;
+        };
+        match direction {
+            Direction::Left | Direction::Right => {
+                let insert_at = if direction == Direction::Left {
+                    col_idx
+                } else {
+                    col_idx + 1
+                };
+                state.columns.insert(insert_at, Column {
+                    windows: vec![wid],
+                    width_offset: 0.0,
+                });
+            }
+            Direction::Up => state.columns[col_idx].windows.insert(row_idx, wid),
+            Direction::Down => state.columns[col_idx].windows.insert(row_idx + 1, wid),
+        }
+        state.selected = Some(wid);
+        state.align_scroll_to_selected();
+        state.clamp_scroll_offset();
+        true
+    }
+
     fn swap_windows(&mut self, layout: LayoutId, a: WindowId, b: WindowId) -> bool {
         let Some(state) = self.layout_state_mut(layout) else {
             return false;
@@ -1203,6 +1325,18 @@ impl LayoutSystem for ScrollingLayoutSystem {
         !state.fullscreen.is_empty() || !state.fullscreen_within_gaps.is_empty()
     }
 
+    fn fullscreen_windows(&self, layout: LayoutId) -> Vec<WindowId> {
+        let Some(state) = self.layout_state(layout) else {
+            return Vec::new();
+        };
+        state
+            .fullscreen
+            .iter()
+            .chain(state.fullscreen_within_gaps.iter())
+            .copied()
+            .collect()
+    }
+
     fn join_selection_with_direction(&mut self, layout: LayoutId, direction: Direction) {
         let Some(state) = self.layout_state_mut(layout) else {
             return;
@@ -1297,6 +1431,8 @@ impl LayoutSystem for ScrollingLayoutSystem {
         state.columns[col_idx].windows.len() > 1
     }
 
+    fn set_stack_representative(&mut self, _layout: LayoutId) -> bool { false }
+
     fn unjoin_selection(&mut self, layout: LayoutId) {
         let Some(state) = self.layout_state_mut(layout) else {
             return;
@@ -1350,7 +1486,20 @@ impl LayoutSystem for ScrollingLayoutSystem {
 
     fn rebalance(&mut self, _layout: LayoutId) {}
 
+    fn equalize(&mut self, layout: LayoutId) {
+        let Some(state) = self.layout_state_mut(layout) else {
+            return;
+        };
+        for column in &mut state.columns {
+            column.width_offset = 0.0;
+        }
+    }
+
+    fn set_selection_ratio(&mut self, _layout: LayoutId, _ratio: f64) -> bool { false }
+
     fn toggle_tile_orientation(&mut self, _layout: LayoutId) {}
+
+    fn rotate_all(&mut self, _layout: LayoutId) {}
 }
 
 #[cfg(test)]
@@ -1863,6 +2012,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn set_selected_column_width_ratio_is_absolute() {
+        let settings = ScrollingLayoutSettings::default();
+        let base = settings.column_width_ratio;
+        let (mut absolute, layout, _, w2) = setup_two_windows(settings.clone());
+        let (mut relative, relative_layout, _, _) = setup_two_windows(settings);
+
+        let gaps = GapSettings::default();
+        let width = |system: &ScrollingLayoutSystem, layout| {
+            frame_for(&render(system, layout, screen(1000.0, 800.0), &gaps), w2).size.width
+        };
+
+        // SetContainerRatio stays a no-op in Scrolling.
+        assert!(!absolute.set_selection_ratio(layout, base + 0.1));
+
+        assert!(absolute.set_selected_column_width_ratio(layout, base + 0.1));
+        let first = width(&absolute, layout);
+        // Setting the same ratio again doesn't accumulate.
+        assert!(absolute.set_selected_column_width_ratio(layout, base + 0.1));
+        assert!((width(&absolute, layout) - first).abs() < 0.01);
+
+        relative.resize_selection_by(relative_layout, 0.1);
+        assert!((width(&relative, relative_layout) - first).abs() < 0.01);
+    }
+
     #[test]
     fn selecting_column_in_niri_mode_reveals_without_centering() {
         let mut settings = ScrollingLayoutSettings::default();
@@ -2013,4 +2187,48 @@ mod tests {
             after.origin.x
         );
     }
+
+    #[test]
+    fn overscroll_uses_per_side_threshold_and_resets_on_reversal() {
+        let mut settings = ScrollingLayoutSettings::default();
+        settings.gestures.workspace_switch_threshold = 0.6;
+        settings.gestures.overscroll_threshold_left = Some(0.3);
+        let mut system = ScrollingLayoutSystem::new(&settings);
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, wid(1, 1));
+        let _ = render(&system, layout, screen(1000.0, 800.0), &GapSettings::default());
+
+        assert_eq!(system.scroll_by_delta(layout, -0.2, None), None);
+        assert_eq!(system.scroll_by_delta(layout, -0.2, None), Some(Direction::Left));
+
+        assert_eq!(system.scroll_by_delta(layout, 0.4, None), None);
+        assert_eq!(system.scroll_by_delta(layout, -0.1, None), None);
+        assert_eq!(
+            system.scroll_by_delta(layout, 0.4, None),
+            None,
+            "reversing direction should restart accumulation"
+        );
+        assert_eq!(system.scroll_by_delta(layout, 0.3, None), Some(Direction::Right));
+    }
+
+    #[test]
+    fn scroll_inversion_follows_the_input_source() {
+        let mut settings = ScrollingLayoutSettings::default();
+        settings.gestures.invert_horizontal = true;
+        let mut system = ScrollingLayoutSystem::new(&settings);
+        let layout = system.create_layout();
+        system.add_window_after_selection(layout, wid(1, 1));
+        let _ = render(&system, layout, screen(1000.0, 800.0), &GapSettings::default());
+
+        // The trackpad is inverted, so pushing right runs past the left edge.
+        assert_eq!(
+            system.scroll_by_delta(layout, 1.0, Some(ScrollSource::Trackpad)),
+            Some(Direction::Left)
+        );
+        assert_eq!(
+            system.scroll_by_delta(layout, 1.0, Some(ScrollSource::MouseWheel)),
+            Some(Direction::Right)
+        );
+        assert_eq!(system.scroll_by_delta(layout, -1.0, None), Some(Direction::Left));
+    }
 }
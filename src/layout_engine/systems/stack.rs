@@ -96,6 +96,13 @@ impl StackLayoutSystem {
         self.inner.set_layout(root, next);
     }
 
+    pub(crate) fn selection_path(
+        &self,
+        layout: LayoutId,
+    ) -> Vec<crate::layout_engine::engine::SelectionPathEntry> {
+        self.inner.selection_path(layout)
+    }
+
     pub(crate) fn collect_group_containers_in_selection_path(
         &self,
         layout: LayoutId,
@@ -210,6 +217,14 @@ impl LayoutSystem for StackLayoutSystem {
         self.inner.window_in_direction(layout, direction)
     }
 
+    fn wrap_focus(
+        &mut self,
+        layout: LayoutId,
+        direction: Direction,
+    ) -> (Option<WindowId>, Vec<WindowId>) {
+        self.inner.wrap_focus(layout, direction)
+    }
+
     fn add_window_after_selection(&mut self, layout: LayoutId, wid: WindowId) {
         self.normalize_layout(layout);
         let node = self.inner.add_window_under(layout, self.inner.root(layout), wid);
@@ -278,6 +293,20 @@ impl LayoutSystem for StackLayoutSystem {
         self.inner.swap_windows(layout, a, b)
     }
 
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        wid: WindowId,
+        target: WindowId,
+        direction: Direction,
+    ) -> bool {
+        let inserted = self.inner.insert_window_beside(layout, wid, target, direction);
+        if inserted {
+            self.normalize_layout(layout);
+        }
+        inserted
+    }
+
     fn move_selection(&mut self, layout: LayoutId, direction: Direction) -> bool {
         let moved = self.inner.move_selection(layout, direction);
         if moved {
@@ -310,6 +339,10 @@ impl LayoutSystem for StackLayoutSystem {
         self.inner.has_any_fullscreen_node(layout)
     }
 
+    fn fullscreen_windows(&self, layout: LayoutId) -> Vec<WindowId> {
+        self.inner.fullscreen_windows(layout)
+    }
+
     fn join_selection_with_direction(&mut self, _layout: LayoutId, _direction: Direction) {}
 
     fn apply_stacking_to_parent_of_selection(
@@ -334,15 +367,25 @@ impl LayoutSystem for StackLayoutSystem {
         self.inner.layout(root).is_stacked()
     }
 
+    fn set_stack_representative(&mut self, layout: LayoutId) -> bool {
+        self.inner.set_stack_representative(layout)
+    }
+
     fn unjoin_selection(&mut self, _layout: LayoutId) {}
 
     fn resize_selection_by(&mut self, _layout: LayoutId, _amount: f64) {}
 
     fn rebalance(&mut self, _layout: LayoutId) {}
 
+    fn equalize(&mut self, _layout: LayoutId) {}
+
+    fn set_selection_ratio(&mut self, _layout: LayoutId, _ratio: f64) -> bool { false }
+
     fn toggle_tile_orientation(&mut self, layout: LayoutId) {
         self.toggle_root_stack_orientation(layout);
     }
+
+    fn rotate_all(&mut self, layout: LayoutId) { self.toggle_root_stack_orientation(layout); }
 }
 
 #[cfg(test)]
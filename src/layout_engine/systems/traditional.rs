@@ -4,6 +4,7 @@ use tracing::warn;
 
 use crate::actor::app::{WindowId, pid_t};
 use crate::common::collections::HashMap;
+use crate::common::config::ResizeMode;
 use crate::layout_engine::systems::constraints::{AxisConstraints, solve_axis_lengths};
 use crate::layout_engine::systems::{LayoutSystem, WindowLayoutConstraints};
 use crate::layout_engine::utils::compute_tiling_area;
@@ -16,6 +17,8 @@ use crate::sys::geometry::Round;
 pub struct TraditionalLayoutSystem {
     pub(crate) tree: Tree<Components>,
     pub(crate) layout_roots: slotmap::SlotMap<LayoutId, OwnedNode>,
+    #[serde(skip)]
+    resize_mode: ResizeMode,
 }
 
 impl Default for TraditionalLayoutSystem {
@@ -23,11 +26,14 @@ impl Default for TraditionalLayoutSystem {
         Self {
             tree: Tree::with_observer(Components::default()),
             layout_roots: Default::default(),
+            resize_mode: ResizeMode::default(),
         }
     }
 }
 
 impl TraditionalLayoutSystem {
+    pub fn update_settings(&mut self, resize_mode: ResizeMode) { self.resize_mode = resize_mode; }
+
     fn find_best_focus_target(&self, node: NodeId) -> Option<(NodeId, WindowId)> {
         if let Some(wid) = self.tree.data.window.at(node) {
             return Some((node, wid));
@@ -536,24 +542,7 @@ impl LayoutSystem for TraditionalLayoutSystem {
     ) -> (Option<WindowId>, Vec<WindowId>) {
         let selection = self.selection(layout);
         if let Some(new_node) = self.traverse_internal(selection, direction) {
-            let focus_target = self.find_best_focus_target(new_node);
-            let Some((focus_node, focus_window)) = focus_target else {
-                return (None, vec![]);
-            };
-            let map = &self.tree.map;
-            let mut highest_revealed = focus_node;
-
-            for (node, parent) in focus_node.ancestors_with_parent(map) {
-                let Some(parent) = parent else { break };
-                let parent_layout = self.layout(parent);
-                if self.tree.data.selection.select_locally(map, node) {
-                    if parent_layout.is_group() {
-                        highest_revealed = node;
-                    }
-                }
-            }
-            let raise_windows = self.visible_windows_under_internal(highest_revealed);
-            (Some(focus_window), raise_windows)
+            self.focus_node(new_node)
         } else {
             (None, vec![])
         }
@@ -563,6 +552,18 @@ impl LayoutSystem for TraditionalLayoutSystem {
         self.window_in_direction_from(self.root(layout), direction)
     }
 
+    fn wrap_focus(
+        &mut self,
+        layout: LayoutId,
+        direction: Direction,
+    ) -> (Option<WindowId>, Vec<WindowId>) {
+        let selection = self.selection(layout);
+        match self.wrap_target(selection, direction) {
+            Some(target) if target != selection => self.focus_node(target),
+            _ => (None, vec![]),
+        }
+    }
+
     fn add_window_after_selection(&mut self, layout: LayoutId, wid: WindowId) {
         let selection = self.selection(layout);
         let node = if selection.parent(self.map()).is_none() {
@@ -753,6 +754,21 @@ impl LayoutSystem for TraditionalLayoutSystem {
             .any(|node| self.tree.data.layout.is_effectively_fullscreen(node))
     }
 
+    fn fullscreen_windows(&self, layout: LayoutId) -> Vec<WindowId> {
+        let root = self.root(layout);
+        let mut windows = Vec::new();
+        for node in root.traverse_preorder(&self.tree.map) {
+            if self.tree.data.layout.is_effectively_fullscreen(node) {
+                for wid in self.visible_windows_under_internal(node) {
+                    if !windows.contains(&wid) {
+                        windows.push(wid);
+                    }
+                }
+            }
+        }
+        windows
+    }
+
     fn join_selection_with_direction(&mut self, layout: LayoutId, direction: Direction) {
         let mut selection = self.selection(layout);
 
@@ -945,6 +961,20 @@ impl LayoutSystem for TraditionalLayoutSystem {
         vec![]
     }
 
+    fn set_stack_representative(&mut self, layout: LayoutId) -> bool {
+        let selection = self.selection(layout);
+        let map = &self.tree.map;
+        let Some((member, container)) =
+            selection.ancestors_with_parent(map).find_map(|(node, parent)| {
+                parent.filter(|&p| self.layout(p).is_stacked()).map(|p| (node, p))
+            })
+        else {
+            return false;
+        };
+        self.tree.data.selection.set_representative(map, container, member);
+        true
+    }
+
     fn parent_of_selection_is_stacked(&self, layout: LayoutId) -> bool {
         let selection = self.selection(layout);
 
@@ -1038,6 +1068,57 @@ impl LayoutSystem for TraditionalLayoutSystem {
         self.rebalance_node(root)
     }
 
+    fn equalize(&mut self, layout: LayoutId) { self.rebalance(layout) }
+
+    fn set_selection_ratio(&mut self, layout: LayoutId, ratio: f64) -> bool {
+        let selection = self.selection(layout);
+        let Some(node) = selection.ancestors(&self.tree.map).find(|&node| {
+            node.parent(&self.tree.map).is_some_and(|parent| {
+                !self.tree.data.layout.kind(parent).is_group()
+                    && parent.children(&self.tree.map).nth(1).is_some()
+            })
+        }) else {
+            return false;
+        };
+        self.tree.data.layout.set_proportion(&self.tree.map, node, ratio as f32);
+        true
+    }
+
+    fn insert_window_beside(
+        &mut self,
+        layout: LayoutId,
+        wid: WindowId,
+        target: WindowId,
+        direction: Direction,
+    ) -> bool {
+        if wid == target || self.tree.data.window.node_for(layout, target).is_none() {
+            return false;
+        }
+        self.remove_window(wid);
+        // Removing the window may have collapsed containers; look the target up again.
+        let Some(target_node) = self.tree.data.window.node_for(layout, target) else {
+            return false;
+        };
+
+        let orientation = direction.orientation();
+        let parent = match target_node.parent(self.map()) {
+            Some(parent) if self.layout(parent).orientation() == orientation => parent,
+            _ => {
+                self.nest_in_container_internal(layout, target_node, LayoutKind::from(orientation))
+            }
+        };
+        debug_assert_eq!(target_node.parent(self.map()), Some(parent));
+
+        let node = match direction {
+            Direction::Left | Direction::Up => self.tree.mk_node().insert_before(target_node),
+            Direction::Right | Direction::Down => self.tree.mk_node().insert_after(target_node),
+        };
+        self.tree.data.window.set_window(layout, node, wid);
+        self.split_new_sibling_from_selection(target_node, node);
+        self.select(node);
+        true
+    }
+
     fn swap_windows(&mut self, layout: LayoutId, a: WindowId, b: WindowId) -> bool {
         let node_a = match self.tree.data.window.node_for(layout, a) {
             Some(n) => n,
@@ -1090,8 +1171,6 @@ impl LayoutSystem for TraditionalLayoutSystem {
     }
 
     fn toggle_tile_orientation(&mut self, layout: LayoutId) {
-        use crate::layout_engine::LayoutKind;
-
         let map = self.map();
         let selection_node = self.selection(layout);
 
@@ -1116,6 +1195,22 @@ impl LayoutSystem for TraditionalLayoutSystem {
 
         self.rebalance(layout);
     }
+
+    fn rotate_all(&mut self, layout: LayoutId) {
+        let containers: Vec<_> = self
+            .root(layout)
+            .traverse_preorder(self.map())
+            .filter(|&node| self.window_at(node).is_none())
+            .collect();
+        for node in containers {
+            let new_kind = match self.layout(node) {
+                LayoutKind::Horizontal => LayoutKind::Vertical,
+                LayoutKind::Vertical => LayoutKind::Horizontal,
+                _ => continue,
+            };
+            self.set_layout(node, new_kind);
+        }
+    }
 }
 
 impl TraditionalLayoutSystem {
@@ -1175,6 +1270,43 @@ impl TraditionalLayoutSystem {
         }
     }
 
+    pub(crate) fn selection_path(
+        &self,
+        layout: LayoutId,
+    ) -> Vec<crate::layout_engine::engine::SelectionPathEntry> {
+        use crate::layout_engine::engine::SelectionPathEntry;
+
+        let map = &self.tree.map;
+        let mut out = Vec::new();
+        let mut node = self.root(layout);
+        loop {
+            if let Some(wid) = self.tree.data.window.at(node) {
+                out.push(SelectionPathEntry {
+                    node_id: node,
+                    container_kind: None,
+                    child_count: 0,
+                    selected_index: None,
+                    window_id: Some(wid),
+                });
+                break;
+            }
+            let children: Vec<_> = node.children(map).collect();
+            let selected = self.tree.data.selection.local_selection(map, node);
+            out.push(SelectionPathEntry {
+                node_id: node,
+                container_kind: Some(self.tree.data.layout.kind(node)),
+                child_count: children.len(),
+                selected_index: selected.and_then(|sel| children.iter().position(|&c| c == sel)),
+                window_id: None,
+            });
+            match selected {
+                Some(next) => node = next,
+                None => break,
+            }
+        }
+        out
+    }
+
     pub(crate) fn collect_group_containers_in_selection_path(
         &self,
         layout: LayoutId,
@@ -1480,10 +1612,88 @@ impl TraditionalLayoutSystem {
         node
     }
 
+    pub(crate) fn selected_node(&self, layout: LayoutId) -> NodeId { self.selection(layout) }
+
+    /// Move `wid` into the container `node` as its last child, so it becomes
+    /// another tab of a stack or another split of a tiled container. Returns
+    /// false if `node` is not a container of `layout`, `wid` is not in the
+    /// layout, or `wid` is already a direct child of `node`.
+    pub(crate) fn join_into(&mut self, layout: LayoutId, node: NodeId, wid: WindowId) -> bool {
+        if !self.tree.contains(node)
+            || self.window_at(node).is_some()
+            || node.ancestors(self.map()).last() != Some(self.root(layout))
+        {
+            return false;
+        }
+        let Some(window_node) = self.tree.data.window.node_for(layout, wid) else {
+            return false;
+        };
+        if window_node.parent(self.map()) == Some(node) {
+            return false;
+        }
+        window_node.detach(&mut self.tree).push_back(node);
+        self.select(window_node);
+        true
+    }
+
     pub(crate) fn window_at(&self, node: NodeId) -> Option<WindowId> {
         self.tree.data.window.at(node)
     }
 
+    pub(crate) fn has_layout(&self, layout: LayoutId) -> bool {
+        self.layout_roots.contains_key(layout)
+    }
+
+    /// Every window in `layout` in tree order, including stack members that
+    /// are not currently shown.
+    pub(crate) fn windows_in_layout(&self, layout: LayoutId) -> Vec<WindowId> {
+        self.root(layout)
+            .traverse_preorder(self.map())
+            .filter_map(|node| self.window_at(node))
+            .collect()
+    }
+
+    /// Build a new layout that holds every window of `layout` as a tab of a
+    /// single stack at the root, keeping the same window selected.
+    pub(crate) fn create_monocle_layout(&mut self, layout: LayoutId) -> LayoutId {
+        let windows = self.windows_in_layout(layout);
+        let selected = self.selected_window(layout);
+        let monocle = self.create_layout();
+        let root = self.root(monocle);
+        self.set_layout(root, LayoutKind::HorizontalStack);
+        for wid in windows {
+            self.add_window_under(monocle, root, wid);
+        }
+        if !selected.is_some_and(|wid| self.select_window(monocle, wid))
+            && let Some(first) = root.first_child(self.map())
+        {
+            self.select(first);
+        }
+        monocle
+    }
+
+    /// Bring `saved` up to date with the windows of `monocle` before it is
+    /// shown again: windows opened in the meantime are added, closed ones
+    /// dropped, and the monocle selection carried over.
+    pub(crate) fn restore_from_monocle(&mut self, saved: LayoutId, monocle: LayoutId) {
+        let current = self.windows_in_layout(monocle);
+        for wid in self.windows_in_layout(saved) {
+            if !current.contains(&wid)
+                && let Some(node) = self.tree.data.window.node_for(saved, wid)
+            {
+                node.detach(&mut self.tree).remove();
+            }
+        }
+        for &wid in &current {
+            if !self.contains_window(saved, wid) {
+                self.add_window_after_selection(saved, wid);
+            }
+        }
+        if let Some(wid) = self.selected_window(monocle) {
+            self.select_window(saved, wid);
+        }
+    }
+
     pub(crate) fn visible_windows_in_subtree(&self, node: NodeId) -> Vec<WindowId> {
         self.visible_windows_under_internal(node)
     }
@@ -1519,7 +1729,7 @@ impl TraditionalLayoutSystem {
         None
     }
 
-    fn rebalance_node(&mut self, node: NodeId) {
+    pub(crate) fn rebalance_node(&mut self, node: NodeId) {
         let map = &self.tree.map;
         let children: Vec<_> = node.children(map).collect();
         let count = children.len() as f32;
@@ -1539,6 +1749,81 @@ impl TraditionalLayoutSystem {
         self.tree.data.selection.select(&self.tree.map, selection)
     }
 
+    /// Select the best window under `new_node` and return it along with the
+    /// windows that became visible.
+    fn focus_node(&mut self, new_node: NodeId) -> (Option<WindowId>, Vec<WindowId>) {
+        let Some((focus_node, focus_window)) = self.find_best_focus_target(new_node) else {
+            return (None, vec![]);
+        };
+        let map = &self.tree.map;
+        let mut highest_revealed = focus_node;
+
+        for (node, parent) in focus_node.ancestors_with_parent(map) {
+            let Some(parent) = parent else { break };
+            let parent_layout = self.layout(parent);
+            if self.tree.data.selection.select_locally(map, node) {
+                if parent_layout.is_group() {
+                    highest_revealed = node;
+                }
+            }
+        }
+        let raise_windows = self.visible_windows_under_internal(highest_revealed);
+        (Some(focus_window), raise_windows)
+    }
+
+    /// The node on the far side of the outermost container split along
+    /// `direction`'s axis, keeping the position along the other axis where the
+    /// containers line up (so wrapping in a grid stays in the same row/column).
+    fn wrap_target(&self, from: NodeId, direction: Direction) -> Option<NodeId> {
+        let map = &self.tree.map;
+        let axis = direction.orientation();
+        let mut steps = Vec::new();
+        let mut outermost = None;
+        for (node, parent) in from.ancestors_with_parent(map) {
+            let Some(parent) = parent else { break };
+            if self.layout(parent).orientation() == axis {
+                outermost = Some(steps.len());
+            }
+            let index = parent.children(map).position(|child| child == node).unwrap_or(0);
+            steps.push((parent, index));
+        }
+        let outermost = outermost?;
+
+        // Child indices in the cross-axis containers between the wrap point and
+        // `from`, from the top down.
+        let mut cross_indices = steps[..outermost]
+            .iter()
+            .rev()
+            .filter(|(parent, _)| {
+                let kind = self.layout(*parent);
+                !kind.is_group() && kind.orientation() != axis
+            })
+            .map(|&(_, index)| index);
+
+        let far_child = |node: NodeId| match direction {
+            Direction::Left | Direction::Up => node.last_child(map),
+            Direction::Right | Direction::Down => node.first_child(map),
+        };
+        let mut current = far_child(steps[outermost].0)?;
+        loop {
+            let children: Vec<_> = current.children(map).collect();
+            if children.is_empty() {
+                return Some(current);
+            }
+            let kind = self.layout(current);
+            current = if kind.is_group() {
+                self.local_selection(current).unwrap_or(children[0])
+            } else if kind.orientation() == axis {
+                far_child(current)?
+            } else {
+                match cross_indices.next() {
+                    Some(index) => children[index.min(children.len() - 1)],
+                    None => self.local_selection(current).unwrap_or(children[0]),
+                }
+            };
+        }
+    }
+
     fn traverse_internal(&self, from: NodeId, direction: Direction) -> Option<NodeId> {
         let map = &self.tree.map;
         if let Some(sibling) = self.move_over(from, direction) {
@@ -1801,12 +2086,25 @@ impl TraditionalLayoutSystem {
                 self.tree.data.layout.info[resizing_node.parent(&self.tree.map).unwrap()].total,
             )
             / exchange_rate;
-        self.tree.data.layout.take_share(
-            &self.tree.map,
-            resizing_node,
-            sibling,
-            local_ratio as f32,
-        );
+        match self.resize_mode {
+            ResizeMode::SiblingOnly => self.tree.data.layout.take_share(
+                &self.tree.map,
+                resizing_node,
+                sibling,
+                local_ratio as f32,
+            ),
+            ResizeMode::Proportional => {
+                let parent = resizing_node.parent(&self.tree.map).unwrap();
+                let info = &self.tree.data.layout.info;
+                let proportion =
+                    (info[resizing_node].size + local_ratio as f32) / info[parent].total;
+                self.tree.data.layout.set_proportion(
+                    &self.tree.map,
+                    resizing_node,
+                    proportion.clamp(0.05, 0.95),
+                );
+            }
+        }
         true
     }
 
@@ -2322,6 +2620,25 @@ impl Layout {
         Some(f64::from(self.info[node].size) / f64::from(self.info[parent].total))
     }
 
+    /// Resize `node` to `proportion` of its parent, scaling its siblings to
+    /// share the rest in their current proportions.
+    fn set_proportion(&mut self, map: &NodeMap, node: NodeId, proportion: f32) {
+        let parent = node.parent(map).unwrap();
+        let siblings: Vec<_> = parent.children(map).filter(|&child| child != node).collect();
+        let count = (siblings.len() + 1) as f32;
+        let rest: f32 = siblings.iter().map(|&child| self.info[child].size.max(0.0)).sum();
+        let remaining = (1.0 - proportion) * count;
+        for &sibling in &siblings {
+            self.info[sibling].size = if rest > f32::EPSILON {
+                self.info[sibling].size.max(0.0) * remaining / rest
+            } else {
+                remaining / siblings.len() as f32
+            };
+        }
+        self.info[node].size = proportion * count;
+        self.info[parent].total = count;
+    }
+
     fn take_share(&mut self, map: &NodeMap, node: NodeId, from: NodeId, share: f32) {
         assert_eq!(node.parent(map), from.parent(map));
         const MIN_NODE_SIZE: f32 = 0.05;
@@ -2903,6 +3220,28 @@ mod tests {
         assert_eq!(system.window_in_direction(layout, Direction::Left), Some(w(2)));
     }
 
+    #[test]
+    fn wrap_focus_keeps_row_and_column_in_grid() {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        for ids in [[1, 2], [3, 4]] {
+            let column = system.tree.mk_node().push_back(root);
+            system.tree.data.layout.set_kind(column, LayoutKind::Vertical);
+            for id in ids {
+                system.add_window_under(layout, column, w(id));
+            }
+        }
+
+        assert!(system.select_window(layout, w(4)));
+        assert_eq!(system.move_focus(layout, Direction::Right).0, None);
+        assert_eq!(system.wrap_focus(layout, Direction::Right).0, Some(w(2)));
+        assert_eq!(system.wrap_focus(layout, Direction::Down).0, Some(w(1)));
+        assert_eq!(system.wrap_focus(layout, Direction::Left).0, Some(w(3)));
+        assert_eq!(system.selected_window(layout), Some(w(3)));
+    }
+
     #[test]
     fn window_in_direction_prefers_top_for_down_direction_after_orientation_toggle() {
         let mut system = TraditionalLayoutSystem::default();
@@ -3200,6 +3539,125 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stack_representative_is_shown_after_focus_leaves_group() {
+        use crate::common::config::StackDefaultOrientation;
+
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        system.add_window_after_selection(layout, w(3));
+
+        system.select_window(layout, w(1));
+        system.join_selection_with_direction(layout, Direction::Right);
+        let _ = system.apply_stacking_to_parent_of_selection(layout, StackDefaultOrientation::Same);
+        let stacked_container = system.selection(layout).parent(system.map()).unwrap();
+        assert!(system.layout(stacked_container).is_stacked());
+
+        system.select_window(layout, w(1));
+        assert!(system.set_stack_representative(layout));
+
+        system.select_window(layout, w(2));
+        system.select_window(layout, w(3));
+        assert!(!system.set_stack_representative(layout));
+
+        let pinned = system.tree.data.window.node_for(layout, w(1));
+        assert_eq!(system.local_selection(stacked_container), pinned);
+    }
+
+    #[test]
+    fn resize_selection_in_a_stack_resizes_the_whole_stack() {
+        use crate::common::config::StackDefaultOrientation;
+
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        system.add_window_after_selection(layout, w(3));
+
+        system.select_window(layout, w(1));
+        system.join_selection_with_direction(layout, Direction::Right);
+        let _ = system.apply_stacking_to_parent_of_selection(layout, StackDefaultOrientation::Same);
+        let selection = system.selection(layout);
+        let stack = selection.parent(system.map()).unwrap();
+        let proportion = |system: &TraditionalLayoutSystem, node| {
+            system.tree.data.layout.proportion(system.map(), node).unwrap()
+        };
+        let (stack_before, window_before) =
+            (proportion(&system, stack), proportion(&system, selection));
+
+        // Splits inside a group are skipped, so the stack grows as a unit.
+        system.resize_selection_by(layout, 0.1);
+        assert!(proportion(&system, stack) > stack_before);
+        assert_eq!(proportion(&system, selection), window_before);
+    }
+
+    #[test]
+    fn join_into_adds_a_window_as_another_tab_of_a_stack() {
+        use crate::common::config::StackDefaultOrientation;
+
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+
+        for idx in 1..=4 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+
+        system.select_window(layout, w(1));
+        system.join_selection_with_direction(layout, Direction::Right);
+        let _ = system.apply_stacking_to_parent_of_selection(layout, StackDefaultOrientation::Same);
+        let stack = system.selection(layout).parent(system.map()).unwrap();
+        assert!(system.layout(stack).is_stacked());
+
+        assert!(system.join_into(layout, stack, w(4)));
+        assert_eq!(stack.children(system.map()).count(), 3);
+        let node = system.tree.data.window.node_for(layout, w(4)).unwrap();
+        assert_eq!(node.parent(system.map()), Some(stack));
+        assert_eq!(system.selected_window(layout), Some(w(4)));
+        assert!(system.layout(stack).is_stacked());
+
+        // Already a member, and windows are not containers.
+        assert!(!system.join_into(layout, stack, w(4)));
+        let leaf = system.tree.data.window.node_for(layout, w(3)).unwrap();
+        assert!(!system.join_into(layout, leaf, w(4)));
+    }
+
+    #[test]
+    fn selection_path_runs_from_root_to_selected_window() {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        system.add_window_after_selection(layout, w(3));
+        system.select_window(layout, w(1));
+        system.join_selection_with_direction(layout, Direction::Right);
+        system.select_window(layout, w(1));
+
+        let path = system.selection_path(layout);
+        assert_eq!(path.first().map(|entry| entry.node_id), Some(root));
+        assert_eq!(path.last().and_then(|entry| entry.window_id), Some(w(1)));
+        assert!(path[..path.len() - 1].iter().all(|entry| entry.container_kind.is_some()));
+        for pair in path.windows(2) {
+            assert_eq!(pair[1].node_id.parent(system.map()), Some(pair[0].node_id));
+        }
+        assert!(
+            path.len() >= 3,
+            "expected a nested container on the path: {path:?}"
+        );
+    }
+
     #[test]
     fn joining_into_existing_stack_keeps_it_stacked() {
         use crate::common::config::StackDefaultOrientation;
@@ -3287,6 +3745,88 @@ mod tests {
         assert_eq!(system.visible_windows_in_layout(layout), vec![w1, w2, w3]);
     }
 
+    #[test]
+    fn insert_window_beside_places_window_next_to_target() {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+
+        let w1 = w(151);
+        let w2 = w(152);
+        let w3 = w(153);
+        system.add_window_after_selection(layout, w1);
+        system.add_window_after_selection(layout, w2);
+        system.add_window_after_selection(layout, w3);
+
+        assert!(system.insert_window_beside(layout, w3, w1, Direction::Left));
+        assert_eq!(system.visible_windows_in_layout(layout), vec![w3, w1, w2]);
+        assert_eq!(system.selected_window(layout), Some(w3));
+
+        assert!(system.insert_window_beside(layout, w3, w2, Direction::Down));
+        let w2_parent = system
+            .tree
+            .data
+            .window
+            .node_for(layout, w2)
+            .and_then(|node| node.parent(system.map()))
+            .expect("w2 parent");
+        assert_ne!(w2_parent, root, "{}", system.draw_tree(layout));
+        assert_eq!(system.layout(w2_parent), LayoutKind::Vertical);
+        let column: Vec<_> = w2_parent
+            .children(system.map())
+            .filter_map(|child| system.window_at(child))
+            .collect();
+        assert_eq!(column, vec![w2, w3], "{}", system.draw_tree(layout));
+
+        assert!(!system.insert_window_beside(layout, w1, w1, Direction::Right));
+    }
+
+    #[test]
+    fn rotate_all_flips_every_split_container() {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+
+        let w1 = w(161);
+        let w2 = w(162);
+        let w3 = w(163);
+        system.add_window_after_selection(layout, w1);
+        system.add_window_after_selection(layout, w2);
+        assert!(system.insert_window_beside(layout, w3, w2, Direction::Down));
+        let column = system
+            .tree
+            .data
+            .window
+            .node_for(layout, w2)
+            .and_then(|node| node.parent(system.map()))
+            .expect("w2 parent");
+        assert_eq!(system.layout(column), LayoutKind::Vertical);
+
+        system.rotate_all(layout);
+
+        assert_eq!(system.layout(root), LayoutKind::Vertical);
+        assert_eq!(system.layout(column), LayoutKind::Horizontal);
+
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 1000.0));
+        let frames: HashMap<WindowId, CGRect> = system
+            .calculate_layout(
+                layout,
+                screen,
+                0.0,
+                &HashMap::default(),
+                &Default::default(),
+                0.0,
+                Default::default(),
+                Default::default(),
+            )
+            .into_iter()
+            .collect();
+        assert!(frames[&w1].max().y <= frames[&w2].min().y + 1.0);
+        assert!(frames[&w2].max().x <= frames[&w3].min().x + 1.0);
+    }
+
     #[test]
     fn joining_siblings_preserves_parent_size_invariants() {
         let mut system = TraditionalLayoutSystem::default();
@@ -5067,4 +5607,46 @@ mod tests {
             .expect("right node proportion missing");
         assert_eq!(before, after);
     }
+
+    fn resize_middle_of_three(mode: ResizeMode) -> [f64; 3] {
+        let mut system = TraditionalLayoutSystem::default();
+        system.update_settings(mode);
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+
+        let windows = [w(91), w(92), w(93)];
+        for wid in windows {
+            system.add_window_after_selection(layout, wid);
+        }
+        system.rebalance(layout);
+        let nodes = windows.map(|wid| system.tree.data.window.node_for(layout, wid).unwrap());
+
+        // Drag the middle window's right edge 100px to the right.
+        let old_frame = CGRect::new(CGPoint::new(300.0, 0.0), CGSize::new(300.0, 800.0));
+        let new_frame = CGRect::new(CGPoint::new(300.0, 0.0), CGSize::new(400.0, 800.0));
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(900.0, 800.0));
+        system.set_frame_from_resize(nodes[1], old_frame, new_frame, screen);
+
+        nodes.map(|node| system.tree.data.layout.proportion(&system.tree.map, node).unwrap())
+    }
+
+    #[test]
+    fn sibling_only_resize_takes_space_from_the_adjacent_window() {
+        let [left, middle, right] = resize_middle_of_three(ResizeMode::SiblingOnly);
+        assert!(
+            (left - 1.0 / 3.0).abs() < 0.001,
+            "left window should be untouched"
+        );
+        assert!((middle - 4.0 / 9.0).abs() < 0.001);
+        assert!((right - 2.0 / 9.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn proportional_resize_spreads_across_the_container() {
+        let [left, middle, right] = resize_middle_of_three(ResizeMode::Proportional);
+        assert!((middle - 4.0 / 9.0).abs() < 0.001);
+        assert!((left - 5.0 / 18.0).abs() < 0.001);
+        assert!((right - 5.0 / 18.0).abs() < 0.001);
+    }
 }
@@ -13,7 +13,7 @@ pub(crate) struct WorkspaceLayouts {
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-struct SpaceLayoutInfo {
+pub(crate) struct SpaceLayoutInfo {
     configurations: crate::common::collections::HashMap<Size, LayoutId>,
     active_size: Size,
     last_saved: Option<LayoutId>,
@@ -134,6 +134,37 @@ impl WorkspaceLayouts {
         self.map.get(&(space, workspace_id)).and_then(|l| l.active())
     }
 
+    /// Make `layout` the active layout of the workspace at its current size,
+    /// returning the layout it replaces.
+    pub(crate) fn swap_active(
+        &mut self,
+        space: SpaceId,
+        workspace_id: crate::model::VirtualWorkspaceId,
+        layout: LayoutId,
+    ) -> Option<LayoutId> {
+        let info = self.map.get_mut(&(space, workspace_id))?;
+        info.configurations.insert(info.active_size, layout)
+    }
+
+    pub(crate) fn last_saved(
+        &self,
+        space: SpaceId,
+        workspace_id: crate::model::VirtualWorkspaceId,
+    ) -> Option<LayoutId> {
+        self.map.get(&(space, workspace_id)).and_then(|info| info.last_saved)
+    }
+
+    pub(crate) fn restore_last_saved(
+        &mut self,
+        space: SpaceId,
+        workspace_id: crate::model::VirtualWorkspaceId,
+        layout: Option<LayoutId>,
+    ) {
+        if let Some(info) = self.map.get_mut(&(space, workspace_id)) {
+            info.last_saved = layout;
+        }
+    }
+
     pub(crate) fn mark_last_saved(
         &mut self,
         space: SpaceId,
@@ -170,6 +201,32 @@ impl WorkspaceLayouts {
             .collect()
     }
 
+    pub(crate) fn info(
+        &self,
+        space: SpaceId,
+        workspace_id: crate::model::VirtualWorkspaceId,
+    ) -> Option<&SpaceLayoutInfo> {
+        self.map.get(&(space, workspace_id))
+    }
+
+    /// Replace the layouts of a workspace, e.g. with ones loaded from a profile
+    /// together with the tree they refer to.
+    pub(crate) fn set_info(
+        &mut self,
+        space: SpaceId,
+        workspace_id: crate::model::VirtualWorkspaceId,
+        info: Option<SpaceLayoutInfo>,
+    ) {
+        match info {
+            Some(info) => {
+                self.map.insert((space, workspace_id), info);
+            }
+            None => {
+                self.map.remove(&(space, workspace_id));
+            }
+        }
+    }
+
     pub(crate) fn ensure_active_for_workspace(
         &mut self,
         space: SpaceId,
@@ -202,6 +259,14 @@ impl WorkspaceLayouts {
         });
     }
 
+    pub(crate) fn remove_workspace(
+        &mut self,
+        space: SpaceId,
+        workspace_id: crate::model::VirtualWorkspaceId,
+    ) {
+        self.map.remove(&(space, workspace_id));
+    }
+
     pub(crate) fn spaces(&self) -> crate::common::collections::BTreeSet<SpaceId> {
         self.map.keys().map(|(sp, _)| *sp).collect()
     }
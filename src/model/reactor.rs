@@ -1,7 +1,10 @@
+use std::time::Instant;
+
 use objc2_core_foundation::CGRect;
 use serde::{Deserialize, Serialize};
 
 use crate::actor::app::{AppInfo, AppThreadHandle, WindowId, pid_t};
+use crate::common::config::Gesture;
 use crate::common::log::MetricsCommand;
 use crate::layout_engine::{Direction, LayoutCommand};
 use crate::sys::app::WindowInfo;
@@ -24,9 +27,19 @@ pub enum Command {
 pub enum DisplaySelector {
     Direction(Direction),
     Index(usize),
+    Cycle(DisplayCycle),
     Uuid(String),
 }
 
+/// Step through the displays in `settings.display_cycle_order`, wrapping at
+/// either end.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplayCycle {
+    Next,
+    Prev,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ReactorCommand {
@@ -51,6 +64,81 @@ pub enum ReactorCommand {
         selector: DisplaySelector,
         window_id: Option<u32>,
     },
+    /// Move the focused window into the workspace currently active on the
+    /// selected display, optionally moving focus along with it.
+    SendToDisplayActiveWorkspace {
+        selector: DisplaySelector,
+        #[serde(default)]
+        follow: bool,
+    },
+    /// Float the `placeholder` window on the selected display and keep it at the
+    /// focused window's relative position and size there as that window moves.
+    MirrorToDisplay {
+        selector: DisplaySelector,
+        placeholder: WindowServerId,
+    },
+    /// Stop mirroring the focused window, or stop using it as a placeholder.
+    ClearMirror,
+    /// Move the focused window so it sits directly on the `direction` side of
+    /// the target window, in the target's workspace.
+    MoveWindowBeside {
+        target_wsid: WindowServerId,
+        direction: Direction,
+    },
+    /// Re-run workspace auto-assignment for one window and move it if the
+    /// result differs from the workspace it is in now.
+    ReassignWindow {
+        wsid: WindowServerId,
+    },
+    /// Move the focused window into the workspace of the window under the cursor.
+    MatchWorkspaceOfCursorWindow,
+    /// Allow or prevent newly created windows from taking focus.
+    SetNewWindowFocusSteal(bool),
+    /// Switch to the next/previous workspace from a swipe gesture. Behaves like
+    /// `next_workspace`/`prev_workspace`, but is recorded with a gesture origin.
+    GestureSwitchWorkspace {
+        next: bool,
+        skip_empty: Option<bool>,
+    },
+    /// Run the layout command bound to `gesture` in `settings.gestures.bindings`.
+    Gesture(Gesture),
+    /// Switch to the next workspace like `next_workspace`, then briefly show
+    /// the new workspace's index and name in an overlay on its screen.
+    NextWorkspaceWithIndicator {
+        #[serde(default)]
+        skip_empty: Option<bool>,
+    },
+    /// Cycle the current space between showing all windows, tiled windows only,
+    /// and floating windows only.
+    ToggleFloatingVisibility,
+    /// Lay out the current space without stack offsets or room for stack
+    /// lines, or restore them when toggled again.
+    ToggleCompactStacks,
+    /// Lay out a space with the named entry of `settings.layout_presets`, or
+    /// with the global layout settings again when `name` is omitted.
+    ApplyLayoutPreset {
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default)]
+        space: Option<SpaceId>,
+    },
+    /// Switch to the workspace holding the app's most recently focused window
+    /// and focus that window. With `launch`, start the app if it isn't running.
+    FocusApp {
+        bundle_id: String,
+        #[serde(default)]
+        launch: bool,
+    },
+    /// Send future windows of the app to `workspace_index` on their space,
+    /// ahead of any `app_rules`, until unpinned or rift restarts.
+    PinAppToWorkspace {
+        bundle_id: String,
+        workspace_index: usize,
+    },
+    /// Drop the pin set by `PinAppToWorkspace`.
+    UnpinApp {
+        bundle_id: String,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -110,13 +198,16 @@ pub enum WorkspaceSwitchState {
     Active,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
 pub enum WorkspaceSwitchOrigin {
     Manual,
     Auto,
+    Gesture,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
 pub enum StaleCleanupState {
     Enabled,
     Suppressed,
@@ -138,6 +229,8 @@ pub(crate) struct AppState {
 #[derive(Debug, Clone)]
 pub(crate) struct PendingSpaceChange {
     pub(crate) spaces: Vec<Option<SpaceId>>,
+    /// When the change was first deferred; used to force-apply stale changes.
+    pub(crate) queued_at: Instant,
 }
 
 #[derive(Debug)]
@@ -150,6 +243,9 @@ pub(crate) struct WindowState {
     pub(crate) frame_monotonic: CGRect,
     pub(crate) is_manageable: bool,
     pub(crate) ignore_app_rule: bool,
+    /// Whether macOS shows the window on several spaces; looked up once when
+    /// the window (or its window server id) is discovered.
+    pub(crate) is_multi_space: bool,
 }
 
 impl From<WindowInfo> for WindowState {
@@ -159,6 +255,7 @@ impl From<WindowInfo> for WindowState {
             info,
             is_manageable: false,
             ignore_app_rule: false,
+            is_multi_space: false,
         }
     }
 }
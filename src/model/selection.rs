@@ -19,6 +19,10 @@ pub enum TreeEvent {
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Selection {
     nodes: slotmap::SecondaryMap<NodeId, SelectionInfo>,
+    /// Pinned child per container. When the selection path leaves a container,
+    /// its local selection snaps back to the pinned child.
+    #[serde(default)]
+    representatives: slotmap::SecondaryMap<NodeId, NodeId>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -53,6 +57,11 @@ impl Selection {
 
     pub fn select_locally(&mut self, map: &NodeMap, node: NodeId) -> bool {
         if let Some(parent) = node.parent(map) {
+            if let Some(old) = self.nodes.get(parent).map(|info| info.selected_child)
+                && old != node
+            {
+                self.restore_representatives_below(old);
+            }
             self.nodes
                 .insert(parent, SelectionInfo {
                     selected_child: node,
@@ -65,12 +74,49 @@ impl Selection {
         }
     }
 
+    /// Pin `child` as the member `container` shows whenever it isn't on the
+    /// selection path, and select it locally.
+    pub fn set_representative(&mut self, map: &NodeMap, container: NodeId, child: NodeId) {
+        debug_assert_eq!(child.parent(map), Some(container));
+        self.representatives.insert(container, child);
+        self.select_locally(map, child);
+    }
+
+    /// `node` just dropped off the selection path; snap every container on its
+    /// old selection path back to its pinned child.
+    fn restore_representatives_below(&mut self, node: NodeId) {
+        if self.representatives.is_empty() {
+            return;
+        }
+        let mut leaving = Vec::new();
+        let mut node = node;
+        while let Some(info) = self.nodes.get(node) {
+            leaving.push(node);
+            if info.stop_here {
+                break;
+            }
+            node = info.selected_child;
+        }
+        for container in leaving {
+            if let Some(&rep) = self.representatives.get(container)
+                && let Some(info) = self.nodes.get_mut(container)
+            {
+                info.selected_child = rep;
+            }
+        }
+    }
+
     pub fn select(&mut self, map: &NodeMap, selection: NodeId) {
         if let Some(info) = self.nodes.get_mut(selection) {
             info.stop_here = true;
         }
         let mut node = selection;
         while let Some(parent) = node.parent(map) {
+            if let Some(old) = self.nodes.get(parent).map(|info| info.selected_child)
+                && old != node
+            {
+                self.restore_representatives_below(old);
+            }
             self.nodes.insert(parent, SelectionInfo {
                 selected_child: node,
                 stop_here: false,
@@ -97,9 +143,19 @@ impl Selection {
                     selected_child,
                     stop_here: self.nodes[src].stop_here,
                 });
+                if let Some(&rep) = self.representatives.get(src)
+                    && let Some(dest_rep) = std::iter::zip(src.children(map), dest.children(map))
+                        .find(|(src_child, _)| *src_child == rep)
+                        .map(|(_, dest_child)| dest_child)
+                {
+                    self.representatives.insert(dest, dest_rep);
+                }
             }
             RemovingFromParent(node) => {
                 let parent = node.parent(map).unwrap();
+                if self.representatives.get(parent) == Some(&node) {
+                    self.representatives.remove(parent);
+                }
                 if self.nodes.get(parent).map(|n| n.selected_child) == Some(node) {
                     if let Some(new_selection) = node.next_sibling(map).or(node.prev_sibling(map)) {
                         self.nodes[parent].selected_child = new_selection;
@@ -110,6 +166,7 @@ impl Selection {
             }
             RemovedFromForest(node) => {
                 self.nodes.remove(node);
+                self.representatives.remove(node);
             }
         }
     }
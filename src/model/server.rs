@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use crate::actor::app::{WindowId, pid_t};
+use crate::common::collections::HashMap;
+use crate::common::config::{AppWorkspaceRule, InnerGaps, OuterGaps};
+use crate::layout_engine::LayoutKind;
+use crate::model::reactor::{StaleCleanupState, WorkspaceSwitchOrigin};
 use crate::sys::app::WindowInfo;
 use crate::sys::geometry::CGRectDef;
 use crate::sys::screen::{ScreenId, ScreenInfo, SpaceId};
@@ -29,6 +33,16 @@ pub struct WorkspaceLayoutData {
     pub is_active: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceSwitchData {
+    /// Milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    pub space_id: u64,
+    pub from_workspace: Option<String>,
+    pub to_workspace: String,
+    pub origin: WorkspaceSwitchOrigin,
+}
+
 #[derive(Debug, Clone)]
 pub struct WindowData {
     pub id: WindowId,
@@ -56,6 +70,145 @@ pub struct LayoutStateData {
     pub focused_window: Option<WindowId>,
 }
 
+/// Gaps applied to a space's active workspace once every override has been resolved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectiveGapsData {
+    pub space_id: u64,
+    pub workspace_id: Option<String>,
+    pub display_uuid: Option<String>,
+    pub outer: OuterGaps,
+    pub inner: InnerGaps,
+}
+
+/// The focused window along with the workspace it belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusedWindowData {
+    #[serde(flatten)]
+    pub window: WindowData,
+    pub space_id: Option<u64>,
+    pub workspace_id: Option<String>,
+    pub workspace_index: Option<usize>,
+    pub workspace_name: Option<String>,
+}
+
+/// Live windows matched by one configured app rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleCoverageData {
+    /// Position of the rule in `virtual_workspaces.app_rules`.
+    pub index: usize,
+    pub rule: AppWorkspaceRule,
+    pub window_count: usize,
+    pub window_ids: Vec<WindowId>,
+}
+
+/// The most recent failed layout update.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutErrorData {
+    /// Milliseconds since the Unix epoch.
+    pub timestamp_ms: u64,
+    /// What the reactor was doing when the update failed.
+    pub context: String,
+    pub message: String,
+}
+
+/// The window being dragged and the window it would swap with if dropped now.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DragSwapTargetData {
+    pub window_id: WindowId,
+    pub target_id: WindowId,
+    #[serde(with = "CGRectDef")]
+    pub target_frame: objc2_core_foundation::CGRect,
+}
+
+/// A window frame the layout would produce after a previewed command.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviewFrameData {
+    pub window_id: WindowId,
+    #[serde(with = "CGRectDef")]
+    pub frame: objc2_core_foundation::CGRect,
+}
+
+/// A node on the selection path, as reported over IPC.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectionPathNodeData {
+    pub node_id: String,
+    /// Container kind, or `None` for the selected window leaf.
+    pub kind: Option<LayoutKind>,
+    pub child_count: usize,
+    pub selected_index: Option<usize>,
+    pub window_id: Option<WindowId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectionPathData {
+    pub space_id: u64,
+    pub workspace_id: Option<String>,
+    /// Ordered from the layout root to the selected node.
+    pub nodes: Vec<SelectionPathNodeData>,
+}
+
+/// One display of the topology snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayTopologyEntryData {
+    /// Position in Rift's screen order.
+    pub index: usize,
+    pub uuid: String,
+    pub name: Option<String>,
+    pub screen_id: u32,
+    #[serde(with = "CGRectDef")]
+    pub frame: objc2_core_foundation::CGRect,
+    /// Space currently shown on the display, if known.
+    pub space_id: Option<u64>,
+    pub is_active: bool,
+    pub active_workspace_id: Option<String>,
+    /// Workspaces of the display's current space, in display order, without
+    /// their windows.
+    pub workspaces: Vec<WorkspaceData>,
+}
+
+/// Displays, their spaces and workspaces, for drawing an overview of the
+/// monitor arrangement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisplayTopologyData {
+    pub displays: Vec<DisplayTopologyEntryData>,
+    pub active_space_ids: Vec<u64>,
+    pub inactive_space_ids: Vec<u64>,
+}
+
+/// Reactor load: events handled, events held back during display churn, and
+/// how many events were waiting in the channel.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventMetricsData {
+    /// Events handled since startup, not counting queries.
+    pub processed: u64,
+    /// Events quarantined while displays were reconfiguring.
+    pub quarantined: u64,
+    /// Events queued behind the one most recently taken from the channel.
+    pub queue_depth: u64,
+    pub max_queue_depth: u64,
+}
+
+/// Whether stale windows are dropped automatically when an app reports its
+/// windows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StaleCleanupData {
+    /// Cleared with `set_stale_cleanup`; no automatic cleanup runs while unset.
+    pub enabled: bool,
+    /// `suppressed` while no screen reports a space, e.g. during sleep or a
+    /// display change.
+    pub state: StaleCleanupState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsData {
+    pub windows_managed: usize,
+    pub workspaces: usize,
+    pub applications: usize,
+    pub screens: usize,
+    pub workspace_stats: HashMap<String, usize>,
+    pub events: EventMetricsData,
+}
+
 #[derive(Debug, Clone)]
 pub struct DisplayData {
     pub info: ScreenInfo,
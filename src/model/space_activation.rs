@@ -6,7 +6,7 @@ use crate::sys::screen::{ScreenId, ScreenInfo, SpaceId};
 /// driven by raw input:
 /// - current screen -> (space, display_uuid) snapshots
 /// - login window activation state
-/// - configuration flags (default_disable, one_space)
+/// - configuration flags (default_disable, one_space, disabled_displays)
 /// - user "toggle" commands (target space/display context)
 #[derive(Debug, Default)]
 pub struct SpaceActivationPolicy {
@@ -182,9 +182,12 @@ impl SpaceActivationPolicy {
         }
     }
 
+    /// Spaces on a display listed in `disabled_displays` are never active, no
+    /// matter what was toggled.
     pub fn compute_active_spaces(
         &self,
         cfg: SpaceActivationConfig,
+        disabled_displays: &[String],
         cur_spaces: &[Option<SpaceId>],
         cur_display_uuids: &[Option<String>],
     ) -> Vec<Option<SpaceId>> {
@@ -204,6 +207,7 @@ impl SpaceActivationPolicy {
             // this is the core logic for deciding whats what
             let enabled = match *space_opt {
                 _ if self.login_window_active => false,
+                _ if display_uuid.is_some_and(|u| disabled_displays.contains(u)) => false,
                 Some(space) if cfg.one_space && Some(space) != self.starting_space => false,
                 Some(space) if self.disabled_spaces.contains(&space) => false,
                 _ if display_disabled => false,
@@ -365,11 +369,12 @@ mod tests {
             input(2, Some(2), Some("display-b")),
         ]);
 
-        let active =
-            policy.compute_active_spaces(cfg, &[Some(SpaceId::new(1)), Some(SpaceId::new(2))], &[
-                Some("display-a".to_string()),
-                Some("display-b".to_string()),
-            ]);
+        let active = policy.compute_active_spaces(
+            cfg,
+            &[],
+            &[Some(SpaceId::new(1)), Some(SpaceId::new(2))],
+            &[Some("display-a".to_string()), Some("display-b".to_string())],
+        );
 
         assert_eq!(active, vec![Some(SpaceId::new(1)), None]);
     }
@@ -388,11 +393,12 @@ mod tests {
             display_uuid: Some("display-a".to_string()),
         });
 
-        let active =
-            policy.compute_active_spaces(cfg, &[Some(SpaceId::new(1)), Some(SpaceId::new(2))], &[
-                Some("display-a".to_string()),
-                Some("display-a".to_string()),
-            ]);
+        let active = policy.compute_active_spaces(
+            cfg,
+            &[],
+            &[Some(SpaceId::new(1)), Some(SpaceId::new(2))],
+            &[Some("display-a".to_string()), Some("display-a".to_string())],
+        );
 
         assert_eq!(active, vec![None, Some(SpaceId::new(2))]);
     }
@@ -411,8 +417,9 @@ mod tests {
             display_uuid: Some("display-a".to_string()),
         });
 
-        let active = policy
-            .compute_active_spaces(cfg, &[Some(SpaceId::new(1))], &[Some("display-a".to_string())]);
+        let active = policy.compute_active_spaces(cfg, &[], &[Some(SpaceId::new(1))], &[Some(
+            "display-a".to_string(),
+        )]);
 
         assert_eq!(active, vec![Some(SpaceId::new(1))]);
     }
@@ -431,8 +438,9 @@ mod tests {
             display_uuid: Some("display-a".to_string()),
         });
 
-        let active = policy
-            .compute_active_spaces(cfg, &[Some(SpaceId::new(2))], &[Some("display-a".to_string())]);
+        let active = policy.compute_active_spaces(cfg, &[], &[Some(SpaceId::new(2))], &[Some(
+            "display-a".to_string(),
+        )]);
 
         assert_eq!(active, vec![Some(SpaceId::new(2))]);
     }
@@ -447,8 +455,9 @@ mod tests {
         };
 
         policy.on_spaces_updated(cfg, &[input(1, Some(1), Some("display-a"))]);
-        let active = policy
-            .compute_active_spaces(cfg, &[Some(SpaceId::new(1))], &[Some("display-a".to_string())]);
+        let active = policy.compute_active_spaces(cfg, &[], &[Some(SpaceId::new(1))], &[Some(
+            "display-a".to_string(),
+        )]);
 
         assert_eq!(active, vec![None]);
     }
@@ -468,13 +477,15 @@ mod tests {
         });
 
         policy.on_spaces_updated(cfg, &[input(1, Some(2), Some("display-a"))]);
-        let active = policy
-            .compute_active_spaces(cfg, &[Some(SpaceId::new(2))], &[Some("display-a".to_string())]);
+        let active = policy.compute_active_spaces(cfg, &[], &[Some(SpaceId::new(2))], &[Some(
+            "display-a".to_string(),
+        )]);
         assert_eq!(active, vec![Some(SpaceId::new(2))]);
 
         policy.on_spaces_updated(cfg, &[input(1, Some(1), Some("display-a"))]);
-        let active = policy
-            .compute_active_spaces(cfg, &[Some(SpaceId::new(1))], &[Some("display-a".to_string())]);
+        let active = policy.compute_active_spaces(cfg, &[], &[Some(SpaceId::new(1))], &[Some(
+            "display-a".to_string(),
+        )]);
         assert_eq!(active, vec![None]);
     }
 
@@ -571,8 +582,42 @@ mod tests {
         };
 
         policy.on_spaces_updated(cfg, &[input(1, None, Some("display-a"))]);
-        let active = policy.compute_active_spaces(cfg, &[None], &[Some("display-a".to_string())]);
+        let active =
+            policy.compute_active_spaces(cfg, &[], &[None], &[Some("display-a".to_string())]);
 
         assert_eq!(active, vec![None]);
     }
+
+    #[test]
+    fn configured_disabled_display_is_never_active() {
+        let mut policy = SpaceActivationPolicy::new();
+        let cfg = SpaceActivationConfig {
+            default_disable: true,
+            one_space: false,
+        };
+        let disabled = vec!["display-tv".to_string()];
+
+        policy.on_spaces_updated(cfg, &[
+            input(1, Some(1), Some("display-a")),
+            input(2, Some(2), Some("display-tv")),
+        ]);
+        for (space, display) in [(1, "display-a"), (2, "display-tv")] {
+            policy.toggle_space_activated(cfg, ToggleSpaceContext {
+                space: SpaceId::new(space),
+                display_uuid: Some(display.to_string()),
+            });
+        }
+
+        let spaces = [Some(SpaceId::new(1)), Some(SpaceId::new(2))];
+        let uuids = [
+            Some("display-a".to_string()),
+            Some("display-tv".to_string()),
+        ];
+        let active = policy.compute_active_spaces(cfg, &disabled, &spaces, &uuids);
+        assert_eq!(active, vec![Some(SpaceId::new(1)), None]);
+
+        let disabled = vec!["display-a".to_string(), "display-tv".to_string()];
+        let active = policy.compute_active_spaces(cfg, &disabled, &spaces, &uuids);
+        assert_eq!(active, vec![None, None]);
+    }
 }
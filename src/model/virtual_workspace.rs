@@ -6,8 +6,10 @@ use tracing::{error, warn};
 
 use crate::actor::app::WindowId;
 use crate::common::collections::{HashMap, HashSet};
+pub use crate::common::config::HideCorner;
 use crate::common::config::{
-    AppWorkspaceRule, LayoutMode, LayoutSettings, VirtualWorkspaceSettings, WorkspaceSelector,
+    AppWorkspaceRule, FloatingRuleFrame, LayoutMode, LayoutSettings, RuleMinSize,
+    UntitledWindowPolicy, VirtualWorkspaceSettings, WorkspaceSelector,
 };
 use crate::common::log::trace_misc;
 use crate::layout_engine::Direction;
@@ -54,6 +56,8 @@ pub struct AppRuleAssignment {
 pub enum AppRuleResult {
     Managed(AppRuleAssignment),
     Unmanaged,
+    /// The window has no title yet and the untitled-window policy defers it.
+    Deferred,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -62,6 +66,9 @@ pub struct VirtualWorkspace {
     pub space: SpaceId,
     windows: HashSet<WindowId>,
     last_focused: Option<WindowId>,
+    /// The window that was `last_focused` before the current one.
+    #[serde(default)]
+    previous_focused: Option<WindowId>,
     #[serde(default = "default_layout_system_kind")]
     pub layout_system: LayoutSystemKind,
     #[serde(default)]
@@ -80,6 +87,7 @@ impl VirtualWorkspace {
             space,
             windows: HashSet::default(),
             last_focused: None,
+            previous_focused: None,
             layout_system,
             layout_mode: mode,
         }
@@ -93,11 +101,15 @@ impl VirtualWorkspace {
 
     pub fn create_layout_system(mode: LayoutMode, settings: &LayoutSettings) -> LayoutSystemKind {
         match mode {
-            LayoutMode::Traditional => LayoutSystemKind::Traditional(
-                crate::layout_engine::systems::TraditionalLayoutSystem::default(),
-            ),
+            LayoutMode::Traditional => {
+                let mut system = crate::layout_engine::systems::TraditionalLayoutSystem::default();
+                system.update_settings(settings.resize_mode);
+                LayoutSystemKind::Traditional(system)
+            }
             LayoutMode::Bsp => {
-                LayoutSystemKind::Bsp(crate::layout_engine::systems::BspLayoutSystem::default())
+                let mut system = crate::layout_engine::systems::BspLayoutSystem::default();
+                system.update_settings(settings.resize_mode);
+                LayoutSystemKind::Bsp(system)
             }
             LayoutMode::Stack => {
                 LayoutSystemKind::Stack(crate::layout_engine::systems::StackLayoutSystem::new(
@@ -125,35 +137,30 @@ impl VirtualWorkspace {
         if self.last_focused == Some(window_id) {
             self.last_focused = None;
         }
+        if self.previous_focused == Some(window_id) {
+            self.previous_focused = None;
+        }
         self.windows.remove(&window_id)
     }
 
     pub fn set_last_focused(&mut self, window_id: Option<WindowId>) {
+        if let Some(current) = self.last_focused
+            && window_id != Some(current)
+        {
+            self.previous_focused = Some(current);
+        }
         self.last_focused = window_id;
     }
 
     pub fn last_focused(&self) -> Option<WindowId> { self.last_focused }
 
-    pub fn window_count(&self) -> usize { self.windows.len() }
-}
+    pub fn previous_focused(&self) -> Option<WindowId> { self.previous_focused }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum HideCorner {
-    BottomLeft,
-    #[default]
-    BottomRight,
-}
-
-impl HideCorner {
-    pub fn opposite(self) -> Self {
-        match self {
-            HideCorner::BottomLeft => HideCorner::BottomRight,
-            HideCorner::BottomRight => HideCorner::BottomLeft,
-        }
-    }
+    pub fn window_count(&self) -> usize { self.windows.len() }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct VirtualWorkspaceManager {
     pub(crate) workspaces: SlotMap<VirtualWorkspaceId, VirtualWorkspace>,
     workspaces_by_space: HashMap<SpaceId, Vec<VirtualWorkspaceId>>,
@@ -164,7 +171,22 @@ pub struct VirtualWorkspaceManager {
     window_rule_floating: HashMap<(SpaceId, WindowId), bool>,
     #[serde(skip)]
     last_rule_decision: HashMap<(SpaceId, WindowId), bool>,
+    /// App-rule frames for newly placed floating windows, waiting for the
+    /// first layout of their workspace to resolve them against the screen.
+    #[serde(skip)]
+    pending_rule_frames: HashMap<WindowId, FloatingRuleFrame>,
+    /// Remembered floating rects, kept per (space, workspace) so a window
+    /// floated on several workspaces keeps a separate rect on each.
     floating_positions: HashMap<(SpaceId, VirtualWorkspaceId), FloatingWindowPositions>,
+    /// Reserved per-space workspace backing the scratchpad. It is kept out of
+    /// `workspaces_by_space` so it never shows up in listings or becomes active.
+    #[serde(default)]
+    scratchpads: HashMap<SpaceId, VirtualWorkspaceId>,
+    #[serde(skip)]
+    scratchpad_visible: HashSet<SpaceId>,
+    /// Windows that follow the active workspace of their space.
+    #[serde(default)]
+    sticky_windows: HashMap<SpaceId, HashSet<WindowId>>,
     workspace_counter: usize,
     #[serde(skip)]
     app_rules: Vec<AppWorkspaceRule>,
@@ -181,8 +203,16 @@ pub struct VirtualWorkspaceManager {
     #[serde(skip)]
     pub workspace_auto_back_and_forth: bool,
     #[serde(skip)]
+    pub create_on_switch: bool,
+    #[serde(skip)]
+    pub untitled_window_policy: UntitledWindowPolicy,
+    #[serde(skip)]
+    pub hide_corner: HideCorner,
+    #[serde(skip)]
     pub workspace_rules: Vec<crate::common::config::WorkspaceLayoutRule>,
     #[serde(skip)]
+    pub display_layout_rules: HashMap<String, LayoutMode>,
+    #[serde(skip)]
     pub default_layout_mode: LayoutMode,
     #[serde(skip)]
     pub layout_settings: LayoutSettings,
@@ -221,7 +251,11 @@ impl VirtualWorkspaceManager {
             window_to_workspace: HashMap::default(),
             window_rule_floating: HashMap::default(),
             last_rule_decision: HashMap::default(),
+            pending_rule_frames: HashMap::default(),
             floating_positions: HashMap::default(),
+            scratchpads: HashMap::default(),
+            scratchpad_visible: HashSet::default(),
+            sticky_windows: HashMap::default(),
             workspace_counter: 1,
             app_rules: config.app_rules.clone(),
             app_rule_regex_cache: Vec::new(),
@@ -230,7 +264,11 @@ impl VirtualWorkspaceManager {
             default_workspace_names: config.workspace_names.clone(),
             default_workspace,
             workspace_auto_back_and_forth: config.workspace_auto_back_and_forth,
+            create_on_switch: config.create_on_switch,
+            untitled_window_policy: config.untitled_window_policy,
+            hide_corner: config.hide_corner,
             workspace_rules: config.workspace_rules.clone(),
+            display_layout_rules: config.display_layout_rules.clone(),
             default_layout_mode: layout_settings.mode,
             layout_settings: layout_settings.clone(),
         };
@@ -246,11 +284,15 @@ impl VirtualWorkspaceManager {
     ) {
         self.app_rules = config.app_rules.clone();
         self.workspace_rules = config.workspace_rules.clone();
+        self.display_layout_rules = config.display_layout_rules.clone();
         self.default_layout_mode = layout_settings.mode;
         self.layout_settings = layout_settings.clone();
         self.default_workspace_count = config.default_workspace_count;
         self.default_workspace_names = config.workspace_names.clone();
         self.workspace_auto_back_and_forth = config.workspace_auto_back_and_forth;
+        self.create_on_switch = config.create_on_switch;
+        self.untitled_window_policy = config.untitled_window_policy;
+        self.hide_corner = config.hide_corner;
         self.rebuild_app_rule_regex_cache();
 
         let target_count = self.default_workspace_count.max(1).min(self.max_workspaces);
@@ -337,7 +379,25 @@ impl VirtualWorkspaceManager {
         self.default_layout_mode
     }
 
-    pub fn desired_layout_mode_for_workspace(&self, index: usize, name: &str) -> LayoutMode {
+    /// Layout mode a workspace should use on the display `display_uuid`:
+    /// workspace rules first, then the display's rule, then the global default.
+    pub fn desired_layout_mode_for_workspace(
+        &self,
+        index: usize,
+        name: &str,
+        display_uuid: Option<&str>,
+    ) -> LayoutMode {
+        let matches_workspace_rule =
+            self.workspace_rules.iter().any(|rule| match &rule.workspace {
+                WorkspaceSelector::Index(idx) => *idx == index,
+                WorkspaceSelector::Name(n) => n == name,
+            });
+        if !matches_workspace_rule
+            && let Some(mode) =
+                display_uuid.and_then(|uuid| self.display_layout_rules.get(uuid).copied())
+        {
+            return mode;
+        }
         self.resolve_layout_mode_for_workspace(index, name)
     }
 
@@ -418,6 +478,106 @@ impl VirtualWorkspaceManager {
             new_positions.insert((target_space, ws_id), positions);
         }
         self.floating_positions = new_positions;
+
+        if let Some(stale) = self.scratchpads.remove(&new_space) {
+            self.workspaces.remove(stale);
+        }
+        if let Some(ws_id) = self.scratchpads.remove(&old_space) {
+            if let Some(ws) = self.workspaces.get_mut(ws_id) {
+                ws.space = new_space;
+            }
+            self.scratchpads.insert(new_space, ws_id);
+        }
+        self.scratchpad_visible.remove(&new_space);
+        if self.scratchpad_visible.remove(&old_space) {
+            self.scratchpad_visible.insert(new_space);
+        }
+
+        if old_space != new_space {
+            self.sticky_windows.remove(&new_space);
+            if let Some(sticky) = self.sticky_windows.remove(&old_space) {
+                self.sticky_windows.insert(new_space, sticky);
+            }
+        }
+    }
+
+    pub fn is_window_sticky(&self, space: SpaceId, window_id: WindowId) -> bool {
+        self.sticky_windows.get(&space).is_some_and(|set| set.contains(&window_id))
+    }
+
+    pub fn sticky_windows(&self, space: SpaceId) -> Vec<WindowId> {
+        let mut windows: Vec<WindowId> = self
+            .sticky_windows
+            .get(&space)
+            .map(|set| set.iter().copied().collect())
+            .unwrap_or_default();
+        windows.sort_unstable();
+        windows
+    }
+
+    /// Flips whether `window_id` is sticky on `space` and returns the new state.
+    pub fn toggle_window_sticky(&mut self, space: SpaceId, window_id: WindowId) -> bool {
+        let set = self.sticky_windows.entry(space).or_default();
+        if set.remove(&window_id) {
+            if set.is_empty() {
+                self.sticky_windows.remove(&space);
+            }
+            false
+        } else {
+            set.insert(window_id);
+            true
+        }
+    }
+
+    fn forget_sticky_window(&mut self, window_id: WindowId) {
+        self.sticky_windows.retain(|_, set| {
+            set.remove(&window_id);
+            !set.is_empty()
+        });
+    }
+
+    /// Returns the scratchpad workspace for `space`, creating it on first use.
+    pub fn scratchpad_workspace(&mut self, space: SpaceId) -> VirtualWorkspaceId {
+        if let Some(&ws_id) = self.scratchpads.get(&space)
+            && self.workspaces.contains_key(ws_id)
+        {
+            return ws_id;
+        }
+        let ws = VirtualWorkspace::new(
+            "Scratchpad".to_string(),
+            space,
+            self.default_layout_mode,
+            &self.layout_settings,
+        );
+        let ws_id = self.workspaces.insert(ws);
+        self.scratchpads.insert(space, ws_id);
+        ws_id
+    }
+
+    pub fn is_scratchpad_workspace(&self, workspace_id: VirtualWorkspaceId) -> bool {
+        self.scratchpads.values().any(|&id| id == workspace_id)
+    }
+
+    pub fn scratchpad_windows(&self, space: SpaceId) -> Vec<WindowId> {
+        self.scratchpads
+            .get(&space)
+            .and_then(|&ws_id| self.workspaces.get(ws_id))
+            .map(|ws| ws.windows().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn is_scratchpad_visible(&self, space: SpaceId) -> bool {
+        self.scratchpad_visible.contains(&space)
+    }
+
+    /// Flips scratchpad visibility for `space` and returns the new state.
+    pub fn toggle_scratchpad_visible(&mut self, space: SpaceId) -> bool {
+        if self.scratchpad_visible.remove(&space) {
+            false
+        } else {
+            self.scratchpad_visible.insert(space);
+            true
+        }
     }
 
     pub fn create_workspace(
@@ -645,6 +805,41 @@ impl VirtualWorkspaceManager {
         })
     }
 
+    /// Replace `workspace_id` with a workspace loaded from a layout profile.
+    /// Saved windows outside `present` are dropped; the rest are moved here from
+    /// whichever workspace of the space holds them now.
+    pub fn restore_workspace(
+        &mut self,
+        workspace_id: VirtualWorkspaceId,
+        mut saved: VirtualWorkspace,
+        present: &HashSet<WindowId>,
+    ) {
+        let Some(space) = self.workspaces.get(workspace_id).map(|ws| ws.space) else {
+            return;
+        };
+        let missing: Vec<WindowId> = saved.windows().filter(|wid| !present.contains(wid)).collect();
+        for wid in missing {
+            saved.remove_window(wid);
+            saved.layout_system.remove_window(wid);
+        }
+        for wid in saved.windows.iter().copied() {
+            if let Some(old) = self.window_to_workspace.insert((space, wid), workspace_id)
+                && old != workspace_id
+                && let Some(old_workspace) = self.workspaces.get_mut(old)
+            {
+                old_workspace.remove_window(wid);
+            }
+        }
+
+        let workspace = &mut self.workspaces[workspace_id];
+        workspace.name = saved.name;
+        workspace.windows = saved.windows;
+        workspace.last_focused = saved.last_focused;
+        workspace.previous_focused = saved.previous_focused;
+        workspace.layout_system = saved.layout_system;
+        workspace.layout_mode = saved.layout_mode;
+    }
+
     pub fn workspace_for_window(
         &self,
         space: SpaceId,
@@ -693,6 +888,8 @@ impl VirtualWorkspaceManager {
                 self.last_rule_decision.remove(&(space, wid));
             }
         }
+        self.pending_rule_frames.remove(&window_id);
+        self.forget_sticky_window(window_id);
     }
 
     pub fn remove_windows_for_app(&mut self, pid: pid_t) {
@@ -717,6 +914,11 @@ impl VirtualWorkspaceManager {
                 self.last_rule_decision.remove(&(space, window_id));
             }
         }
+        self.pending_rule_frames.retain(|wid, _| wid.pid != pid);
+        self.sticky_windows.retain(|_, set| {
+            set.retain(|wid| wid.pid != pid);
+            !set.is_empty()
+        });
     }
 
     /// Gets all windows in the active virtual workspace for a given native space.
@@ -787,13 +989,13 @@ impl VirtualWorkspaceManager {
                 "us.zoom.xos" => CGPoint::new(0.0, 0.0),
                 _ => match corner {
                     HideCorner::BottomLeft => CGPoint::new(1.0, -1.0),
-                    HideCorner::BottomRight => CGPoint::new(1.0, 1.0),
+                    _ => CGPoint::new(1.0, 1.0),
                 },
             }
         } else {
             match corner {
                 HideCorner::BottomLeft => CGPoint::new(1.0, -1.0),
-                HideCorner::BottomRight => CGPoint::new(1.0, 1.0),
+                _ => CGPoint::new(1.0, 1.0),
             }
         };
 
@@ -812,6 +1014,14 @@ impl VirtualWorkspaceManager {
                     bottom_right.y - one_pixel_offset.y,
                 )
             }
+            HideCorner::TopLeft => CGPoint::new(
+                screen_frame.origin.x + one_pixel_offset.x - original_size.width + 1.0,
+                screen_frame.origin.y + one_pixel_offset.y - original_size.height,
+            ),
+            HideCorner::TopRight => CGPoint::new(
+                screen_frame.max().x - one_pixel_offset.x - 1.0,
+                screen_frame.origin.y + one_pixel_offset.y - original_size.height,
+            ),
         };
 
         CGRect::new(hidden_point, original_size)
@@ -918,7 +1128,7 @@ impl VirtualWorkspaceManager {
         let hidden_rect = self.choose_hidden_position(
             *screen_frame,
             rect.size,
-            HideCorner::BottomRight,
+            self.hide_corner,
             app_bundle_id,
             &[],
         );
@@ -948,7 +1158,7 @@ impl VirtualWorkspaceManager {
         let hidden_rect = self.choose_hidden_position(
             *screen_frame,
             rect.size,
-            HideCorner::BottomRight,
+            self.hide_corner,
             app_bundle_id,
             &other_screens,
         );
@@ -1016,6 +1226,28 @@ impl VirtualWorkspaceManager {
             .store_position(window_id, position);
     }
 
+    /// Take the app-rule frames waiting for windows of `workspace_id`. Each
+    /// frame is handed out once, so later layouts keep whatever rect the
+    /// window has moved to since.
+    pub fn take_pending_rule_frames(
+        &mut self,
+        space: SpaceId,
+        workspace_id: VirtualWorkspaceId,
+    ) -> Vec<(WindowId, FloatingRuleFrame)> {
+        if self.pending_rule_frames.is_empty() {
+            return Vec::new();
+        }
+        let wids: Vec<WindowId> = self
+            .pending_rule_frames
+            .keys()
+            .copied()
+            .filter(|&wid| self.workspace_for_window(space, wid) == Some(workspace_id))
+            .collect();
+        wids.into_iter()
+            .filter_map(|wid| Some((wid, self.pending_rule_frames.remove(&wid)?)))
+            .collect()
+    }
+
     pub fn store_floating_position_if_absent(
         &mut self,
         space: SpaceId,
@@ -1055,6 +1287,9 @@ impl VirtualWorkspaceManager {
         }
     }
 
+    /// Remembered rects for the windows currently assigned to `workspace_id`.
+    /// Rects a window left behind on other workspaces stay stored but are not
+    /// returned until it moves back.
     pub fn get_workspace_floating_positions(
         &self,
         space: SpaceId,
@@ -1064,6 +1299,9 @@ impl VirtualWorkspaceManager {
         if let Some(positions) = self.floating_positions.get(&key) {
             positions
                 .windows()
+                .filter(|&window_id| {
+                    self.workspace_for_window(space, window_id) == Some(workspace_id)
+                })
                 .filter_map(|window_id| {
                     positions.get_position(window_id).map(|position| (window_id, position))
                 })
@@ -1114,6 +1352,53 @@ impl VirtualWorkspaceManager {
         }
     }
 
+    /// Remove an empty workspace from `space`. If it was active, the previous
+    /// workspace (or the next, for the first) takes over. The last workspace
+    /// of a space is never removed.
+    pub fn remove_workspace(&mut self, space: SpaceId, workspace_id: VirtualWorkspaceId) -> bool {
+        let Some(ids) = self.workspaces_by_space.get_mut(&space) else {
+            return false;
+        };
+        let Some(index) = ids.iter().position(|&id| id == workspace_id) else {
+            return false;
+        };
+        if ids.len() <= 1
+            || self.workspaces.get(workspace_id).is_some_and(|ws| !ws.windows.is_empty())
+        {
+            return false;
+        }
+        ids.remove(index);
+        let neighbor = ids[index.saturating_sub(1)];
+
+        if let Some((last, active)) = self.active_workspace_per_space.get_mut(&space) {
+            if *active == workspace_id {
+                *active = neighbor;
+                *last = None;
+            } else if *last == Some(workspace_id) {
+                *last = None;
+            }
+        }
+        self.window_to_workspace.retain(|_, ws_id| *ws_id != workspace_id);
+        self.floating_positions.retain(|&(_, ws_id), _| ws_id != workspace_id);
+        self.workspaces.remove(workspace_id);
+        true
+    }
+
+    /// Move the workspace at index `from` to index `to`, shifting the ones in
+    /// between. Workspaces keep their ids, so the active one stays active.
+    pub fn move_workspace(&mut self, space: SpaceId, from: usize, to: usize) -> bool {
+        self.ensure_space_initialized(space);
+        let Some(ids) = self.workspaces_by_space.get_mut(&space) else {
+            return false;
+        };
+        if from >= ids.len() || to >= ids.len() {
+            return false;
+        }
+        let workspace_id = ids.remove(from);
+        ids.insert(to, workspace_id);
+        true
+    }
+
     pub fn workspace_windows(
         &self,
         space: SpaceId,
@@ -1152,6 +1437,57 @@ impl VirtualWorkspaceManager {
         window_title: Option<&str>,
         ax_role: Option<&str>,
         ax_subrole: Option<&str>,
+    ) -> Result<AppRuleResult, WorkspaceError> {
+        let untitled = window_title.is_none_or(|title| title.trim().is_empty());
+        if !untitled || self.untitled_window_policy == UntitledWindowPolicy::Rules {
+            return self.assign_window_by_app_rules(
+                window_id,
+                space,
+                app_bundle_id,
+                app_name,
+                window_title,
+                ax_role,
+                ax_subrole,
+            );
+        }
+
+        if self.untitled_window_policy == UntitledWindowPolicy::Defer
+            && !self.window_to_workspace.contains_key(&(space, window_id))
+        {
+            return Ok(AppRuleResult::Deferred);
+        }
+
+        let result = self.assign_window_by_app_rules(
+            window_id,
+            space,
+            app_bundle_id,
+            app_name,
+            window_title,
+            ax_role,
+            ax_subrole,
+        )?;
+        Ok(match result {
+            AppRuleResult::Managed(mut assignment) => {
+                match self.untitled_window_policy {
+                    UntitledWindowPolicy::Float => assignment.floating = true,
+                    UntitledWindowPolicy::Tile => assignment.floating = false,
+                    UntitledWindowPolicy::Rules | UntitledWindowPolicy::Defer => {}
+                }
+                AppRuleResult::Managed(assignment)
+            }
+            other => other,
+        })
+    }
+
+    fn assign_window_by_app_rules(
+        &mut self,
+        window_id: WindowId,
+        space: SpaceId,
+        app_bundle_id: Option<&str>,
+        app_name: Option<&str>,
+        window_title: Option<&str>,
+        ax_role: Option<&str>,
+        ax_subrole: Option<&str>,
     ) -> Result<AppRuleResult, WorkspaceError> {
         let prev_rule_decision =
             self.last_rule_decision.get(&(space, window_id)).copied().unwrap_or(false);
@@ -1178,60 +1514,23 @@ impl VirtualWorkspaceManager {
                 return Ok(AppRuleResult::Unmanaged);
             }
 
-            let target_workspace_id = if let Some(ref ws_sel) = rule.workspace {
-                let maybe_idx: Option<usize> = match ws_sel {
-                    WorkspaceSelector::Index(i) => Some(*i),
-                    WorkspaceSelector::Name(name) => {
-                        let workspaces = self.list_workspaces(space);
-                        match workspaces.iter().position(|(_, n)| n == name) {
-                            Some(idx) => Some(idx),
-                            None => {
-                                tracing::warn!(
-                                    "App rule references workspace name '{}' which could not be resolved for space {:?}; falling back to default workspace",
-                                    name,
-                                    space
-                                );
-                                None
-                            }
-                        }
-                    }
-                };
-
-                if let Some(workspace_idx) = maybe_idx {
-                    let len = self
-                        .workspaces_by_space
-                        .get(&space)
-                        .map(|v: &Vec<VirtualWorkspaceId>| v.len())
-                        .unwrap_or(0);
-                    if workspace_idx >= len {
-                        tracing::warn!(
-                            "App rule references non-existent workspace index {}, falling back to active workspace",
-                            workspace_idx
-                        );
+            let target_workspace_id = match rule.workspace.as_ref() {
+                Some(ws_sel) => match self.rule_workspace(space, ws_sel) {
+                    Some(workspace_id) => workspace_id,
+                    // An index past the last workspace falls back to the default
+                    // workspace; an unknown name keeps the current assignment.
+                    None if matches!(ws_sel, WorkspaceSelector::Index(_)) => {
                         self.get_default_workspace(space)?
-                    } else {
-                        let workspaces = self.list_workspaces(space);
-                        if let Some((workspace_id, _)) = workspaces.get(workspace_idx) {
-                            *workspace_id
-                        } else {
-                            tracing::warn!(
-                                "App rule references invalid workspace index {}, falling back to active workspace",
-                                workspace_idx
-                            );
-                            self.get_default_workspace(space)?
-                        }
                     }
-                } else if let Some(existing_ws) = existing_assignment {
-                    existing_ws
-                } else {
-                    self.get_default_workspace(space)?
-                }
-            } else {
-                if let Some(existing_ws) = existing_assignment {
-                    existing_ws
-                } else {
-                    self.get_default_workspace(space)?
-                }
+                    None => match existing_assignment {
+                        Some(existing_ws) => existing_ws,
+                        None => self.get_default_workspace(space)?,
+                    },
+                },
+                None => match existing_assignment {
+                    Some(existing_ws) => existing_ws,
+                    None => self.get_default_workspace(space)?,
+                },
             };
 
             if let Some(existing_ws) = existing_assignment {
@@ -1250,6 +1549,9 @@ impl VirtualWorkspaceManager {
             if self.assign_window_to_workspace(space, window_id, target_workspace_id) {
                 if rule.floating {
                     self.window_rule_floating.insert((space, window_id), true);
+                    if let Some(frame) = rule.floating_frame {
+                        self.pending_rule_frames.insert(window_id, frame);
+                    }
                 } else {
                     self.window_rule_floating.remove(&(space, window_id));
                 }
@@ -1286,6 +1588,69 @@ impl VirtualWorkspaceManager {
         }
     }
 
+    /// Resolve an app rule's workspace selector on `space`, or `None` when it
+    /// does not name an existing workspace.
+    fn rule_workspace(
+        &mut self,
+        space: SpaceId,
+        ws_sel: &WorkspaceSelector,
+    ) -> Option<VirtualWorkspaceId> {
+        let workspaces = self.list_workspaces(space);
+        let workspace_idx = match ws_sel {
+            WorkspaceSelector::Index(i) => *i,
+            WorkspaceSelector::Name(name) => match workspaces.iter().position(|(_, n)| n == name) {
+                Some(idx) => idx,
+                None => {
+                    tracing::warn!(
+                        "App rule references workspace name '{}' which could not be resolved for space {:?}; falling back to default workspace",
+                        name,
+                        space
+                    );
+                    return None;
+                }
+            },
+        };
+        match workspaces.get(workspace_idx) {
+            Some((workspace_id, _)) => Some(*workspace_id),
+            None => {
+                tracing::warn!(
+                    "App rule references non-existent workspace index {}, falling back to active workspace",
+                    workspace_idx
+                );
+                None
+            }
+        }
+    }
+
+    /// Work out where auto-assignment would put a window on `space` if it were
+    /// new, ignoring its current assignment. Returns `None` when an app rule
+    /// marks the window as unmanaged.
+    pub fn auto_assignment_target(
+        &mut self,
+        space: SpaceId,
+        app_bundle_id: Option<&str>,
+        app_name: Option<&str>,
+        window_title: Option<&str>,
+        ax_role: Option<&str>,
+        ax_subrole: Option<&str>,
+    ) -> Result<Option<VirtualWorkspaceId>, WorkspaceError> {
+        self.ensure_space_initialized(space);
+        let rule = self
+            .find_matching_app_rule(app_bundle_id, app_name, window_title, ax_role, ax_subrole)
+            .cloned();
+        if let Some(rule) = rule {
+            if !rule.manage {
+                return Ok(None);
+            }
+            if let Some(workspace_id) =
+                rule.workspace.as_ref().and_then(|ws_sel| self.rule_workspace(space, ws_sel))
+            {
+                return Ok(Some(workspace_id));
+            }
+        }
+        self.get_default_workspace(space).map(Some)
+    }
+
     fn get_default_workspace(
         &mut self,
         space: SpaceId,
@@ -1317,97 +1682,167 @@ impl VirtualWorkspaceManager {
         }
     }
 
-    fn find_matching_app_rule(
+    /// Indices of every app rule that matches a window with these properties,
+    /// whether or not it would win over the other matching rules.
+    pub fn matching_app_rule_indices(
         &self,
         app_bundle_id: Option<&str>,
         app_name: Option<&str>,
         window_title: Option<&str>,
         ax_role: Option<&str>,
         ax_subrole: Option<&str>,
-    ) -> Option<&AppWorkspaceRule> {
-        let mut matches: Vec<(usize, &AppWorkspaceRule, usize)> = Vec::new();
+    ) -> Vec<usize> {
+        self.app_rules
+            .iter()
+            .enumerate()
+            .filter(|(idx, rule)| {
+                self.app_rule_matches(
+                    *idx,
+                    rule,
+                    app_bundle_id,
+                    app_name,
+                    window_title,
+                    ax_role,
+                    ax_subrole,
+                )
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
 
-        for (idx, rule) in self.app_rules.iter().enumerate() {
-            if let Some(ref rule_app_id) = rule.app_id {
-                match app_bundle_id {
-                    Some(bundle_id) if rule_app_id.eq_ignore_ascii_case(bundle_id) => {}
-                    _ => continue,
-                }
+    /// Minimum tiled size from the last matching app rule that sets one.
+    pub fn app_rule_min_size(
+        &self,
+        app_bundle_id: Option<&str>,
+        app_name: Option<&str>,
+        window_title: Option<&str>,
+        ax_role: Option<&str>,
+        ax_subrole: Option<&str>,
+    ) -> Option<RuleMinSize> {
+        self.matching_app_rule_indices(app_bundle_id, app_name, window_title, ax_role, ax_subrole)
+            .into_iter()
+            .rev()
+            .find_map(|idx| self.app_rules[idx].min_size)
+    }
+
+    pub fn app_rules(&self) -> &[AppWorkspaceRule] { &self.app_rules }
+
+    fn app_rule_matches(
+        &self,
+        idx: usize,
+        rule: &AppWorkspaceRule,
+        app_bundle_id: Option<&str>,
+        app_name: Option<&str>,
+        window_title: Option<&str>,
+        ax_role: Option<&str>,
+        ax_subrole: Option<&str>,
+    ) -> bool {
+        if let Some(ref rule_app_id) = rule.app_id {
+            match app_bundle_id {
+                Some(bundle_id) if rule_app_id.eq_ignore_ascii_case(bundle_id) => {}
+                _ => return false,
             }
+        }
 
-            if let Some(ref rule_name) = rule.app_name {
-                match app_name {
-                    Some(name) => {
-                        let name_l = name.to_lowercase();
-                        let rule_name_l = rule_name.to_lowercase();
-                        if !(name_l.contains(&rule_name_l) || rule_name_l.contains(&name_l)) {
-                            continue;
-                        }
+        if let Some(ref rule_name) = rule.app_name {
+            match app_name {
+                Some(name) => {
+                    let name_l = name.to_lowercase();
+                    let rule_name_l = rule_name.to_lowercase();
+                    if !(name_l.contains(&rule_name_l) || rule_name_l.contains(&name_l)) {
+                        return false;
                     }
-                    None => continue,
                 }
+                None => return false,
             }
+        }
 
-            if let Some(ref rule_re) = rule.title_regex {
-                if rule_re.is_empty() {
-                    continue;
-                }
-                match window_title {
-                    Some(title) => match self.app_rule_regex_cache.get(idx) {
-                        Some(Some(re)) => {
-                            if !re.is_match(title) {
-                                continue;
-                            }
+        if let Some(ref rule_re) = rule.title_regex {
+            if rule_re.is_empty() {
+                return false;
+            }
+            match window_title {
+                Some(title) => match self.app_rule_regex_cache.get(idx) {
+                    Some(Some(re)) => {
+                        if !re.is_match(title) {
+                            return false;
                         }
-                        _ => continue,
-                    },
-                    None => continue,
-                }
+                    }
+                    _ => return false,
+                },
+                None => return false,
             }
+        }
 
-            // Case-insensitive substring matching for title_substring
-            if let Some(ref title_sub) = rule.title_substring {
-                if title_sub.is_empty() {
-                    continue;
-                }
-                match window_title {
-                    Some(title) => {
-                        let title_l = title.to_lowercase();
-                        let sub_l = title_sub.to_lowercase();
-                        if !title_l.contains(&sub_l) {
-                            continue;
-                        }
+        // Case-insensitive substring matching for title_substring
+        if let Some(ref title_sub) = rule.title_substring {
+            if title_sub.is_empty() {
+                return false;
+            }
+            match window_title {
+                Some(title) => {
+                    let title_l = title.to_lowercase();
+                    let sub_l = title_sub.to_lowercase();
+                    if !title_l.contains(&sub_l) {
+                        return false;
                     }
-                    None => continue,
                 }
+                None => return false,
             }
+        }
 
-            if let Some(ref rule_ax_role) = rule.ax_role {
-                if rule_ax_role.is_empty() {
-                    continue;
-                }
-                match ax_role {
-                    Some(r) => {
-                        if r != rule_ax_role.as_str() {
-                            continue;
-                        }
+        if let Some(ref rule_ax_role) = rule.ax_role {
+            if rule_ax_role.is_empty() {
+                return false;
+            }
+            match ax_role {
+                Some(r) => {
+                    if r != rule_ax_role.as_str() {
+                        return false;
                     }
-                    None => continue,
                 }
+                None => return false,
             }
+        }
 
-            if let Some(ref rule_ax_sub) = rule.ax_subrole {
-                if rule_ax_sub.is_empty() {
-                    continue;
-                }
-                match ax_subrole {
-                    Some(sr) => {
-                        if sr != rule_ax_sub.as_str() {
-                            continue;
-                        }
+        if let Some(ref rule_ax_sub) = rule.ax_subrole {
+            if rule_ax_sub.is_empty() {
+                return false;
+            }
+            match ax_subrole {
+                Some(sr) => {
+                    if sr != rule_ax_sub.as_str() {
+                        return false;
                     }
-                    None => continue,
                 }
+                None => return false,
+            }
+        }
+
+        true
+    }
+
+    fn find_matching_app_rule(
+        &self,
+        app_bundle_id: Option<&str>,
+        app_name: Option<&str>,
+        window_title: Option<&str>,
+        ax_role: Option<&str>,
+        ax_subrole: Option<&str>,
+    ) -> Option<&AppWorkspaceRule> {
+        let mut matches: Vec<(usize, &AppWorkspaceRule, usize)> = Vec::new();
+
+        for (idx, rule) in self.app_rules.iter().enumerate() {
+            if !self.app_rule_matches(
+                idx,
+                rule,
+                app_bundle_id,
+                app_name,
+                window_title,
+                ax_role,
+                ax_subrole,
+            ) {
+                continue;
             }
 
             let mut score = 0usize;
@@ -1554,6 +1989,9 @@ mod tests {
             Ok(AppRuleResult::Unmanaged) => {
                 panic!("App rule unexpectedly marked window as unmanaged")
             }
+            Ok(AppRuleResult::Deferred) => {
+                panic!("App rule unexpectedly deferred window assignment")
+            }
             Err(e) => panic!("assign_window_with_app_info failed: {:?}", e),
         }
     }
@@ -1733,6 +2171,8 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                floating_frame: None,
+                min_size: None,
             },
             // Match by app_name -> workspace 1
             AppWorkspaceRule {
@@ -1745,6 +2185,8 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                floating_frame: None,
+                min_size: None,
             },
             // Title substring -> workspace 0
             AppWorkspaceRule {
@@ -1757,6 +2199,8 @@ mod tests {
                 title_substring: Some("Preferences".into()),
                 ax_role: None,
                 ax_subrole: None,
+                floating_frame: None,
+                min_size: None,
             },
             // Title regex -> workspace 2
             AppWorkspaceRule {
@@ -1769,6 +2213,8 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                floating_frame: None,
+                min_size: None,
             },
             // AX role + subrole floating
             AppWorkspaceRule {
@@ -1781,6 +2227,8 @@ mod tests {
                 title_substring: None,
                 ax_role: Some("AXWindow".into()),
                 ax_subrole: Some("AXDialog".into()),
+                floating_frame: None,
+                min_size: None,
             },
             // Workspace by name
             AppWorkspaceRule {
@@ -1793,6 +2241,8 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                floating_frame: None,
+                min_size: None,
             },
             // Specificity tie breaking generic vs substring (generic workspace 0, specific workspace 2)
             AppWorkspaceRule {
@@ -1805,6 +2255,8 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                floating_frame: None,
+                min_size: None,
             },
             AppWorkspaceRule {
                 app_id: Some("com.example.tie".into()),
@@ -1816,6 +2268,8 @@ mod tests {
                 title_substring: Some("Editor".into()),
                 ax_role: None,
                 ax_subrole: None,
+                floating_frame: None,
+                min_size: None,
             },
             // Reapplication: Bitwarden title becomes floating
             AppWorkspaceRule {
@@ -1828,6 +2282,8 @@ mod tests {
                 title_substring: Some("Bitwarden".into()),
                 ax_role: None,
                 ax_subrole: None,
+                floating_frame: None,
+                min_size: None,
             },
             AppWorkspaceRule {
                 app_id: Some("app.zen-browser.zen".into()),
@@ -1839,6 +2295,8 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                floating_frame: None,
+                min_size: None,
             },
             // Workspace override when specific rule matches different workspace + floating
             AppWorkspaceRule {
@@ -1851,6 +2309,8 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                floating_frame: None,
+                min_size: None,
             },
             AppWorkspaceRule {
                 app_id: Some("app.zen-browser.zen".into()),
@@ -1862,6 +2322,8 @@ mod tests {
                 title_substring: Some("bitwarden".into()),
                 ax_role: None,
                 ax_subrole: None,
+                floating_frame: None,
+                min_size: None,
             },
         ];
 
@@ -2055,4 +2517,25 @@ mod tests {
         );
         assert!(bw2_updated_assignment.floating);
     }
+
+    #[test]
+    fn configured_hide_corner_is_used_for_parking_and_occlusion_checks() {
+        let settings = VirtualWorkspaceSettings {
+            hide_corner: HideCorner::TopLeft,
+            ..Default::default()
+        };
+        let manager =
+            VirtualWorkspaceManager::new_with_config(&settings, &LayoutSettings::default());
+        let top = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let below = CGRect::new(CGPoint::new(0.0, 800.0), CGSize::new(1000.0, 800.0));
+        let size = CGSize::new(400.0, 300.0);
+
+        let hidden =
+            manager.calculate_hidden_position_multi(top, size, manager.hide_corner, None, &[
+                top, below,
+            ]);
+        assert_eq!(hidden.origin, CGPoint::new(-398.0, -299.0));
+        assert!(hidden.max().y <= below.origin.y);
+        assert!(manager.is_hidden_position_multi(&top, &hidden, None, &[top, below]));
+    }
 }
@@ -109,6 +109,25 @@ impl State {
     }
 }
 
+/// Suspend the current task once, letting the run loop and other tasks on
+/// this thread make progress before it resumes.
+pub async fn yield_now() { YieldNow(false).await }
+
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+        self.0 = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
 struct WakerImpl(WakeupHandle);
 
 impl Wake for WakerImpl {
@@ -538,6 +538,9 @@ impl ScreenId {
     pub fn new(id: u32) -> Self { ScreenId(id) }
 
     pub fn as_u32(&self) -> u32 { self.0 }
+
+    /// Full bounds of the display, including the menu bar and Dock areas.
+    pub fn display_bounds(&self) -> CGRect { CGDisplayBounds(self.0) }
 }
 
 pub trait NSScreenExt {
@@ -289,6 +289,14 @@ pub fn window_is_ordered_in(id: WindowServerId) -> bool {
     false
 }
 
+pub fn set_window_alpha(id: WindowServerId, alpha: f32) -> bool {
+    cg_ok(unsafe { SLSSetWindowAlpha(*G_CONNECTION, id.as_u32(), alpha) }).is_ok()
+}
+
+pub fn set_window_level(id: WindowServerId, level: NSWindowLevel) -> bool {
+    cg_ok(unsafe { SLSSetWindowLevel(*G_CONNECTION, id.as_u32(), level as c_int) }).is_ok()
+}
+
 fn get_visible_windows_raw<T: Type>() -> CFRetained<CFArray<T>> {
     unsafe {
         // TODO: cgwindowlistcopywindowinfo does not appear to order windows properly
@@ -826,6 +834,16 @@ pub fn make_key_window(pid: pid_t, wsid: WindowServerId) -> Result<(), CGError>
     Ok(())
 }
 
+/// Bring `pid` to the front without ordering any of its windows forward. Used to
+/// give focus to Finder's desktop.
+pub fn make_process_frontmost_without_windows(pid: pid_t) -> Result<(), CGError> {
+    #[allow(non_upper_case_globals)]
+    const kCPSNoWindows: u32 = 0x400;
+
+    let psn = ProcessSerialNumber::for_pid(pid)?;
+    unsafe { cg_ok(_SLPSSetFrontProcessWithOptions(&psn, 0, kCPSNoWindows)) }
+}
+
 pub fn allow_hide_mouse() -> Result<(), CGError> {
     let cid = unsafe { SLSMainConnectionID() };
     let property = CFString::from_str("SetsCursorInBackground");
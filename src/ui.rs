@@ -2,3 +2,4 @@ pub mod common;
 pub mod menu_bar;
 pub mod mission_control;
 pub mod stack_line;
+pub mod workspace_indicator;
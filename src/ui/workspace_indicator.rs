@@ -0,0 +1,87 @@
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2_app_kit::NSPopUpMenuWindowLevel;
+use objc2_core_foundation::{CFString, CGPoint, CGRect, CGSize};
+use objc2_core_graphics::CGColor;
+use objc2_quartz_core::{CALayer, CATextLayer, kCATextLayerAlignmentCenter};
+use tracing::warn;
+
+use crate::sys::cgs_window::{CgsWindow, CgsWindowError};
+use crate::ui::common::{render_layer_to_cgs_window, with_disabled_actions};
+
+const WIDTH: f64 = 280.0;
+const HEIGHT: f64 = 72.0;
+const FONT_SIZE: f64 = 24.0;
+
+/// A transient overlay centered on a screen that names the workspace that was
+/// just switched to.
+pub struct WorkspaceIndicatorWindow {
+    frame: CGRect,
+    root_layer: Retained<CALayer>,
+    text_layer: Retained<CATextLayer>,
+    cgs_window: CgsWindow,
+}
+
+impl WorkspaceIndicatorWindow {
+    pub fn new() -> Result<Self, CgsWindowError> {
+        let frame = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(WIDTH, HEIGHT));
+
+        let root_layer = CALayer::layer();
+        root_layer.setFrame(frame);
+        root_layer.setCornerRadius(12.0);
+        let background = CGColor::new_generic_gray(0.0, 0.7);
+        root_layer.setBackgroundColor(Some(&*background));
+
+        let text_layer = CATextLayer::layer();
+        root_layer.addSublayer(&text_layer);
+        text_layer.setFrame(CGRect::new(
+            CGPoint::new(0.0, (HEIGHT - FONT_SIZE * 1.25) / 2.0),
+            CGSize::new(WIDTH, FONT_SIZE * 1.25),
+        ));
+        text_layer.setFontSize(FONT_SIZE);
+        let foreground = CGColor::new_generic_gray(1.0, 1.0);
+        text_layer.setForegroundColor(Some(&*foreground));
+        unsafe { text_layer.setAlignmentMode(kCATextLayerAlignmentCenter) };
+
+        let cgs_window = CgsWindow::new(frame)?;
+        if let Err(err) = cgs_window.set_opacity(false) {
+            warn!(error=?err, "failed to set workspace indicator opacity");
+        }
+        if let Err(err) = cgs_window.set_alpha(1.0) {
+            warn!(error=?err, "failed to set workspace indicator alpha");
+        }
+        if let Err(err) = cgs_window.set_level(NSPopUpMenuWindowLevel as i32) {
+            warn!(error=?err, "failed to set workspace indicator level");
+        }
+        if let Err(err) = cgs_window.set_tags(1 << 3) {
+            warn!(error=?err, "failed to disable workspace indicator shadow");
+        }
+
+        Ok(Self {
+            frame,
+            root_layer,
+            text_layer,
+            cgs_window,
+        })
+    }
+
+    /// Center the overlay on `screen` and show `label` in it.
+    pub fn show(&mut self, screen: CGRect, label: &str) -> Result<(), CgsWindowError> {
+        let center = screen.mid();
+        let frame = CGRect::new(
+            CGPoint::new(center.x - WIDTH / 2.0, center.y - HEIGHT / 2.0),
+            CGSize::new(WIDTH, HEIGHT),
+        );
+        self.cgs_window.set_shape(frame)?;
+        self.frame = frame;
+
+        let text = CFString::from_str(label);
+        let text: &AnyObject = text.as_ref();
+        with_disabled_actions(|| unsafe { self.text_layer.setString(Some(text)) });
+
+        render_layer_to_cgs_window(self.cgs_window.id(), self.frame.size, &self.root_layer);
+        self.cgs_window.order_above(None)
+    }
+
+    pub fn hide(&self) -> Result<(), CgsWindowError> { self.cgs_window.order_out() }
+}